@@ -0,0 +1,349 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use anyhow::{bail, Context};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::time;
+use tokio_tungstenite::{
+    connect_async, tungstenite::Message as WsMessage, MaybeTlsStream,
+    WebSocketStream,
+};
+use tracing::warn;
+
+use crate::message::{Message, MessageKind};
+
+/// Of one OBS request, connecting included.
+const OBS_TIMEOUT: Duration = Duration::from_secs(5);
+/// obs-websocket 5.x protocol version.
+const OBS_RPC_VERSION: u64 = 1;
+
+/// What happens as a message of some kind is forwarded. Everything set
+/// runs at once, in the background.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct Alert {
+    /// Sound file played, empty for none.
+    pub sound: String,
+    /// Shows the message as a desktop notification.
+    pub notify: bool,
+    /// obs-websocket request sent, e.g. `SetCurrentProgramScene`. Empty
+    /// for none.
+    pub obs_request: String,
+    /// JSON `requestData` of `obs_request`, empty for none.
+    pub obs_data: String,
+    /// Reads the message out with the system voice.
+    pub tts: bool,
+}
+
+impl Alert {
+    pub fn is_empty(&self) -> bool {
+        self.sound.is_empty()
+            && !self.notify
+            && self.obs_request.is_empty()
+            && !self.tts
+    }
+}
+
+/// Alerts by message kind, with where OBS is for those that need it.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    pub alerts: HashMap<MessageKind, Alert>,
+    /// obs-websocket server, e.g. `ws://127.0.0.1:4455`.
+    pub obs_url: String,
+    /// Kept in the OS keyring, not with the rest.
+    #[serde(skip)]
+    pub obs_password: String,
+}
+
+impl fmt::Debug for AlertConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlertConfig")
+            .field("alerts", &self.alerts)
+            .field("obs_url", &self.obs_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AlertConfig {
+    pub fn alert(&self, kind: MessageKind) -> Option<&Alert> {
+        self.alerts.get(&kind).filter(|it| !it.is_empty())
+    }
+
+    /// Runs the alert for `msg`'s kind, if any. Has to be called within
+    /// a tokio runtime, failures are only logged.
+    pub fn fire(&self, msg: &Message) {
+        let Some(alert) = self.alert(msg.kind) else {
+            return;
+        };
+        let title = match msg.username {
+            Some(ref username) => {
+                format!("{} from {username}", msg.kind.name())
+            }
+            None => msg.kind.name().to_owned(),
+        };
+        let text = match msg.username {
+            Some(ref username) => format!("{username}: {}", msg.text),
+            None => msg.text.clone(),
+        };
+
+        if !alert.sound.is_empty() {
+            spawn("play sound", play_sound(alert.sound.clone()));
+        }
+        if alert.notify {
+            spawn("notify", notify(title, msg.text.clone()));
+        }
+        if alert.tts {
+            spawn("speak", speak(text));
+        }
+        if !alert.obs_request.is_empty() {
+            let url = self.obs_url.clone();
+            let password = self.obs_password.clone();
+            let request = alert.obs_request.clone();
+            let data = alert.obs_data.clone();
+            spawn("send OBS request", async move {
+                let data = match data.trim() {
+                    "" => None,
+                    data => Some(
+                        serde_json::from_str(data)
+                            .context("OBS request data is not JSON")?,
+                    ),
+                };
+                time::timeout(
+                    OBS_TIMEOUT,
+                    obs_request(&url, &password, &request, data),
+                )
+                .await
+                .context("timed out")?
+            });
+        }
+    }
+}
+
+fn spawn(
+    what: &'static str,
+    fut: impl std::future::Future<Output = anyhow::Result<()>>
+        + Send
+        + 'static,
+) {
+    tokio::spawn(async move {
+        if let Err(err) = fut.await {
+            warn!("failed to {what} for alert: {err:?}");
+        }
+    });
+}
+
+/// Waits for `command` to exit, arguments are passed as is so the
+/// message can't inject anything.
+async fn run(command: Command) -> anyhow::Result<()> {
+    let status = tokio::process::Command::from(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .context("failed to run command")?;
+    if !status.success() {
+        bail!("command exited with {status}");
+    }
+    Ok(())
+}
+
+/// PowerShell running `script`, which reads its input from the
+/// environment rather than having it spliced in.
+#[cfg(windows)]
+fn powershell(script: &str, env: &[(&str, &str)]) -> Command {
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    command.envs(env.iter().copied());
+    command
+}
+
+async fn play_sound(path: String) -> anyhow::Result<()> {
+    #[cfg(windows)]
+    let command = powershell(
+        "(New-Object Media.SoundPlayer $env:BL_SOUND).PlaySync()",
+        &[("BL_SOUND", &path)],
+    );
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut command = Command::new("afplay");
+        command.arg(&path);
+        command
+    };
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let command = {
+        let mut command = Command::new("paplay");
+        command.arg(&path);
+        command
+    };
+    run(command).await
+}
+
+/// Shows `body` as a desktop notification.
+pub(crate) fn notify_command(title: &str, body: &str) -> Command {
+    #[cfg(windows)]
+    let command = powershell(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $icon = New-Object System.Windows.Forms.NotifyIcon; \
+         $icon.Icon = [System.Drawing.SystemIcons]::Information; \
+         $icon.Visible = $true; \
+         $icon.ShowBalloonTip(5000, $env:BL_TITLE, $env:BL_BODY, 'None'); \
+         Start-Sleep -Seconds 5; $icon.Dispose()",
+        &[("BL_TITLE", title), ("BL_BODY", body)],
+    );
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 2 of argv) with title \
+             (item 1 of argv)",
+            "-e",
+            "end run",
+            title,
+            body,
+        ]);
+        command
+    };
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let command = {
+        let mut command = Command::new("notify-send");
+        command.args(["--", title, body]);
+        command
+    };
+    command
+}
+
+async fn notify(title: String, body: String) -> anyhow::Result<()> {
+    run(notify_command(&title, &body)).await
+}
+
+async fn speak(text: String) -> anyhow::Result<()> {
+    #[cfg(windows)]
+    let command = powershell(
+        "Add-Type -AssemblyName System.Speech; \
+         (New-Object System.Speech.Synthesis.SpeechSynthesizer)\
+         .Speak($env:BL_TEXT)",
+        &[("BL_TEXT", &text)],
+    );
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut command = Command::new("say");
+        command.args(["--", &text]);
+        command
+    };
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let command = {
+        let mut command = Command::new("espeak");
+        command.args(["--", &text]);
+        command
+    };
+    run(command).await
+}
+
+/// `authentication` of an obs-websocket Identify, from the `salt` and
+/// `challenge` of its Hello.
+pub fn obs_auth(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64_STANDARD
+        .encode(Sha256::digest(format!("{password}{salt}")));
+    BASE64_STANDARD.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+type ObsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Sends one request to obs-websocket 5.x at `url` and waits for it to
+/// succeed, connecting just for it.
+pub async fn obs_request(
+    url: &str,
+    password: &str,
+    request: &str,
+    data: Option<Value>,
+) -> anyhow::Result<()> {
+    if url.is_empty() {
+        bail!("no OBS url");
+    }
+    let (mut ws, _) = connect_async(url)
+        .await
+        .with_context(|| format!("failed to connect {url}"))?;
+
+    let hello = recv_op(&mut ws, 0).await.context("no hello from OBS")?;
+    let mut identify = json!({"rpcVersion": OBS_RPC_VERSION});
+    if let Some(auth) = hello.get("authentication") {
+        let field = |name: &str| {
+            auth.get(name)
+                .and_then(Value::as_str)
+                .with_context(|| format!("no {name} in hello"))
+        };
+        identify["authentication"] =
+            obs_auth(password, field("salt")?, field("challenge")?)
+                .into();
+    }
+    send_op(&mut ws, 1, identify).await?;
+    recv_op(&mut ws, 2)
+        .await
+        .context("OBS refused to identify, check the password")?;
+
+    let mut request_data = json!({
+        "requestType": request,
+        "requestId": "blooming-light",
+    });
+    if let Some(data) = data {
+        request_data["requestData"] = data;
+    }
+    send_op(&mut ws, 6, request_data).await?;
+    let response = recv_op(&mut ws, 7).await?;
+    let _ = ws.close(None).await;
+
+    let status = &response["requestStatus"];
+    if status["result"].as_bool() != Some(true) {
+        bail!(
+            "OBS request {request} failed with {}: {}",
+            status["code"],
+            status["comment"].as_str().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+async fn send_op(
+    ws: &mut ObsStream,
+    op: u64,
+    d: Value,
+) -> anyhow::Result<()> {
+    let frame = json!({"op": op, "d": d}).to_string();
+    ws.send(WsMessage::Text(frame))
+        .await
+        .context("failed to send to OBS")
+}
+
+/// The `d` of the next message with opcode `op`, skipping events.
+async fn recv_op(ws: &mut ObsStream, op: u64) -> anyhow::Result<Value> {
+    loop {
+        let msg = ws
+            .next()
+            .await
+            .context("OBS closed the connection")?
+            .context("failed to receive from OBS")?;
+        let WsMessage::Text(msg) = msg else {
+            continue;
+        };
+        let mut msg = serde_json::from_str::<Value>(&msg)
+            .context("invalid frame from OBS")?;
+        if msg["op"].as_u64() == Some(op) {
+            return Ok(msg["d"].take());
+        }
+    }
+}
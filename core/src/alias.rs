@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// What the streamer calls a user locally, never sent anywhere.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct UserAlias {
+    /// Shown next to their name.
+    pub alias: String,
+    /// Shown on hover, e.g. who they are an alt of.
+    pub note: String,
+}
+
+impl UserAlias {
+    pub fn is_empty(&self) -> bool {
+        self.alias.trim().is_empty() && self.note.trim().is_empty()
+    }
+}
+
+/// Aliases by username, the only thing sources identify users by.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct UserAliases(BTreeMap<String, UserAlias>);
+
+impl UserAliases {
+    pub fn get(&self, username: &str) -> Option<&UserAlias> {
+        self.0.get(username)
+    }
+
+    /// Of the sender of `msg`, if any.
+    pub fn of(&self, msg: &Message) -> Option<&UserAlias> {
+        msg.username.as_deref().and_then(|it| self.get(it))
+    }
+
+    /// Replaces the alias of `username`, or forgets it if `alias` is
+    /// empty.
+    pub fn set(&mut self, username: &str, alias: UserAlias) {
+        let username = username.trim();
+        if username.is_empty() {
+            return;
+        }
+        if alias.is_empty() {
+            self.0.remove(username);
+        } else {
+            self.0.insert(username.to_owned(), alias);
+        }
+    }
+
+    pub fn remove(&mut self, username: &str) {
+        self.0.remove(username);
+    }
+
+    /// By username.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &UserAlias)> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::Notifier;
+
+/// Bounded multi-producer single-consumer channel that never blocks the
+/// sender: when full, the oldest item is dropped and counted instead.
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    with_notifier(capacity, None)
+}
+
+/// Like [`bounded`], also calling `notifier` on every send, so a
+/// receiver outside the runtime (the frontend) doesn't have to poll.
+pub fn notifying<T>(
+    capacity: usize,
+    notifier: Notifier,
+) -> (Sender<T>, Receiver<T>) {
+    with_notifier(capacity, Some(notifier))
+}
+
+fn with_notifier<T>(
+    capacity: usize,
+    notifier: Option<Notifier>,
+) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(VecDeque::new()),
+        capacity,
+        dropped: AtomicU64::new(0),
+        senders: AtomicUsize::new(1),
+        receiver_alive: AtomicBool::new(true),
+        notify: Notify::new(),
+        notifier,
+    });
+    (
+        Sender {
+            shared: Arc::clone(&shared),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Depth and overflow counters of a channel, for display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub len: usize,
+    pub capacity: usize,
+    pub dropped: u64,
+}
+
+struct Shared<T> {
+    queue: Mutex<VecDeque<T>>,
+    capacity: usize,
+    dropped: AtomicU64,
+    senders: AtomicUsize,
+    receiver_alive: AtomicBool,
+    notify: Notify,
+    notifier: Option<Notifier>,
+}
+
+impl<T> Shared<T> {
+    fn stats(&self) -> ChannelStats {
+        ChannelStats {
+            len: self.queue.lock().unwrap().len(),
+            capacity: self.capacity,
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Gives the item back if the receiver is gone.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        if !self.shared.receiver_alive.load(Ordering::Acquire) {
+            return Err(item);
+        }
+        {
+            let mut queue = self.shared.queue.lock().unwrap();
+            if queue.len() == self.shared.capacity {
+                queue.pop_front();
+                self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            queue.push_back(item);
+        }
+        self.shared.notify.notify_one();
+        if let Some(ref notifier) = self.shared.notifier {
+            notifier.notify();
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.shared.stats()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.notify.notify_one();
+        }
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    pub fn try_recv(&self) -> Option<T> {
+        self.shared.queue.lock().unwrap().pop_front()
+    }
+
+    /// Takes up to `max` items at once, oldest first.
+    pub fn drain(&self, max: usize) -> Vec<T> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        let len = queue.len().min(max);
+        queue.drain(..len).collect()
+    }
+
+    /// Waits for the next item, `None` once every sender is gone and the
+    /// queue is drained.
+    pub async fn recv(&self) -> Option<T> {
+        loop {
+            if let Some(item) = self.try_recv() {
+                return Some(item);
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                return self.try_recv();
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.shared.stats()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_alive.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    /// Wall-clock time, for deadlines that must keep their meaning across
+    /// restarts.
+    fn now_utc(&self) -> DateTime<Utc>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when [`ManualClock::advance`] is called, so the
+/// pipeline can be driven deterministically.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    start_utc: DateTime<Utc>,
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::starting_at(Utc::now())
+    }
+
+    /// Starts the wall clock at `start_utc`, e.g. to simulate a restart
+    /// some time after a snapshot was taken.
+    pub fn starting_at(start_utc: DateTime<Utc>) -> Self {
+        Self {
+            start: Instant::now(),
+            start_utc,
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock().unwrap() += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.offset.lock().unwrap()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.start_utc + *self.offset.lock().unwrap()
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::{Message, MessageKind};
+
+/// Frame sent to overlays in place of a repeat of a recently forwarded
+/// text, e.g. a chant. The overlay shows it as `text ×count`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboUpdate {
+    /// Always `combo`, tells it apart from message envelopes.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: String,
+    /// Times the text has been forwarded in this combo, including the
+    /// first one sent as a plain message.
+    pub count: u32,
+}
+
+impl ComboUpdate {
+    pub fn new(text: impl Into<String>, count: u32) -> Self {
+        Self {
+            kind: "combo".to_owned(),
+            text: text.into(),
+            count,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Combo {
+    count: u32,
+    last_at: Instant,
+}
+
+/// Counts repeats of the same chat text. A combo lasts as long as each
+/// repeat comes within the window of the previous one.
+#[derive(Debug, Default)]
+pub struct ComboCounter {
+    combos: HashMap<String, Combo>,
+}
+
+impl ComboCounter {
+    /// Records `msg` as forwarded at `now` and returns its place in the
+    /// running combo, 1 if it starts a new one. Only plain chat messages
+    /// are counted, everything else is always 1. A zero `window` turns
+    /// combos off.
+    pub fn count(
+        &mut self,
+        msg: &Message,
+        now: Instant,
+        window: Duration,
+    ) -> u32 {
+        self.combos.retain(|_, it| {
+            now.saturating_duration_since(it.last_at) <= window
+        });
+        if window.is_zero()
+            || msg.kind != MessageKind::Chat
+            || msg.has_image()
+            || msg.text.trim().is_empty()
+        {
+            return 1;
+        }
+
+        let combo =
+            self.combos.entry(msg.text.clone()).or_insert(Combo {
+                count: 0,
+                last_at: now,
+            });
+        combo.count += 1;
+        combo.last_at = now;
+        combo.count
+    }
+}
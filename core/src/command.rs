@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    message::{Message, MessageKind},
+    network::Action,
+};
+
+/// What a chat command does, in place of entering the queue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum CommandHandler {
+    /// Drops it and that's it.
+    #[default]
+    Ignore,
+    /// Queues a canned message, `{user}` in it replaced by the sender.
+    Respond(String),
+    Action(Action),
+}
+
+impl CommandHandler {
+    /// One of each kind, with nothing set.
+    pub const KINDS: [CommandHandler; 3] = [
+        CommandHandler::Ignore,
+        CommandHandler::Respond(String::new()),
+        CommandHandler::Action(Action::TogglePause),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommandHandler::Ignore => "Ignore",
+            CommandHandler::Respond(_) => "Respond",
+            CommandHandler::Action(_) => "Run action",
+        }
+    }
+
+    pub fn same_kind(&self, other: &CommandHandler) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// A `!name` command typed in chat.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatCommand {
+    /// Without the `!`, matched in any case.
+    pub name: String,
+    pub handler: CommandHandler,
+    /// Comma separated usernames allowed to run it. Anyone if empty,
+    /// but nobody for an action.
+    pub users: String,
+    /// Least time between two responses, 0 for none.
+    pub cooldown_secs: f64,
+}
+
+impl ChatCommand {
+    pub fn allows(&self, username: Option<&str>) -> bool {
+        let mut users = self
+            .users
+            .split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .peekable();
+        if users.peek().is_none() {
+            return !matches!(self.handler, CommandHandler::Action(_));
+        }
+        username.is_some_and(|username| users.any(|it| it == username))
+    }
+
+    /// Lowercase, without the `!`.
+    fn key(&self) -> String {
+        self.name.trim().trim_start_matches('!').to_lowercase()
+    }
+
+    /// The message queued for `msg` by a [`CommandHandler::Respond`].
+    pub fn response(&self, msg: &Message) -> Option<Message> {
+        let CommandHandler::Respond(ref text) = self.handler else {
+            return None;
+        };
+        let user = msg.username.as_deref().unwrap_or_default();
+        Some(Message::chat(text.replace("{user}", user)))
+    }
+}
+
+/// Where [`ChatCommands::route`] sends a message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Routed<'a> {
+    /// Not a command, or one left for the queue.
+    Queue,
+    /// A command not run, for the sender isn't allowed to or it's
+    /// unknown.
+    Drop,
+    Run(&'a ChatCommand),
+}
+
+/// Chat messages starting with `!` handled as commands instead of
+/// queued.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatCommands {
+    pub enabled: bool,
+    pub commands: Vec<ChatCommand>,
+    /// Also drops commands not in `commands`, queued otherwise.
+    pub drop_unknown: bool,
+}
+
+impl ChatCommands {
+    pub fn route(&self, msg: &Message) -> Routed<'_> {
+        if !self.enabled || msg.kind != MessageKind::Chat {
+            return Routed::Queue;
+        }
+        let Some(name) = msg
+            .text
+            .trim()
+            .strip_prefix('!')
+            .and_then(|it| it.split_whitespace().next())
+        else {
+            return Routed::Queue;
+        };
+        let name = name.to_lowercase();
+        let command = self.commands.iter().find(|it| it.key() == name);
+        match command {
+            Some(command) if command.allows(msg.username.as_deref()) => {
+                Routed::Run(command)
+            }
+            Some(_) => Routed::Drop,
+            None if self.drop_unknown => Routed::Drop,
+            None => Routed::Queue,
+        }
+    }
+}
+
+/// When each command last responded, for
+/// [`ChatCommand::cooldown_secs`].
+#[derive(Debug, Default)]
+pub struct CommandCooldowns {
+    responded_at: HashMap<String, Instant>,
+}
+
+impl CommandCooldowns {
+    /// Whether `command` may respond at `now`, which it's then taken to
+    /// have.
+    pub fn respond(
+        &mut self,
+        command: &ChatCommand,
+        now: Instant,
+    ) -> bool {
+        let cooldown =
+            Duration::from_secs_f64(command.cooldown_secs.max(0.0));
+        let key = command.key();
+        if let Some(at) = self.responded_at.get(&key) {
+            if now.saturating_duration_since(*at) < cooldown {
+                return false;
+            }
+        }
+        self.responded_at.insert(key, now);
+        true
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+pub use self::{
+    scenario::Scenario,
+    stress::{Stress, StressConfig},
+};
+
+use anyhow::Context;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use rand::{
+    distributions::WeightedIndex, prelude::Distribution, rngs::StdRng,
+    Rng, SeedableRng,
+};
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::message::Message;
+
+mod scenario;
+mod stress;
+
+pub struct DemoSource {
+    last_time: Instant,
+    rng: StdRng,
+
+    path: PathBuf,
+    demo_data: Option<DemoData>,
+    watcher: Option<DemoWatcher>,
+
+    stress: Option<(Stress, Instant)>,
+}
+
+struct DemoWatcher {
+    _watcher: RecommendedWatcher,
+    event_rx: mpsc::Receiver<notify::Result<notify::Event>>,
+}
+
+enum DemoData {
+    Weighted {
+        messages: Vec<Message>,
+        weights: WeightedIndex<f64>,
+    },
+    /// Loaded from a `.scenario` file, played back from `start`.
+    Scenario {
+        scenario: Box<Scenario>,
+        start: Instant,
+    },
+}
+
+/// One entry of a `.json` (array) or `.jsonl` demo file, e.g.
+/// `{"username": "a", "type": "gift", "text": "...", "weight": 2}`.
+#[derive(Deserialize)]
+struct DemoEntry {
+    #[serde(flatten)]
+    message: Message,
+    #[serde(default = "default_weight")]
+    weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl Default for DemoSource {
+    fn default() -> Self {
+        Self::new(default_path())
+    }
+}
+
+impl DemoSource {
+    pub fn new(path: PathBuf) -> Self {
+        let demo_data = load_demo_data(&path);
+        let watcher = watch_demo_file(&path);
+
+        Self {
+            last_time: Instant::now(),
+            rng: StdRng::from_entropy(),
+
+            path,
+            demo_data,
+            watcher,
+
+            stress: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Switches to another demo file, reloading the data if the path
+    /// differs from the current one.
+    pub fn set_path(&mut self, path: PathBuf) {
+        if path != self.path {
+            self.watcher = watch_demo_file(&path);
+            self.path = path;
+            self.reload();
+        }
+    }
+
+    pub fn reload(&mut self) {
+        self.demo_data = load_demo_data(&self.path);
+    }
+
+    /// Reloads the demo file if it changed on disk since the last call.
+    /// Returns whether it was reloaded.
+    pub fn poll_changes(&mut self) -> bool {
+        let Some(ref watcher) = self.watcher else {
+            return false;
+        };
+
+        let mut changed = false;
+        while let Ok(event) = watcher.event_rx.try_recv() {
+            match event {
+                Ok(event) => {
+                    changed |= !event.kind.is_access()
+                        && event.paths.iter().any(|it| {
+                            it.file_name() == self.path.file_name()
+                        });
+                }
+                Err(err) => debug!("demo file watcher error: {err:?}"),
+            }
+        }
+
+        if changed {
+            info!("demo file changed, reloading");
+            self.reload();
+        }
+        changed
+    }
+
+    /// Number of entries (or scenario events) loaded from the demo file,
+    /// `None` if the built-in messages are used instead.
+    pub fn data_len(&self) -> Option<usize> {
+        match self.demo_data {
+            Some(DemoData::Weighted { ref messages, .. }) => {
+                Some(messages.len())
+            }
+            Some(DemoData::Scenario { ref scenario, .. }) => {
+                Some(scenario.len())
+            }
+            None => None,
+        }
+    }
+
+    /// The scenario being played and the time since it started, if the
+    /// demo file is a scenario.
+    pub fn scenario(&self) -> Option<(&Scenario, Duration)> {
+        match self.demo_data {
+            Some(DemoData::Scenario {
+                ref scenario,
+                start,
+            }) => Some((scenario.as_ref(), start.elapsed())),
+            _ => None,
+        }
+    }
+
+    /// Plays the scenario from the beginning again.
+    pub fn restart_scenario(&mut self) {
+        if let Some(DemoData::Scenario {
+            ref mut scenario,
+            ref mut start,
+        }) = self.demo_data
+        {
+            scenario.restart();
+            *start = Instant::now();
+        }
+    }
+
+    /// Starts a stress run, which takes over from the demo data until it
+    /// finishes or is stopped.
+    pub fn start_stress(&mut self, config: StressConfig) {
+        info!("starting stress test, {} messages", config.total());
+        self.stress = Some((Stress::new(config), Instant::now()));
+    }
+
+    pub fn stop_stress(&mut self) {
+        self.stress = None;
+    }
+
+    /// The running stress test and the time since it started.
+    pub fn stress(&self) -> Option<(&Stress, Duration)> {
+        self.stress
+            .as_ref()
+            .map(|(stress, start)| (stress, start.elapsed()))
+    }
+
+    /// Returns the next demo message if one is due. Call repeatedly until
+    /// `None`: scenario bursts yield many messages at once.
+    ///
+    /// `interval_secs` is ignored when playing a scenario or running a
+    /// stress test.
+    pub fn pull_demo_msg(
+        &mut self,
+        interval_secs: f64,
+    ) -> Option<Message> {
+        if let Some((ref mut stress, start)) = self.stress {
+            let msg = stress.pull(start.elapsed(), &mut self.rng);
+            if stress.is_finished() {
+                info!(
+                    "stress test finished, {} messages in {:.1}s",
+                    stress.sent(),
+                    start.elapsed().as_secs_f64()
+                );
+                self.stress = None;
+            }
+            return msg;
+        }
+
+        if let Some(DemoData::Scenario {
+            ref mut scenario,
+            start,
+        }) = self.demo_data
+        {
+            return scenario.pull(start.elapsed());
+        }
+
+        if self.last_time.elapsed().as_secs_f64() >= interval_secs {
+            self.last_time = Instant::now();
+            if let Some(DemoData::Weighted {
+                ref messages,
+                ref weights,
+            }) = self.demo_data
+            {
+                let idx = weights.sample(&mut self.rng);
+                Some(messages[idx].clone())
+            } else {
+                let idx = self.rng.gen_range(0..MSGS.len());
+                Some(Message::chat(MSGS[idx]))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// `demo.txt` in the current working directory.
+pub fn default_path() -> PathBuf {
+    current_dir().unwrap_or_default().join("demo.txt")
+}
+
+/// Watches the directory rather than the file itself, so the watch
+/// survives editors that save by replacing the file.
+fn watch_demo_file(path: &Path) -> Option<DemoWatcher> {
+    let watch = || {
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(event_tx)
+            .context("failed to create watcher")?;
+        let dir = path.parent().context("demo file has no parent")?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| {
+                format!("failed to watch {}", dir.display())
+            })?;
+
+        anyhow::Result::<_>::Ok(DemoWatcher {
+            _watcher: watcher,
+            event_rx,
+        })
+    };
+
+    match watch() {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            debug!("{err:?}");
+            None
+        }
+    }
+}
+
+fn load_demo_data(path: &Path) -> Option<DemoData> {
+    let get_demo_data = || {
+        let data = std::fs::read_to_string(path)?;
+
+        let extension = path.extension().and_then(|it| it.to_str());
+        if extension == Some("scenario") {
+            let scenario = Scenario::parse(&data)
+                .context("failed to parse scenario")?;
+            if scenario.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some(DemoData::Scenario {
+                scenario: Box::new(scenario),
+                start: Instant::now(),
+            }));
+        }
+
+        let entries = match extension {
+            Some("json") => serde_json::from_str::<Vec<DemoEntry>>(&data)
+                .context("failed to parse json demo file")?,
+            Some("jsonl") => data
+                .lines()
+                .filter(|it| !it.trim().is_empty())
+                .enumerate()
+                .map(|(idx, line)| {
+                    serde_json::from_str::<DemoEntry>(line).with_context(
+                        || format!("failed to parse line {}", idx + 1),
+                    )
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            _ => data
+                .lines()
+                .map(|it| DemoEntry {
+                    message: Message::chat(it),
+                    weight: default_weight(),
+                })
+                .collect(),
+        };
+        if entries.is_empty() {
+            return anyhow::Result::<_>::Ok(None);
+        }
+
+        let weights =
+            WeightedIndex::new(entries.iter().map(|it| it.weight))
+                .context("invalid weights")?;
+        Ok(Some(DemoData::Weighted {
+            messages: entries.into_iter().map(|it| it.message).collect(),
+            weights,
+        }))
+    };
+
+    match get_demo_data().with_context(|| {
+        format!("failed to read demo file {}", path.display())
+    }) {
+        Ok(demo_data) => demo_data,
+        Err(err) => {
+            debug!("{err:?}");
+            None
+        }
+    }
+}
+
+const MSGS: &[&str] = &[
+    "兰茶荼",
+    "兰萨卡",
+    "兰非拉",
+    "兰拉迦",
+    "兰拉娜",
+    "兰拉吉",
+    "兰加惟",
+    "兰多摩",
+    "兰伊舍",
+    "兰纳迦",
+    "兰利遮",
+    "兰纳真",
+    "兰迦鲁",
+    "兰般度",
+    "兰伽卢",
+    "兰贡迪",
+    "兰犍多",
+    "兰难世",
+    "兰梨娄",
+    "兰玛尼",
+    "兰陀娑",
+    "兰耶娑",
+    "兰雅玛",
+    "兰玛哈",
+    "兰帝裟",
+    "兰钵答",
+    "兰随尼",
+    "兰羯磨",
+    "兰耶师",
+    "兰宁巴",
+    "兰沙恭",
+    "兰提沙",
+    "兰阐荼",
+    "兰沙陀",
+    "兰沙诃",
+    "兰耶多",
+    "兰耆都",
+    "兰卑浮",
+    "兰阿帕斯",
+    "兰帕卡提",
+    "兰百梨迦",
+    "兰多希陀",
+    "兰修提袈",
+    "兰弥纳离",
+    "兰陀尼什",
+    "兰穆护昆达",
+    "冒失的兰那罗",
+    "收集材料的兰那罗",
+    "爱音乐的兰那罗",
+    "迷茫的兰那罗",
+    "淘气的兰那罗",
+    "兰宵宫",
+];
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::MSGS;
+use crate::message::{Message, MessageKind};
+
+/// Timed demo events, parsed from lines like `+0.5s: message`,
+/// `+3s: burst of 50`, `+10s: superchat @someone thanks` or
+/// `+20s: follow @someone`.
+///
+/// Each offset is relative to the previous event. Bursts and events
+/// without text use built-in messages picked by a fixed-seed rng, so
+/// every playback is identical.
+pub struct Scenario {
+    events: Vec<ScenarioEvent>,
+
+    next: usize,
+    burst_left: usize,
+    rng: StdRng,
+}
+
+struct ScenarioEvent {
+    at: Duration,
+    action: ScenarioAction,
+}
+
+enum ScenarioAction {
+    Message(Message),
+    Burst(usize),
+}
+
+impl Scenario {
+    pub fn parse(src: &str) -> anyhow::Result<Self> {
+        let mut events = vec![];
+        let mut at = Duration::ZERO;
+
+        for (idx, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let event = parse_event(line, &mut at)
+                .with_context(|| format!("invalid line {}", idx + 1))?;
+            events.push(event);
+        }
+
+        Ok(Self {
+            events,
+
+            next: 0,
+            burst_left: 0,
+            rng: StdRng::seed_from_u64(0),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Number of events already played.
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Time of the next event since playback started.
+    pub fn next_at(&self) -> Option<Duration> {
+        self.events.get(self.next).map(|it| it.at)
+    }
+
+    pub fn restart(&mut self) {
+        self.next = 0;
+        self.burst_left = 0;
+        self.rng = StdRng::seed_from_u64(0);
+    }
+
+    /// Returns the next message due `elapsed` after playback started, one
+    /// at a time.
+    pub fn pull(&mut self, elapsed: Duration) -> Option<Message> {
+        if self.burst_left > 0 {
+            self.burst_left -= 1;
+            return Some(Message::chat(self.random_text()));
+        }
+
+        let event = self.events.get(self.next)?;
+        if event.at > elapsed {
+            return None;
+        }
+        self.next += 1;
+
+        match event.action {
+            ScenarioAction::Message(ref msg) => {
+                let mut msg = msg.clone();
+                if msg.text.is_empty() {
+                    msg.text = self.random_text();
+                }
+                Some(msg)
+            }
+            ScenarioAction::Burst(count) => {
+                self.burst_left = count;
+                self.pull(elapsed)
+            }
+        }
+    }
+
+    fn random_text(&mut self) -> String {
+        MSGS[self.rng.gen_range(0..MSGS.len())].to_owned()
+    }
+}
+
+fn parse_event(
+    line: &str,
+    at: &mut Duration,
+) -> anyhow::Result<ScenarioEvent> {
+    let Some((offset, action)) = line.split_once(':') else {
+        bail!("expected `+<secs>s: <action>`");
+    };
+    let offset = offset
+        .trim()
+        .strip_prefix('+')
+        .and_then(|it| it.strip_suffix('s'))
+        .context("offset should look like `+1.5s`")?;
+    let offset = offset
+        .parse::<f64>()
+        .ok()
+        .and_then(|it| Duration::try_from_secs_f64(it).ok())
+        .with_context(|| format!("invalid offset `{offset}`"))?;
+    *at += offset;
+
+    Ok(ScenarioEvent {
+        at: *at,
+        action: parse_action(action.trim())?,
+    })
+}
+
+fn parse_action(action: &str) -> anyhow::Result<ScenarioAction> {
+    if let Some(rest) = strip_keyword(action, "burst") {
+        let count = strip_keyword(rest, "of").unwrap_or(rest);
+        let count = count
+            .parse()
+            .with_context(|| format!("invalid burst size `{count}`"))?;
+        return Ok(ScenarioAction::Burst(count));
+    }
+
+    let (kind, rest) = MessageKind::ALL
+        .into_iter()
+        .find_map(|kind| {
+            strip_keyword(action, &kind.name().to_lowercase())
+                .map(|rest| (kind, rest))
+        })
+        .unwrap_or((MessageKind::Chat, action));
+
+    let (username, text) = match rest.strip_prefix('@') {
+        Some(rest) => {
+            let (username, text) = rest
+                .split_once(char::is_whitespace)
+                .unwrap_or((rest, ""));
+            (Some(username.to_owned()), text.trim())
+        }
+        None => (None, rest),
+    };
+
+    Ok(ScenarioAction::Message(Message {
+        kind,
+        username,
+        text: text.to_owned(),
+        attachments: vec![],
+        gift: None,
+        paid: None,
+        event: None,
+    }))
+}
+
+/// `Some(rest)` if `s` is `keyword` alone or followed by whitespace.
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = s.strip_prefix(keyword)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::MSGS;
+use crate::message::Message;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressConfig {
+    /// Messages per second.
+    pub rate: f64,
+    /// Message length in chars, picked uniformly from
+    /// `min_len..=max_len`.
+    pub min_len: usize,
+    pub max_len: usize,
+    pub duration_secs: f64,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            rate: 100.0,
+            min_len: 2,
+            max_len: 20,
+            duration_secs: 10.0,
+        }
+    }
+}
+
+impl StressConfig {
+    pub fn total(&self) -> u64 {
+        (self.rate * self.duration_secs).floor() as u64
+    }
+}
+
+/// Generates `config.rate` messages per second of random built-in
+/// characters until `config.duration_secs` has passed.
+pub struct Stress {
+    config: StressConfig,
+    sent: u64,
+}
+
+impl Stress {
+    pub fn new(config: StressConfig) -> Self {
+        Self { config, sent: 0 }
+    }
+
+    pub fn config(&self) -> &StressConfig {
+        &self.config
+    }
+
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.sent >= self.config.total()
+    }
+
+    /// Returns the next message due `elapsed` after the run started, one
+    /// at a time, catching up with the rate if frames were slow.
+    pub fn pull(
+        &mut self,
+        elapsed: Duration,
+        rng: &mut impl Rng,
+    ) -> Option<Message> {
+        let elapsed =
+            elapsed.as_secs_f64().min(self.config.duration_secs);
+        let due = (elapsed * self.config.rate).floor() as u64;
+        if self.sent >= due {
+            return None;
+        }
+        self.sent += 1;
+
+        let min_len = self.config.min_len.max(1);
+        let len =
+            rng.gen_range(min_len..=self.config.max_len.max(min_len));
+        let text = std::iter::repeat_with(|| {
+            let msg = MSGS[rng.gen_range(0..MSGS.len())];
+            msg.chars().collect::<Vec<_>>()
+        })
+        .flatten()
+        .take(len)
+        .collect::<String>();
+        Some(Message::chat(text))
+    }
+}
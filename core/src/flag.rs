@@ -0,0 +1,200 @@
+use std::{
+    borrow::Cow,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use self::word_list::{WordList, WordListSource};
+use crate::{message::Message, Notifier};
+
+pub mod word_list;
+
+/// Notifications waiting for a click at once, later flagged messages
+/// are only shown in the queue.
+const MAX_OPEN: usize = 5;
+
+/// Words that flag a message for a closer look as it's queued, matched
+/// case-insensitively against its text and sender.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct FlagWords {
+    pub words: Vec<String>,
+    /// Imported on top of `words`, only enabled ones count.
+    pub lists: Vec<WordList>,
+    /// Shows a desktop notification for each flagged message.
+    pub notify: bool,
+}
+
+impl FlagWords {
+    /// The first word `msg` is flagged for, if any.
+    pub fn matches(&self, msg: &Message) -> Option<&str> {
+        self.matching(msg).next().map(|it| it.word)
+    }
+
+    /// Every word `msg` is flagged for, own words first.
+    pub fn matching<'a>(
+        &'a self,
+        msg: &Message,
+    ) -> impl Iterator<Item = FlagMatch<'a>> {
+        let text = msg.text.to_lowercase();
+        let username = msg.username.as_deref().map(str::to_lowercase);
+        let own = self
+            .words
+            .iter()
+            .map(|it| it.trim())
+            .filter(|it| !it.is_empty())
+            .map(|word| (word, Cow::Owned(word.to_lowercase()), None));
+        // already lowercase
+        let listed =
+            self.lists.iter().filter(|it| it.enabled).flat_map(|list| {
+                list.words.iter().map(move |word| {
+                    (&**word, Cow::from(word), Some(list))
+                })
+            });
+        own.chain(listed).filter_map(move |(word, lower, list)| {
+            let found = text.contains(&*lower)
+                || username
+                    .as_ref()
+                    .is_some_and(|it| it.contains(&*lower));
+            found.then_some(FlagMatch {
+                word,
+                list: list.map(|it| &it.source),
+            })
+        })
+    }
+}
+
+/// A word a message is flagged for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagMatch<'a> {
+    pub word: &'a str,
+    /// The list it's from, `None` for [`FlagWords::words`].
+    pub list: Option<&'a WordListSource>,
+}
+
+/// Clicked on a flagged message's notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagAction {
+    Delete,
+    /// Forwards it right away.
+    Approve,
+}
+
+impl FlagAction {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "delete" => Some(FlagAction::Delete),
+            "approve" => Some(FlagAction::Approve),
+            _ => None,
+        }
+    }
+}
+
+/// Shows flagged messages as desktop notifications, each on a thread of
+/// its own as it waits for a click. Only notifications through
+/// `notify-send` carry the Delete and Approve buttons, see
+/// [`Self::HAS_ACTIONS`].
+pub struct FlagNotifier {
+    action_tx: mpsc::Sender<(Message, FlagAction)>,
+    action_rx: mpsc::Receiver<(Message, FlagAction)>,
+    open: Arc<AtomicUsize>,
+    notifier: Notifier,
+}
+
+impl FlagNotifier {
+    /// Whether notifications carry the Delete and Approve buttons here.
+    /// Windows toasts and macOS notifications just tell for now, their
+    /// actions need the app registered with the system first.
+    pub const HAS_ACTIONS: bool =
+        cfg!(not(any(windows, target_os = "macos")));
+
+    /// `notifier` is called on every click.
+    pub fn new(notifier: Notifier) -> Self {
+        let (action_tx, action_rx) = mpsc::channel();
+        Self {
+            action_tx,
+            action_rx,
+            open: Arc::new(AtomicUsize::new(0)),
+            notifier,
+        }
+    }
+
+    pub fn notify(&self, msg: Message, word: &str) {
+        if self.open.fetch_add(1, Ordering::Relaxed) >= MAX_OPEN {
+            self.open.fetch_sub(1, Ordering::Relaxed);
+            debug!("too many flag notifications open, skipping");
+            return;
+        }
+        let title = format!("Flagged for \"{word}\"");
+        let body = match msg.username {
+            Some(ref username) => format!("{username}: {}", msg.text),
+            None => msg.text.clone(),
+        };
+        let action_tx = self.action_tx.clone();
+        let open = Arc::clone(&self.open);
+        let notifier = self.notifier.clone();
+        thread::spawn(move || {
+            match wait_click(&title, &body) {
+                Ok(Some(action)) => {
+                    let _ = action_tx.send((msg, action));
+                    notifier.notify();
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    warn!("failed to notify flagged message: {err:?}")
+                }
+            }
+            open.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Clicks since the last call, with the message clicked on.
+    pub fn pull(&self) -> Vec<(Message, FlagAction)> {
+        self.action_rx.try_iter().collect()
+    }
+}
+
+/// Shows the notification and waits until it's clicked or closed.
+fn wait_click(
+    title: &str,
+    body: &str,
+) -> anyhow::Result<Option<FlagAction>> {
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let mut command = {
+        let mut command = std::process::Command::new("notify-send");
+        command.args([
+            "--app-name=Blooming Light",
+            "--expire-time=30000",
+            "--wait",
+            "--action=delete=Delete",
+            "--action=approve=Approve",
+            "--",
+            title,
+            body,
+        ]);
+        command
+    };
+    #[cfg(any(windows, target_os = "macos"))]
+    let mut command = crate::alert::notify_command(title, body);
+
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("failed to run command")?;
+    if !output.status.success() {
+        bail!("command exited with {}", output.status);
+    }
+    let key = String::from_utf8_lossy(&output.stdout);
+    Ok(FlagAction::from_key(key.trim()))
+}
@@ -0,0 +1,168 @@
+use std::{fmt, fs, path::PathBuf};
+
+use anyhow::Context;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::network::{fetch, proxy::ProxyConfig};
+
+/// First cells of a CSV header row rather than a word.
+const CSV_HEADERS: [&str; 5] =
+    ["word", "words", "term", "phrase", "keyword"];
+
+/// Where a word list is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WordListSource {
+    File(PathBuf),
+    Url(String),
+}
+
+impl WordListSource {
+    /// A URL if it starts with `http://` or `https://`, a path otherwise.
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        if s.starts_with("http://") || s.starts_with("https://") {
+            WordListSource::Url(s.to_owned())
+        } else {
+            WordListSource::File(PathBuf::from(s))
+        }
+    }
+
+    /// The file name, last segment of the URL's path included.
+    pub fn name(&self) -> String {
+        match self {
+            WordListSource::File(path) => path
+                .file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned(),
+            WordListSource::Url(url) => url
+                .split(['?', '#'])
+                .next()
+                .and_then(|it| {
+                    it.trim_end_matches('/').rsplit('/').next()
+                })
+                .unwrap_or(url)
+                .to_owned(),
+        }
+    }
+
+    fn is_csv(&self) -> bool {
+        self.name().to_ascii_lowercase().ends_with(".csv")
+    }
+
+    /// Its words, as [`parse_words`] reads them.
+    pub async fn load(
+        &self,
+        proxy: &ProxyConfig,
+    ) -> anyhow::Result<Vec<String>> {
+        let data = match self {
+            WordListSource::File(path) => {
+                fs::read(path).with_context(|| {
+                    format!("failed to read {}", path.display())
+                })?
+            }
+            WordListSource::Url(url) => {
+                fetch::fetch(url, "text/plain, text/csv, */*", proxy)
+                    .await?
+                    .bytes
+                    .to_vec()
+            }
+        };
+        Ok(parse_words(&String::from_utf8_lossy(&data), self.is_csv()))
+    }
+
+    /// [`Self::load`] on a throwaway runtime, for callers outside of
+    /// tokio.
+    pub fn load_blocking(
+        &self,
+        proxy: &ProxyConfig,
+    ) -> anyhow::Result<Vec<String>> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("failed to build tokio runtime")?
+            .block_on(self.load(proxy))
+    }
+}
+
+impl fmt::Display for WordListSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordListSource::File(path) => path.display().fmt(f),
+            WordListSource::Url(url) => f.write_str(url),
+        }
+    }
+}
+
+/// Flag words kept in a file or at a URL, e.g. a shared blocklist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordList {
+    pub source: WordListSource,
+    pub enabled: bool,
+    /// Minutes between syncs, 0 to only sync by hand.
+    pub resync_mins: u32,
+    /// As of the last sync, lowercase, so the list works offline.
+    pub words: Vec<String>,
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+impl WordList {
+    /// Enabled and not synced yet.
+    pub fn new(source: WordListSource) -> Self {
+        Self {
+            source,
+            enabled: true,
+            resync_mins: 60,
+            words: vec![],
+            synced_at: None,
+        }
+    }
+
+    /// Whether it's time for its periodic sync at `now`.
+    pub fn resync_due(&self, now: DateTime<Utc>) -> bool {
+        if !self.enabled || self.resync_mins == 0 {
+            return false;
+        }
+        self.synced_at.is_none_or(|it| {
+            now - it >= TimeDelta::minutes(self.resync_mins.into())
+        })
+    }
+}
+
+/// Words of a list, lowercase. Plain text has one per line, with `#`
+/// comments and blank lines skipped. CSV takes the first cell of each
+/// row, quoted or not, skipping a header.
+pub fn parse_words(data: &str, csv: bool) -> Vec<String> {
+    let mut words = data
+        .lines()
+        .map(str::trim)
+        .filter(|it| !it.is_empty() && !it.starts_with('#'))
+        .map(|it| if csv { first_cell(it) } else { it.to_owned() })
+        .map(|it| it.trim().to_lowercase())
+        .filter(|it| !it.is_empty())
+        .collect::<Vec<_>>();
+    if csv && words.first().is_some_and(|it| CSV_HEADERS.contains(&&**it))
+    {
+        words.remove(0);
+    }
+    words
+}
+
+fn first_cell(row: &str) -> String {
+    let Some(quoted) = row.strip_prefix('"') else {
+        return row.split(',').next().unwrap_or_default().to_owned();
+    };
+    let mut cell = String::new();
+    let mut chars = quoted.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char == '"' {
+            // doubled to escape
+            if chars.next_if_eq(&'"').is_none() {
+                break;
+            }
+        }
+        cell.push(char);
+    }
+    cell
+}
@@ -0,0 +1,158 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// How many messages a user can send within a window before the rest
+/// are collapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloodLimit {
+    /// 0 for no limit.
+    pub max_messages: usize,
+    pub window_secs: f64,
+}
+
+impl Default for FloodLimit {
+    fn default() -> Self {
+        Self {
+            max_messages: 0,
+            window_secs: 10.0,
+        }
+    }
+}
+
+impl FloodLimit {
+    fn window(&self) -> Duration {
+        Duration::from_secs_f64(self.window_secs.max(0.0))
+    }
+
+    fn is_off(&self) -> bool {
+        self.max_messages == 0 || self.window_secs <= 0.0
+    }
+}
+
+/// Messages from one user over their limit, queued as one message.
+#[derive(Debug)]
+pub struct FloodSummary {
+    /// "...and 12 more from X".
+    pub msg: Message,
+    /// When the first collapsed message was received.
+    pub received_at: Instant,
+    /// Every message collapsed, oldest first.
+    pub messages: Vec<Message>,
+}
+
+impl FloodSummary {
+    fn new(username: &str, msg: Message, received_at: Instant) -> Self {
+        let mut summary = Self {
+            msg: Message {
+                username: Some(username.to_owned()),
+                ..Message::chat("")
+            },
+            received_at,
+            messages: vec![],
+        };
+        summary.add(msg);
+        summary
+    }
+
+    fn add(&mut self, msg: Message) {
+        self.messages.push(msg);
+        self.msg.text = format!(
+            "…and {} more from {}",
+            self.messages.len(),
+            self.msg.username.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+/// Lets a user's messages through up to a [`FloodLimit`], and holds the
+/// rest back for a window to be queued as a single summary instead of
+/// taking over the list.
+#[derive(Debug, Default)]
+pub struct FloodCollapser {
+    /// When each user's messages let through were received, oldest
+    /// first.
+    recent: HashMap<String, VecDeque<Instant>>,
+    pending: Vec<FloodSummary>,
+}
+
+impl FloodCollapser {
+    /// Takes in `msg` to collapse if its sender is over `limit`, or hands
+    /// it back.
+    pub fn push(
+        &mut self,
+        msg: Message,
+        received_at: Instant,
+        limit: FloodLimit,
+    ) -> Option<Message> {
+        let Some(username) = msg.username.clone() else {
+            return Some(msg);
+        };
+        if limit.is_off() {
+            return Some(msg);
+        }
+
+        let window = limit.window();
+        let recent = self.recent.entry(username.clone()).or_default();
+        while recent.front().is_some_and(|it| {
+            received_at.saturating_duration_since(*it) >= window
+        }) {
+            recent.pop_front();
+        }
+        if recent.len() < limit.max_messages {
+            recent.push_back(received_at);
+            return Some(msg);
+        }
+
+        let summary = self
+            .pending
+            .iter_mut()
+            .find(|it| it.msg.username.as_deref() == Some(&username));
+        match summary {
+            Some(summary) => summary.add(msg),
+            None => self.pending.push(FloodSummary::new(
+                &username,
+                msg,
+                received_at,
+            )),
+        }
+        None
+    }
+
+    /// Summaries whose window has passed since their first message, in
+    /// the order those arrived.
+    pub fn take_due(
+        &mut self,
+        now: Instant,
+        limit: FloodLimit,
+    ) -> Vec<FloodSummary> {
+        let window = limit.window();
+        let (due, pending) = self.pending.drain(..).partition(|it| {
+            now.saturating_duration_since(it.received_at) >= window
+        });
+        self.pending = pending;
+        // forget users gone quiet
+        self.recent.retain(|_, recent| {
+            recent.back().is_some_and(|it| {
+                now.saturating_duration_since(*it) < window
+            })
+        });
+        due
+    }
+
+    pub fn take_all(&mut self) -> Vec<FloodSummary> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// When the oldest summary is due.
+    pub fn next_due(&self, limit: FloodLimit) -> Option<Instant> {
+        let window = limit.window();
+        self.pending.iter().map(|it| it.received_at + window).min()
+    }
+}
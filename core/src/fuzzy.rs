@@ -0,0 +1,44 @@
+/// How well `query` matches `text`, higher is better, or `None` unless
+/// every character of it shows up in `text` in order, ignoring case.
+/// Runs of matched characters and ones starting a word count for more,
+/// so `rs` ranks "Restart server" over "Redact users".
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let text = text.chars().collect::<Vec<_>>();
+    let word_starts = (0..text.len())
+        .map(|i| i == 0 || !text[i - 1].is_alphanumeric())
+        .collect::<Vec<_>>();
+    let text = text
+        .iter()
+        .map(|it| it.to_lowercase().next().unwrap_or(*it))
+        .collect::<Vec<_>>();
+
+    // best score so far with the last matched character at each index
+    // of the text, `None` for the empty query
+    let mut best = None::<Vec<Option<u32>>>;
+    for query_char in query
+        .chars()
+        .filter(|it| !it.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        let mut next = vec![None; text.len()];
+        let mut best_before = best.is_none().then_some(0);
+        for (i, &text_char) in text.iter().enumerate() {
+            if text_char == query_char {
+                let run = best
+                    .as_ref()
+                    .and_then(|it| it[i.checked_sub(1)?])
+                    .map(|it| it + 2);
+                let bonus = if word_starts[i] { 4 } else { 1 };
+                next[i] = run.max(best_before).map(|it| it + bonus);
+            }
+            if let Some(ref best) = best {
+                best_before = best_before.max(best[i]);
+            }
+        }
+        best = Some(next);
+    }
+    match best {
+        Some(best) => best.into_iter().flatten().max(),
+        None => Some(0),
+    }
+}
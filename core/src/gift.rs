@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+use crate::message::{Gift, Message, MessageKind};
+
+/// Gifts from one user, folded into one message.
+#[derive(Debug)]
+pub struct GiftSummary {
+    /// The first gift with the count and value of all of them.
+    pub msg: Message,
+    /// When the first gift was received.
+    pub received_at: Instant,
+    /// Every gift folded in, oldest first.
+    pub gifts: Vec<Message>,
+}
+
+impl GiftSummary {
+    fn add(&mut self, msg: Message) {
+        let more = gift_of(&msg);
+        let mut gift = gift_of(&self.msg);
+        gift.count += more.count;
+        gift.value += more.value;
+        self.msg.text = format!("{} x{}", gift.name, gift.count);
+        self.msg.gift = Some(gift);
+        self.gifts.push(msg);
+    }
+}
+
+/// Holds gifts back for a window after the first one from a user, so a
+/// gift storm reaches the queue as one summarized message per user and
+/// gift instead of hundreds.
+#[derive(Debug, Default)]
+pub struct GiftAggregator {
+    pending: Vec<GiftSummary>,
+}
+
+impl GiftAggregator {
+    /// Takes in a gift to summarize, or hands `msg` back if it isn't one
+    /// or `window` is zero.
+    pub fn push(
+        &mut self,
+        msg: Message,
+        received_at: Instant,
+        window: Duration,
+    ) -> Option<Message> {
+        if msg.kind != MessageKind::Gift || window.is_zero() {
+            return Some(msg);
+        }
+
+        let name = gift_of(&msg).name;
+        let summary = self.pending.iter_mut().find(|it| {
+            it.msg.username == msg.username
+                && gift_of(&it.msg).name == name
+        });
+        match summary {
+            Some(summary) => summary.add(msg),
+            None => self.pending.push(GiftSummary {
+                msg: msg.clone(),
+                received_at,
+                gifts: vec![msg],
+            }),
+        }
+        None
+    }
+
+    /// Summaries whose window has passed since their first gift, in the
+    /// order those arrived.
+    pub fn take_due(
+        &mut self,
+        now: Instant,
+        window: Duration,
+    ) -> Vec<GiftSummary> {
+        let (due, pending) = self.pending.drain(..).partition(|it| {
+            now.saturating_duration_since(it.received_at) >= window
+        });
+        self.pending = pending;
+        due
+    }
+
+    pub fn take_all(&mut self) -> Vec<GiftSummary> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// When the oldest summary is due.
+    pub fn next_due(&self, window: Duration) -> Option<Instant> {
+        self.pending.iter().map(|it| it.received_at + window).min()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// What `msg` gives, one of its text if the source didn't say.
+fn gift_of(msg: &Message) -> Gift {
+    msg.gift.clone().unwrap_or_else(|| Gift {
+        name: msg.text.clone(),
+        count: 1,
+        value: 0,
+    })
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    message::Message,
+    queue::{QueueSnapshot, SnapshotEntry},
+};
+
+/// Older entries fall off.
+const MAX_LEN: usize = 200;
+
+/// A moderation action, with what it takes to undo it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Moderation {
+    /// Taken off the queue, put back with its deadlines.
+    Delete(SnapshotEntry),
+    /// Forwarded ahead of its deadline, out before it could be undone.
+    Approve(Message),
+    /// Everything that was pending.
+    Purge(QueueSnapshot),
+}
+
+impl Moderation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Moderation::Delete(_) => "Delete",
+            Moderation::Approve(_) => "Approve",
+            Moderation::Purge(_) => "Purge",
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !matches!(self, Moderation::Approve(_))
+    }
+
+    /// Purging again would take what arrived since with it.
+    pub fn can_redo(&self) -> bool {
+        matches!(self, Moderation::Delete(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub at: DateTime<Utc>,
+    pub action: Moderation,
+    pub undone: bool,
+}
+
+/// Moderation actions, oldest first. Undo takes back the latest one
+/// that can be, skipping approvals, and redo goes back over what was
+/// undone until something new happens.
+#[derive(Debug, Default)]
+pub struct ModerationHistory {
+    entries: Vec<HistoryEntry>,
+    /// Indices into `entries` of undone ones to redo, latest last.
+    redo: Vec<usize>,
+}
+
+impl ModerationHistory {
+    pub fn push(&mut self, action: Moderation, at: DateTime<Utc>) {
+        self.redo.clear();
+        if self.entries.len() == MAX_LEN {
+            self.entries.remove(0);
+        }
+        self.entries.push(HistoryEntry {
+            at,
+            action,
+            undone: false,
+        });
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|it| !it.undone && it.action.can_undo())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Marks the latest action that can be undone as undone and returns
+    /// it to be taken back.
+    pub fn undo(&mut self) -> Option<&mut Moderation> {
+        let idx = self
+            .entries
+            .iter()
+            .rposition(|it| !it.undone && it.action.can_undo())?;
+        let entry = &mut self.entries[idx];
+        entry.undone = true;
+        if entry.action.can_redo() {
+            self.redo.push(idx);
+        }
+        Some(&mut entry.action)
+    }
+
+    /// Marks the latest undone action as done again and returns it to be
+    /// run again.
+    pub fn redo(&mut self) -> Option<&mut Moderation> {
+        let entry = &mut self.entries[self.redo.pop()?];
+        entry.undone = false;
+        Some(&mut entry.action)
+    }
+}
@@ -0,0 +1,212 @@
+use std::{fmt, str::FromStr, sync::mpsc};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::Notifier;
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// Combos the OS or most apps already act on, never taken over.
+const RESERVED: [&str; 8] = [
+    "Alt+F4", "Alt+Tab", "Ctrl+A", "Ctrl+C", "Ctrl+V", "Ctrl+X",
+    "Ctrl+Z", "Ctrl+S",
+];
+/// Opens the command palette, can't be rebound.
+pub const PALETTE: &str = "Ctrl+P";
+/// Redoes a moderation action, can't be rebound. Undo is Ctrl+Z.
+pub const REDO: &str = "Ctrl+Shift+Z";
+
+/// A key with modifiers, written like `Ctrl+Shift+P`. The key is a name
+/// as egui spells it, e.g. `P`, `F9`, `Space` or `Escape`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: String,
+}
+
+impl FromStr for Hotkey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: String::new(),
+        };
+        let mut parts = s.split('+').map(str::trim).peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                let mut chars = part.chars();
+                let first = chars.next().context("no key")?;
+                hotkey.key =
+                    first.to_uppercase().chain(chars).collect::<String>();
+                break;
+            }
+            let modifier = match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" => &mut hotkey.ctrl,
+                "shift" => &mut hotkey.shift,
+                "alt" | "option" => &mut hotkey.alt,
+                _ => bail!("unknown modifier `{part}`"),
+            };
+            if *modifier {
+                bail!("`{part}` given twice");
+            }
+            *modifier = true;
+        }
+        if !(hotkey.ctrl || hotkey.alt || is_function_key(&hotkey.key)) {
+            bail!("needs Ctrl or Alt, unless it's a function key");
+        }
+        Ok(hotkey)
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl TryFrom<String> for Hotkey {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Hotkey> for String {
+    fn from(hotkey: Hotkey) -> Self {
+        hotkey.to_string()
+    }
+}
+
+fn is_function_key(key: &str) -> bool {
+    key.strip_prefix('F')
+        .and_then(|it| it.parse::<u8>().ok())
+        .is_some_and(|it| (1..=24).contains(&it))
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum HotkeyAction {
+    /// Holds the queue until pressed again.
+    TogglePause,
+    /// Deletes every pending message, without confirming.
+    Purge,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 2] =
+        [HotkeyAction::TogglePause, HotkeyAction::Purge];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HotkeyAction::TogglePause => "Pause/Resume",
+            HotkeyAction::Purge => "Purge queue",
+        }
+    }
+}
+
+/// Which hotkey runs each action, unbound ones are left out.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct HotkeyBindings {
+    pub bindings: Vec<(HotkeyAction, Hotkey)>,
+    /// Grabbed with [`GlobalHotkeys`], rather than only seen while the
+    /// window has focus.
+    pub global: bool,
+}
+
+impl HotkeyBindings {
+    pub fn get(&self, action: HotkeyAction) -> Option<&Hotkey> {
+        self.bindings
+            .iter()
+            .find(|(it, _)| *it == action)
+            .map(|(_, hotkey)| hotkey)
+    }
+
+    pub fn set(&mut self, action: HotkeyAction, hotkey: Option<Hotkey>) {
+        self.bindings.retain(|(it, _)| *it != action);
+        if let Some(hotkey) = hotkey {
+            self.bindings.push((action, hotkey));
+        }
+    }
+
+    /// Why `hotkey` can't be bound to `action`: it's reserved or
+    /// already runs another action.
+    pub fn conflict(
+        &self,
+        action: HotkeyAction,
+        hotkey: &Hotkey,
+    ) -> Option<String> {
+        let name = hotkey.to_string();
+        if RESERVED.contains(&name.as_str()) {
+            return Some(format!("{name} is reserved by the system"));
+        }
+        if name == PALETTE {
+            return Some(format!("{name} opens the command palette"));
+        }
+        if name == REDO {
+            return Some(format!("{name} redoes moderation actions"));
+        }
+        self.bindings
+            .iter()
+            .find(|(it, bound)| *it != action && bound == hotkey)
+            .map(|(it, _)| format!("{name} already runs {}", it.name()))
+    }
+}
+
+/// Hotkeys grabbed from the whole desktop, so they're pressed whatever
+/// window has focus, read on a thread of its own until dropped. Only
+/// X11 is supported, elsewhere grabbing fails, see [`Self::SUPPORTED`].
+pub struct GlobalHotkeys {
+    action_rx: mpsc::Receiver<HotkeyAction>,
+    #[cfg(target_os = "linux")]
+    _grab: x11::Grab,
+}
+
+impl GlobalHotkeys {
+    /// Whether this platform can grab at all, X11 still has to be
+    /// running. Windows and macOS aren't supported yet.
+    pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+    /// `notifier` is called on every press.
+    pub fn grab(
+        bindings: &HotkeyBindings,
+        notifier: Notifier,
+    ) -> anyhow::Result<Self> {
+        let (action_tx, action_rx) = mpsc::channel();
+        #[cfg(target_os = "linux")]
+        return Ok(Self {
+            action_rx,
+            _grab: x11::Grab::new(bindings, action_tx, notifier)?,
+        });
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (bindings, action_tx, action_rx, notifier);
+            bail!("system-wide hotkeys are only supported on X11")
+        }
+    }
+
+    pub fn pull(&self) -> Vec<HotkeyAction> {
+        self.action_rx.try_iter().collect()
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use anyhow::Context;
+use tracing::{info, warn};
+use x11rb::{
+    connection::Connection,
+    protocol::{
+        xproto::{
+            AtomEnum, ClientMessageEvent, ConnectionExt as _,
+            CreateWindowAux, EventMask, GrabMode, Keycode, ModMask,
+            Window, WindowClass,
+        },
+        Event,
+    },
+    rust_connection::RustConnection,
+    COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT,
+};
+
+use super::{Hotkey, HotkeyAction, HotkeyBindings};
+use crate::Notifier;
+
+/// Passive grabs of the bound keys on the root window.
+pub(super) struct Grab {
+    conn: Arc<RustConnection>,
+    root: Window,
+    /// Sent a message to wake the thread up for stopping.
+    window: Window,
+    grabbed: Vec<(Keycode, ModMask)>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Grab {
+    pub(super) fn new(
+        bindings: &HotkeyBindings,
+        action_tx: mpsc::Sender<HotkeyAction>,
+        notifier: Notifier,
+    ) -> anyhow::Result<Self> {
+        let (conn, screen) = RustConnection::connect(None)
+            .context("failed to connect to the X server")?;
+        let setup = conn.setup();
+        let root = setup.roots[screen].root;
+        let (min_keycode, max_keycode) =
+            (setup.min_keycode, setup.max_keycode);
+        let window = conn.generate_id()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_ONLY,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )?
+        .check()
+        .context("failed to create hotkey window")?;
+        let mut grab = Self {
+            conn: Arc::new(conn),
+            root,
+            window,
+            grabbed: vec![],
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+
+        let mapping = grab
+            .conn
+            .get_keyboard_mapping(
+                min_keycode,
+                max_keycode - min_keycode + 1,
+            )?
+            .reply()
+            .context("failed to get keyboard mapping")?;
+        let per_keycode = usize::from(mapping.keysyms_per_keycode).max(1);
+        let mut keys = vec![];
+        for (action, hotkey) in &bindings.bindings {
+            let keysym = keysym(&hotkey.key).with_context(|| {
+                format!("{hotkey} can't be grabbed system-wide")
+            })?;
+            let idx = mapping
+                .keysyms
+                .chunks(per_keycode)
+                .position(|it| it.contains(&keysym))
+                .with_context(|| {
+                    format!("no key on the keyboard makes {hotkey}")
+                })?;
+            let keycode = min_keycode + idx as u8;
+            let modifiers = modifiers(hotkey);
+            // Caps Lock and Num Lock don't change the hotkey pressed
+            let locks = [
+                ModMask::from(0u16),
+                ModMask::LOCK,
+                ModMask::M2,
+                ModMask::LOCK | ModMask::M2,
+            ];
+            for lock in locks {
+                grab.conn
+                    .grab_key(
+                        false,
+                        root,
+                        modifiers | lock,
+                        keycode,
+                        GrabMode::ASYNC,
+                        GrabMode::ASYNC,
+                    )?
+                    .check()
+                    .with_context(|| {
+                        format!("{hotkey} is taken by another app")
+                    })?;
+                grab.grabbed.push((keycode, modifiers | lock));
+            }
+            keys.push((keycode, modifiers, *action));
+        }
+        info!("grabbed {} hotkeys system-wide", keys.len());
+
+        let conn = grab.conn.clone();
+        let stop = grab.stop.clone();
+        let relevant = ModMask::SHIFT | ModMask::CONTROL | ModMask::M1;
+        thread::spawn(move || loop {
+            let event = match conn.wait_for_event() {
+                Ok(it) => it,
+                Err(err) => {
+                    warn!("X connection for hotkeys closed: {err}");
+                    return;
+                }
+            };
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let Event::KeyPress(event) = event else {
+                continue;
+            };
+            let state = u16::from(event.state) & u16::from(relevant);
+            let action = keys.iter().find(|(keycode, modifiers, _)| {
+                *keycode == event.detail && u16::from(*modifiers) == state
+            });
+            if let Some((_, _, action)) = action {
+                if action_tx.send(*action).is_err() {
+                    return;
+                }
+                notifier.notify();
+            }
+        });
+        Ok(grab)
+    }
+}
+
+impl Drop for Grab {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for (keycode, modifiers) in self.grabbed.drain(..) {
+            let _ = self.conn.ungrab_key(keycode, self.root, modifiers);
+        }
+        let wake = ClientMessageEvent::new(
+            32,
+            self.window,
+            AtomEnum::NONE,
+            [0u32; 5],
+        );
+        let _ = self.conn.send_event(
+            false,
+            self.window,
+            EventMask::NO_EVENT,
+            wake,
+        );
+        let _ = self.conn.destroy_window(self.window);
+        let _ = self.conn.flush();
+    }
+}
+
+fn modifiers(hotkey: &Hotkey) -> ModMask {
+    let mut modifiers = ModMask::from(0u16);
+    for (on, modifier) in [
+        (hotkey.ctrl, ModMask::CONTROL),
+        (hotkey.shift, ModMask::SHIFT),
+        (hotkey.alt, ModMask::M1),
+    ] {
+        if on {
+            modifiers |= modifier;
+        }
+    }
+    modifiers
+}
+
+/// X keysym of a key named as egui does, for the keys hotkeys are
+/// likely bound to.
+fn keysym(key: &str) -> Option<u32> {
+    let mut chars = key.chars();
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+        // lowercase, as letters are listed first on their keys
+        if char.is_ascii_alphanumeric() {
+            return Some(char.to_ascii_lowercase() as u32);
+        }
+    }
+    if let Some(idx) = key
+        .strip_prefix('F')
+        .and_then(|it| it.parse::<u32>().ok())
+        .filter(|it| (1..=24).contains(it))
+    {
+        return Some(0xffbe + idx - 1);
+    }
+    let keysym = match key {
+        "Space" => 0x20,
+        "Escape" => 0xff1b,
+        "Enter" => 0xff0d,
+        "Tab" => 0xff09,
+        "Backspace" => 0xff08,
+        "Insert" => 0xff63,
+        "Delete" => 0xffff,
+        "Home" => 0xff50,
+        "End" => 0xff57,
+        "PageUp" => 0xff55,
+        "PageDown" => 0xff56,
+        "ArrowLeft" => 0xff51,
+        "ArrowUp" => 0xff52,
+        "ArrowRight" => 0xff53,
+        "ArrowDown" => 0xff54,
+        _ => return None,
+    };
+    Some(keysym)
+}
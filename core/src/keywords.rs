@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// Too common to be worth listing.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "at", "be", "do", "for", "i", "in", "is",
+    "it", "me", "my", "no", "of", "on", "or", "so", "that", "the",
+    "this", "to", "we", "what", "you",
+];
+
+/// Words and phrases of messages received over a trailing window, to
+/// notice chat repeating itself.
+#[derive(Debug, Clone)]
+pub struct Keywords {
+    messages: VecDeque<(Instant, Vec<String>)>,
+    pub window: Duration,
+}
+
+impl Default for Keywords {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5 * 60))
+    }
+}
+
+impl Keywords {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            window,
+        }
+    }
+
+    pub fn record(&mut self, now: Instant, text: &str) {
+        self.messages.push_back((now, tokenize(text)));
+        self.expire(now);
+    }
+
+    /// At most `n`, by the number of messages within the window that
+    /// have them, ties by text.
+    pub fn top(
+        &mut self,
+        now: Instant,
+        n: usize,
+    ) -> Vec<(String, usize)> {
+        self.expire(now);
+        let mut counts = HashMap::<&str, usize>::new();
+        for (_, tokens) in &self.messages {
+            for token in tokens {
+                *counts.entry(token).or_default() += 1;
+            }
+        }
+        let mut top = counts
+            .into_iter()
+            .map(|(token, count)| (token.to_owned(), count))
+            .collect::<Vec<_>>();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(n);
+        top
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(first, _)) = self.messages.front() {
+            if now.saturating_duration_since(first) < self.window {
+                break;
+            }
+            self.messages.pop_front();
+        }
+    }
+}
+
+/// Lowercased words split on anything that isn't a letter or digit, and
+/// overlapping pairs of characters in runs of CJK text, which has no
+/// spaces to split on. Each at most once, stop words and single letters
+/// left out.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    let mut cjk = Vec::<char>::new();
+    // the trailing space ends whatever is left
+    for c in text.chars().chain([' ']) {
+        if !is_cjk(c) && !cjk.is_empty() {
+            match cjk.as_slice() {
+                [only] => tokens.push(only.to_string()),
+                _ => tokens
+                    .extend(cjk.windows(2).map(|it| it.iter().collect())),
+            }
+            cjk.clear();
+        }
+        if (is_cjk(c) || !c.is_alphanumeric()) && !word.is_empty() {
+            if word.chars().count() > 1 && !STOP_WORDS.contains(&&*word) {
+                tokens.push(word.clone());
+            }
+            word.clear();
+        }
+        if is_cjk(c) {
+            cjk.push(c);
+        } else if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+    }
+    let mut seen = HashSet::new();
+    tokens.retain(|it| seen.insert(it.clone()));
+    tokens
+}
+
+/// Han, kana and hangul.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+    )
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// What a message is written in, told by the script of its letters.
+/// Latin and Cyrillic are shared by too many languages to tell apart
+/// without a model, so they're left at the script.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Chinese,
+    Japanese,
+    Korean,
+    Latin,
+    Cyrillic,
+    Arabic,
+    Thai,
+    /// Letters of any other script.
+    Other,
+}
+
+impl Language {
+    pub const ALL: [Language; 8] = [
+        Language::Chinese,
+        Language::Japanese,
+        Language::Korean,
+        Language::Latin,
+        Language::Cyrillic,
+        Language::Arabic,
+        Language::Thai,
+        Language::Other,
+    ];
+
+    /// Named by the script, it's all that's told apart.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Chinese => "Han (Chinese)",
+            Language::Japanese => "Kana (Japanese)",
+            Language::Korean => "Hangul (Korean)",
+            Language::Latin => "Latin",
+            Language::Cyrillic => "Cyrillic",
+            Language::Arabic => "Arabic",
+            Language::Thai => "Thai",
+            Language::Other => "Other",
+        }
+    }
+
+    /// Of the script most of `text` is in, Japanese if there's any
+    /// kana and it's mostly kana and kanji. Scripts spacing their words
+    /// count by the word, the others by the letter, as a Han character
+    /// says about as much as a word, so the call in 打call doesn't
+    /// outweigh the Chinese. Latin words of one or two letters only
+    /// count without anything else. `None` without letters, e.g. only
+    /// emoji or numbers.
+    pub fn detect(text: &str) -> Option<Self> {
+        let mut counts = [0_usize; Self::ALL.len()];
+        let mut short_latin = false;
+        for run in text.split(|it: char| !it.is_alphabetic()) {
+            let mut letters = run.chars().map(script).peekable();
+            while let Some((lang, per_letter)) = letters.next() {
+                if per_letter {
+                    counts[lang as usize] += 1;
+                    continue;
+                }
+                let mut len = 1;
+                while letters.next_if_eq(&(lang, false)).is_some() {
+                    len += 1;
+                }
+                if lang == Language::Latin && len <= 2 {
+                    short_latin = true;
+                } else {
+                    counts[lang as usize] += 1;
+                }
+            }
+        }
+        if counts[Language::Japanese as usize] > 0 {
+            // kanji count as Chinese until kana say otherwise
+            counts[Language::Japanese as usize] +=
+                std::mem::take(&mut counts[Language::Chinese as usize]);
+        }
+        if short_latin && counts.iter().all(|it| *it == 0) {
+            return Some(Language::Latin);
+        }
+        let (idx, count) = counts
+            .iter()
+            .enumerate()
+            // the first of a tie
+            .rev()
+            .max_by_key(|(_, count)| **count)?;
+        (*count > 0).then_some(Self::ALL[idx])
+    }
+}
+
+/// The script of the letter `c`, and whether it's counted by the letter
+/// rather than the word.
+fn script(c: char) -> (Language, bool) {
+    match c {
+        '\u{3040}'..='\u{30ff}' | '\u{31f0}'..='\u{31ff}' => {
+            (Language::Japanese, true)
+        }
+        '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}' => (Language::Chinese, true),
+        '\u{1100}'..='\u{11ff}'
+        | '\u{3130}'..='\u{318f}'
+        | '\u{ac00}'..='\u{d7af}' => (Language::Korean, true),
+        '\u{e00}'..='\u{e7f}' => (Language::Thai, true),
+        'a'..='z' | 'A'..='Z' | '\u{c0}'..='\u{24f}' => {
+            (Language::Latin, false)
+        }
+        '\u{400}'..='\u{52f}' => (Language::Cyrillic, false),
+        '\u{600}'..='\u{6ff}' | '\u{750}'..='\u{77f}' => {
+            (Language::Arabic, false)
+        }
+        _ => (Language::Other, false),
+    }
+}
+
+/// Scripts marked in the queue, e.g. to translate, and ones dropped
+/// before they're queued, e.g. ones nobody can moderate.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct LanguageFilter {
+    pub tagged: Vec<Language>,
+    pub dropped: Vec<Language>,
+}
+
+impl LanguageFilter {
+    pub fn drops(&self, msg: &Message) -> bool {
+        !self.dropped.is_empty()
+            && Language::detect(&msg.text)
+                .is_some_and(|it| self.dropped.contains(&it))
+    }
+
+    /// The language of `msg` if it's tagged.
+    pub fn tag(&self, msg: &Message) -> Option<Language> {
+        if self.tagged.is_empty() {
+            return None;
+        }
+        Language::detect(&msg.text).filter(|it| self.tagged.contains(it))
+    }
+
+    pub fn set_tagged(&mut self, lang: Language, on: bool) {
+        set(&mut self.tagged, lang, on);
+    }
+
+    pub fn set_dropped(&mut self, lang: Language, on: bool) {
+        set(&mut self.dropped, lang, on);
+    }
+}
+
+fn set(list: &mut Vec<Language>, lang: Language, on: bool) {
+    list.retain(|it| *it != lang);
+    if on {
+        list.push(lang);
+    }
+}
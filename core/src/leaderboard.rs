@@ -0,0 +1,107 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+
+use crate::message::{Message, MessageKind};
+
+/// Messages kept per chatter to look back through.
+const RECENT_LEN: usize = 50;
+
+/// What a chatter sent this session.
+#[derive(Debug, Clone, Default)]
+pub struct Chatter {
+    pub username: String,
+    /// Gifts not included.
+    pub messages: usize,
+    /// In the platform's own unit, see [`crate::message::Gift::value`].
+    pub gift_value: u64,
+    /// Of SuperChats and other paid messages, in the platform's
+    /// currency.
+    pub paid: f64,
+    recent: VecDeque<(DateTime<Utc>, Message)>,
+}
+
+impl Chatter {
+    /// The last few of their messages and gifts, newest first, with when
+    /// they were received.
+    pub fn recent(
+        &self,
+    ) -> impl Iterator<Item = &(DateTime<Utc>, Message)> {
+        self.recent.iter().rev()
+    }
+}
+
+/// What [`Leaderboard::sorted`] ranks by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LeaderboardSort {
+    #[default]
+    Messages,
+    Gifts,
+    Paid,
+}
+
+/// Chatters of the session by who sent what.
+#[derive(Debug, Clone, Default)]
+pub struct Leaderboard {
+    chatters: HashMap<String, Chatter>,
+}
+
+impl Leaderboard {
+    /// Counts `msg` towards its sender, received at `at`. Messages
+    /// without one are left out.
+    pub fn record(&mut self, msg: &Message, at: DateTime<Utc>) {
+        let Some(ref username) = msg.username else {
+            return;
+        };
+        let chatter = self
+            .chatters
+            .entry(username.clone())
+            .or_insert_with(|| Chatter {
+                username: username.clone(),
+                ..Default::default()
+            });
+        match msg.gift {
+            Some(ref gift) if msg.kind == MessageKind::Gift => {
+                chatter.gift_value += gift.value;
+            }
+            _ => chatter.messages += 1,
+        }
+        if let Some(ref paid) = msg.paid {
+            chatter.paid += paid.amount;
+        }
+        if chatter.recent.len() == RECENT_LEN {
+            chatter.recent.pop_front();
+        }
+        chatter.recent.push_back((at, msg.clone()));
+    }
+
+    /// Highest first, ties by name.
+    pub fn sorted(&self, by: LeaderboardSort) -> Vec<&Chatter> {
+        let mut chatters = self.chatters.values().collect::<Vec<_>>();
+        chatters.sort_by(|a, b| {
+            let order = match by {
+                LeaderboardSort::Messages => b.messages.cmp(&a.messages),
+                LeaderboardSort::Gifts => b.gift_value.cmp(&a.gift_value),
+                LeaderboardSort::Paid => b.paid.total_cmp(&a.paid),
+            };
+            order.then_with(|| a.username.cmp(&b.username))
+        });
+        chatters
+    }
+
+    pub fn get(&self, username: &str) -> Option<&Chatter> {
+        self.chatters.get(username)
+    }
+
+    pub fn len(&self) -> usize {
+        self.chatters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chatters.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.chatters.clear();
+    }
+}
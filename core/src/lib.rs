@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+pub mod alert;
+pub mod alias;
+pub mod channel;
+pub mod clock;
+pub mod combo;
+pub mod command;
+pub mod demo_source;
+pub mod flag;
+pub mod flood;
+pub mod fuzzy;
+pub mod gift;
+pub mod history;
+pub mod hotkey;
+pub mod keywords;
+pub mod lang;
+pub mod leaderboard;
+pub mod log;
+pub mod mention;
+pub mod message;
+pub mod midi;
+pub mod network;
+pub mod poll;
+pub mod preview;
+pub mod qna;
+pub mod queue;
+pub mod raffle;
+pub mod release;
+pub mod revenue;
+pub mod schedule;
+pub mod sim;
+pub mod stats;
+pub mod superchat;
+pub mod text;
+pub mod timeout;
+pub mod timer;
+pub mod update;
+
+/// Callback used by background tasks to wake up the frontend when
+/// something new (a message, an error) is ready to be pulled.
+#[derive(Clone)]
+pub struct Notifier(Arc<dyn Fn() + Send + Sync>);
+
+impl Notifier {
+    pub fn new(f: impl Fn() + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub fn noop() -> Self {
+        Self::new(|| {})
+    }
+
+    pub fn notify(&self) {
+        (self.0)()
+    }
+}
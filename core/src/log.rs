@@ -0,0 +1,532 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    env::current_dir,
+    fs::File,
+    future::Future,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::AsyncWriteExt,
+    select,
+    sync::{mpsc as ampsc, oneshot},
+    task as atask, time,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use self::{
+    redact::{Found, Redaction},
+    upload::UploadTarget,
+};
+use crate::{
+    channel,
+    message::{
+        Attachment, Gift, Message, MessageKind, Paid, PlatformEvent,
+    },
+    text::UrlHit,
+};
+
+pub mod archive;
+pub mod hmac;
+pub mod redact;
+pub mod report;
+pub mod upload;
+
+/// Entries written to the file at once, at most.
+const WRITE_BATCH: usize = 1000;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LogEvent {
+    /// The network thread opened the log.
+    Start,
+    /// The network thread shut down cleanly.
+    End,
+    /// A message entered the queue.
+    Receive,
+    /// Entries written before events were logged are forwards or
+    /// deletes, told apart by `is_delete`.
+    #[default]
+    Forward,
+    Delete,
+    /// Deleted along with the rest of the queue by an emergency purge.
+    Purge,
+    /// Dropped by the overflow policy of a full queue.
+    Overflow,
+    /// Folded into a gift summary, which is received on its own.
+    Merge,
+    /// Put back by undoing a delete or purge, pending again as if just
+    /// received.
+    Restore,
+}
+
+/// One line of `log.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub msg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(rename = "type", default)]
+    pub kind: MessageKind,
+    /// Kept for readers predating `event`.
+    #[serde(default)]
+    pub is_delete: bool,
+    #[serde(default)]
+    pub event: LogEvent,
+    /// Why the message was purged, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Links found in a forwarded message and what was done with them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<UrlHit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gift: Option<Gift>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paid: Option<Paid>,
+    /// The message's `event`, renamed as that holds the [`LogEvent`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_event: Option<PlatformEvent>,
+    /// On the [`LogEvent::Start`] of a session logging pseudonyms, whose
+    /// messages can't be recovered as received.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub anonymized: bool,
+    pub ts: chrono::DateTime<Utc>,
+}
+
+impl LogEntry {
+    pub fn new(msg: Message, event: LogEvent) -> Self {
+        Self {
+            msg: msg.text,
+            username: msg.username,
+            kind: msg.kind,
+            is_delete: matches!(
+                event,
+                LogEvent::Delete
+                    | LogEvent::Purge
+                    | LogEvent::Overflow
+                    | LogEvent::Merge
+            ),
+            event,
+            reason: None,
+            urls: vec![],
+            attachments: msg.attachments,
+            gift: msg.gift,
+            paid: msg.paid,
+            platform_event: msg.event,
+            anonymized: false,
+            ts: Utc::now(),
+        }
+    }
+
+    pub fn with_reason(mut self, reason: Option<String>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn with_urls(mut self, urls: Vec<UrlHit>) -> Self {
+        self.urls = urls;
+        self
+    }
+
+    pub fn marker(event: LogEvent) -> Self {
+        Self::new(Message::chat(""), event)
+    }
+
+    /// `event`, with entries predating it told apart by `is_delete`.
+    pub fn event(&self) -> LogEvent {
+        match self.event {
+            LogEvent::Forward if self.is_delete => LogEvent::Delete,
+            event => event,
+        }
+    }
+
+    fn message(&self) -> Message {
+        Message {
+            kind: self.kind,
+            username: self.username.clone(),
+            text: self.msg.clone(),
+            attachments: self.attachments.clone(),
+            gift: self.gift.clone(),
+            paid: self.paid.clone(),
+            event: self.platform_event.clone(),
+        }
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    current_dir().unwrap_or_default().join("log.jsonl")
+}
+
+/// When written entries are synced to disk. Until then a power loss may
+/// take them, a crash of the app alone does not.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPolicy {
+    EveryWrite,
+    /// At most once per this many milliseconds.
+    Interval(u64),
+    #[default]
+    OnShutdown,
+}
+
+impl SyncPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EveryWrite => "Every write",
+            Self::Interval(_) => "Interval",
+            Self::OnShutdown => "On shutdown",
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct LogConfig {
+    pub sync: SyncPolicy,
+    /// Total size of the log and its archives, past which the oldest
+    /// archives are deleted. 0 for no limit.
+    pub max_total_mb: u64,
+    /// Where each session is uploaded once it ends. Never serialized,
+    /// it carries credentials.
+    #[serde(skip)]
+    pub upload: UploadTarget,
+    /// Writes a [`Pseudonyms`] stand-in instead of each username.
+    pub anonymize: bool,
+}
+
+/// Stand-ins for usernames, stable for one instance, so a session's
+/// log still tells chatters apart, but unrelated across instances.
+#[derive(Clone)]
+pub struct Pseudonyms {
+    salt: [u8; 32],
+}
+
+impl Default for Pseudonyms {
+    fn default() -> Self {
+        Self {
+            salt: rand::random(),
+        }
+    }
+}
+
+impl Pseudonyms {
+    pub fn of(&self, username: &str) -> String {
+        let hash = hmac::hmac_sha256(&self.salt, &[username.as_bytes()]);
+        let hex = hash[..5]
+            .iter()
+            .map(|it| format!("{it:02x}"))
+            .collect::<String>();
+        format!("user-{hex}")
+    }
+
+    pub fn apply(&self, entry: &mut LogEntry) {
+        if let Some(ref mut username) = entry.username {
+            *username = self.of(username);
+        }
+    }
+}
+
+/// Appends entries from `log_rx` to the log at `path`, between a
+/// [`LogEvent::Start`] and, once the token is cancelled and what's left
+/// is written, a [`LogEvent::End`] marker. Whatever arrived meanwhile is
+/// written as one batch, so a burst costs one write rather than one per
+/// entry.
+///
+/// A log grown past [`archive::ROTATE_BYTES`] is archived first, then
+/// compressed and pruned down to the configured size in the background.
+/// Resolves to the offset the session starts at in the log.
+///
+/// Redactions from `redact_rx` are applied in between writes, the log
+/// being reopened after.
+pub fn run_writer(
+    path: PathBuf,
+    log_rx: channel::Receiver<LogEntry>,
+    mut redact_rx: ampsc::UnboundedReceiver<RedactRequest>,
+    config: LogConfig,
+) -> (CancellationToken, impl Future<Output = anyhow::Result<u64>>) {
+    let stop_token = CancellationToken::new();
+    let stop_token_cloned = stop_token.clone();
+    let fut = async move {
+        let rotated = archive::rotate(&path, archive::ROTATE_BYTES)
+            .context("failed to archive log")?;
+        if let Some(ref rotated) = rotated {
+            info!("archived log to {}", rotated.display());
+        }
+        let path_cloned = path.clone();
+        atask::spawn_blocking(move || {
+            match archive::tidy(&path_cloned, config.max_total_mb << 20) {
+                Ok(deleted) => {
+                    for it in deleted {
+                        info!("deleted log archive {}", it.display());
+                    }
+                }
+                Err(err) => error!("failed to tidy log archives: {err}"),
+            }
+        });
+
+        let file = open_append(&path).await?;
+        let mut session_start = file
+            .metadata()
+            .await
+            .context("failed to read log file metadata")?
+            .len();
+        let mut writer = Writer {
+            file,
+            sync: config.sync,
+            pseudonyms: config.anonymize.then(Pseudonyms::default),
+            synced_at: time::Instant::now(),
+            dirty: false,
+        };
+        let start = LogEntry {
+            anonymized: config.anonymize,
+            ..LogEntry::marker(LogEvent::Start)
+        };
+        writer.write(&[start]).await?;
+
+        let mut redact_open = true;
+        loop {
+            let sync_at = writer.sync_at();
+            select! {
+                _ = stop_token_cloned.cancelled() => break,
+                log = log_rx.recv() => {
+                    let Some(log) = log else {
+                        break;
+                    };
+                    let mut batch = vec![log];
+                    batch.extend(log_rx.drain(WRITE_BATCH - 1));
+                    writer.write(&batch).await?;
+                }
+                _ = time::sleep_until(sync_at.unwrap_or_else(time::Instant::now)), if sync_at.is_some() => {
+                    writer.sync().await?;
+                }
+                req = redact_rx.recv(), if redact_open => {
+                    let Some((redaction, done_tx)) = req else {
+                        redact_open = false;
+                        continue;
+                    };
+                    // the user's entries still queued go in first
+                    let rest = log_rx.drain(usize::MAX);
+                    if !rest.is_empty() {
+                        writer.write(&rest).await?;
+                    }
+                    writer.sync().await?;
+                    let path_cloned = path.clone();
+                    let result = atask::spawn_blocking(move || {
+                        redact::apply(&path_cloned, &redaction, session_start)
+                    })
+                    .await
+                    .context("failed to join redaction task")?;
+                    writer.file = open_append(&path).await?;
+                    let _ = done_tx.send(result.map(|(found, mark)| {
+                        session_start = mark;
+                        found
+                    }));
+                }
+            }
+        }
+
+        let mut rest = log_rx.drain(usize::MAX);
+        rest.push(LogEntry::marker(LogEvent::End));
+        writer.write(&rest).await?;
+        writer.sync().await?;
+        Ok(session_start)
+    };
+    (stop_token, fut)
+}
+
+/// A [`Redaction`] for the log writer to apply, with where to send what
+/// it found.
+pub type RedactRequest =
+    (Redaction, oneshot::Sender<anyhow::Result<Vec<Found>>>);
+
+async fn open_append(path: &Path) -> anyhow::Result<tokio::fs::File> {
+    tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .context("failed to open log file")
+}
+
+struct Writer {
+    file: tokio::fs::File,
+    sync: SyncPolicy,
+    pseudonyms: Option<Pseudonyms>,
+    synced_at: time::Instant,
+    /// Written but not synced.
+    dirty: bool,
+}
+
+impl Writer {
+    async fn write(&mut self, batch: &[LogEntry]) -> anyhow::Result<()> {
+        let mut buf = String::new();
+        for log in batch {
+            let line = match self.pseudonyms {
+                Some(ref pseudonyms) if log.username.is_some() => {
+                    let mut log = log.clone();
+                    pseudonyms.apply(&mut log);
+                    serde_json::to_string(&log)
+                }
+                _ => serde_json::to_string(log),
+            }
+            .context("failed to serialize log")?;
+            buf += &line;
+            buf.push('\n');
+        }
+        self.file
+            .write_all(buf.as_bytes())
+            .await
+            .context("failed to write log")?;
+        self.file.flush().await.context("failed to flush log")?;
+        self.dirty = true;
+        if self.sync == SyncPolicy::EveryWrite {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    async fn sync(&mut self) -> anyhow::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.file.sync_data().await.context("failed to sync log")?;
+        self.dirty = false;
+        self.synced_at = time::Instant::now();
+        Ok(())
+    }
+
+    /// When the next timed sync is due, if there's anything to sync.
+    fn sync_at(&self) -> Option<time::Instant> {
+        match self.sync {
+            SyncPolicy::Interval(ms) if self.dirty => {
+                Some(self.synced_at + Duration::from_millis(ms))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How much of the end of the log is scanned for the last session.
+const RECOVERY_TAIL_BYTES: u64 = 4 << 20;
+
+/// Messages the last session received but neither forwarded nor
+/// deleted, oldest first. Empty if that session ended cleanly, logged
+/// pseudonyms or can't be found in the tail of the log.
+pub fn unfinished_messages(path: &Path) -> anyhow::Result<Vec<Message>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(vec![]);
+        }
+        Err(err) => {
+            return Err(err).with_context(|| {
+                format!("failed to open {}", path.display())
+            });
+        }
+    };
+    let len = file.metadata().context("failed to stat log")?.len();
+    let start = len.saturating_sub(RECOVERY_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .context("failed to seek log")?;
+    let mut tail = vec![];
+    file.read_to_end(&mut tail).context("failed to read log")?;
+    let tail = String::from_utf8_lossy(&tail);
+
+    let mut lines = tail.lines();
+    if start > 0 {
+        // most likely cut in the middle
+        lines.next();
+    }
+    let entries = lines.filter_map(parse_line).collect::<Vec<_>>();
+    Ok(unfinished_in(&entries))
+}
+
+/// Every entry of the log at `path`, oldest first, skipping lines that
+/// aren't one.
+pub fn read_entries(path: &Path) -> anyhow::Result<Vec<LogEntry>> {
+    let log = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(log.lines().filter_map(parse_line).collect())
+}
+
+/// Whether the `.jsonl` file at `path` is a message log rather than a
+/// demo file, going by its first line.
+pub fn is_log(path: &Path) -> anyhow::Result<bool> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let mut lines = BufReader::new(file).lines();
+    let line = loop {
+        match lines.next() {
+            Some(line) => {
+                let line = line.context("failed to read log")?;
+                if !line.trim().is_empty() {
+                    break line;
+                }
+            }
+            None => return Ok(false),
+        }
+    };
+    // demo entries are messages, which have no timestamp
+    let value = serde_json::from_str::<serde_json::Value>(&line).ok();
+    Ok(value.is_some_and(|it| it.get("ts").is_some()))
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    serde_json::from_str(line).ok()
+}
+
+fn unfinished_in(entries: &[LogEntry]) -> Vec<Message> {
+    let Some(session_start) =
+        entries.iter().rposition(|it| it.event() == LogEvent::Start)
+    else {
+        return vec![];
+    };
+    if entries[session_start].anonymized {
+        return vec![];
+    }
+    let session = &entries[session_start + 1..];
+    if session.iter().any(|it| it.event() == LogEvent::End) {
+        return vec![];
+    }
+
+    // messages carry no id, so a forward or delete settles the oldest
+    // receive with the same content
+    let mut open = HashMap::<_, VecDeque<usize>>::new();
+    let mut pending = vec![];
+    for entry in session {
+        let key = (entry.kind, entry.username.clone(), entry.msg.clone());
+        match entry.event() {
+            LogEvent::Receive | LogEvent::Restore => {
+                open.entry(key).or_default().push_back(pending.len());
+                pending.push(Some(entry.message()));
+            }
+            LogEvent::Forward
+            | LogEvent::Delete
+            | LogEvent::Purge
+            | LogEvent::Overflow
+            | LogEvent::Merge => {
+                if let Some(idx) =
+                    open.get_mut(&key).and_then(VecDeque::pop_front)
+                {
+                    pending[idx] = None;
+                }
+            }
+            LogEvent::Start | LogEvent::End => {}
+        }
+    }
+    pending.into_iter().flatten().collect()
+}
@@ -0,0 +1,196 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+/// Size the log grows to before it's archived, checked when a session
+/// starts. Never in the middle of one, recovery looks for the session
+/// start in the current log only.
+pub const ROTATE_BYTES: u64 = 16 << 20;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// How an archive is compressed, told by its first bytes when read so
+/// older archives stay readable if [`Format::NEW`] changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    Gzip,
+}
+
+impl Format {
+    /// Of archives compressed from now on.
+    pub const NEW: Self = Self::Gzip;
+    const COMPRESSED: [Self; 1] = [Self::Gzip];
+
+    fn ext(self) -> &'static str {
+        match self {
+            Self::Plain => "",
+            Self::Gzip => "gz",
+        }
+    }
+
+    pub fn of(path: &Path) -> io::Result<Self> {
+        let mut magic = vec![];
+        File::open(path)?.take(2).read_to_end(&mut magic)?;
+        Ok(if magic == GZIP_MAGIC {
+            Self::Gzip
+        } else {
+            Self::Plain
+        })
+    }
+
+    pub fn read_to_string(self, file: File) -> io::Result<String> {
+        let mut text = String::new();
+        match self {
+            Self::Plain => BufReader::new(file).read_to_string(&mut text),
+            Self::Gzip => GzDecoder::new(file).read_to_string(&mut text),
+        }?;
+        Ok(text)
+    }
+
+    /// `text` written to `file` as a whole, returning it to be synced.
+    pub fn write(self, mut file: File, text: &[u8]) -> io::Result<File> {
+        match self {
+            Self::Plain => file.write_all(text).map(|()| file),
+            Self::Gzip => {
+                let mut encoder =
+                    GzEncoder::new(file, Compression::default());
+                encoder.write_all(text)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Moves the log at `path` aside, named after the current time, if it
+/// grew past `max_bytes`. Returns where it went, still to be compressed.
+pub fn rotate(
+    path: &Path,
+    max_bytes: u64,
+) -> io::Result<Option<PathBuf>> {
+    let len = match fs::metadata(path) {
+        Ok(it) => it.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(None)
+        }
+        Err(err) => return Err(err),
+    };
+    if len <= max_bytes {
+        return Ok(None);
+    }
+    let to = archive_path(
+        path,
+        &Utc::now().format("%Y%m%dT%H%M%S").to_string(),
+    );
+    fs::rename(path, &to)?;
+    Ok(Some(to))
+}
+
+/// `path` archived at `stamp`, e.g. `log-20241001T200000.jsonl`.
+fn archive_path(path: &Path, stamp: &str) -> PathBuf {
+    let mut name = prefix(path);
+    name.push(stamp);
+    if let Some(ext) = path.extension() {
+        name.push(".");
+        name.push(ext);
+    }
+    path.with_file_name(name)
+}
+
+fn prefix(path: &Path) -> OsString {
+    let mut prefix = path.file_stem().unwrap_or_default().to_owned();
+    prefix.push("-");
+    prefix
+}
+
+/// Compresses an archived log as [`Format::NEW`] next to itself,
+/// removing the original once that's complete.
+pub fn compress(path: &Path) -> io::Result<PathBuf> {
+    let mut to = path.as_os_str().to_owned();
+    to.push(".");
+    to.push(Format::NEW.ext());
+    let to = PathBuf::from(to);
+
+    // at most ROTATE_BYTES, unless the limit changed
+    let text = fs::read(path)?;
+    Format::NEW.write(File::create(&to)?, &text)?.sync_all()?;
+    fs::remove_file(path)?;
+    Ok(to)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        Format::COMPRESSED.iter().any(|it| ext == it.ext())
+    })
+}
+
+/// Archives of the log at `path`, oldest first, compressed or not.
+pub fn archives(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = prefix(path).to_string_lossy().into_owned();
+    let ext = path
+        .extension()
+        .map(|it| format!(".{}", it.to_string_lossy()))
+        .unwrap_or_default();
+    let mut archives = fs::read_dir(dir)?
+        .filter_map(|it| it.ok())
+        .map(|it| it.path())
+        .filter(|it| {
+            let Some(name) = it.file_name() else {
+                return false;
+            };
+            let name = name.to_string_lossy();
+            let compressed = Format::COMPRESSED
+                .iter()
+                .any(|it| name.ends_with(&format!("{ext}.{}", it.ext())));
+            name.starts_with(&prefix)
+                && (name.ends_with(&ext) || compressed)
+        })
+        .collect::<Vec<_>>();
+    // the time stamps sort as text
+    archives.sort();
+    Ok(archives)
+}
+
+/// Compresses archives left uncompressed, e.g. by a crash while doing
+/// so, then deletes the oldest until the log and its archives take up
+/// at most `max_total_bytes`, 0 for no limit. Returns the deleted ones.
+pub fn tidy(
+    path: &Path,
+    max_total_bytes: u64,
+) -> io::Result<Vec<PathBuf>> {
+    for archive in archives(path)? {
+        if !is_compressed(&archive) {
+            compress(&archive)?;
+        }
+    }
+    if max_total_bytes == 0 {
+        return Ok(vec![]);
+    }
+
+    let archives = archives(path)?
+        .into_iter()
+        .map(|it| Ok((fs::metadata(&it)?.len(), it)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut total = fs::metadata(path).map(|it| it.len()).unwrap_or(0)
+        + archives.iter().map(|(len, _)| len).sum::<u64>();
+    let mut deleted = vec![];
+    for (len, archive) in archives {
+        if total <= max_total_bytes {
+            break;
+        }
+        fs::remove_file(&archive)?;
+        total -= len;
+        deleted.push(archive);
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,32 @@
+use sha2::{
+    digest::{core_api::BlockSizeUser, Output},
+    Digest, Sha256,
+};
+
+/// HMAC (RFC 2104) over `parts` concatenated.
+pub fn hmac<D: Digest + BlockSizeUser>(
+    key: &[u8],
+    parts: &[&[u8]],
+) -> Output<D> {
+    let mut block = vec![0; D::block_size()];
+    if key.len() > block.len() {
+        let key = D::digest(key);
+        block[..key.len()].copy_from_slice(&key);
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = D::new();
+    inner.update(block.iter().map(|it| it ^ 0x36).collect::<Vec<_>>());
+    for part in parts {
+        inner.update(part);
+    }
+    let mut outer = D::new();
+    outer.update(block.iter().map(|it| it ^ 0x5c).collect::<Vec<_>>());
+    outer.update(inner.finalize());
+    outer.finalize()
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    hmac::<Sha256>(key, parts).into()
+}
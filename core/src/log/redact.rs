@@ -0,0 +1,212 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    archive::{self, Format},
+    parse_line,
+};
+
+/// Text left in place of a redacted message.
+pub const REDACTED: &str = "[redacted]";
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum RedactMode {
+    /// Drops the user's entries.
+    #[default]
+    Delete,
+    /// Keeps the user's entries for counts, without their name, text or
+    /// attachments.
+    Redact,
+}
+
+impl RedactMode {
+    pub const ALL: [RedactMode; 2] =
+        [RedactMode::Delete, RedactMode::Redact];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RedactMode::Delete => "Delete",
+            RedactMode::Redact => "Redact",
+        }
+    }
+}
+
+/// Everything logged from one user, to be removed from the log and its
+/// archives, e.g. on their request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redaction {
+    /// Matched exactly, so it has to be a pseudonym in an anonymized
+    /// log.
+    pub username: String,
+    pub mode: RedactMode,
+}
+
+/// Entries of the user found in one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub path: PathBuf,
+    pub entries: usize,
+    /// Lines that aren't an entry, e.g. cut off by a crash, so can't be
+    /// told to be the user's. Left as they are.
+    pub unreadable: usize,
+}
+
+/// A file's text without the user's entries.
+struct Redacted {
+    text: String,
+    entries: usize,
+    unreadable: usize,
+    /// Where the line at the given mark starts now.
+    mark: u64,
+}
+
+/// What [`apply`] would change, without changing anything.
+pub fn preview(
+    path: &Path,
+    redaction: &Redaction,
+) -> anyhow::Result<Vec<Found>> {
+    let mut found = vec![];
+    for file in files(path)? {
+        let redacted = redact(&read(&file)?, redaction, 0)?;
+        if redacted.entries > 0 || redacted.unreadable > 0 {
+            found.push(Found {
+                path: file,
+                entries: redacted.entries,
+                unreadable: redacted.unreadable,
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// Rewrites the log at `path` and its archives without the user's
+/// entries. `mark` is an offset into the log, returned moved to where
+/// the same line starts afterwards. Files with unreadable lines are
+/// listed even if nothing was removed from them.
+///
+/// The log must not be written meanwhile, so this is run by the log
+/// writer, see [`crate::network::Network::redact_log`].
+pub fn apply(
+    path: &Path,
+    redaction: &Redaction,
+    mark: u64,
+) -> anyhow::Result<(Vec<Found>, u64)> {
+    let mut found = vec![];
+    let mut new_mark = mark;
+    for file in files(path)? {
+        let file_mark = if file == path { mark } else { 0 };
+        let redacted = redact(&read(&file)?, redaction, file_mark)?;
+        if redacted.entries > 0 {
+            write(&file, &redacted.text)?;
+            if file == path {
+                new_mark = redacted.mark;
+            }
+        }
+        if redacted.entries > 0 || redacted.unreadable > 0 {
+            found.push(Found {
+                path: file,
+                entries: redacted.entries,
+                unreadable: redacted.unreadable,
+            });
+        }
+    }
+    Ok((found, new_mark))
+}
+
+/// The archives of the log at `path`, then the log itself.
+fn files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files =
+        archive::archives(path).context("failed to list log archives")?;
+    if path.exists() {
+        files.push(path.to_owned());
+    }
+    Ok(files)
+}
+
+fn read(path: &Path) -> anyhow::Result<String> {
+    let format = Format::of(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let file = File::open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    format
+        .read_to_string(file)
+        .with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Replaces the file at `path` as a whole in the format it was in, so
+/// it's never left half written.
+fn write(path: &Path, text: &str) -> anyhow::Result<()> {
+    let format = Format::of(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let mut tmp = OsString::from(path);
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let file = File::create(&tmp)
+        .with_context(|| format!("failed to create {}", tmp.display()))?;
+    let file = format
+        .write(file, text.as_bytes())
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    file.sync_all()
+        .with_context(|| format!("failed to sync {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("failed to replace {}", path.display()))
+}
+
+fn redact(
+    text: &str,
+    redaction: &Redaction,
+    mark: u64,
+) -> anyhow::Result<Redacted> {
+    let mut out = String::with_capacity(text.len());
+    let mut entries = 0;
+    let mut unreadable = 0;
+    let mut offset = 0;
+    let mut new_mark = None;
+    for line in text.split_inclusive('\n') {
+        if new_mark.is_none() && offset >= mark {
+            new_mark = Some(out.len() as u64);
+        }
+        offset += line.len() as u64;
+
+        let content = line.trim_end_matches('\n');
+        let Some(mut entry) = parse_line(content) else {
+            if !content.trim().is_empty() {
+                unreadable += 1;
+            }
+            out += line;
+            continue;
+        };
+        if entry.username.as_deref() != Some(redaction.username.as_str())
+        {
+            out += line;
+            continue;
+        }
+        entries += 1;
+        if redaction.mode == RedactMode::Delete {
+            continue;
+        }
+        entry.username = None;
+        entry.msg = REDACTED.to_owned();
+        entry.reason = None;
+        entry.urls.clear();
+        entry.attachments.clear();
+        out += &serde_json::to_string(&entry)
+            .context("failed to serialize log")?;
+        out.push('\n');
+    }
+    Ok(Redacted {
+        mark: new_mark.unwrap_or(out.len() as u64),
+        text: out,
+        entries,
+        unreadable,
+    })
+}
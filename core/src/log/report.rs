@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+use super::{read_entries, LogEntry, LogEvent, Pseudonyms};
+use crate::{
+    message::{Message, MessageKind},
+    revenue::Revenue,
+};
+
+/// Chatters listed by message count, and supporters by revenue.
+const TOP_CHATTERS: usize = 10;
+/// Volume rows at most, buckets widen by whole minutes to fit.
+const MAX_BUCKETS: i64 = 60;
+/// Width of the bar of the busiest bucket.
+const BAR_WIDTH: usize = 40;
+
+/// Summary of one session of the log.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionReport {
+    pub start: Option<DateTime<Utc>>,
+    /// `None` if the session is still running or didn't end cleanly.
+    pub end: Option<DateTime<Utc>>,
+    pub received: usize,
+    pub forwarded: usize,
+    /// Deleted by hand, by a filter or in a purge.
+    pub deleted: usize,
+    /// Dropped by a full queue.
+    pub overflowed: usize,
+    pub superchats: usize,
+    /// Of all superchats, in the platform's currency.
+    pub superchat_total: f64,
+    /// Gifts folded into a summary count through that.
+    pub revenue: Revenue,
+    /// Most active first, by messages received.
+    pub top_chatters: Vec<(String, usize)>,
+    /// Messages received from each bucket start on, oldest first.
+    pub volume: Vec<(DateTime<Utc>, usize)>,
+    pub bucket: Duration,
+}
+
+impl SessionReport {
+    /// `entries` are those of one session, start marker included.
+    pub fn new(entries: &[LogEntry]) -> Self {
+        let mut report = SessionReport {
+            bucket: Duration::minutes(1),
+            ..Default::default()
+        };
+        let mut chatters = HashMap::<&str, usize>::new();
+        let mut received_at = vec![];
+        for entry in entries {
+            match entry.event() {
+                LogEvent::Start => report.start = Some(entry.ts),
+                LogEvent::End => report.end = Some(entry.ts),
+                LogEvent::Receive => {
+                    report.received += 1;
+                    received_at.push(entry.ts);
+                    if let Some(ref username) = entry.username {
+                        *chatters.entry(username).or_default() += 1;
+                    }
+                    if entry.kind == MessageKind::SuperChat {
+                        report.superchats += 1;
+                        report.superchat_total += entry
+                            .paid
+                            .as_ref()
+                            .map_or(0.0, |it| it.amount);
+                    }
+                    report.revenue.record(&message_of(entry));
+                }
+                LogEvent::Forward => report.forwarded += 1,
+                LogEvent::Delete | LogEvent::Purge => report.deleted += 1,
+                LogEvent::Overflow => report.overflowed += 1,
+                LogEvent::Merge => {
+                    report.revenue.unrecord(&message_of(entry))
+                }
+                LogEvent::Restore => {
+                    report.deleted = report.deleted.saturating_sub(1)
+                }
+            }
+        }
+
+        let mut chatters = chatters
+            .into_iter()
+            .map(|(name, count)| (name.to_owned(), count))
+            .collect::<Vec<_>>();
+        // ties by name, so the report is the same every time
+        chatters
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        chatters.truncate(TOP_CHATTERS);
+        report.top_chatters = chatters;
+
+        if let (Some(first), Some(last)) =
+            (received_at.iter().min(), received_at.iter().max())
+        {
+            let minutes = (*last - *first).num_minutes() + 1;
+            report.bucket = Duration::minutes(
+                (minutes + MAX_BUCKETS - 1) / MAX_BUCKETS,
+            );
+        }
+        report.volume = volume(&received_at, report.bucket);
+        report
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let format_ts = |ts: Option<DateTime<Utc>>| {
+            ts.map_or("-".to_owned(), |it| {
+                it.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+            })
+        };
+        let mut md = String::new();
+        let _ = writeln!(md, "# Session report\n");
+        let _ = writeln!(md, "| | |\n|---|---|");
+        let _ = writeln!(md, "| Start | {} |", format_ts(self.start));
+        let _ = writeln!(md, "| End | {} |", format_ts(self.end));
+        let _ = writeln!(md, "| Received | {} |", self.received);
+        let _ = writeln!(md, "| Forwarded | {} |", self.forwarded);
+        let _ = writeln!(md, "| Deleted | {} |", self.deleted);
+        let _ =
+            writeln!(md, "| Dropped on overflow | {} |", self.overflowed);
+        let _ = writeln!(md, "| SuperChats | {} |", self.superchats);
+        let _ = writeln!(
+            md,
+            "| SuperChat total | {:.2} |",
+            self.superchat_total
+        );
+        let total = self.revenue.total();
+        let _ = writeln!(md, "| Gifts | {} |", total.gifts);
+        let _ = writeln!(md, "| Gift value | {} |", total.gift_value);
+
+        let supporters = self.revenue.by_user();
+        if !supporters.is_empty() {
+            let _ = writeln!(md, "\n## Top supporters\n");
+            let _ = writeln!(
+                md,
+                "| User | Gifts | Gift value | Paid |\n|---|---|---|---|"
+            );
+            for (name, it) in supporters.into_iter().take(TOP_CHATTERS) {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {:.2} |",
+                    escape(name),
+                    it.gifts,
+                    it.gift_value,
+                    it.paid
+                );
+            }
+        }
+
+        if !self.top_chatters.is_empty() {
+            let _ = writeln!(md, "\n## Top chatters\n");
+            let _ = writeln!(md, "| User | Messages |\n|---|---|");
+            for (name, count) in &self.top_chatters {
+                let _ = writeln!(md, "| {} | {count} |", escape(name));
+            }
+        }
+
+        if !self.volume.is_empty() {
+            let _ = writeln!(
+                md,
+                "\n## Messages per {} min\n",
+                self.bucket.num_minutes()
+            );
+            let max =
+                self.volume.iter().map(|it| it.1).max().unwrap_or(0);
+            let _ = writeln!(md, "```");
+            for (start, count) in &self.volume {
+                let bar = (count * BAR_WIDTH).div_ceil(max.max(1));
+                let _ = writeln!(
+                    md,
+                    "{} {count:>6} {}",
+                    start.format("%H:%M"),
+                    "#".repeat(bar)
+                );
+            }
+            let _ = writeln!(md, "```");
+        }
+        md
+    }
+}
+
+/// Messages received in each minute of `entries`, oldest first, quiet
+/// minutes included.
+pub fn activity(entries: &[LogEntry]) -> Vec<(DateTime<Utc>, usize)> {
+    let received_at = entries
+        .iter()
+        .filter(|it| it.event() == LogEvent::Receive)
+        .map(|it| it.ts)
+        .collect::<Vec<_>>();
+    volume(&received_at, Duration::minutes(1))
+}
+
+/// Counts of `received_at` from each `bucket` start on, the first one
+/// truncated to a whole bucket.
+fn volume(
+    received_at: &[DateTime<Utc>],
+    bucket: Duration,
+) -> Vec<(DateTime<Utc>, usize)> {
+    let (Some(first), Some(last)) =
+        (received_at.iter().min(), received_at.iter().max())
+    else {
+        return vec![];
+    };
+    let first = first.duration_trunc(bucket).unwrap_or(*first);
+    let buckets = ((*last - first).num_seconds() / bucket.num_seconds()
+        + 1) as usize;
+    let mut volume = (0..buckets)
+        .map(|idx| (first + bucket * idx as i32, 0))
+        .collect::<Vec<_>>();
+    for ts in received_at {
+        let idx =
+            ((*ts - first).num_seconds() / bucket.num_seconds()) as usize;
+        volume[idx].1 += 1;
+    }
+    volume
+}
+
+/// Entries of the last session in the log at `path`, start marker
+/// included.
+pub fn last_session(path: &Path) -> anyhow::Result<Vec<LogEntry>> {
+    let mut entries = read_entries(path)?;
+    if let Some(start) =
+        entries.iter().rposition(|it| it.event() == LogEvent::Start)
+    {
+        entries.drain(..start);
+    }
+    Ok(entries)
+}
+
+/// Writes a report of the last session next to the log at `path`,
+/// returning where, and its revenue by user as CSV if there was any.
+/// Chatters are listed by pseudonym if `anonymize`.
+pub fn generate(path: &Path, anonymize: bool) -> anyhow::Result<PathBuf> {
+    let mut entries = last_session(path)?;
+    if anonymize {
+        let pseudonyms = Pseudonyms::default();
+        entries.iter_mut().for_each(|it| pseudonyms.apply(it));
+    }
+    let report = SessionReport::new(&entries);
+    let stamp = report
+        .start
+        .unwrap_or_else(Utc::now)
+        .format("%Y%m%dT%H%M%S");
+    let report_path = path.with_file_name(format!("report-{stamp}.md"));
+    fs::write(&report_path, report.to_markdown()).with_context(|| {
+        format!("failed to write {}", report_path.display())
+    })?;
+    if !report.revenue.is_empty() {
+        let csv_path =
+            path.with_file_name(format!("revenue-{stamp}.csv"));
+        fs::write(&csv_path, report.revenue.to_csv()).with_context(
+            || format!("failed to write {}", csv_path.display()),
+        )?;
+    }
+    Ok(report_path)
+}
+
+/// What [`Revenue`] needs of a logged message.
+fn message_of(entry: &LogEntry) -> Message {
+    Message {
+        kind: entry.kind,
+        username: entry.username.clone(),
+        gift: entry.gift.clone(),
+        paid: entry.paid.clone(),
+        ..Message::chat("")
+    }
+}
+
+/// Keeps a name from breaking out of its table cell.
+fn escape(name: &str) -> String {
+    name.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,289 @@
+use std::{fmt, path::Path, time::Duration};
+
+use anyhow::{anyhow, bail, Context};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use hyper::{body::Bytes, header, Request, Uri};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{task, time};
+use tracing::{info, warn};
+
+use super::hmac::hmac_sha256 as hmac;
+use crate::network::{access_log, fetch, proxy::ProxyConfig};
+
+/// Attempts at each file before giving up on it.
+const ATTEMPTS: u32 = 4;
+/// Before the first retry, doubled for every one after.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+/// Of one attempt, so a stalled endpoint doesn't take up every retry.
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Of uploading a session as the network stops, which holds up quitting.
+pub const STOP_DEADLINE: Duration = Duration::from_secs(15);
+
+/// Where finished sessions are archived, off the streaming machine.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum UploadTarget {
+    #[default]
+    None,
+    /// Files are put in the collection at `url`, which has to exist.
+    WebDav {
+        url: String,
+        /// Leave empty for no authentication.
+        username: String,
+        password: String,
+    },
+    /// Addressed path style, so any S3 compatible `endpoint` works.
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        access_key: String,
+        secret_key: String,
+    },
+}
+
+impl fmt::Debug for UploadTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl UploadTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UploadTarget::None => "None",
+            UploadTarget::WebDav { .. } => "WebDAV",
+            UploadTarget::S3 { .. } => "S3",
+        }
+    }
+
+    /// Puts `body` as the file `name`, which is used as is in the url.
+    pub async fn put(
+        &self,
+        name: &str,
+        body: Bytes,
+        proxy: &ProxyConfig,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let request = match self {
+            UploadTarget::None => bail!("no upload target"),
+            UploadTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let uri = format!("{}/{name}", url.trim_end_matches('/'));
+                let request = Request::put(uri);
+                if username.is_empty() {
+                    request
+                } else {
+                    let auth = BASE64_STANDARD
+                        .encode(format!("{username}:{password}"));
+                    request.header(
+                        header::AUTHORIZATION,
+                        format!("Basic {auth}"),
+                    )
+                }
+            }
+            UploadTarget::S3 {
+                endpoint,
+                region,
+                bucket,
+                access_key,
+                secret_key,
+            } => {
+                let uri = format!(
+                    "{}/{bucket}/{name}",
+                    endpoint.trim_end_matches('/')
+                )
+                .parse::<Uri>()
+                .context("invalid S3 endpoint")?;
+                let host = uri.authority().context("url has no host")?;
+                let date = now.format("%Y%m%dT%H%M%SZ").to_string();
+                let payload_hash = hex(&Sha256::digest(&body));
+                let sig = SigV4 {
+                    secret_key,
+                    region,
+                    service: "s3",
+                    date: &date,
+                };
+                let headers = [
+                    ("host", host.as_str()),
+                    ("x-amz-content-sha256", &payload_hash),
+                    ("x-amz-date", &date),
+                ];
+                let authorization = format!(
+                    "AWS4-HMAC-SHA256 Credential={access_key}/{}, \
+                     SignedHeaders={}, Signature={}",
+                    sig.scope(),
+                    SigV4::signed_headers(&headers),
+                    sig.signature(
+                        "PUT",
+                        uri.path(),
+                        &headers,
+                        &payload_hash
+                    ),
+                );
+                Request::put(uri)
+                    .header("x-amz-date", &date)
+                    .header("x-amz-content-sha256", &payload_hash)
+                    .header(header::AUTHORIZATION, authorization)
+            }
+        };
+
+        let res = fetch::send(request, body, proxy).await?;
+        let status = res.status();
+        if !status.is_success() {
+            bail!("uploading {name} failed with {status}");
+        }
+        Ok(())
+    }
+
+    /// [`UploadTarget::put`], retried with backoff.
+    pub async fn put_with_retry(
+        &self,
+        name: &str,
+        body: Bytes,
+        proxy: &ProxyConfig,
+    ) -> anyhow::Result<()> {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            let put = self.put(name, body.clone(), proxy, Utc::now());
+            let result = time::timeout(ATTEMPT_TIMEOUT, put)
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("timed out")));
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < ATTEMPTS => {
+                    warn!(
+                        "failed to upload {name}, retrying in {delay:?}: \
+                         {err:?}"
+                    );
+                    time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!(
+                            "failed to upload {name} in {ATTEMPTS} tries"
+                        )
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// AWS signature version 4 of requests without a query string.
+pub struct SigV4<'a> {
+    pub secret_key: &'a str,
+    pub region: &'a str,
+    pub service: &'a str,
+    /// As in `x-amz-date`, `20130524T000000Z`.
+    pub date: &'a str,
+}
+
+impl SigV4<'_> {
+    pub fn scope(&self) -> String {
+        format!(
+            "{}/{}/{}/aws4_request",
+            &self.date[..8],
+            self.region,
+            self.service
+        )
+    }
+
+    pub fn signing_key(&self) -> [u8; 32] {
+        let key = hmac(
+            format!("AWS4{}", self.secret_key).as_bytes(),
+            &[&self.date.as_bytes()[..8]],
+        );
+        [self.region, self.service, "aws4_request"]
+            .into_iter()
+            .fold(key, |key, it| hmac(&key, &[it.as_bytes()]))
+    }
+
+    /// `headers` are all signed, named in lowercase and sorted by name.
+    pub fn signed_headers(headers: &[(&str, &str)]) -> String {
+        let names = headers.iter().map(|(name, _)| *name);
+        names.collect::<Vec<_>>().join(";")
+    }
+
+    /// Hex of the signature, see [`SigV4::signed_headers`] for `headers`.
+    pub fn signature(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        payload_hash: &str,
+    ) -> String {
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{value}\n"))
+            .collect::<String>();
+        let canonical = format!(
+            "{method}\n{path}\n\n{canonical_headers}\n{}\n{payload_hash}",
+            Self::signed_headers(headers),
+        );
+        let to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            self.date,
+            self.scope(),
+            hex(&Sha256::digest(canonical))
+        );
+        hex(&hmac(&self.signing_key(), &[to_sign.as_bytes()]))
+    }
+}
+
+/// Uploads what a session appended to the log at `log_path` from
+/// `session_start` on, as written, then
+/// what it wrote to the access log at `access_log_path` from
+/// `started_at` on, if anything. Both are named by when the session
+/// ended.
+pub async fn upload_session(
+    target: &UploadTarget,
+    proxy: &ProxyConfig,
+    log_path: &Path,
+    session_start: u64,
+    access_log_path: &Path,
+    started_at: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+
+    let log = tokio::fs::read(log_path)
+        .await
+        .context("failed to read log file")?;
+    let start = usize::try_from(session_start)
+        .ok()
+        .filter(|it| *it <= log.len())
+        .context("log is shorter than where the session started")?;
+    let session = Bytes::from(log).slice(start..);
+    let name = format!("session-{stamp}.jsonl");
+    target.put_with_retry(&name, session, proxy).await?;
+    info!("uploaded {name}");
+
+    let access_log_path = access_log_path.to_owned();
+    let access_log = task::spawn_blocking(move || {
+        access_log::read_since(&access_log_path, started_at)
+    })
+    .await
+    .context("failed to join access log reader")?
+    .context("failed to read access log")?;
+    if access_log.is_empty() {
+        return Ok(());
+    }
+    let name = format!("access-{stamp}.log");
+    target
+        .put_with_retry(&name, Bytes::from(access_log), proxy)
+        .await?;
+    info!("uploaded {name}");
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|it| format!("{it:02x}")).collect()
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// Names whose mentions mark a message in the queue, e.g. the streamer's
+/// own handle and co-hosts'. Matched case-insensitively as whole words,
+/// with or without an `@`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mentions {
+    pub names: Vec<String>,
+    /// Send delay of messages with a mention instead of the source's,
+    /// `None` to leave it.
+    pub fast_track_secs: Option<f64>,
+}
+
+impl Mentions {
+    /// The first name mentioned in the text of `msg`, if any.
+    pub fn matches(&self, msg: &Message) -> Option<&str> {
+        let text = msg.text.to_lowercase();
+        self.names
+            .iter()
+            .map(|it| it.trim().trim_start_matches('@'))
+            .filter(|it| !it.is_empty())
+            .find(|name| mentions(&text, &name.to_lowercase()))
+    }
+
+    /// Of `msg`, fast tracked if it has a mention.
+    pub fn delay_secs(
+        &self,
+        msg: &Message,
+        delay_secs: Option<f64>,
+    ) -> Option<f64> {
+        match self.fast_track_secs {
+            Some(secs) if self.matches(msg).is_some() => Some(secs),
+            _ => delay_secs,
+        }
+    }
+}
+
+/// Whether `name` is in `text` and not part of a longer word.
+fn mentions(text: &str, name: &str) -> bool {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(idx, _)| {
+        let before = text[..idx].chars().next_back();
+        let after = text[idx + name.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageKind {
+    #[default]
+    Chat,
+    Gift,
+    SuperChat,
+    /// A new follower.
+    Follow,
+    /// A new or renewed subscription, e.g. a bilibili guard.
+    Subscription,
+    /// Another channel sending its viewers over, a raid or host.
+    Raid,
+}
+
+impl MessageKind {
+    pub const ALL: [MessageKind; 6] = [
+        MessageKind::Chat,
+        MessageKind::Gift,
+        MessageKind::SuperChat,
+        MessageKind::Follow,
+        MessageKind::Subscription,
+        MessageKind::Raid,
+    ];
+    /// Platform events rather than something a viewer wrote.
+    pub const EVENTS: [MessageKind; 3] = [
+        MessageKind::Follow,
+        MessageKind::Subscription,
+        MessageKind::Raid,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageKind::Chat => "Chat",
+            MessageKind::Gift => "Gift",
+            MessageKind::SuperChat => "SuperChat",
+            MessageKind::Follow => "Follow",
+            MessageKind::Subscription => "Subscription",
+            MessageKind::Raid => "Raid",
+        }
+    }
+
+    pub fn is_event(self) -> bool {
+        Self::EVENTS.contains(&self)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentKind {
+    #[default]
+    Image,
+    Sticker,
+    /// Of a platform emote code taken out of the text.
+    Emote,
+}
+
+impl AttachmentKind {
+    pub const ALL: [AttachmentKind; 3] = [
+        AttachmentKind::Image,
+        AttachmentKind::Sticker,
+        AttachmentKind::Emote,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "Image",
+            AttachmentKind::Sticker => "Sticker",
+            AttachmentKind::Emote => "Emote",
+        }
+    }
+}
+
+/// An image carried by a message, either linked or inlined.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Base64 of the image bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+}
+
+impl Attachment {
+    pub fn url(kind: AttachmentKind, url: impl Into<String>) -> Self {
+        Self {
+            kind,
+            url: Some(url.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Where to load the image from, a `data:` URI for inlined ones.
+    pub fn src(&self) -> Option<String> {
+        match (&self.url, &self.data) {
+            (Some(url), _) => Some(url.clone()),
+            (None, Some(data)) => Some(format!(
+                "data:{};base64,{data}",
+                self.mime.as_deref().unwrap_or("image/png")
+            )),
+            (None, None) => None,
+        }
+    }
+}
+
+/// What a gift message is for, when the source says so.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Gift {
+    pub name: String,
+    pub count: u32,
+    /// Total worth of the gifts in the platform's own unit, e.g. bilibili
+    /// gold coins. 0 if unknown.
+    pub value: u64,
+}
+
+/// What a paid message like a SuperChat was paid.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Paid {
+    /// In the platform's currency, e.g. CNY on bilibili.
+    pub amount: f64,
+    /// How long the overlay should keep it pinned, filled in when
+    /// forwarded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_secs: Option<f64>,
+}
+
+/// Details of a platform event, when the source says so.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct PlatformEvent {
+    /// Subscribed for, of a subscription. 0 if unknown.
+    pub months: u32,
+    /// Brought along, of a raid. 0 if unknown.
+    pub viewers: u32,
+}
+
+/// A message flowing through the pipeline, also the envelope broadcast to
+/// overlay clients as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    #[serde(rename = "type", default)]
+    pub kind: MessageKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gift: Option<Gift>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paid: Option<Paid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<PlatformEvent>,
+}
+
+impl Message {
+    pub fn chat(text: impl Into<String>) -> Self {
+        Self {
+            kind: MessageKind::Chat,
+            username: None,
+            text: text.into(),
+            attachments: Vec::new(),
+            gift: None,
+            paid: None,
+            event: None,
+        }
+    }
+
+    /// A message of `kind` marked as a test, for checking how overlays
+    /// show it without waiting for a real one.
+    pub fn test(kind: MessageKind) -> Self {
+        let text = format!("[TEST] {} message", kind.name());
+        let mut msg = Self {
+            kind,
+            username: Some("Blooming Light".to_owned()),
+            ..Self::chat(text)
+        };
+        match kind {
+            MessageKind::Chat => {}
+            MessageKind::Gift => {
+                msg.gift = Some(Gift {
+                    name: "Test gift".to_owned(),
+                    count: 1,
+                    value: 0,
+                })
+            }
+            MessageKind::SuperChat => {
+                msg.paid = Some(Paid {
+                    amount: 30.0,
+                    pin_secs: None,
+                })
+            }
+            MessageKind::Follow => {}
+            MessageKind::Subscription => {
+                msg.event = Some(PlatformEvent {
+                    months: 1,
+                    viewers: 0,
+                })
+            }
+            MessageKind::Raid => {
+                msg.event = Some(PlatformEvent {
+                    months: 0,
+                    viewers: 42,
+                })
+            }
+        }
+        msg
+    }
+
+    pub fn has_image(&self) -> bool {
+        !self.attachments.is_empty()
+    }
+}
+
+/// Kinds dropped before they are queued, e.g. follows on a busy stream.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct KindFilter {
+    pub dropped: Vec<MessageKind>,
+}
+
+impl KindFilter {
+    pub fn drops(&self, msg: &Message) -> bool {
+        self.drops_kind(msg.kind)
+    }
+
+    pub fn drops_kind(&self, kind: MessageKind) -> bool {
+        self.dropped.contains(&kind)
+    }
+
+    pub fn set_dropped(&mut self, kind: MessageKind, dropped: bool) {
+        self.dropped.retain(|it| *it != kind);
+        if dropped {
+            self.dropped.push(kind);
+        }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Self::chat(text)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Self::chat(text)
+    }
+}
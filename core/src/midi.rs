@@ -0,0 +1,317 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::JoinHandle,
+};
+#[cfg(unix)]
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    os::{fd::AsFd, unix::fs::OpenOptionsExt},
+    thread,
+};
+
+#[cfg(not(unix))]
+use anyhow::bail;
+#[cfg(unix)]
+use anyhow::Context;
+#[cfg(unix)]
+use nix::{
+    errno::Errno,
+    fcntl::OFlag,
+    poll::{poll, PollFd, PollFlags},
+};
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use tracing::{info, warn};
+
+use crate::Notifier;
+
+/// A pad, key or button on a controller.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum MidiControl {
+    Note(u8),
+    /// Control change, pressed as its value goes from below 64 to 64 or
+    /// above, as most pads send 127 then 0.
+    Cc(u8),
+}
+
+/// A control on one channel, 1-16 as controllers label them.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct MidiInput {
+    pub channel: u8,
+    pub control: MidiControl,
+}
+
+impl fmt::Display for MidiInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.control {
+            MidiControl::Note(note) => write!(f, "Note {note}")?,
+            MidiControl::Cc(cc) => write!(f, "CC {cc}")?,
+        }
+        write!(f, " ch{}", self.channel)
+    }
+}
+
+/// Turns the raw byte stream of a MIDI port into presses. Running
+/// status is followed, everything but notes and control changes is
+/// skipped.
+#[derive(Debug, Default)]
+pub struct MidiParser {
+    status: Option<u8>,
+    data: Vec<u8>,
+    /// Last value of each control change, for telling presses apart.
+    cc_values: HashMap<(u8, u8), u8>,
+}
+
+impl MidiParser {
+    pub fn push(&mut self, byte: u8) -> Option<MidiInput> {
+        match byte {
+            // real-time, can come in between anything
+            0xf8.. => return None,
+            // system common and sysex, clear running status
+            0xf0..=0xf7 => {
+                self.status = None;
+                return None;
+            }
+            0x80..=0xef => {
+                self.status = Some(byte);
+                self.data.clear();
+                return None;
+            }
+            _ => {}
+        }
+        let status = self.status?;
+        self.data.push(byte);
+        let len = match status & 0xf0 {
+            0xc0 | 0xd0 => 1,
+            _ => 2,
+        };
+        if self.data.len() < len {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data);
+        let channel = (status & 0x0f) + 1;
+        let control = match (status & 0xf0, data.as_slice()) {
+            (0x90, &[note, velocity]) if velocity > 0 => {
+                MidiControl::Note(note)
+            }
+            (0xb0, &[cc, value]) => {
+                let last = self.cc_values.insert((channel, cc), value);
+                if value < 64 || last.is_some_and(|it| it >= 64) {
+                    return None;
+                }
+                MidiControl::Cc(cc)
+            }
+            _ => return None,
+        };
+        Some(MidiInput { channel, control })
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum MidiAction {
+    /// Holds the queue until pressed again.
+    TogglePause,
+    /// By the nudge step.
+    DelayUp,
+    DelayDown,
+    /// Forwards the oldest pending message right away.
+    ApproveNext,
+    /// Deletes the oldest pending message.
+    DeleteNext,
+}
+
+impl MidiAction {
+    pub const ALL: [MidiAction; 5] = [
+        MidiAction::TogglePause,
+        MidiAction::DelayUp,
+        MidiAction::DelayDown,
+        MidiAction::ApproveNext,
+        MidiAction::DeleteNext,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MidiAction::TogglePause => "Pause/Resume",
+            MidiAction::DelayUp => "Delay up",
+            MidiAction::DelayDown => "Delay down",
+            MidiAction::ApproveNext => "Approve next",
+            MidiAction::DeleteNext => "Delete next",
+        }
+    }
+}
+
+/// Which control runs each action, unbound ones are left out. A control
+/// runs one action at most.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct MidiBindings {
+    /// Port read from, e.g. `/dev/snd/midiC1D0`. Empty for none.
+    pub device: String,
+    pub bindings: Vec<(MidiAction, MidiInput)>,
+}
+
+impl MidiBindings {
+    pub fn get(&self, action: MidiAction) -> Option<MidiInput> {
+        self.bindings
+            .iter()
+            .find(|(it, _)| *it == action)
+            .map(|(_, input)| *input)
+    }
+
+    /// Takes `input` off any other action.
+    pub fn set(&mut self, action: MidiAction, input: Option<MidiInput>) {
+        self.bindings
+            .retain(|(it, bound)| *it != action && Some(*bound) != input);
+        if let Some(input) = input {
+            self.bindings.push((action, input));
+        }
+    }
+
+    pub fn action(&self, input: MidiInput) -> Option<MidiAction> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == input)
+            .map(|(action, _)| *action)
+    }
+}
+
+/// Raw MIDI ports of the sound cards. Only ALSA ones are found, on
+/// other platforms this is empty.
+pub fn devices() -> Vec<PathBuf> {
+    let mut devices = vec![];
+    for dir in ["/dev/snd", "/dev"] {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("midi") {
+                devices.push(entry.path());
+            }
+        }
+    }
+    devices.sort();
+    devices
+}
+
+/// How long a read waits for input before checking whether the
+/// listener was dropped, the longest dropping it blocks for.
+#[cfg(unix)]
+const POLL_TIMEOUT_MS: u16 = 100;
+
+/// Presses read from a MIDI port on a thread of its own, until dropped.
+/// The port is closed by the time it's dropped, so it can be opened
+/// again right away.
+pub struct MidiListener {
+    input_rx: mpsc::Receiver<MidiInput>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MidiListener {
+    /// `notifier` is called on every press.
+    #[cfg(unix)]
+    pub fn open(path: &Path, notifier: Notifier) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(OFlag::O_NONBLOCK.bits())
+            .open(path)
+            .with_context(|| {
+                format!("failed to open {}", path.display())
+            })?;
+        info!("listening MIDI port {}", path.display());
+        let (input_tx, input_rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_cloned = stop.clone();
+        let path = path.to_owned();
+        let thread = thread::spawn(move || {
+            let result = listen(file, &input_tx, &notifier, &stop_cloned);
+            if let Err(err) = result {
+                warn!(
+                    "failed to read MIDI port {}: {err}",
+                    path.display()
+                );
+            }
+            info!("MIDI port {} closed", path.display());
+        });
+        Ok(Self {
+            input_rx,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn open(path: &Path, notifier: Notifier) -> anyhow::Result<Self> {
+        let _ = notifier;
+        bail!(
+            "failed to open {}: raw MIDI ports are only read on Linux",
+            path.display()
+        )
+    }
+
+    pub fn pull(&self) -> Vec<MidiInput> {
+        self.input_rx.try_iter().collect()
+    }
+}
+
+impl Drop for MidiListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Reads presses from `file` until it's closed or `stop` is set.
+#[cfg(unix)]
+fn listen(
+    mut file: File,
+    input_tx: &mpsc::Sender<MidiInput>,
+    notifier: &Notifier,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    let mut parser = MidiParser::default();
+    let mut buf = [0; 64];
+    while !stop.load(Ordering::Relaxed) {
+        let mut fds = [PollFd::new(file.as_fd(), PollFlags::POLLIN)];
+        match poll(&mut fds, POLL_TIMEOUT_MS) {
+            Ok(0) | Err(Errno::EINTR) => continue,
+            Ok(_) => {}
+            Err(err) => return Err(err.into()),
+        }
+        let len = match file.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                continue
+            }
+            Err(err) => return Err(err),
+        };
+        for &byte in &buf[..len] {
+            if let Some(input) = parser.push(byte) {
+                // the receiver lives as long as the listener
+                let _ = input_tx.send(input);
+                notifier.notify();
+            }
+        }
+    }
+    Ok(())
+}
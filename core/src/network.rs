@@ -0,0 +1,701 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use anyhow::{anyhow, bail, Context};
+use chrono::Utc;
+use serde::Serialize;
+use tokio::{
+    select,
+    sync::{broadcast, mpsc as ampsc, oneshot, watch},
+    task as atask, time,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use self::supervisor::Backoff;
+use crate::{
+    channel::{self, ChannelStats},
+    combo::ComboUpdate,
+    log::{
+        self,
+        redact::{Found, Redaction},
+        upload::{self, UploadTarget},
+        LogConfig, LogEntry, LogEvent, RedactRequest,
+    },
+    message::Message,
+    poll::PollFrame,
+    queue::SharedQueue,
+    release::{next_wake, ReleaseConfig, Released, Releaser},
+    timer::TimerFrame,
+    Notifier,
+};
+
+pub use self::{
+    actions::{Action, ActionRequest, ActionState},
+    runtime::RuntimeConfig,
+    server::{OverlayFrame, ServerConfig},
+    status::SourceStatus,
+    ws_client::WsClientConfig,
+};
+
+pub mod access;
+pub mod access_log;
+pub mod actions;
+pub mod decoder;
+pub mod discovery;
+pub mod emote;
+pub mod fetch;
+mod local_socket;
+pub mod operator;
+pub mod proxy;
+mod runtime;
+mod server;
+pub mod status;
+pub mod supervisor;
+pub mod theme;
+pub mod tls;
+mod ws_client;
+
+/// Messages from the source not yet pulled by the frontend. Beyond this
+/// the oldest are dropped.
+const WS_MSG_RECV_CAPACITY: usize = 10000;
+/// Log entries not yet written to disk.
+const LOG_CAPACITY: usize = 10000;
+/// Messages kept for overlays that fall behind. Beyond this they skip
+/// the oldest.
+const WS_MSG_SEND_CAPACITY: usize = 114514;
+
+pub struct Network {
+    join_handle: JoinHandle<()>,
+
+    err_rx: mpsc::Receiver<anyhow::Error>,
+    err_server_rx: mpsc::Receiver<anyhow::Error>,
+    err_ws_client_rx: mpsc::Receiver<anyhow::Error>,
+
+    ws_msg_recv_rx: channel::Receiver<(Message, Instant)>,
+    ws_status_rx: mpsc::Receiver<SourceStatus>,
+    ws_msg_send_tx: broadcast::Sender<OverlayFrame>,
+    ws_lagged: Arc<AtomicU64>,
+
+    release_config_tx: watch::Sender<ReleaseConfig>,
+    released_rx: mpsc::Receiver<Released>,
+    action_rx: mpsc::Receiver<ActionRequest>,
+
+    stop_token: CancellationToken,
+
+    ctrl_tx: ampsc::UnboundedSender<NetworkCmd>,
+    log_tx: channel::Sender<LogEntry>,
+    log_redact_tx: ampsc::UnboundedSender<RedactRequest>,
+}
+
+impl Network {
+    /// Messages are released from `queue` on the network thread, see
+    /// [`Network::set_release_config`].
+    pub fn new(
+        notifier: Notifier,
+        queue: SharedQueue,
+        runtime_config: RuntimeConfig,
+        log_config: LogConfig,
+        server_config: ServerConfig,
+        ws_client_config: WsClientConfig,
+    ) -> Self {
+        info!("initializing network");
+        let (err_tx, err_rx) = mpsc::channel();
+        let (err_server_tx, err_server_rx) = mpsc::channel();
+        let (err_ws_client_tx, err_ws_client_rx) = mpsc::channel();
+
+        let (ws_msg_recv_tx, ws_msg_recv_rx) =
+            channel::notifying(WS_MSG_RECV_CAPACITY, notifier.clone());
+        let (ws_status_tx, ws_status_rx) = mpsc::channel();
+        let (ws_msg_send_tx, _) =
+            broadcast::channel::<OverlayFrame>(WS_MSG_SEND_CAPACITY);
+        let ws_lagged = Arc::new(AtomicU64::new(0));
+        // nothing goes out before the frontend sends its settings
+        let (release_config_tx, release_config_rx) =
+            watch::channel(ReleaseConfig {
+                paused: true,
+                ..Default::default()
+            });
+        let (released_tx, released_rx) = mpsc::channel();
+        let (action_tx, action_rx) = mpsc::channel();
+
+        let stop_token = CancellationToken::new();
+        let (ctrl_tx, mut ctrl_rx) = ampsc::unbounded_channel();
+        let (log_tx, log_rx) = channel::bounded(LOG_CAPACITY);
+        let (log_redact_tx, log_redact_rx) = ampsc::unbounded_channel();
+
+        let stop_token_cloned = stop_token.clone();
+        let notifier_cloned = notifier.clone();
+        let ws_msg_send_tx_cloned = ws_msg_send_tx.clone();
+        let ws_lagged_cloned = Arc::clone(&ws_lagged);
+        let log_tx_cloned = log_tx.clone();
+        let network_fut = async move {
+            let mut server_config = server_config;
+            let mut ws_client_config = ws_client_config;
+
+            let spawn_server = |config: &ServerConfig| {
+                let (stop_token, fut) = server::run_server(
+                    config.clone(),
+                    ws_msg_send_tx_cloned.clone(),
+                    Arc::clone(&ws_lagged_cloned),
+                    action_tx.clone(),
+                    notifier_cloned.clone(),
+                );
+                (stop_token, atask::spawn(fut))
+            };
+            let spawn_ws_client = |config: &WsClientConfig| {
+                let (stop_token, fut) = ws_client::run_ws_client(
+                    config.clone(),
+                    ws_msg_recv_tx.clone(),
+                    ws_status_tx.clone(),
+                    notifier_cloned.clone(),
+                );
+                (stop_token, atask::spawn(fut))
+            };
+
+            let (mut server_stop_token, mut server_handle) =
+                spawn_server(&server_config);
+            let mut server_backoff = Backoff::default();
+            server_backoff.on_start(Instant::now());
+            let mut server_running = true;
+            let mut server_retry_at = None;
+            let (mut ws_client_stop_token, mut ws_client_handle) =
+                spawn_ws_client(&ws_client_config);
+            let mut ws_client_backoff = Backoff::default();
+            ws_client_backoff.on_start(Instant::now());
+            let mut ws_client_running = true;
+            let mut ws_client_retry_at = None;
+
+            let upload_target = log_config.upload.clone();
+            let started_at = Utc::now();
+            let (log_writer_stop_token, log_writer_fut) = log::run_writer(
+                log::default_path(),
+                log_rx,
+                log_redact_rx,
+                log_config,
+            );
+            let mut log_writer_handle = atask::spawn(log_writer_fut);
+
+            let releaser_stop_token = stop_token_cloned.child_token();
+            let releaser_handle = atask::spawn(run_releaser(
+                queue,
+                release_config_rx,
+                ws_msg_send_tx_cloned.clone(),
+                log_tx_cloned,
+                released_tx,
+                notifier_cloned.clone(),
+                releaser_stop_token.clone(),
+            ));
+
+            // NOTE: tuple due to rustfmt will mess with args formatting
+            let handle_task_result = |(name, result, err_tx): (
+                &'static str,
+                Result<anyhow::Result<()>, atask::JoinError>,
+                Option<mpsc::Sender<anyhow::Error>>,
+            )| {
+                let err = match result.with_context(|| {
+                    format!("failed to join {name} task")
+                }) {
+                    Ok(result) => {
+                        match result.with_context(|| {
+                            format!("{name} task exited with an error")
+                        }) {
+                            Ok(_) => {
+                                info!("{name} exited");
+                                Some(anyhow!("{name} exited"))
+                            }
+                            Err(err) => {
+                                error!("{err:?}");
+                                Some(err)
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        error!("{err:?}");
+                        Some(err)
+                    }
+                };
+                if let (Some(err_tx), Some(err)) = (err_tx, err) {
+                    let _ = err_tx.send(err);
+                    notifier_cloned.notify();
+                }
+            };
+
+            loop {
+                select! {
+                    _ = stop_token_cloned.cancelled()=> {
+                        break;
+                    }
+                    cmd = ctrl_rx.recv() => {
+                        let Some(cmd) = cmd else {
+                            break;
+                        };
+                        match cmd {
+                            NetworkCmd::RestartServer(config, done_tx) => {
+                                info!("restarting server");
+                                if let Some(config) = config {
+                                    server_config = *config;
+                                }
+                                server_stop_token.cancel();
+                                if server_running {
+                                    info!("waiting previous server to finish");
+                                    handle_task_result(("server", server_handle.await, None));
+                                }
+                                (server_stop_token, server_handle) = spawn_server(&server_config);
+                                server_backoff.reset();
+                                server_backoff.on_start(Instant::now());
+                                server_running = true;
+                                server_retry_at = None;
+                                let _ = done_tx.send(());
+                            },
+                            NetworkCmd::RestartWsClient(config, done_tx) => {
+                                info!("restarting ws_client");
+                                if let Some(config) = config {
+                                    ws_client_config = *config;
+                                }
+                                ws_client_stop_token.cancel();
+                                if ws_client_running {
+                                    info!("waiting previous ws_client to finish");
+                                    handle_task_result(("ws_client", ws_client_handle.await, None));
+                                }
+                                (ws_client_stop_token, ws_client_handle) = spawn_ws_client(&ws_client_config);
+                                ws_client_backoff.reset();
+                                ws_client_backoff.on_start(Instant::now());
+                                ws_client_running = true;
+                                ws_client_retry_at = None;
+                                let _ = done_tx.send(());
+                            },
+                            NetworkCmd::RestartAll(done_tx) => {
+                                info!("restarting all");
+                                server_stop_token.cancel();
+                                ws_client_stop_token.cancel();
+                                if server_running {
+                                    handle_task_result(("server", server_handle.await, None));
+                                }
+                                if ws_client_running {
+                                    handle_task_result(("ws_client", ws_client_handle.await, None));
+                                }
+                                (server_stop_token, server_handle) = spawn_server(&server_config);
+                                server_backoff.reset();
+                                server_backoff.on_start(Instant::now());
+                                server_running = true;
+                                server_retry_at = None;
+                                (ws_client_stop_token, ws_client_handle) = spawn_ws_client(&ws_client_config);
+                                ws_client_backoff.reset();
+                                ws_client_backoff.on_start(Instant::now());
+                                ws_client_running = true;
+                                ws_client_retry_at = None;
+                                let _ = done_tx.send(());
+                            },
+                            NetworkCmd::Drain(done_tx) => {
+                                info!("draining, stopping ws_client");
+                                ws_client_stop_token.cancel();
+                                if ws_client_running {
+                                    handle_task_result(("ws_client", (&mut ws_client_handle).await, None));
+                                }
+                                ws_client_running = false;
+                                ws_client_retry_at = None;
+                                let _ = done_tx.send(());
+                            },
+                        }
+                    }
+                    result = &mut log_writer_handle => {
+                        result.context("failed to join log writer task")??;
+                        bail!("log writer exited");
+                    }
+                    result = &mut server_handle, if server_running => {
+                        server_running = false;
+                        if let Some(delay) = server_backoff.on_exit(Instant::now()) {
+                            handle_task_result(("server", result, None));
+                            warn!("restarting server in {delay:?}, attempt {}", server_backoff.failures());
+                            server_retry_at = Some(time::Instant::now() + delay);
+                        } else {
+                            handle_task_result(("server", result, Some(err_server_tx.clone())));
+                        }
+                    }
+                    _ = time::sleep_until(server_retry_at.unwrap_or_else(time::Instant::now)), if server_retry_at.is_some() => {
+                        info!("restarting server");
+                        (server_stop_token, server_handle) = spawn_server(&server_config);
+                        server_backoff.on_start(Instant::now());
+                        server_running = true;
+                        server_retry_at = None;
+                    }
+                    result = &mut ws_client_handle, if ws_client_running => {
+                        ws_client_running = false;
+                        if let Some(delay) = ws_client_backoff.on_exit(Instant::now()) {
+                            handle_task_result(("ws_client", result, None));
+                            warn!("restarting ws_client in {delay:?}, attempt {}", ws_client_backoff.failures());
+                            ws_client_retry_at = Some(time::Instant::now() + delay);
+                        } else {
+                            handle_task_result(("ws_client", result, Some(err_ws_client_tx.clone())));
+                        }
+                    }
+                    _ = time::sleep_until(ws_client_retry_at.unwrap_or_else(time::Instant::now)), if ws_client_retry_at.is_some() => {
+                        info!("restarting ws_client");
+                        (ws_client_stop_token, ws_client_handle) = spawn_ws_client(&ws_client_config);
+                        ws_client_backoff.on_start(Instant::now());
+                        ws_client_running = true;
+                        ws_client_retry_at = None;
+                    }
+                };
+            }
+
+            server_stop_token.cancel();
+            ws_client_stop_token.cancel();
+            if server_running {
+                handle_task_result(("server", server_handle.await, None));
+            }
+            if ws_client_running {
+                handle_task_result((
+                    "ws_client",
+                    ws_client_handle.await,
+                    None,
+                ));
+            }
+            // its last forwards go in the log before the end marker
+            releaser_stop_token.cancel();
+            if let Err(err) = releaser_handle.await {
+                error!("failed to join releaser task: {err:?}");
+            }
+
+            log_writer_stop_token.cancel();
+            let session_start = log_writer_handle
+                .await
+                .context("failed to join log writer task")??;
+
+            if upload_target != UploadTarget::None {
+                info!(
+                    "uploading session log to {}",
+                    upload_target.name()
+                );
+                let (log_path, access_log_path) =
+                    (log::default_path(), access_log::default_path());
+                let upload = upload::upload_session(
+                    &upload_target,
+                    &ws_client_config.proxy,
+                    &log_path,
+                    session_start,
+                    &access_log_path,
+                    started_at,
+                );
+                match time::timeout(upload::STOP_DEADLINE, upload).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => error!("{err:?}"),
+                    Err(_) => error!(
+                        "gave up uploading session log after {:?}",
+                        upload::STOP_DEADLINE
+                    ),
+                }
+            }
+
+            anyhow::Result::<()>::Ok(())
+        };
+
+        let network_handle = {
+            thread::spawn(move || {
+                let result = runtime_config
+                    .build()
+                    .context("failed to build tokio runtime")
+                    .and_then(|rt| rt.block_on(network_fut));
+
+                if let Err(err) = result {
+                    error!("{err:?}");
+                    let _ = err_tx.send(err);
+                    notifier.notify();
+                };
+            })
+        };
+
+        Self {
+            join_handle: network_handle,
+
+            err_rx,
+            err_server_rx,
+            err_ws_client_rx,
+
+            ws_msg_recv_rx,
+            ws_status_rx,
+            ws_msg_send_tx,
+            ws_lagged,
+
+            release_config_tx,
+            released_rx,
+            action_rx,
+
+            stop_token,
+            ctrl_tx,
+            log_tx,
+            log_redact_tx,
+        }
+    }
+
+    pub fn pull_err(&self) -> Option<anyhow::Error> {
+        self.err_rx.try_recv().ok()
+    }
+
+    pub fn pull_server_err(&self) -> Option<anyhow::Error> {
+        self.err_server_rx.try_recv().ok()
+    }
+
+    pub fn pull_ws_client_err(&self) -> Option<anyhow::Error> {
+        self.err_ws_client_rx.try_recv().ok()
+    }
+
+    /// Returns up to `max` messages, oldest first, along with when the
+    /// source received them. The frontend is notified on arrival, there
+    /// is no need to call this unless woken up.
+    pub fn pull_ws_messages(
+        &self,
+        max: usize,
+    ) -> Vec<(Message, Instant)> {
+        self.ws_msg_recv_rx.drain(max)
+    }
+
+    pub fn pull_ws_client_status(&self) -> Option<SourceStatus> {
+        self.ws_status_rx.try_recv().ok()
+    }
+
+    /// Returns whether any overlay client was listening.
+    pub fn ws_message_stats(&self) -> ChannelStats {
+        self.ws_msg_recv_rx.stats()
+    }
+
+    pub fn log_stats(&self) -> ChannelStats {
+        self.log_tx.stats()
+    }
+
+    /// Messages the slowest overlay has yet to get, and how many
+    /// overlays skipped by falling behind.
+    pub fn ws_broadcast_stats(&self) -> ChannelStats {
+        ChannelStats {
+            len: self.ws_msg_send_tx.len(),
+            capacity: WS_MSG_SEND_CAPACITY,
+            dropped: self.ws_lagged.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn broadcast_ws_message(&self, msg: &Message) -> bool {
+        broadcast(&self.ws_msg_send_tx, msg, OverlayFrame::Message)
+    }
+
+    pub fn broadcast_combo(&self, combo: &ComboUpdate) -> bool {
+        broadcast(&self.ws_msg_send_tx, combo, OverlayFrame::Message)
+    }
+
+    pub fn broadcast_timer(&self, timer: &TimerFrame) -> bool {
+        broadcast(&self.ws_msg_send_tx, timer, OverlayFrame::Control)
+    }
+
+    pub fn broadcast_poll(&self, poll: &PollFrame) -> bool {
+        broadcast(&self.ws_msg_send_tx, poll, OverlayFrame::Control)
+    }
+
+    /// Cheap to call every frame, the releasing task only wakes up when
+    /// something changed.
+    pub fn set_release_config(&self, config: ReleaseConfig) {
+        self.release_config_tx.send_if_modified(|it| {
+            if *it == config {
+                return false;
+            }
+            *it = config;
+            true
+        });
+    }
+
+    /// Messages forwarded since the last call, already broadcast and
+    /// logged.
+    pub fn pull_released(&self) -> Vec<Released> {
+        self.released_rx.try_iter().collect()
+    }
+
+    /// Action API requests since the last call, each waiting for a
+    /// reply. The frontend is notified on arrival.
+    pub fn pull_action_requests(&self) -> Vec<ActionRequest> {
+        self.action_rx.try_iter().collect()
+    }
+
+    pub fn write_log(&self, msg: Message, event: LogEvent) {
+        self.write_log_entry(LogEntry::new(msg, event));
+    }
+
+    pub fn write_log_entry(&self, entry: LogEntry) {
+        let result = self.log_tx.send(entry);
+        if result.is_err() {
+            error!("failed to write log: log task is gone");
+        }
+    }
+
+    /// Waits for the log writer to rewrite the log and its archives.
+    pub fn redact_log(
+        &self,
+        redaction: Redaction,
+    ) -> anyhow::Result<Vec<Found>> {
+        let (tx, rx) = oneshot::channel();
+        self.log_redact_tx
+            .send((redaction, tx))
+            .context("failed to send redaction: log writer is gone")?;
+        rx.blocking_recv()
+            .context("log writer exited before redacting")?
+    }
+
+    pub fn restart_server(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::RestartServer(None, tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    /// Replaces the server config and restarts the server with it.
+    pub fn set_server_config(
+        &self,
+        config: ServerConfig,
+    ) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::RestartServer(Some(Box::new(config)), tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    pub fn restart_ws_client(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::RestartWsClient(None, tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    /// Replaces the ws_client config and reconnects with it.
+    pub fn set_ws_client_config(
+        &self,
+        config: WsClientConfig,
+    ) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::RestartWsClient(Some(Box::new(config)), tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    pub fn restart_all(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::RestartAll(tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    /// Stops the ws_client without restarting it, so no new source
+    /// messages arrive while the server keeps forwarding what's already
+    /// queued. Undone by restarting the ws_client.
+    pub fn drain(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.ctrl_tx
+            .send(NetworkCmd::Drain(tx))
+            .context("failed to send command")?;
+        let _ = rx.blocking_recv();
+        Ok(())
+    }
+
+    pub fn stop(self) {
+        self.stop_token.cancel();
+        info!("waiting network thread to finish");
+        if let Err(err) = self.join_handle.join() {
+            error!("network thread panic with: {err:?}");
+        }
+    }
+}
+
+enum NetworkCmd {
+    RestartServer(Option<Box<ServerConfig>>, oneshot::Sender<()>),
+    RestartWsClient(Option<Box<WsClientConfig>>, oneshot::Sender<()>),
+    RestartAll(oneshot::Sender<()>),
+    Drain(oneshot::Sender<()>),
+}
+
+/// Releases due messages from `queue` as soon as they are due, rather
+/// than whenever the frontend happens to draw a frame.
+async fn run_releaser(
+    queue: SharedQueue,
+    mut config_rx: watch::Receiver<ReleaseConfig>,
+    ws_msg_send_tx: broadcast::Sender<OverlayFrame>,
+    log_tx: channel::Sender<LogEntry>,
+    released_tx: mpsc::Sender<Released>,
+    notifier: Notifier,
+    stop_token: CancellationToken,
+) {
+    let mut releaser = Releaser::default();
+    loop {
+        let (released, wait, alerts) = {
+            let config = config_rx.borrow_and_update();
+            let mut queue = queue.lock_quiet();
+            let released = releaser.release(&mut queue, &config);
+            let alerts =
+                (!released.is_empty()).then(|| config.alerts.clone());
+            (released, next_wake(&queue, &config), alerts)
+        };
+        if let Some(alerts) = alerts {
+            for mut it in released {
+                alerts.fire(&it.filtered);
+                for frame in &it.frames {
+                    it.sent |= broadcast(
+                        &ws_msg_send_tx,
+                        frame,
+                        OverlayFrame::Message,
+                    );
+                }
+                let entry =
+                    LogEntry::new(it.msg.clone(), LogEvent::Forward)
+                        .with_urls(it.urls.clone());
+                if log_tx.send(entry).is_err() {
+                    error!("failed to write log: log task is gone");
+                }
+                let _ = released_tx.send(it);
+            }
+            notifier.notify();
+        }
+
+        select! {
+            _ = stop_token.cancelled() => break,
+            _ = queue.changed() => {}
+            result = config_rx.changed() => {
+                if result.is_err() {
+                    break;
+                }
+            }
+            _ = time::sleep(wait.unwrap_or_default()), if wait.is_some() => {}
+        }
+    }
+}
+
+fn broadcast(
+    ws_msg_send_tx: &broadcast::Sender<OverlayFrame>,
+    msg: &impl Serialize,
+    to_frame: fn(String) -> OverlayFrame,
+) -> bool {
+    let msg = match serde_json::to_string(msg) {
+        Ok(msg) => msg,
+        Err(err) => {
+            error!("failed to serialize message: {err:?}");
+            return false;
+        }
+    };
+    let result = ws_msg_send_tx.send(to_frame(msg));
+    if let Err(err) = result {
+        debug!("failed to send message to websocket threads: {err}");
+        return false;
+    }
+    true
+}
@@ -0,0 +1,136 @@
+use std::{fmt, net::IpAddr, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A single address or a CIDR range, e.g. `192.168.1.0/24`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpRange {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, canonical(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let (net, ip) =
+                    (net.to_bits().into(), ip.to_bits().into());
+                prefix_eq(net, ip, 32, self.prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_eq(net.to_bits(), ip.to_bits(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s.trim(), None),
+        };
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid address {addr:?}"))?;
+        let addr = canonical(addr);
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|it| *it <= max)
+                .ok_or_else(|| format!("invalid prefix {prefix:?}"))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let max = if self.addr.is_ipv4() { 32 } else { 128 };
+        if self.prefix == max {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix)
+        }
+    }
+}
+
+impl TryFrom<String> for IpRange {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<IpRange> for String {
+    fn from(value: IpRange) -> Self {
+        value.to_string()
+    }
+}
+
+/// Which clients may reach the embedded server. Clients on this machine
+/// are always let in, so local overlays keep working whatever the lists
+/// say.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpAccess {
+    /// Empty to allow everyone not denied.
+    pub allow: Vec<IpRange>,
+    pub deny: Vec<IpRange>,
+}
+
+impl IpAccess {
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        if canonical(ip).is_loopback() {
+            return true;
+        }
+        if self.deny.iter().any(|it| it.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty()
+            || self.allow.iter().any(|it| it.contains(ip))
+    }
+
+    /// Ranges, comma separated.
+    pub fn format(ranges: &[IpRange]) -> String {
+        ranges
+            .iter()
+            .map(IpRange::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Reads [`Self::format`] back, `None` if any range is invalid.
+    pub fn parse(ranges: &str) -> Option<Vec<IpRange>> {
+        ranges
+            .split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .map(|it| it.parse().ok())
+            .collect()
+    }
+}
+
+/// IPv4 clients of a dual-stack listener show up as mapped IPv6
+/// addresses.
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => {
+            v6.to_ipv4_mapped().map_or(IpAddr::V6(v6), IpAddr::V4)
+        }
+        ip => ip,
+    }
+}
+
+fn prefix_eq(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let shift = bits - prefix;
+    shift == bits || net >> shift == ip >> shift
+}
@@ -0,0 +1,124 @@
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Utc};
+
+/// Size the log grows to before it's rotated out.
+pub const MAX_BYTES: u64 = 10 << 20;
+/// Rotated logs kept besides the current one.
+pub const ROTATED_FILES: usize = 3;
+
+const STAMP: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+pub fn default_path() -> PathBuf {
+    current_dir().unwrap_or_default().join("access.log")
+}
+
+/// `path` rotated out `idx` times, `access.log.1` being the newest.
+pub fn rotated_path(path: &Path, idx: usize) -> PathBuf {
+    let mut name = OsString::from(path);
+    name.push(format!(".{idx}"));
+    name.into()
+}
+
+/// Requests to the embedded server and overlays connecting to it, one
+/// plain text line each. Kept apart from the tracing output so
+/// connectivity problems can be looked into after the fact.
+pub struct AccessLog {
+    path: PathBuf,
+    max_bytes: u64,
+    /// With how much is in it.
+    file: Mutex<(File, u64)>,
+}
+
+impl AccessLog {
+    pub fn open(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        let file = append(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_owned(),
+            max_bytes,
+            file: Mutex::new((file, len)),
+        })
+    }
+
+    /// Appends `what` happened with the client at `addr`, rotating the
+    /// log first if it would grow past its limit.
+    pub fn write(&self, addr: SocketAddr, what: &str) -> io::Result<()> {
+        let line =
+            format!("{} {addr} {what}\n", Utc::now().format(STAMP));
+        let mut file = self.file.lock().unwrap();
+        let (ref mut file, ref mut len) = *file;
+        if *len > 0 && *len + line.len() as u64 > self.max_bytes {
+            *file = self.rotate()?;
+            *len = 0;
+        }
+        file.write_all(line.as_bytes())?;
+        *len += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&self) -> io::Result<File> {
+        let _ = fs::remove_file(rotated_path(&self.path, ROTATED_FILES));
+        for idx in (1..ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, idx);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, idx + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        append(&self.path)
+    }
+}
+
+/// `path_and_query` with the value of any `token` parameter replaced,
+/// so the action API token isn't written to the log.
+pub fn redact(path_and_query: &str) -> Cow<'_, str> {
+    let Some((path, query)) = path_and_query.split_once('?') else {
+        return Cow::Borrowed(path_and_query);
+    };
+    let params = query.split('&').map(|it| match it.split_once('=') {
+        Some(("token", _)) => "token=redacted",
+        _ => it,
+    });
+    Cow::Owned(format!("{path}?{}", params.collect::<Vec<_>>().join("&")))
+}
+
+/// Lines written to the log at `path`, or rotated out of it, at or
+/// after `since`, oldest first.
+pub fn read_since(
+    path: &Path,
+    since: DateTime<Utc>,
+) -> io::Result<String> {
+    let since = since.format(STAMP).to_string();
+    let paths = (1..=ROTATED_FILES)
+        .rev()
+        .map(|idx| rotated_path(path, idx))
+        .chain([path.to_owned()]);
+    let mut lines = String::new();
+    for path in paths {
+        let content = match fs::read_to_string(&path) {
+            Ok(it) => it,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        // stamps are fixed width, so they order as text
+        for line in content.lines().filter(|it| *it >= since.as_str()) {
+            lines.push_str(line);
+            lines.push('\n');
+        }
+    }
+    Ok(lines)
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
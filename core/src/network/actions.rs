@@ -0,0 +1,101 @@
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use super::operator::same;
+
+/// Run by the frontend when asked over `/api/actions/<slug>`, meant to
+/// be bound to Stream Deck buttons, or by a chat command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// Holds the queue, or lets it go again.
+    TogglePause,
+    /// Forwards the oldest pending message right away.
+    SendNext,
+    /// Deletes every pending message.
+    Purge,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] =
+        [Action::TogglePause, Action::SendNext, Action::Purge];
+
+    pub fn slug(self) -> &'static str {
+        match self {
+            Action::TogglePause => "pause",
+            Action::SendNext => "send-next",
+            Action::Purge => "purge",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::TogglePause => "Pause/Resume",
+            Action::SendNext => "Send next",
+            Action::Purge => "Purge queue",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Action::ALL.into_iter().find(|it| it.slug() == slug)
+    }
+}
+
+/// Replied to every action API request, so the button can show it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionState {
+    /// Held by hand or by the pause schedule.
+    pub paused: bool,
+    /// Queued, waiting and scheduled messages.
+    pub pending: usize,
+}
+
+/// An action API request waiting for the frontend, `None` to only ask
+/// for the state.
+#[derive(Debug)]
+pub struct ActionRequest {
+    pub action: Option<Action>,
+    reply_tx: oneshot::Sender<ActionState>,
+}
+
+impl ActionRequest {
+    pub fn new(
+        action: Option<Action>,
+    ) -> (Self, oneshot::Receiver<ActionState>) {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        (Self { action, reply_tx }, reply_rx)
+    }
+
+    /// With the state after running the action.
+    pub fn reply(self, state: ActionState) {
+        // the client may have given up
+        let _ = self.reply_tx.send(state);
+    }
+}
+
+/// Random token for [`super::ServerConfig::api_token`].
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Whether a request carries `token`, as `Authorization: Bearer <token>`
+/// or a `token` query parameter for clients that can only open a URL.
+/// Never with an empty `token`, the API is off then.
+pub fn authorized(
+    token: &str,
+    authorization: Option<&str>,
+    query_token: Option<&str>,
+) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let given = authorization
+        .and_then(|it| it.strip_prefix("Bearer "))
+        .or(query_token);
+    given.is_some_and(|given| same(given.as_bytes(), token.as_bytes()))
+}
@@ -0,0 +1,277 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::emote::{twitch_emotes, Emote, EmoteMode};
+use crate::message::{
+    Attachment, AttachmentKind, Gift, Message, MessageKind, Paid,
+    PlatformEvent,
+};
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DecoderKind {
+    /// Every text frame is a chat message.
+    #[default]
+    Text,
+    /// Frames are already in the overlay envelope format.
+    Envelope,
+    /// Fields are picked out of a JSON frame by [`JsonPaths`].
+    JsonPath,
+    /// `DANMU_MSG`, `SEND_GIFT`, `SUPER_CHAT_MESSAGE`, `GUARD_BUY` and
+    /// follows in `INTERACT_WORD` commands of the bilibili live danmaku
+    /// protocol, as relayed in decoded JSON form.
+    Bilibili,
+}
+
+impl DecoderKind {
+    pub const ALL: [DecoderKind; 4] = [
+        DecoderKind::Text,
+        DecoderKind::Envelope,
+        DecoderKind::JsonPath,
+        DecoderKind::Bilibili,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DecoderKind::Text => "Plain text",
+            DecoderKind::Envelope => "Envelope",
+            DecoderKind::JsonPath => "JSON path",
+            DecoderKind::Bilibili => "bilibili",
+        }
+    }
+}
+
+/// Dotted paths into a JSON frame, e.g. `data.info.1`. Numeric segments
+/// index into arrays.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonPaths {
+    pub text: String,
+    /// Empty for no username.
+    pub username: String,
+    /// Should point at `chat`, `gift`, `superchat`, `follow`,
+    /// `subscription` or `raid`. Empty or anything else means chat.
+    pub kind: String,
+    /// URL of an attached image. Empty for none.
+    pub image: String,
+    /// Amount paid for the message. Empty for none.
+    pub amount: String,
+    /// A Twitch style `emotes` tag, e.g. `25:0-4/1902:6-10`. Empty for
+    /// none.
+    pub emotes: String,
+}
+
+impl Default for JsonPaths {
+    fn default() -> Self {
+        Self {
+            text: "text".to_owned(),
+            username: String::new(),
+            kind: String::new(),
+            image: String::new(),
+            amount: String::new(),
+            emotes: String::new(),
+        }
+    }
+}
+
+/// How raw upstream frames map into [`Message`]s.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecoderConfig {
+    pub kind: DecoderKind,
+    pub json_paths: JsonPaths,
+    pub emotes: EmoteMode,
+}
+
+impl DecoderConfig {
+    /// Returns `None` for frames that carry no message, like heartbeats
+    /// or commands the decoder doesn't know about.
+    pub fn decode(&self, frame: &str) -> anyhow::Result<Option<Message>> {
+        match self.kind {
+            DecoderKind::Text => Ok(Some(Message::chat(frame))),
+            DecoderKind::Envelope => serde_json::from_str(frame)
+                .map(Some)
+                .context("invalid envelope frame"),
+            DecoderKind::JsonPath => {
+                let value = parse(frame)?;
+                let msg = decode_json_path(&self.json_paths, &value)?;
+                Ok(msg.map(|mut msg| {
+                    let emotes =
+                        lookup_str(&value, &self.json_paths.emotes)
+                            .map(|it| twitch_emotes(&it, &msg.text))
+                            .unwrap_or_default();
+                    self.emotes.apply(&mut msg, emotes);
+                    msg
+                }))
+            }
+            DecoderKind::Bilibili => {
+                let value = parse(frame)?;
+                Ok(decode_bilibili(&value).map(|mut msg| {
+                    let emotes = bilibili_emotes(&value, &msg.text);
+                    self.emotes.apply(&mut msg, emotes);
+                    msg
+                }))
+            }
+        }
+    }
+}
+
+fn parse(frame: &str) -> anyhow::Result<Value> {
+    serde_json::from_str(frame).context("frame is not json")
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, seg| match value {
+        Value::Array(items) => items.get(seg.parse::<usize>().ok()?),
+        _ => value.get(seg),
+    })
+}
+
+fn lookup_str(value: &Value, path: &str) -> Option<String> {
+    if path.is_empty() {
+        return None;
+    }
+    match lookup(value, path)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn decode_json_path(
+    paths: &JsonPaths,
+    value: &Value,
+) -> anyhow::Result<Option<Message>> {
+    let Some(text) = lookup_str(value, &paths.text) else {
+        return Ok(None);
+    };
+    let kind = lookup(value, &paths.kind)
+        .filter(|_| !paths.kind.is_empty())
+        .and_then(|it| MessageKind::deserialize(it).ok())
+        .unwrap_or_default();
+    Ok(Some(Message {
+        kind,
+        username: lookup_str(value, &paths.username),
+        text,
+        attachments: lookup_str(value, &paths.image)
+            .map(|it| Attachment::url(AttachmentKind::Image, it))
+            .into_iter()
+            .collect(),
+        gift: None,
+        paid: lookup_amount(value, &paths.amount),
+        event: None,
+    }))
+}
+
+fn lookup_amount(value: &Value, path: &str) -> Option<Paid> {
+    let amount = lookup_str(value, path)?.parse::<f64>().ok()?;
+    Some(Paid {
+        amount,
+        pin_secs: None,
+    })
+}
+
+fn decode_bilibili(value: &Value) -> Option<Message> {
+    let mut gift = None;
+    let mut paid = None;
+    let mut event = None;
+    let (kind, username, text) =
+        match value.get("cmd")?.as_str()?.split(':').next()? {
+            "DANMU_MSG" => (
+                MessageKind::Chat,
+                lookup_str(value, "info.2.1"),
+                lookup_str(value, "info.1")?,
+            ),
+            "SEND_GIFT" => {
+                let name = lookup_str(value, "data.giftName")?;
+                let count = lookup_str(value, "data.num")
+                    .and_then(|it| it.parse().ok())
+                    .unwrap_or(1);
+                let text = format!("{name} x{count}");
+                gift = Some(Gift {
+                    name,
+                    count,
+                    value: lookup_str(value, "data.total_coin")
+                        .and_then(|it| it.parse().ok())
+                        .unwrap_or(0),
+                });
+                (MessageKind::Gift, lookup_str(value, "data.uname"), text)
+            }
+            "SUPER_CHAT_MESSAGE" => {
+                paid = lookup_amount(value, "data.price");
+                (
+                    MessageKind::SuperChat,
+                    lookup_str(value, "data.user_info.uname"),
+                    lookup_str(value, "data.message")?,
+                )
+            }
+            // entering the room and the like share the command
+            "INTERACT_WORD"
+                if lookup_str(value, "data.msg_type")? == "2" =>
+            {
+                (
+                    MessageKind::Follow,
+                    lookup_str(value, "data.uname"),
+                    "followed".to_owned(),
+                )
+            }
+            "GUARD_BUY" => {
+                let name = lookup_str(value, "data.gift_name")?;
+                let months = lookup_str(value, "data.num")
+                    .and_then(|it| it.parse().ok())
+                    .unwrap_or(1);
+                event = Some(PlatformEvent { months, viewers: 0 });
+                (
+                    MessageKind::Subscription,
+                    lookup_str(value, "data.username"),
+                    format!("{name} x{months}"),
+                )
+            }
+            _ => return None,
+        };
+    // stickers sent in place of a danmaku
+    let attachments = lookup_str(value, "info.0.13.url")
+        .filter(|_| kind == MessageKind::Chat)
+        .map(|it| Attachment::url(AttachmentKind::Sticker, it))
+        .into_iter()
+        .collect();
+    Some(Message {
+        kind,
+        username,
+        text,
+        attachments,
+        gift,
+        paid,
+        event,
+    })
+}
+
+/// Emoticons in a danmaku, listed by code in the `emots` of the JSON
+/// string at `info.0.15.extra`.
+fn bilibili_emotes(value: &Value, text: &str) -> Vec<Emote> {
+    let Some(extra) = lookup_str(value, "info.0.15.extra")
+        .and_then(|it| serde_json::from_str::<Value>(&it).ok())
+    else {
+        return vec![];
+    };
+    let Some(emots) = extra.get("emots").and_then(Value::as_object)
+    else {
+        return vec![];
+    };
+    let mut emotes = vec![];
+    for (code, emot) in emots {
+        if code.is_empty() {
+            continue;
+        }
+        let url = emot.get("url").and_then(Value::as_str);
+        emotes.extend(text.match_indices(code.as_str()).map(
+            |(idx, _)| Emote {
+                range: idx..idx + code.len(),
+                url: url.map(str::to_owned),
+            },
+        ));
+    }
+    emotes
+}
@@ -0,0 +1,99 @@
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, SocketAddr},
+};
+
+use anyhow::Context;
+use if_addrs::IfOperStatus;
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use tracing::{info, warn};
+
+/// mDNS service type the embedded server is announced as.
+pub const SERVICE_TYPE: &str = "_blooming-light._tcp.local.";
+
+/// Addresses other machines can reach a server listening on `addr` at:
+/// that address itself, or every interface's when listening on all of
+/// them. Empty when only this machine can.
+pub fn lan_ips(addr: SocketAddr) -> Vec<IpAddr> {
+    let ip = addr.ip();
+    if ip.is_loopback() {
+        return vec![];
+    }
+    if !ip.is_unspecified() {
+        return vec![ip];
+    }
+    let interfaces = match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces,
+        Err(err) => {
+            warn!("failed to list network interfaces: {err}");
+            return vec![];
+        }
+    };
+    interfaces
+        .into_iter()
+        .filter(|it| {
+            it.oper_status != IfOperStatus::Down
+                && !it.is_loopback()
+                && !it.is_link_local()
+                // listeners only take their own family
+                && it.ip().is_ipv4() == ip.is_ipv4()
+        })
+        .map(|it| it.ip())
+        .collect()
+}
+
+/// Announces the server on the LAN for as long as it's kept around.
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullnames: Vec<String>,
+}
+
+impl Advertisement {
+    /// One service per port of `addrs`. `None` if no other machine
+    /// could reach any of them anyway.
+    pub fn start(addrs: &[SocketAddr]) -> anyhow::Result<Option<Self>> {
+        let mut ports = BTreeMap::<u16, Vec<IpAddr>>::new();
+        for addr in addrs {
+            ports.entry(addr.port()).or_default().extend(lan_ips(*addr));
+        }
+        ports.retain(|_, ips| !ips.is_empty());
+        if ports.is_empty() {
+            return Ok(None);
+        }
+
+        let daemon = ServiceDaemon::new()
+            .context("failed to start mdns daemon")?;
+        let mut fullnames = vec![];
+        for (port, ips) in ports {
+            let service = ServiceInfo::new(
+                SERVICE_TYPE,
+                &format!("Blooming Light {port}"),
+                "blooming-light.local.",
+                ips.as_slice(),
+                port,
+                &[("path", "/")][..],
+            )
+            .context("invalid mdns service")?;
+            let fullname = service.get_fullname().to_owned();
+            daemon
+                .register(service)
+                .context("failed to register mdns service")?;
+            info!("advertising {fullname} on {ips:?}");
+            fullnames.push(fullname);
+        }
+        Ok(Some(Self { daemon, fullnames }))
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        for fullname in &self.fullnames {
+            if let Err(err) = self.daemon.unregister(fullname) {
+                warn!("failed to unregister mdns service: {err}");
+            }
+        }
+        if let Err(err) = self.daemon.shutdown() {
+            warn!("failed to stop mdns daemon: {err}");
+        }
+    }
+}
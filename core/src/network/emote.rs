@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::{Attachment, AttachmentKind, Message};
+
+const PLACEHOLDER: &str = "[emote]";
+
+/// What happens to platform emote codes in the text of a source's
+/// messages, e.g. `Kappa` on Twitch or `[doge]` on bilibili.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EmoteMode {
+    #[default]
+    Keep,
+    Strip,
+    /// Each replaced with `[emote]`.
+    Placeholder,
+    /// Taken out of the text and attached as images, codes without one
+    /// are kept.
+    Image,
+}
+
+impl EmoteMode {
+    pub const ALL: [EmoteMode; 4] = [
+        EmoteMode::Keep,
+        EmoteMode::Strip,
+        EmoteMode::Placeholder,
+        EmoteMode::Image,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmoteMode::Keep => "Keep codes",
+            EmoteMode::Strip => "Strip",
+            EmoteMode::Placeholder => "[emote] placeholder",
+            EmoteMode::Image => "Images",
+        }
+    }
+
+    /// Rewrites the `emotes` found in the text of `msg`. Ones overlapping
+    /// an earlier one or out of bounds are ignored.
+    pub fn apply(self, msg: &mut Message, mut emotes: Vec<Emote>) {
+        if self == EmoteMode::Keep || emotes.is_empty() {
+            return;
+        }
+        emotes.sort_by_key(|it| it.range.start);
+        let text = &msg.text;
+        let mut out = String::with_capacity(text.len());
+        let mut at = 0;
+        for emote in emotes {
+            let Range { start, mut end } = emote.range;
+            if start < at
+                || text.get(start..end).is_none_or(|it| it.is_empty())
+            {
+                continue;
+            }
+            out.push_str(&text[at..start]);
+            let removed = match (self, emote.url) {
+                (EmoteMode::Placeholder, _) => {
+                    out.push_str(PLACEHOLDER);
+                    false
+                }
+                (EmoteMode::Image, None) => {
+                    out.push_str(&text[start..end]);
+                    false
+                }
+                (EmoteMode::Image, Some(url)) => {
+                    msg.attachments.push(Attachment::url(
+                        AttachmentKind::Emote,
+                        url,
+                    ));
+                    true
+                }
+                _ => true,
+            };
+            // no double spaces where one was taken out
+            if removed
+                && (out.is_empty() || out.ends_with(' '))
+                && text[end..].starts_with(' ')
+            {
+                end += 1;
+            }
+            at = end;
+        }
+        out.push_str(&text[at..]);
+        msg.text = out.trim().to_owned();
+    }
+}
+
+/// An emote code in the text of a message, by byte range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emote {
+    pub range: Range<usize>,
+    /// Of its image, if known.
+    pub url: Option<String>,
+}
+
+/// Emotes of a Twitch `emotes` tag, e.g. `25:0-4,12-16/1902:6-10`, which
+/// indexes `text` by character.
+pub fn twitch_emotes(tag: &str, text: &str) -> Vec<Emote> {
+    // byte offset of each character, and of the end
+    let offsets = text
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .chain([text.len()])
+        .collect::<Vec<_>>();
+    let mut emotes = vec![];
+    for emote in tag.split('/') {
+        let Some((id, ranges)) = emote.split_once(':') else {
+            continue;
+        };
+        for range in ranges.split(',') {
+            let Some((start, end)) = range.split_once('-') else {
+                continue;
+            };
+            let (Ok(start), Ok(end)) =
+                (start.parse::<usize>(), end.parse::<usize>())
+            else {
+                continue;
+            };
+            // inclusive end
+            let (Some(&start), Some(&end)) =
+                (offsets.get(start), offsets.get(end + 1))
+            else {
+                continue;
+            };
+            emotes.push(Emote {
+                range: start..end,
+                url: Some(format!(
+                    "https://static-cdn.jtvnw.net/emoticons/v2/{id}/default/dark/1.0"
+                )),
+            });
+        }
+    }
+    emotes
+}
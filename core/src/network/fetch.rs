@@ -0,0 +1,150 @@
+use anyhow::{anyhow, bail, Context};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+    body::{Bytes, Incoming},
+    header,
+    http::request,
+    Request, Response, Uri,
+};
+use hyper_util::rt::TokioIo;
+use tokio_tungstenite::MaybeTlsStream;
+
+use super::{proxy, proxy::ProxyConfig, tls::TlsConfig};
+
+/// Larger bodies are refused.
+const MAX_BODY_BYTES: usize = 8 << 20;
+const MAX_REDIRECTS: usize = 3;
+
+/// A downloaded body and its `Content-Type`.
+pub struct Fetched {
+    pub bytes: Bytes,
+    pub mime: Option<String>,
+}
+
+/// Downloads `url` over http(s) asking for `accept`, following a few
+/// redirects. Used for attachment thumbnails and word lists, so one
+/// connection per request is fine.
+pub async fn fetch(
+    url: &str,
+    accept: &str,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Fetched> {
+    let mut uri = url
+        .parse::<Uri>()
+        .with_context(|| format!("invalid url {url}"))?;
+    for _ in 0..=MAX_REDIRECTS {
+        let res = get(&uri, accept, proxy).await?;
+        let status = res.status();
+        if status.is_redirection() {
+            let location = res
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|it| it.to_str().ok())
+                .context("redirect without location")?;
+            uri = redirect_target(&uri, location)?;
+            continue;
+        }
+        if !status.is_success() {
+            bail!("fetching {uri} failed with {status}");
+        }
+        let mime = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|it| it.to_str().ok())
+            .map(str::to_owned);
+        let bytes = Limited::new(res.into_body(), MAX_BODY_BYTES)
+            .collect()
+            .await
+            .map_err(|err| anyhow!(err))
+            .with_context(|| format!("failed to read {uri}"))?
+            .to_bytes();
+        return Ok(Fetched { bytes, mime });
+    }
+    bail!("too many redirects fetching {url}")
+}
+
+/// [`fetch`] on a throwaway runtime, for callers outside of tokio.
+pub fn fetch_blocking(
+    url: &str,
+    accept: &str,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Fetched> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime")?
+        .block_on(fetch(url, accept, proxy))
+}
+
+async fn get(
+    uri: &Uri,
+    accept: &str,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Response<Incoming>> {
+    let request = Request::get(uri).header(header::ACCEPT, accept);
+    send(request, Bytes::new(), proxy)
+        .await
+        .with_context(|| format!("failed to fetch {uri}"))
+}
+
+/// Sends `request` with `body` over a connection of its own, setting
+/// `Host` from its uri, which has to be absolute.
+pub async fn send(
+    request: request::Builder,
+    body: Bytes,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Response<Incoming>> {
+    let uri = request.uri_ref().context("request has no uri")?.clone();
+    let secure = match uri.scheme_str() {
+        Some("http") => false,
+        Some("https") => true,
+        scheme => bail!("unsupported scheme {scheme:?}"),
+    };
+    let host = uri.host().context("url has no host")?.to_owned();
+    let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+    let stream = proxy::connect(proxy, &host, port).await?;
+    let stream = if secure {
+        let connector = TlsConfig::default().connector()?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(&host, stream)
+            .await
+            .with_context(|| {
+                format!("tls handshake with {host} failed")
+            })?;
+        MaybeTlsStream::NativeTls(stream)
+    } else {
+        MaybeTlsStream::Plain(stream)
+    };
+
+    let (mut sender, conn) =
+        hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .with_context(|| format!("failed to connect {uri}"))?;
+    tokio::spawn(conn);
+
+    let authority = uri.authority().context("url has no host")?;
+    let path = uri.path_and_query().map_or("/", |it| it.as_str());
+    let request = request
+        .uri(path)
+        .header(header::HOST, authority.as_str())
+        .body(Full::new(body))
+        .context("failed to build request")?;
+    sender
+        .send_request(request)
+        .await
+        .with_context(|| format!("request to {uri} failed"))
+}
+
+fn redirect_target(base: &Uri, location: &str) -> anyhow::Result<Uri> {
+    let location = location
+        .parse::<Uri>()
+        .with_context(|| format!("invalid redirect {location}"))?;
+    if location.scheme().is_some() {
+        return Ok(location);
+    }
+    let mut parts = location.into_parts();
+    parts.scheme = base.scheme().cloned();
+    parts.authority = base.authority().cloned();
+    Uri::from_parts(parts).context("invalid redirect")
+}
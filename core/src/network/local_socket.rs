@@ -0,0 +1,118 @@
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+};
+
+use axum::{extract::ConnectInfo, Extension, Router};
+use hyper::server::conn::http1;
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+#[cfg(windows)]
+use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+use tokio::select;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// A Unix socket, removed again when dropped.
+#[cfg(unix)]
+pub struct LocalListener {
+    listener: tokio::net::UnixListener,
+    path: std::path::PathBuf,
+}
+
+#[cfg(unix)]
+impl LocalListener {
+    pub fn bind(path: &str) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        // left behind by a server that didn't stop cleanly, anything
+        // else at the path is the user's and makes binding fail
+        let stale = std::fs::symlink_metadata(path)
+            .is_ok_and(|it| it.file_type().is_socket());
+        if stale {
+            std::fs::remove_file(path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)?;
+        Ok(Self {
+            listener,
+            path: path.into(),
+        })
+    }
+
+    async fn accept(&mut self) -> io::Result<tokio::net::UnixStream> {
+        Ok(self.listener.accept().await?.0)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for LocalListener {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            warn!("failed to remove {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// A Windows named pipe, one instance per client.
+#[cfg(windows)]
+pub struct LocalListener {
+    name: String,
+    server: NamedPipeServer,
+}
+
+#[cfg(windows)]
+impl LocalListener {
+    pub fn bind(name: &str) -> io::Result<Self> {
+        let server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(name)?;
+        Ok(Self {
+            name: name.to_owned(),
+            server,
+        })
+    }
+
+    async fn accept(&mut self) -> io::Result<NamedPipeServer> {
+        self.server.connect().await?;
+        // the connected instance is the client's, the next one waits
+        let next = ServerOptions::new().create(&self.name)?;
+        Ok(std::mem::replace(&mut self.server, next))
+    }
+}
+
+/// Serves `router` to every client of `listener` until `stop_token` is
+/// cancelled, then lets open connections finish their requests.
+pub async fn serve(
+    mut listener: LocalListener,
+    router: Router,
+    stop_token: CancellationToken,
+) -> io::Result<()> {
+    // clients here are all on this machine, handlers still look for an
+    // address to check and log
+    let local = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    let router = router.layer(Extension(ConnectInfo(local)));
+
+    loop {
+        let stream = select! {
+            res = listener.accept() => res?,
+            _ = stop_token.cancelled() => return Ok(()),
+        };
+        let service = TowerToHyperService::new(router.clone());
+        let stop_token = stop_token.clone();
+        tokio::spawn(async move {
+            let conn = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades();
+            tokio::pin!(conn);
+            let res = select! {
+                res = conn.as_mut() => res,
+                _ = stop_token.cancelled() => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+            };
+            if let Err(err) = res {
+                warn!("local socket connection failed: {err}");
+            }
+        });
+    }
+}
@@ -0,0 +1,43 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+/// Login asked of operator-facing routes, everything under `/api`, with
+/// HTTP basic auth. Overlay routes stay open. Off while the password is
+/// empty.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OperatorLogin {
+    pub username: String,
+    /// Kept in the OS keyring, not with the rest.
+    #[serde(skip)]
+    pub password: String,
+}
+
+impl OperatorLogin {
+    pub fn is_on(&self) -> bool {
+        !self.password.is_empty()
+    }
+
+    /// Whether `authorization` is `Basic` with these credentials. Never
+    /// while off.
+    pub fn authorized(&self, authorization: Option<&str>) -> bool {
+        if !self.is_on() {
+            return false;
+        }
+        let given = authorization
+            .and_then(|it| it.strip_prefix("Basic "))
+            .and_then(|it| BASE64_STANDARD.decode(it.trim()).ok());
+        let expected = format!("{}:{}", self.username, self.password);
+        given.is_some_and(|given| same(&given, expected.as_bytes()))
+    }
+}
+
+/// In the same time whatever the mismatch.
+pub(crate) fn same(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
@@ -0,0 +1,131 @@
+use anyhow::{bail, Context};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_socks::tcp::Socks5Stream;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum ProxyKind {
+    #[default]
+    None,
+    /// HTTP `CONNECT` tunnel.
+    Http,
+    Socks5,
+}
+
+impl ProxyKind {
+    pub const ALL: [ProxyKind; 3] =
+        [ProxyKind::None, ProxyKind::Http, ProxyKind::Socks5];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProxyKind::None => "None",
+            ProxyKind::Http => "HTTP",
+            ProxyKind::Socks5 => "SOCKS5",
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub kind: ProxyKind,
+    /// `host:port` of the proxy.
+    pub addr: String,
+    /// Leave empty for no authentication. Kept in the OS keyring with
+    /// the password, not with the rest.
+    #[serde(skip)]
+    pub username: String,
+    #[serde(skip)]
+    pub password: String,
+}
+
+/// Opens a TCP connection to `host:port`, tunneled through the proxy if
+/// one is configured.
+pub async fn connect(
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<TcpStream> {
+    match proxy.kind {
+        ProxyKind::None => TcpStream::connect((host, port))
+            .await
+            .with_context(|| format!("failed to connect {host}:{port}")),
+        ProxyKind::Http => http_connect(proxy, host, port).await,
+        ProxyKind::Socks5 => {
+            let stream = if proxy.username.is_empty() {
+                Socks5Stream::connect(proxy.addr.as_str(), (host, port))
+                    .await
+            } else {
+                Socks5Stream::connect_with_password(
+                    proxy.addr.as_str(),
+                    (host, port),
+                    &proxy.username,
+                    &proxy.password,
+                )
+                .await
+            };
+            stream
+                .with_context(|| {
+                    format!(
+                        "failed to connect {host}:{port} via socks5 {}",
+                        proxy.addr
+                    )
+                })
+                .map(Socks5Stream::into_inner)
+        }
+    }
+}
+
+async fn http_connect(
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<TcpStream> {
+    let mut stream =
+        TcpStream::connect(proxy.addr.as_str()).await.with_context(
+            || format!("failed to connect http proxy {}", proxy.addr),
+        )?;
+
+    let mut request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n"
+    );
+    if !proxy.username.is_empty() {
+        let credentials = BASE64_STANDARD
+            .encode(format!("{}:{}", proxy.username, proxy.password));
+        request +=
+            &format!("Proxy-Authorization: Basic {credentials}\r\n");
+    }
+    request += "\r\n";
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .context("failed to send CONNECT request")?;
+
+    // read byte by byte so nothing after the header is consumed
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 8192 {
+            bail!("CONNECT response header too large");
+        }
+        let byte = stream
+            .read_u8()
+            .await
+            .context("failed to read CONNECT response")?;
+        response.push(byte);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1);
+    if status != Some("200") {
+        bail!("proxy refused CONNECT: {status_line}");
+    }
+
+    Ok(stream)
+}
@@ -0,0 +1,45 @@
+use std::{
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Builder, Runtime};
+
+/// How the network thread's tokio runtime is built, only read when the
+/// network starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// 0 for one per CPU core.
+    pub worker_threads: usize,
+    /// Worker threads are named this with an index appended, so they can
+    /// be told apart in a profiler or debugger.
+    pub thread_name: String,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: 0,
+            thread_name: "blooming-light-net".to_owned(),
+        }
+    }
+}
+
+impl RuntimeConfig {
+    pub fn build(&self) -> io::Result<Runtime> {
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all();
+        if self.worker_threads > 0 {
+            builder.worker_threads(self.worker_threads);
+        }
+        let name = self.thread_name.clone();
+        let next_id = AtomicUsize::new(0);
+        builder.thread_name_fn(move || {
+            let id = next_id.fetch_add(1, Ordering::Relaxed);
+            format!("{name}-{id}")
+        });
+        builder.build()
+    }
+}
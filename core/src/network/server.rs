@@ -0,0 +1,837 @@
+use std::{
+    collections::HashMap,
+    future::{Future, IntoFuture},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use axum::{
+    extract::{
+        ws::{self, WebSocket},
+        ConnectInfo, Path, Query, State, WebSocketUpgrade,
+    },
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{self, get},
+    Extension, Json, Router,
+};
+use futures_util::future;
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::{
+    net::TcpListener,
+    select,
+    sync::{broadcast, mpsc as tokio_mpsc, Semaphore},
+    time,
+};
+use tokio_util::sync::CancellationToken;
+use tower_http::{timeout::TimeoutLayer, trace::TraceLayer};
+use tracing::{debug, debug_span, error, info, warn};
+
+use super::{
+    access::IpAccess,
+    access_log::{self, AccessLog},
+    actions::{self, Action, ActionRequest},
+    discovery::{lan_ips, Advertisement},
+    local_socket::{self, LocalListener},
+    operator::OperatorLogin,
+    theme::{self, Layout, ThemeFile, ThemeVars},
+};
+use crate::Notifier;
+
+/// Sent to overlays to have them reload the page.
+const RELOAD_FRAME: &str = r#"{"type":"reload"}"#;
+/// For the frontend to run an action API request, it may be busy or
+/// gone.
+const ACTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// A save is often several file events, overlays reload once for them.
+const RELOAD_SETTLE: Duration = Duration::from_millis(100);
+
+/// A serialized frame for overlays.
+#[derive(Debug, Clone)]
+pub enum OverlayFrame {
+    /// Chat messages and combos, batched when a window is set.
+    Message(String),
+    /// Reloads, timers and polls, overlays act on them alone so they are
+    /// sent bare.
+    Control(String),
+}
+
+impl OverlayFrame {
+    fn into_text(self) -> String {
+        match self {
+            Self::Message(text) | Self::Control(text) => text,
+        }
+    }
+}
+
+/// Embedded server settings, applied on every (re)start.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Addresses to listen on, all serving the same overlays. IPv6 ones
+    /// only take IPv6 clients, list both `0.0.0.0:<port>` and
+    /// `[::]:<port>` to be reachable from other machines over either.
+    pub addrs: Vec<SocketAddr>,
+    /// Clients allowed to connect, checked on every request.
+    pub access: IpAccess,
+    /// Messages arriving within this window after the first one are sent
+    /// to overlays as a single JSON array frame. 0 disables batching.
+    pub batch_window_ms: u64,
+    /// Overlay theme served at `/`, a directory name under
+    /// [`theme::default_dir`]. Empty for the built-in [`Self::layout`].
+    pub theme: String,
+    pub layout: Layout,
+    pub theme_vars: ThemeVars,
+    /// Serves the built-in overlay from [`theme::dev_dir`] instead of the
+    /// embedded copy, and has overlays reload whenever a file there or
+    /// under [`theme::default_dir`] changes.
+    pub dev_mode: bool,
+    /// Announces the server on the LAN via mDNS, as
+    /// [`super::discovery::SERVICE_TYPE`], when other machines can reach
+    /// it.
+    pub advertise: bool,
+    /// Also serves everything on this Unix socket path, or Windows named
+    /// pipe like `\\.\pipe\blooming-light`, for automation clients on
+    /// this machine. Empty for none.
+    pub local_socket: String,
+    /// Writes every request and overlay connection to
+    /// [`access_log::default_path`].
+    pub access_log: bool,
+    /// Required by `/api/actions/<action>` and `/api/state` unless
+    /// [`Self::operator`] is given, they are off while both are. Kept in
+    /// the OS keyring, not with the rest.
+    #[serde(skip)]
+    pub api_token: String,
+    pub operator: OperatorLogin,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addrs: vec![SocketAddr::from((Ipv4Addr::LOCALHOST, 8081))],
+            access: IpAccess::default(),
+            batch_window_ms: 0,
+            theme: String::new(),
+            layout: Layout::default(),
+            theme_vars: ThemeVars::default(),
+            dev_mode: false,
+            advertise: false,
+            local_socket: String::new(),
+            access_log: false,
+            api_token: String::new(),
+            operator: OperatorLogin::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Where overlays see [`Self::theme`], even before it's saved.
+    pub fn preview_url(&self) -> String {
+        match self.theme.as_str() {
+            "" => self.layout_url(self.layout),
+            theme => format!("{}/themes/{theme}/", self.local_url()),
+        }
+    }
+
+    /// Where `action` is run, the token given as
+    /// `Authorization: Bearer`.
+    pub fn action_url(&self, action: Action) -> String {
+        format!("{}/api/actions/{}", self.local_url(), action.slug())
+    }
+
+    /// [`Self::action_url`] with the token in it, for clients that can
+    /// only open a URL. Their history and proxies see the token then.
+    pub fn action_url_with_token(&self, action: Action) -> String {
+        format!("{}?token={}", self.action_url(action), self.api_token)
+    }
+
+    /// Where overlays see the built-in `layout`, whichever is selected.
+    pub fn layout_url(&self, layout: Layout) -> String {
+        format!("{}/layouts/{}/", self.local_url(), layout.slug())
+    }
+
+    /// Where other machines see the overlay, one URL per address they
+    /// can reach the server at.
+    pub fn lan_urls(&self) -> Vec<String> {
+        let mut urls = vec![];
+        for addr in &self.addrs {
+            for ip in lan_ips(*addr) {
+                let url = format!(
+                    "http://{}/",
+                    SocketAddr::new(ip, addr.port())
+                );
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+        urls
+    }
+
+    /// The first of [`Self::lan_urls`], the one from this machine if
+    /// there are none.
+    pub fn lan_url(&self) -> String {
+        self.lan_urls()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| format!("{}/", self.local_url()))
+    }
+
+    /// The server as reached from this machine, over the first address.
+    fn local_url(&self) -> String {
+        let Some(&addr) = self.addrs.first() else {
+            return String::new();
+        };
+        let ip = match addr.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => {
+                Ipv4Addr::LOCALHOST.into()
+            }
+            IpAddr::V6(ip) if ip.is_unspecified() => {
+                Ipv6Addr::LOCALHOST.into()
+            }
+            ip => ip,
+        };
+        format!("http://{}", SocketAddr::new(ip, addr.port()))
+    }
+
+    /// Addresses, comma separated.
+    pub fn format_addrs(addrs: &[SocketAddr]) -> String {
+        addrs
+            .iter()
+            .map(SocketAddr::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Reads [`Self::format_addrs`] back, `None` if any address is
+    /// invalid or there are none.
+    pub fn parse_addrs(addrs: &str) -> Option<Vec<SocketAddr>> {
+        let addrs = addrs
+            .split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .map(|it| it.parse().ok())
+            .collect::<Option<Vec<_>>>()?;
+        (!addrs.is_empty()).then_some(addrs)
+    }
+
+    fn batch_window(&self) -> Option<Duration> {
+        (self.batch_window_ms > 0)
+            .then(|| Duration::from_millis(self.batch_window_ms))
+    }
+}
+
+/// `ws_lagged` counts messages overlays fell too far behind to get.
+/// Action API requests go to the frontend through `action_tx`, woken up
+/// by `notifier`.
+pub fn run_server(
+    config: ServerConfig,
+    ws_msg_send_tx: broadcast::Sender<OverlayFrame>,
+    ws_lagged: Arc<AtomicU64>,
+    action_tx: mpsc::Sender<ActionRequest>,
+    notifier: Notifier,
+) -> (CancellationToken, impl Future<Output = anyhow::Result<()>>) {
+    let stop_token = CancellationToken::new();
+    let stop_token_cloned = stop_token.clone();
+
+    let fut = async move {
+        let ws_stop_token = CancellationToken::new();
+        let ws_semaphore_capacity =
+            (Semaphore::MAX_PERMITS as u128).min(u32::MAX as u128) as u32;
+        let ws_semaphore =
+            Arc::new(Semaphore::new(ws_semaphore_capacity as usize));
+
+        if config.dev_mode {
+            tokio::spawn(watch_reload(
+                vec![theme::dev_dir(), theme::default_dir()],
+                ws_msg_send_tx.clone(),
+                ws_stop_token.clone(),
+            ));
+        }
+
+        let access_log = if config.access_log {
+            let path = access_log::default_path();
+            let log = AccessLog::open(&path, access_log::MAX_BYTES)
+                .with_context(|| {
+                    format!("failed to open {}", path.display())
+                })?;
+            Some(Arc::new(log))
+        } else {
+            None
+        };
+
+        let state = ServerState {
+            ws_stop_token: ws_stop_token.clone(),
+            ws_semaphore: Arc::clone(&ws_semaphore),
+            ws_msg_send_tx,
+            ws_lagged,
+            batch_window: config.batch_window(),
+            theme: config.theme.clone(),
+            layout: config.layout,
+            theme_vars: Arc::new(config.theme_vars.clone()),
+            themes_dir: theme::default_dir(),
+            dev_dir: config.dev_mode.then(theme::dev_dir),
+            access: Arc::new(config.access.clone()),
+            access_log,
+            api_token: config.api_token.as_str().into(),
+            operator: Arc::new(config.operator.clone()),
+            action_tx,
+            notifier,
+        };
+        let operator_routes = Router::new()
+            .route("/api/state", get(state_handler))
+            .route(
+                "/api/actions/{action}",
+                get(action_handler).post(action_handler),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                check_operator,
+            ));
+        let router = Router::new()
+            .route("/ws", routing::any(ws_handler))
+            .merge(operator_routes)
+            .route("/", get(root_handler))
+            .route("/{*path}", get(theme_handler))
+            // any theme, for previewing before switching to it
+            .route("/themes/{name}/", get(preview_handler))
+            .route("/themes/{name}/{*path}", get(preview_handler))
+            // every built-in layout, whatever the selected theme
+            .route("/layouts/{layout}/", get(layout_handler))
+            .route("/layouts/{layout}/{*path}", get(layout_handler))
+            .layer((
+                // as the default, but keeping the token out of the log
+                TraceLayer::new_for_http().make_span_with(
+                    |request: &Request<axum::body::Body>| {
+                        let uri = request.uri().to_string();
+                        debug_span!(
+                            "request",
+                            method = %request.method(),
+                            uri = %access_log::redact(&uri),
+                            version = ?request.version(),
+                        )
+                    },
+                ),
+                TimeoutLayer::new(Duration::from_secs(15)),
+                middleware::from_fn_with_state(state.clone(), log_access),
+                middleware::from_fn_with_state(
+                    state.clone(),
+                    check_access,
+                ),
+            ))
+            .with_state(state);
+
+        let mut listeners = vec![];
+        let mut local_addrs = vec![];
+        for addr in &config.addrs {
+            let tcp_listener = listen(*addr)
+                .with_context(|| format!("failed to listen {addr}"))?;
+            let local_addr = tcp_listener.local_addr().unwrap();
+            info!("server listening on {local_addr}");
+            listeners.push(tcp_listener);
+            local_addrs.push(local_addr);
+        }
+        let local_listener = match config.local_socket.as_str() {
+            "" => None,
+            path => {
+                let listener =
+                    LocalListener::bind(path).with_context(|| {
+                        format!("failed to listen {path}")
+                    })?;
+                info!("server listening on {path}");
+                Some(listener)
+            }
+        };
+        // kept until the server stops
+        let _advertisement = if config.advertise {
+            Advertisement::start(&local_addrs).unwrap_or_else(|err| {
+                warn!("failed to advertise server: {err:?}");
+                None
+            })
+        } else {
+            None
+        };
+
+        let make_service = router
+            .clone()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let tcp = future::try_join_all(listeners.into_iter().map(
+            |tcp_listener| {
+                axum::serve(tcp_listener, make_service.clone())
+                    .with_graceful_shutdown(
+                        stop_token_cloned.clone().cancelled_owned(),
+                    )
+                    .into_future()
+            },
+        ));
+        let local = async {
+            match local_listener {
+                Some(listener) => {
+                    let stop_token = stop_token_cloned.clone();
+                    local_socket::serve(listener, router, stop_token)
+                        .await
+                }
+                None => Ok(()),
+            }
+        };
+        future::try_join(tcp, local)
+            .await
+            .context("failed to axum::serve")?;
+
+        ws_stop_token.cancel();
+        info!("waitting ws sockets to close");
+        let _ = ws_semaphore.acquire_many(ws_semaphore_capacity).await;
+
+        anyhow::Result::<()>::Ok(())
+    };
+
+    (stop_token, fut)
+}
+
+/// IPv6 sockets are made IPv6 only, some platforms would otherwise take
+/// IPv4 on them too and clash with an IPv4 listener on the same port.
+fn listen(addr: SocketAddr) -> anyhow::Result<TcpListener> {
+    let socket = Socket::new(
+        Domain::for_address(addr),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    // like tokio's own bind, lets a restart take the port right away
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    Ok(TcpListener::from_std(socket.into())?)
+}
+
+#[derive(Clone)]
+struct ServerState {
+    ws_stop_token: CancellationToken,
+    ws_semaphore: Arc<Semaphore>,
+    ws_msg_send_tx: broadcast::Sender<OverlayFrame>,
+    ws_lagged: Arc<AtomicU64>,
+    batch_window: Option<Duration>,
+    theme: String,
+    layout: Layout,
+    theme_vars: Arc<ThemeVars>,
+    themes_dir: PathBuf,
+    /// Where the built-in overlay is read from in dev mode.
+    dev_dir: Option<PathBuf>,
+    access: Arc<IpAccess>,
+    access_log: Option<Arc<AccessLog>>,
+    api_token: Arc<str>,
+    operator: Arc<OperatorLogin>,
+    action_tx: mpsc::Sender<ActionRequest>,
+    notifier: Notifier,
+}
+
+impl ServerState {
+    fn log_access(&self, addr: SocketAddr, what: &str) {
+        if let Some(log) = &self.access_log {
+            if let Err(err) = log.write(addr, what) {
+                warn!("failed to write access log: {err}");
+            }
+        }
+    }
+
+    fn load(&self, name: &str, path: &str) -> Option<ThemeFile> {
+        // read on every request, so edits show up on reload
+        match name {
+            "" => self.load_layout(self.layout, path),
+            name => theme::load(
+                &self.themes_dir,
+                name,
+                path,
+                &self.theme_vars,
+            ),
+        }
+    }
+
+    fn load_layout(
+        &self,
+        layout: Layout,
+        path: &str,
+    ) -> Option<ThemeFile> {
+        let vars = &self.theme_vars;
+        match &self.dev_dir {
+            Some(dir) => {
+                theme::load_builtin_from(dir, layout, path, vars)
+            }
+            None => theme::load_builtin(layout, path, vars),
+        }
+    }
+}
+
+async fn log_access(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<ServerState>,
+    request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    if state.access_log.is_none() {
+        return next.run(request).await;
+    }
+    let uri = request.uri().to_string();
+    let what =
+        format!("{} {}", request.method(), access_log::redact(&uri));
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    let elapsed = start.elapsed().as_millis();
+    state.log_access(addr, &format!("{what} {status} {elapsed}ms"));
+    response
+}
+
+async fn check_access(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<ServerState>,
+    request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    if !state.access.allows(addr.ip()) {
+        warn!("refused {addr}, not in the allowed clients");
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    next.run(request).await
+}
+
+/// Whether a request gave [`OperatorLogin`], set on every operator
+/// route.
+#[derive(Clone, Copy)]
+struct Operator(bool);
+
+/// Operator routes ask for [`OperatorLogin`] while it's on. Requests
+/// with the action API token get through to be checked for it, so
+/// buttons keep working with only that.
+async fn check_operator(
+    Query(query): Query<HashMap<String, String>>,
+    State(state): State<ServerState>,
+    mut request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|it| it.to_str().ok());
+    let operator = state.operator.authorized(authorization);
+    let query_token = query.get("token").map(String::as_str);
+    if !state.operator.is_on()
+        || operator
+        || actions::authorized(
+            &state.api_token,
+            authorization,
+            query_token,
+        )
+    {
+        request.extensions_mut().insert(Operator(operator));
+        return next.run(request).await;
+    }
+    warn!("refused operator request without the login");
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static(r#"Basic realm="Blooming Light""#),
+        )],
+    )
+        .into_response()
+}
+
+async fn root_handler(State(state): State<ServerState>) -> Response {
+    file_response(state.load(&state.theme, ""))
+}
+
+async fn theme_handler(
+    Path(path): Path<String>,
+    State(state): State<ServerState>,
+) -> Response {
+    file_response(state.load(&state.theme, &path))
+}
+
+async fn preview_handler(
+    Path(params): Path<HashMap<String, String>>,
+    State(state): State<ServerState>,
+) -> Response {
+    let name = params.get("name").map_or("", String::as_str);
+    let path = params.get("path").map_or("", String::as_str);
+    file_response(state.load(name, path))
+}
+
+async fn layout_handler(
+    Path(params): Path<HashMap<String, String>>,
+    State(state): State<ServerState>,
+) -> Response {
+    let layout = params.get("layout").map_or("", String::as_str);
+    let path = params.get("path").map_or("", String::as_str);
+    file_response(
+        Layout::from_slug(layout)
+            .and_then(|layout| state.load_layout(layout, path)),
+    )
+}
+
+async fn state_handler(
+    Query(query): Query<HashMap<String, String>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    headers: HeaderMap,
+    State(state): State<ServerState>,
+) -> Response {
+    run_action(&state, None, operator, &headers, &query).await
+}
+
+/// GET too, for buttons that can only open a URL.
+async fn action_handler(
+    Path(action): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    Extension(Operator(operator)): Extension<Operator>,
+    headers: HeaderMap,
+    State(state): State<ServerState>,
+) -> Response {
+    let Some(action) = Action::from_slug(&action) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    run_action(&state, Some(action), operator, &headers, &query).await
+}
+
+/// Hands `action` to the frontend and replies with the state after it.
+/// `operator` requests gave the login and need no token.
+async fn run_action(
+    state: &ServerState,
+    action: Option<Action>,
+    operator: bool,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+) -> Response {
+    if state.api_token.is_empty() && !operator {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|it| it.to_str().ok());
+    let query_token = query.get("token").map(String::as_str);
+    if !operator
+        && !actions::authorized(
+            &state.api_token,
+            authorization,
+            query_token,
+        )
+    {
+        warn!("refused action API request without the token");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    info!(?action, "action API request");
+    let (request, reply_rx) = ActionRequest::new(action);
+    if state.action_tx.send(request).is_err() {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+    state.notifier.notify();
+    match time::timeout(ACTION_TIMEOUT, reply_rx).await {
+        Ok(Ok(reply)) => Json(reply).into_response(),
+        _ => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+fn file_response(file: Option<ThemeFile>) -> Response {
+    let Some(file) = file else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(file.content_type),
+        )],
+        file.body,
+    )
+        .into_response()
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<ServerState>,
+) -> impl IntoResponse {
+    info!("new ws connection from {addr}");
+
+    ws.on_upgrade(move |socket| async move {
+        state.log_access(addr, "ws connected");
+        handle_socket(socket, state.clone()).await;
+        state.log_access(addr, "ws disconnected");
+    })
+}
+
+async fn handle_socket(mut socket: WebSocket, state: ServerState) {
+    let permit = match state.ws_semaphore.acquire().await {
+        Ok(permit) => permit,
+        Err(_) => {
+            error!("semaphore closed, closing socket");
+            if let Err(err) = socket.close().await {
+                error!("failed to close socket: {err:?}");
+            }
+            return;
+        }
+    };
+
+    let mut ws_msg_send_rx = state.ws_msg_send_tx.subscribe();
+
+    let mut continous_err_count = 0;
+    'recv: loop {
+        let msg = select! {
+            _ = state.ws_stop_token.cancelled() => {
+                info!("socket closing");
+                if let Err(err) = socket.close().await {
+                    error!("failed to close socket: {err:?}");
+                }
+                return;
+            },
+            msg = socket.recv() => {
+                if msg.is_none() {
+                    return
+                }
+                continue;
+            },
+            msg = ws_msg_send_rx.recv() => {
+                match msg {
+                    Ok(msg) => {msg},
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    },
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("lagged, {skipped} message skipped");
+                        let lagged = &state.ws_lagged;
+                        lagged.fetch_add(skipped, Ordering::Relaxed);
+                        continue;
+                    },
+                }
+            }
+        };
+
+        let frames = match state.batch_window {
+            Some(window) => match msg {
+                OverlayFrame::Message(msg) => {
+                    let lagged = &state.ws_lagged;
+                    batch(msg, &mut ws_msg_send_rx, window, lagged).await
+                }
+                control => vec![control.into_text()],
+            },
+            None => vec![msg.into_text()],
+        };
+
+        for msg in frames {
+            let result = socket.send(ws::Message::Text(msg)).await;
+            if let Err(err) = result {
+                error!("failed to send message: {err}");
+                continous_err_count += 1;
+                if continous_err_count > 5 {
+                    error!(
+                        "too much error when sending message, closing"
+                    );
+                    let _ = socket.close().await;
+                    break 'recv;
+                }
+            } else {
+                continous_err_count = 0;
+            }
+        }
+    }
+    drop(permit);
+}
+
+/// Tells overlays to reload whenever a file under `dirs` changes.
+async fn watch_reload(
+    dirs: Vec<PathBuf>,
+    ws_msg_send_tx: broadcast::Sender<OverlayFrame>,
+    stop_token: CancellationToken,
+) {
+    let (event_tx, mut event_rx) = tokio_mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |event| {
+        let _ = event_tx.send(event);
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!("failed to create overlay file watcher: {err:?}");
+            return;
+        }
+    };
+    for dir in &dirs {
+        // the dev directory is only there once created
+        if let Err(err) = watcher.watch(dir, RecursiveMode::Recursive) {
+            debug!("failed to watch {}: {err:?}", dir.display());
+        }
+    }
+
+    loop {
+        let event = select! {
+            _ = stop_token.cancelled() => return,
+            event = event_rx.recv() => event,
+        };
+        match event {
+            None => return,
+            Some(Ok(event)) if !event.kind.is_access() => {}
+            Some(Ok(_)) => continue,
+            Some(Err(err)) => {
+                debug!("overlay file watcher error: {err:?}");
+                continue;
+            }
+        }
+        time::sleep(RELOAD_SETTLE).await;
+        while event_rx.try_recv().is_ok() {}
+        info!("overlay files changed, reloading overlays");
+        // no overlay connected is fine
+        let reload = OverlayFrame::Control(RELOAD_FRAME.to_owned());
+        let _ = ws_msg_send_tx.send(reload);
+    }
+}
+
+/// Collects messages arriving within `window` after `first` into one JSON
+/// array frame. A lone message is passed through as is. A control frame
+/// ends the batch early and follows it bare. Skipped messages are added
+/// to `lagged`.
+async fn batch(
+    first: String,
+    ws_msg_send_rx: &mut broadcast::Receiver<OverlayFrame>,
+    window: Duration,
+    lagged: &AtomicU64,
+) -> Vec<String> {
+    let mut batch = vec![first];
+    let mut control = None;
+    let deadline = time::sleep(window);
+    tokio::pin!(deadline);
+    loop {
+        select! {
+            _ = &mut deadline => break,
+            msg = ws_msg_send_rx.recv() => match msg {
+                Ok(OverlayFrame::Message(msg)) => batch.push(msg),
+                Ok(OverlayFrame::Control(msg)) => {
+                    control = Some(msg);
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("lagged, {skipped} message skipped");
+                    lagged.fetch_add(skipped, Ordering::Relaxed);
+                }
+            },
+        }
+    }
+
+    let batch = if batch.len() == 1 {
+        batch.pop().unwrap()
+    } else {
+        format!("[{}]", batch.join(","))
+    };
+    [batch].into_iter().chain(control).collect()
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+/// Reported by a source task whenever its connection changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceStatus {
+    /// Connecting, or connecting again after a restart.
+    Connecting,
+    Connected,
+    /// The connection ended, with the error if there was one.
+    Disconnected(Option<String>),
+}
+
+impl SourceStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceStatus::Connecting => "Reconnecting",
+            SourceStatus::Connected => "Connected",
+            SourceStatus::Disconnected(None) => "Disconnected",
+            SourceStatus::Disconnected(Some(_)) => "Error",
+        }
+    }
+}
+
+/// Frontend side view of a source, built from its status events and the
+/// messages pulled from it.
+#[derive(Debug, Clone)]
+pub struct SourceState {
+    status: SourceStatus,
+    since: Instant,
+    last_message: Option<Instant>,
+}
+
+impl SourceState {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            status: SourceStatus::Connecting,
+            since: now,
+            last_message: None,
+        }
+    }
+
+    pub fn status(&self) -> &SourceStatus {
+        &self.status
+    }
+
+    pub fn set_status(&mut self, status: SourceStatus, now: Instant) {
+        if status != self.status {
+            self.status = status;
+            self.since = now;
+        }
+    }
+
+    pub fn on_message(&mut self, now: Instant) {
+        self.last_message = Some(now);
+    }
+
+    /// Time spent in the current status.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.since)
+    }
+
+    /// `None` until a message has been received.
+    pub fn last_message_age(&self, now: Instant) -> Option<Duration> {
+        self.last_message
+            .map(|it| now.saturating_duration_since(it))
+    }
+}
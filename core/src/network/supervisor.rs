@@ -0,0 +1,83 @@
+use std::time::{Duration, Instant};
+
+/// Restart policy for a child task of the network loop.
+///
+/// Every exit is a failure. Failures back off exponentially from
+/// `initial` up to `max`, and after `max_failures` in a row the task is
+/// left down so the error can be surfaced. A task that stayed up for
+/// `healthy_after` starts counting from zero again.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub max_failures: u32,
+    pub healthy_after: Duration,
+
+    failures: u32,
+    started_at: Option<Instant>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(1),
+            Duration::from_secs(30),
+            5,
+            Duration::from_secs(30),
+        )
+    }
+}
+
+impl Backoff {
+    pub fn new(
+        initial: Duration,
+        max: Duration,
+        max_failures: u32,
+        healthy_after: Duration,
+    ) -> Self {
+        Self {
+            initial,
+            max,
+            max_failures,
+            healthy_after,
+
+            failures: 0,
+            started_at: None,
+        }
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Forgets previous failures, e.g. after a manual restart.
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    pub fn on_start(&mut self, now: Instant) {
+        self.started_at = Some(now);
+    }
+
+    /// Returns how long to wait before restarting, or `None` to give up.
+    pub fn on_exit(&mut self, now: Instant) -> Option<Duration> {
+        let uptime = self
+            .started_at
+            .take()
+            .map(|it| now.saturating_duration_since(it))
+            .unwrap_or_default();
+        if uptime >= self.healthy_after {
+            self.failures = 0;
+        }
+
+        self.failures += 1;
+        if self.failures > self.max_failures {
+            return None;
+        }
+        Some(
+            self.initial
+                .saturating_mul(1 << (self.failures - 1).min(16))
+                .min(self.max),
+        )
+    }
+}
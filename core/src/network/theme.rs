@@ -0,0 +1,298 @@
+use std::{
+    borrow::Cow,
+    env::current_dir,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+const BUILTIN_INDEX_HTML: &str =
+    include_str!("../../../frontend/dist/index.html");
+const BUILTIN_INDEX_JS: &str =
+    include_str!("../../../frontend/dist/index.js");
+const BUILTIN_OVERLAY_JS: &str =
+    include_str!("../../../frontend/dist/overlay.js");
+const LIST_HTML: &str =
+    include_str!("../../../frontend/dist/layouts/list.html");
+const BUBBLES_HTML: &str =
+    include_str!("../../../frontend/dist/layouts/bubbles.html");
+const TICKER_HTML: &str =
+    include_str!("../../../frontend/dist/layouts/ticker.html");
+const VERTICAL_HTML: &str =
+    include_str!("../../../frontend/dist/layouts/vertical.html");
+
+/// Overlays embedded in the binary, served when no theme is selected.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Messages scrolling across the screen in rows.
+    #[default]
+    Danmaku,
+    /// Newest message at the bottom, older ones scrolling up.
+    List,
+    /// Chat bubbles that fade out after a while.
+    Bubbles,
+    /// A single line scrolling along the bottom edge.
+    Ticker,
+    /// Messages falling down the screen in columns.
+    Vertical,
+}
+
+impl Layout {
+    pub const ALL: [Self; 5] = [
+        Self::Danmaku,
+        Self::List,
+        Self::Bubbles,
+        Self::Ticker,
+        Self::Vertical,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Danmaku => "Danmaku",
+            Self::List => "Scrolling list",
+            Self::Bubbles => "Bubble chat",
+            Self::Ticker => "Ticker",
+            Self::Vertical => "Vertical danmaku",
+        }
+    }
+
+    /// Path segment under `/layouts/` serving this layout.
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::Danmaku => "danmaku",
+            Self::List => "list",
+            Self::Bubbles => "bubbles",
+            Self::Ticker => "ticker",
+            Self::Vertical => "vertical",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|it| it.slug() == slug)
+    }
+
+    /// Where this layout's page lives under `frontend/dist`.
+    fn html_path(self) -> &'static str {
+        match self {
+            Self::Danmaku => "index.html",
+            Self::List => "layouts/list.html",
+            Self::Bubbles => "layouts/bubbles.html",
+            Self::Ticker => "layouts/ticker.html",
+            Self::Vertical => "layouts/vertical.html",
+        }
+    }
+
+    fn index_html(self) -> &'static str {
+        match self {
+            Self::Danmaku => BUILTIN_INDEX_HTML,
+            Self::List => LIST_HTML,
+            Self::Bubbles => BUBBLES_HTML,
+            Self::Ticker => TICKER_HTML,
+            Self::Vertical => VERTICAL_HTML,
+        }
+    }
+}
+
+/// Settings handed to overlay themes. Every `{{name}}` in a theme's
+/// html, css and js files is replaced with the field of that name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeVars {
+    pub text_color: String,
+    pub gift_color: String,
+    pub superchat_color: String,
+    /// Of follows, subscriptions and raids.
+    pub event_color: String,
+    pub font_family: String,
+    /// In percent of the overlay width.
+    pub font_size: f32,
+    /// Minimum gap between messages in a row, in pixels.
+    pub spacing: f32,
+    /// Scroll speed, in overlay widths per second.
+    pub speed: f32,
+}
+
+impl Default for ThemeVars {
+    fn default() -> Self {
+        Self {
+            text_color: "#ffffff".to_owned(),
+            gift_color: "#88ccff".to_owned(),
+            superchat_color: "#ffcc44".to_owned(),
+            event_color: "#99ee99".to_owned(),
+            font_family: "sans-serif".to_owned(),
+            font_size: 1.5,
+            spacing: 5.0,
+            speed: 0.1,
+        }
+    }
+}
+
+impl ThemeVars {
+    pub fn render(&self, template: &str) -> String {
+        [
+            ("text_color", self.text_color.clone()),
+            ("gift_color", self.gift_color.clone()),
+            ("superchat_color", self.superchat_color.clone()),
+            ("event_color", self.event_color.clone()),
+            ("font_family", self.font_family.clone()),
+            ("font_size", self.font_size.to_string()),
+            ("spacing", self.spacing.to_string()),
+            ("speed", self.speed.to_string()),
+        ]
+        .into_iter()
+        .fold(template.to_owned(), |out, (name, value)| {
+            out.replace(&format!("{{{{{name}}}}}"), &value)
+        })
+    }
+}
+
+/// `themes` under the working directory, one subdirectory per theme.
+pub fn default_dir() -> PathBuf {
+    current_dir().unwrap_or_default().join("themes")
+}
+
+/// `frontend/dist` under the working directory, the built-in overlay's
+/// sources when working on it.
+pub fn dev_dir() -> PathBuf {
+    current_dir().unwrap_or_default().join("frontend/dist")
+}
+
+/// Names of the themes in `dir` with an `index.html`, sorted.
+pub fn list(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut names = entries
+        .filter_map(Result::ok)
+        .filter(|it| it.path().join("index.html").is_file())
+        .filter_map(|it| it.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// A file served to overlays.
+pub struct ThemeFile {
+    pub body: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+/// The file at `path` of the built-in `layout`, templates rendered with
+/// `vars`. `None` for files it doesn't have.
+pub fn load_builtin(
+    layout: Layout,
+    path: &str,
+    vars: &ThemeVars,
+) -> Option<ThemeFile> {
+    let path = index_path(path);
+    let body = builtin_file(layout, path)?;
+    Some(ThemeFile {
+        body: vars.render(body).into_bytes(),
+        content_type: content_type(path),
+    })
+}
+
+/// [`load_builtin`], read from `dir` laid out like `frontend/dist` so
+/// edits show up without a rebuild.
+pub fn load_builtin_from(
+    dir: &Path,
+    layout: Layout,
+    path: &str,
+    vars: &ThemeVars,
+) -> Option<ThemeFile> {
+    let path = index_path(path);
+    let file = match path {
+        "index.html" => layout.html_path(),
+        "index.js" | "overlay.js" => path,
+        _ => return None,
+    };
+    let body = std::fs::read_to_string(dir.join(file)).ok()?;
+    Some(ThemeFile {
+        body: vars.render(&body).into_bytes(),
+        content_type: content_type(path),
+    })
+}
+
+/// The file at `path` of the theme `name` in `dir`, templates rendered
+/// with `vars`. Themes that don't bring their own scripts get the
+/// built-in ones. `None` for missing files and paths leaving the theme.
+pub fn load(
+    dir: &Path,
+    name: &str,
+    path: &str,
+    vars: &ThemeVars,
+) -> Option<ThemeFile> {
+    let path = index_path(path);
+    let content_type = content_type(path);
+
+    let file = theme_path(dir, name, path)?;
+    let body: Cow<[u8]> = match std::fs::read(file) {
+        Ok(body) => body.into(),
+        Err(_) => match path {
+            "index.js" | "overlay.js" => {
+                builtin_file(Layout::Danmaku, path)?.as_bytes().into()
+            }
+            _ => return None,
+        },
+    };
+    let body = if is_template(path) {
+        vars.render(&String::from_utf8_lossy(&body)).into_bytes()
+    } else {
+        body.into_owned()
+    };
+    Some(ThemeFile { body, content_type })
+}
+
+fn index_path(path: &str) -> &str {
+    match path.trim_start_matches('/') {
+        "" => "index.html",
+        path => path,
+    }
+}
+
+fn builtin_file(layout: Layout, path: &str) -> Option<&'static str> {
+    match path {
+        "index.html" => Some(layout.index_html()),
+        "index.js" => Some(BUILTIN_INDEX_JS),
+        "overlay.js" => Some(BUILTIN_OVERLAY_JS),
+        _ => None,
+    }
+}
+
+fn theme_path(dir: &Path, name: &str, path: &str) -> Option<PathBuf> {
+    let normal = |path: &Path| {
+        path.components()
+            .all(|it| matches!(it, Component::Normal(_)))
+    };
+    let (name, path) = (Path::new(name), Path::new(path));
+    (!name.as_os_str().is_empty() && normal(name) && normal(path))
+        .then(|| dir.join(name).join(path))
+}
+
+fn extension(path: &str) -> &str {
+    path.rsplit_once('.').map_or("", |(_, ext)| ext)
+}
+
+fn is_template(path: &str) -> bool {
+    matches!(extension(path), "html" | "css" | "js")
+}
+
+fn content_type(path: &str) -> &'static str {
+    match extension(path) {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        _ => "application/octet-stream",
+    }
+}
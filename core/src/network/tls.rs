@@ -0,0 +1,111 @@
+use anyhow::{bail, Context};
+use native_tls::{Certificate, TlsConnector};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM bundle of extra root certificates, trusted alongside the
+    /// system ones. Empty for none.
+    pub ca_file: String,
+    /// Hex SHA-256 fingerprint of the server's leaf certificate (colons
+    /// allowed). When set, only that certificate is accepted and chain
+    /// validation is skipped.
+    pub pinned_sha256: String,
+}
+
+impl TlsConfig {
+    pub fn connector(&self) -> anyhow::Result<TlsConnector> {
+        let mut builder = TlsConnector::builder();
+
+        if !self.ca_file.is_empty() {
+            let pem =
+                std::fs::read(&self.ca_file).with_context(|| {
+                    format!("failed to read CA file {}", self.ca_file)
+                })?;
+            let certs = pem_certificates(&pem)?;
+            if certs.is_empty() {
+                bail!("no certificate found in {}", self.ca_file);
+            }
+            for cert in certs {
+                builder.add_root_certificate(cert);
+            }
+        }
+        if self.pinned_fingerprint()?.is_some() {
+            // checked against the pin after the handshake instead
+            builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+
+        builder.build().context("failed to build tls connector")
+    }
+
+    pub fn pinned_fingerprint(&self) -> anyhow::Result<Option<[u8; 32]>> {
+        let hex = self
+            .pinned_sha256
+            .chars()
+            .filter(|it| *it != ':' && !it.is_whitespace())
+            .collect::<String>();
+        if hex.is_empty() {
+            return Ok(None);
+        }
+        if hex.len() != 64 || !hex.is_ascii() {
+            bail!("pinned fingerprint should be 32 hex bytes");
+        }
+
+        let mut fingerprint = [0; 32];
+        for (idx, byte) in fingerprint.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16)
+                .context("invalid hex in pinned fingerprint")?;
+        }
+        Ok(Some(fingerprint))
+    }
+
+    /// Checks the server's leaf certificate against the pin, if any.
+    pub fn verify_pin(
+        &self,
+        peer_cert: Option<Certificate>,
+    ) -> anyhow::Result<()> {
+        let Some(pinned) = self.pinned_fingerprint()? else {
+            return Ok(());
+        };
+        let cert = peer_cert.context("server sent no certificate")?;
+        let der =
+            cert.to_der().context("failed to encode certificate")?;
+        let fingerprint: [u8; 32] = Sha256::digest(der).into();
+        if fingerprint != pinned {
+            bail!(
+                "certificate fingerprint mismatch, server sent {}",
+                fingerprint
+                    .iter()
+                    .map(|it| format!("{it:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(":")
+            );
+        }
+        Ok(())
+    }
+}
+
+fn pem_certificates(pem: &[u8]) -> anyhow::Result<Vec<Certificate>> {
+    const END: &str = "-----END CERTIFICATE-----";
+
+    let pem = std::str::from_utf8(pem).context("CA file is not PEM")?;
+    let mut certs = vec![];
+    let mut rest = pem;
+    while let Some(start) = rest.find("-----BEGIN CERTIFICATE-----") {
+        let end = rest[start..]
+            .find(END)
+            .context("unterminated certificate in CA file")?
+            + start
+            + END.len();
+        certs.push(
+            Certificate::from_pem(&rest.as_bytes()[start..end])
+                .context("invalid certificate in CA file")?,
+        );
+        rest = &rest[end..];
+    }
+    Ok(certs)
+}
@@ -0,0 +1,197 @@
+use std::{future::Future, sync::mpsc, time::Instant};
+
+use anyhow::{bail, Context};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use tokio_tungstenite::{
+    client_async,
+    tungstenite::{
+        client::IntoClientRequest,
+        handshake::client::Request,
+        http::{header, HeaderName, HeaderValue},
+        Message as WsMessage,
+    },
+    MaybeTlsStream,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use super::{
+    decoder::DecoderConfig,
+    proxy::{self, ProxyConfig},
+    status::SourceStatus,
+    tls::TlsConfig,
+};
+use crate::{channel, message::Message, Notifier};
+
+/// Upstream connection settings, applied on every (re)connect.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WsClientConfig {
+    pub url: String,
+    /// Kept in the OS keyring with the cookie, not with the rest.
+    #[serde(skip)]
+    pub headers: Vec<(String, String)>,
+    /// Sent as the `Cookie` header, e.g. `a=1; b=2`.
+    #[serde(skip)]
+    pub cookie: String,
+    /// Sent as `Sec-WebSocket-Protocol`.
+    pub subprotocols: Vec<String>,
+    pub proxy: ProxyConfig,
+    pub tls: TlsConfig,
+    pub decoder: DecoderConfig,
+    /// Send delay for this source's messages, the global one if `None`.
+    pub delay_secs: Option<f64>,
+    /// Repeats of a text within this many seconds are forwarded as a
+    /// combo count, 0 for off.
+    pub combo_window_secs: f64,
+}
+
+impl Default for WsClientConfig {
+    fn default() -> Self {
+        Self {
+            url: "ws://127.0.0.1:8082".to_owned(),
+            headers: vec![],
+            cookie: String::new(),
+            subprotocols: vec![],
+            proxy: ProxyConfig::default(),
+            tls: TlsConfig::default(),
+            decoder: DecoderConfig::default(),
+            delay_secs: None,
+            combo_window_secs: 0.0,
+        }
+    }
+}
+
+impl WsClientConfig {
+    fn request(&self) -> anyhow::Result<Request> {
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .with_context(|| format!("invalid url {}", self.url))?;
+        let headers = request.headers_mut();
+
+        for (name, value) in &self.headers {
+            let name = HeaderName::try_from(name)
+                .with_context(|| format!("invalid header name {name}"))?;
+            let value =
+                HeaderValue::try_from(value).with_context(|| {
+                    format!("invalid value for header {name}")
+                })?;
+            headers.append(name, value);
+        }
+        if !self.cookie.is_empty() {
+            headers.insert(
+                header::COOKIE,
+                HeaderValue::try_from(&self.cookie)
+                    .context("invalid cookie")?,
+            );
+        }
+        if !self.subprotocols.is_empty() {
+            headers.insert(
+                header::SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::try_from(self.subprotocols.join(", "))
+                    .context("invalid subprotocols")?,
+            );
+        }
+
+        Ok(request)
+    }
+}
+
+pub fn run_ws_client(
+    config: WsClientConfig,
+    message_tx: channel::Sender<(Message, Instant)>,
+    status_tx: mpsc::Sender<SourceStatus>,
+    notifier: Notifier,
+) -> (CancellationToken, impl Future<Output = anyhow::Result<()>>) {
+    let stop_token = CancellationToken::new();
+    let stop_token_cloned = stop_token.clone();
+
+    let set_status = move |status| {
+        let _ = status_tx.send(status);
+        notifier.notify();
+    };
+    let set_status_cloned = set_status.clone();
+    let session = async move {
+        let request = config.request()?;
+        let uri = request.uri();
+        let secure = match uri.scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            scheme => bail!("unsupported scheme {scheme:?}"),
+        };
+        let host = uri.host().context("url has no host")?.to_owned();
+        let port =
+            uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+        let stream = proxy::connect(&config.proxy, &host, port).await?;
+        let stream = if secure {
+            let connector = config.tls.connector()?;
+            let stream = tokio_native_tls::TlsConnector::from(connector)
+                .connect(&host, stream)
+                .await
+                .with_context(|| {
+                    format!("tls handshake with {host} failed")
+                })?;
+            let peer_cert = stream
+                .get_ref()
+                .peer_certificate()
+                .context("failed to get server certificate")?;
+            config.tls.verify_pin(peer_cert)?;
+            MaybeTlsStream::NativeTls(stream)
+        } else {
+            MaybeTlsStream::Plain(stream)
+        };
+        let (ws_stream, _) =
+            client_async(request, stream).await.with_context(|| {
+                format!("failed to connect {}", config.url)
+            })?;
+        let (_, mut read) = ws_stream.split();
+        set_status_cloned(SourceStatus::Connected);
+
+        loop {
+            select! {
+                msg = read.next() => {
+                    let Some(msg) = msg else {
+                        break;
+                    };
+                    let msg = msg?;
+                    let WsMessage::Text(msg) = msg else {
+                        continue;
+                    };
+                    let msg = match config.decoder.decode(&msg) {
+                        Ok(Some(msg)) => msg,
+                        Ok(None) => continue,
+                        Err(err) => {
+                            warn!("dropping frame: {err:?}");
+                            continue;
+                        }
+                    };
+                    // wakes the frontend on its own
+                    let result = message_tx.send((msg, Instant::now()));
+                    if result.is_err() {
+                        break;
+                    }
+                }
+                _ = stop_token_cloned.cancelled() => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    };
+    let fut = async move {
+        set_status(SourceStatus::Connecting);
+        let result = session.await;
+        set_status(SourceStatus::Disconnected(
+            result.as_ref().err().map(|err| format!("{err:#}")),
+        ));
+        result
+    };
+
+    (stop_token, fut)
+}
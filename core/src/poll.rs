@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::{Message, MessageKind};
+
+/// Frame sent to overlays with the results of a poll as it closes. Its
+/// text is plain, unlike that of message envelopes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollFrame {
+    /// Always `poll`, tells it apart from message envelopes.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub question: String,
+    /// In the order they were listed.
+    pub results: Vec<PollResult>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollResult {
+    pub option: String,
+    pub votes: usize,
+}
+
+/// A question put to chat. A chat message votes by an option's number,
+/// counting from 1, or by its text, and each user gets one vote.
+#[derive(Debug, Clone)]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+    /// Option picked by each voter.
+    votes: HashMap<String, usize>,
+    open: bool,
+}
+
+impl Poll {
+    /// Opens right away.
+    pub fn new(
+        question: impl Into<String>,
+        options: Vec<String>,
+    ) -> Self {
+        Self {
+            question: question.into(),
+            options,
+            votes: HashMap::new(),
+            open: true,
+        }
+    }
+
+    /// Index of the option `text` votes for, if it's nothing but one.
+    pub fn option_of(&self, text: &str) -> Option<usize> {
+        let text = text.trim();
+        if let Ok(number) = text.parse::<usize>() {
+            return (1..=self.options.len())
+                .contains(&number)
+                .then(|| number - 1);
+        }
+        self.options
+            .iter()
+            .position(|it| it.trim().eq_ignore_ascii_case(text))
+    }
+
+    /// Counts `msg` as a vote while open, unless its sender already
+    /// voted. Returns whether it was one, counted or not.
+    pub fn vote(&mut self, msg: &Message) -> bool {
+        if !self.open || msg.kind != MessageKind::Chat {
+            return false;
+        }
+        let Some(option) = self.option_of(&msg.text) else {
+            return false;
+        };
+        if let Some(ref username) = msg.username {
+            self.votes.entry(username.clone()).or_insert(option);
+        }
+        true
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Stops taking votes, returning the results to show.
+    pub fn close(&mut self) -> PollFrame {
+        self.open = false;
+        self.frame()
+    }
+
+    /// Votes for each option, in the order they were listed.
+    pub fn counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.options.len()];
+        for option in self.votes.values() {
+            counts[*option] += 1;
+        }
+        counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn frame(&self) -> PollFrame {
+        PollFrame {
+            kind: "poll".to_owned(),
+            question: self.question.clone(),
+            results: self
+                .options
+                .iter()
+                .zip(self.counts())
+                .map(|(option, votes)| PollResult {
+                    option: option.clone(),
+                    votes,
+                })
+                .collect(),
+            total: self.total(),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    combo::ComboUpdate,
+    message::{Message, MessageKind},
+};
+
+/// A message scrolling across the preview.
+#[derive(Debug, Clone)]
+pub struct PreviewItem {
+    pub kind: MessageKind,
+    pub label: String,
+    /// Sources of the images drawn in front of the label.
+    pub images: Vec<String>,
+    /// Of the images and the label together.
+    pub width: f32,
+    /// Left edge, from the left edge of the overlay.
+    pub x: f32,
+    combo_text: Option<String>,
+}
+
+/// A paid message held at the bottom of the preview.
+#[derive(Debug, Clone)]
+pub struct PinnedItem {
+    pub kind: MessageKind,
+    pub label: String,
+    pub until: Instant,
+}
+
+/// The overlay being approximated. Sizes are in whatever unit the
+/// caller measures text in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreviewFrame {
+    pub width: f32,
+    pub height: f32,
+    /// Height of one line of text.
+    pub row_height: f32,
+    /// In overlay widths per second.
+    pub speed: f32,
+    /// Gap a row needs before taking the next message.
+    pub spacing: f32,
+}
+
+impl PreviewFrame {
+    fn row_num(&self) -> usize {
+        (self.height / self.row_height).floor() as usize
+    }
+}
+
+/// Lays out forwarded messages the way the built-in danmaku overlay
+/// does, so the app can draw an approximation of what it shows.
+#[derive(Debug, Default)]
+pub struct OverlayPreview {
+    pending: VecDeque<PreviewItem>,
+    rows: Vec<Vec<PreviewItem>>,
+    pinned: Vec<PinnedItem>,
+}
+
+impl OverlayPreview {
+    /// What the overlay prints for `msg`, the username in front for
+    /// everything but chat.
+    pub fn label(msg: &Message) -> String {
+        match &msg.username {
+            Some(username) if msg.kind != MessageKind::Chat => {
+                format!("{username}: {}", msg.text)
+            }
+            _ => msg.text.clone(),
+        }
+    }
+
+    pub fn combo_label(text: &str, count: u32) -> String {
+        format!("{text} ×{count}")
+    }
+
+    /// Queues a message forwarded at `now`, [`Self::label`] measuring
+    /// `width` together with its images. Pinned paid messages go to the
+    /// bottom instead.
+    pub fn push(&mut self, msg: &Message, width: f32, now: Instant) {
+        let label = Self::label(msg);
+        if let Some(secs) = msg.paid.as_ref().and_then(|it| it.pin_secs) {
+            self.pinned.push(PinnedItem {
+                kind: msg.kind,
+                label,
+                until: now + Duration::from_secs_f64(secs),
+            });
+            // run out first at the front
+            self.pinned.sort_by_key(|it| it.until);
+            return;
+        }
+        let images =
+            msg.attachments.iter().filter_map(|it| it.src()).collect();
+        let combo_text = (msg.kind == MessageKind::Chat
+            && msg.attachments.is_empty())
+        .then(|| msg.text.clone());
+        self.pending.push_back(PreviewItem {
+            kind: msg.kind,
+            label,
+            images,
+            width,
+            x: 0.0,
+            combo_text,
+        });
+    }
+
+    /// Updates the newest item showing the combo's text in place, or
+    /// queues a new one. [`Self::combo_label`] measures `width`.
+    pub fn push_combo(&mut self, combo: &ComboUpdate, width: f32) {
+        let label = Self::combo_label(&combo.text, combo.count);
+        let item = self
+            .pending
+            .iter_mut()
+            .chain(self.rows.iter_mut().flatten())
+            .filter(|it| it.combo_text.as_ref() == Some(&combo.text))
+            .last();
+        match item {
+            Some(item) => {
+                item.label = label;
+                item.width = width;
+            }
+            None => self.pending.push_back(PreviewItem {
+                kind: MessageKind::Chat,
+                label,
+                images: vec![],
+                width,
+                x: 0.0,
+                combo_text: Some(combo.text.clone()),
+            }),
+        }
+    }
+
+    /// Moves everything `dt` seconds along.
+    pub fn step(&mut self, dt: f32, frame: &PreviewFrame, now: Instant) {
+        self.pinned.retain(|it| it.until > now);
+        let width = frame.width;
+        let row_num =
+            frame.row_num().saturating_sub(self.pinned_len(frame));
+        if self.rows.len() < row_num {
+            self.rows.resize_with(row_num, Vec::new);
+        }
+
+        for row in self.rows.iter_mut().take(row_num) {
+            if self.pending.is_empty() {
+                break;
+            }
+            if let Some(last) = row.last() {
+                if width - (last.x + last.width) < frame.spacing {
+                    continue;
+                }
+            }
+            let mut item = self.pending.pop_front().unwrap();
+            item.x = width;
+            row.push(item);
+        }
+
+        // catches up when messages pile up
+        let speed =
+            frame.speed * (1.0 + self.pending.len() as f32 / 20.0);
+        for row in &mut self.rows {
+            row.retain(|it| it.x >= -it.width);
+            for item in row {
+                item.x -= speed * width * dt;
+            }
+        }
+    }
+
+    /// Rows from the top with what is scrolling in each.
+    pub fn rows(&self) -> impl Iterator<Item = &[PreviewItem]> {
+        self.rows.iter().map(Vec::as_slice)
+    }
+
+    /// Pinned messages from the bottom up, as many as fit.
+    pub fn pinned(
+        &self,
+        frame: &PreviewFrame,
+    ) -> impl Iterator<Item = &PinnedItem> {
+        self.pinned.iter().rev().take(self.pinned_len(frame))
+    }
+
+    /// Waiting for room in a row.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Nothing is moving, nothing needs to be redrawn.
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+            && self.pinned.is_empty()
+            && self.rows.iter().all(Vec::is_empty)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn pinned_len(&self, frame: &PreviewFrame) -> usize {
+        self.pinned.len().min(frame.row_num())
+    }
+}
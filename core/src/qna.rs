@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::message::{Message, MessageKind};
+
+/// Questions held at most, older ones make room.
+const MAX_QUESTIONS: usize = 500;
+
+/// Which chat messages are questions, routed to the Q&A pane instead of
+/// the queue while on.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct QnaConfig {
+    pub enabled: bool,
+    /// Tags a question without a question mark, e.g. `Q:`. Empty for
+    /// none.
+    pub prefix: String,
+}
+
+impl QnaConfig {
+    /// Chat ending with a question mark, or starting with the prefix
+    /// in any case.
+    pub fn is_question(&self, msg: &Message) -> bool {
+        if !self.enabled || msg.kind != MessageKind::Chat {
+            return false;
+        }
+        let text = msg.text.trim();
+        let prefix = self.prefix.trim();
+        text.ends_with(['?', '？'])
+            || (!prefix.is_empty()
+                && text
+                    .get(..prefix.len())
+                    .is_some_and(|it| it.eq_ignore_ascii_case(prefix)))
+    }
+}
+
+/// Questions waiting to be forwarded or dismissed, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct Questions {
+    questions: VecDeque<(DateTime<Utc>, Message)>,
+}
+
+impl Questions {
+    /// Returns the oldest question if it had to make room.
+    pub fn push(
+        &mut self,
+        msg: Message,
+        at: DateTime<Utc>,
+    ) -> Option<Message> {
+        let dropped = (self.questions.len() == MAX_QUESTIONS)
+            .then(|| self.questions.pop_front())
+            .flatten()
+            .map(|it| it.1);
+        self.questions.push_back((at, msg));
+        dropped
+    }
+
+    /// With when each was received.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = &(DateTime<Utc>, Message)> {
+        self.questions.iter()
+    }
+
+    pub fn take(&mut self, idx: usize) -> Option<Message> {
+        self.questions.remove(idx).map(|it| it.1)
+    }
+
+    pub fn take_all(&mut self) -> Vec<Message> {
+        self.questions.drain(..).map(|it| it.1).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+}
@@ -0,0 +1,778 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::{
+    clock::{Clock, SystemClock},
+    message::Message,
+};
+
+pub struct PendingMessage {
+    pub msg: Message,
+    /// When the source delivered the message, before any pause.
+    pub received_at: Instant,
+    /// When the message entered the queue.
+    pub arrive_at: DateTime<Utc>,
+    /// Released once the clock passes this. Fixed when the message enters
+    /// the queue, later delay changes don't move it.
+    pub send_at: DateTime<Utc>,
+    pub delete: bool,
+    /// Released on the next update whatever the deadline or pause, see
+    /// [`MessageQueue::send_next`].
+    pub forced: bool,
+}
+
+impl PendingMessage {
+    /// Fraction of the way from `arrive_at` to `send_at` at `now`,
+    /// clamped to `0..=1`.
+    pub fn progress(&self, now: DateTime<Utc>) -> f32 {
+        let total = (self.send_at - self.arrive_at).num_milliseconds();
+        if total <= 0 {
+            return 1.0;
+        }
+        let elapsed = (now - self.arrive_at).num_milliseconds();
+        (elapsed as f64 / total as f64).clamp(0.0, 1.0) as f32
+    }
+
+    /// As saved in a [`QueueSnapshot`], with its deadlines.
+    pub fn snapshot_entry(&self) -> SnapshotEntry {
+        SnapshotEntry {
+            msg: self.msg.clone(),
+            arrive_at: Some(self.arrive_at),
+            send_at: Some(self.send_at),
+            delay_secs: None,
+            scheduled: false,
+        }
+    }
+}
+
+/// What happens to messages arriving while the queue is at
+/// [`QueueLimit::max_len`].
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum OverflowPolicy {
+    /// Make room by dropping the oldest pending message.
+    #[default]
+    DropOldest,
+    /// Drop the arriving message.
+    DropNewest,
+    /// Accept the message, the frontend stops pulling from the sources
+    /// until there is room again.
+    PauseSources,
+}
+
+impl OverflowPolicy {
+    pub const ALL: [OverflowPolicy; 3] = [
+        OverflowPolicy::DropOldest,
+        OverflowPolicy::DropNewest,
+        OverflowPolicy::PauseSources,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OverflowPolicy::DropOldest => "Drop oldest",
+            OverflowPolicy::DropNewest => "Drop newest",
+            OverflowPolicy::PauseSources => "Pause sources",
+        }
+    }
+}
+
+/// Cap on pending messages, queued and waiting together.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueLimit {
+    /// 0 for no cap.
+    pub max_len: usize,
+    pub policy: OverflowPolicy,
+}
+
+struct WaitingMessage {
+    msg: Message,
+    received_at: Instant,
+    /// Overrides the delay passed to [`MessageQueue::update`].
+    delay_secs: Option<f64>,
+}
+
+/// Delay queue between the sources and the overlay.
+///
+/// Incoming messages wait in `message_waiting` until the queue is
+/// updated while not paused, then stay in `message` until their send
+/// deadline has passed. Messages scheduled for a time of their own stay
+/// in `scheduled` until then, apart from the limit and slow mode.
+pub struct MessageQueue {
+    clock: Arc<dyn Clock>,
+
+    message: VecDeque<PendingMessage>,
+    message_waiting: VecDeque<WaitingMessage>,
+    /// Soonest first.
+    scheduled: Vec<PendingMessage>,
+
+    /// Slow mode, zero to release every due message at once.
+    min_spacing: Duration,
+    last_release: Option<DateTime<Utc>>,
+
+    limit: QueueLimit,
+    overflowed: Vec<Message>,
+    overflow_count: u64,
+}
+
+impl Default for MessageQueue {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl MessageQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+
+            message: VecDeque::new(),
+            message_waiting: VecDeque::new(),
+            scheduled: vec![],
+
+            min_spacing: Duration::ZERO,
+            last_release: None,
+
+            limit: QueueLimit::default(),
+            overflowed: vec![],
+            overflow_count: 0,
+        }
+    }
+
+    /// Releases at most one message per `min_spacing`, so a backlog that
+    /// built up e.g. during a pause trickles out instead of going out at
+    /// once. Zero turns slow mode off.
+    pub fn set_min_spacing(&mut self, min_spacing: Duration) {
+        self.min_spacing = min_spacing;
+    }
+
+    pub fn min_spacing(&self) -> Duration {
+        self.min_spacing
+    }
+
+    pub fn set_limit(&mut self, limit: QueueLimit) {
+        self.limit = limit;
+    }
+
+    pub fn limit(&self) -> &QueueLimit {
+        &self.limit
+    }
+
+    /// Whether pending messages reached the cap.
+    pub fn is_full(&self) -> bool {
+        self.limit.max_len > 0
+            && self.message.len() + self.message_waiting.len()
+                >= self.limit.max_len
+    }
+
+    /// Whether the frontend should stop pulling from the sources, see
+    /// [`OverflowPolicy::PauseSources`].
+    pub fn pauses_sources(&self) -> bool {
+        self.limit.policy == OverflowPolicy::PauseSources
+            && self.is_full()
+    }
+
+    /// How many more messages the frontend can pull from the sources
+    /// before [`Self::pauses_sources`], unbounded for the other
+    /// policies.
+    pub fn source_room(&self) -> usize {
+        if self.limit.policy != OverflowPolicy::PauseSources
+            || self.limit.max_len == 0
+        {
+            return usize::MAX;
+        }
+        self.limit.max_len.saturating_sub(
+            self.message.len() + self.message_waiting.len(),
+        )
+    }
+
+    /// Messages dropped by the overflow policy so far.
+    pub fn overflow_count(&self) -> u64 {
+        self.overflow_count
+    }
+
+    /// Returns the messages dropped by the overflow policy since the last
+    /// call, for logging.
+    pub fn take_overflowed(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.overflowed)
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        self.clock.now_utc()
+    }
+
+    pub fn push(&mut self, msg: Message) {
+        self.push_received(msg, self.clock.now());
+    }
+
+    /// Like [`MessageQueue::push`], for messages stamped by the source.
+    pub fn push_received(&mut self, msg: Message, received_at: Instant) {
+        self.push_with_delay(msg, received_at, None);
+    }
+
+    /// Like [`MessageQueue::push_received`], for sources with their own
+    /// send delay.
+    pub fn push_with_delay(
+        &mut self,
+        msg: Message,
+        received_at: Instant,
+        delay_secs: Option<f64>,
+    ) {
+        if self.is_full() {
+            match self.limit.policy {
+                OverflowPolicy::DropOldest => {
+                    let oldest = match self
+                        .message
+                        .iter()
+                        .position(|it| !it.delete)
+                    {
+                        Some(idx) => {
+                            self.message.remove(idx).map(|it| it.msg)
+                        }
+                        None => self
+                            .message_waiting
+                            .pop_front()
+                            .map(|it| it.msg),
+                    };
+                    if let Some(oldest) = oldest {
+                        self.overflowed.push(oldest);
+                        self.overflow_count += 1;
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    self.overflowed.push(msg);
+                    self.overflow_count += 1;
+                    return;
+                }
+                OverflowPolicy::PauseSources => {}
+            }
+        }
+        self.message_waiting.push_back(WaitingMessage {
+            msg,
+            received_at,
+            delay_secs,
+        });
+    }
+
+    /// Sends `msg` once the clock passes `send_at`, unless paused then.
+    pub fn schedule(&mut self, msg: Message, send_at: DateTime<Utc>) {
+        let idx =
+            self.scheduled.partition_point(|it| it.send_at <= send_at);
+        self.scheduled.insert(
+            idx,
+            PendingMessage {
+                msg,
+                received_at: self.clock.now(),
+                arrive_at: self.clock.now_utc(),
+                send_at,
+                delete: false,
+                forced: false,
+            },
+        );
+    }
+
+    pub fn scheduled_len(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Soonest first.
+    pub fn scheduled_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut PendingMessage> {
+        self.scheduled.iter_mut()
+    }
+
+    pub fn waiting_len(&self) -> usize {
+        self.message_waiting.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.message.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.message.is_empty()
+    }
+
+    /// Newest first, the order messages are displayed in.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut PendingMessage> {
+        self.message.iter_mut().rev()
+    }
+
+    /// Moves waiting messages into the queue with a deadline `delay_secs`
+    /// (or their source's own delay) from now unless paused, then
+    /// returns every message whose deadline has passed, oldest first. In
+    /// slow mode only the oldest of them is returned, once `min_spacing`
+    /// has passed since the last release. Scheduled messages are
+    /// returned once due either way, forced ones right away even while
+    /// paused.
+    pub fn update(
+        &mut self,
+        pause: bool,
+        delay_secs: f64,
+    ) -> Vec<PendingMessage> {
+        let mut released = vec![];
+        if self.message.iter().any(|it| it.forced) {
+            let kept;
+            (released, kept) = std::mem::take(&mut self.message)
+                .into_iter()
+                .partition(|it| it.forced && !it.delete);
+            self.message = kept.into();
+        }
+        if pause {
+            return released;
+        }
+
+        let now = self.clock.now_utc();
+        while let Some(waiting) = self.message_waiting.pop_front() {
+            let delay = waiting.delay_secs.unwrap_or(delay_secs);
+            let delay =
+                TimeDelta::milliseconds((delay * 1000.0).round() as i64);
+            self.message.push_back(PendingMessage {
+                msg: waiting.msg,
+                received_at: waiting.received_at,
+                arrive_at: now,
+                send_at: now + delay,
+                delete: false,
+                forced: false,
+            });
+        }
+
+        let due = |it: &PendingMessage| !it.delete && it.send_at <= now;
+        if self.min_spacing.is_zero() {
+            let (ready, kept): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut self.message)
+                    .into_iter()
+                    .partition(due);
+            released.extend(ready);
+            self.message = kept.into();
+        } else if self
+            .last_release
+            .is_none_or(|it| now >= it + self.min_spacing)
+        {
+            if let Some(idx) = self.message.iter().position(due) {
+                released.extend(self.message.remove(idx));
+            }
+        }
+        if !released.is_empty() {
+            self.last_release = Some(now);
+        }
+
+        let due = self
+            .scheduled
+            .iter()
+            .position(|it| it.send_at > now)
+            .unwrap_or(self.scheduled.len());
+        if due > 0 {
+            released.extend(
+                self.scheduled.drain(..due).filter(|it| !it.delete),
+            );
+            released.sort_by_key(|it| it.send_at);
+        }
+
+        released
+    }
+
+    /// Has the next update release the oldest pending message at once,
+    /// even while paused, before its deadline or in slow mode. Returns
+    /// whether there was one.
+    pub fn send_next(&mut self) -> bool {
+        self.force(|_| true)
+    }
+
+    /// [`Self::send_next`] for the oldest pending message equal to `msg`.
+    pub fn send_now(&mut self, msg: &Message) -> bool {
+        self.force(|it| it == msg)
+    }
+
+    /// What [`Self::send_next`] would send.
+    pub fn next_pending(&self) -> Option<&Message> {
+        self.message
+            .iter()
+            .find(|it| !it.delete && !it.forced)
+            .map(|it| &it.msg)
+            .or_else(|| self.message_waiting.front().map(|it| &it.msg))
+    }
+
+    fn force(&mut self, pred: impl Fn(&Message) -> bool) -> bool {
+        if let Some(pending) = self
+            .message
+            .iter_mut()
+            .find(|it| !it.delete && !it.forced && pred(&it.msg))
+        {
+            pending.forced = true;
+            return true;
+        }
+        let Some(idx) =
+            self.message_waiting.iter().position(|it| pred(&it.msg))
+        else {
+            return false;
+        };
+        let waiting = self.message_waiting.remove(idx).unwrap();
+        let now = self.clock.now_utc();
+        self.message.push_back(PendingMessage {
+            msg: waiting.msg,
+            received_at: waiting.received_at,
+            arrive_at: now,
+            send_at: now,
+            delete: false,
+            forced: true,
+        });
+        true
+    }
+
+    /// How long until the next update would release something, zero if
+    /// it already would or messages are waiting to enter. `None` with
+    /// nothing pending, so the releaser only has to wake up then. Slow
+    /// mode holds the earliest deadline back as [`Self::update`] does.
+    pub fn next_release_in(&self) -> Option<Duration> {
+        if !self.message_waiting.is_empty() {
+            return Some(Duration::ZERO);
+        }
+        let now = self.clock.now_utc();
+        let send_at = self
+            .message
+            .iter()
+            .filter(|it| !it.delete)
+            .map(|it| it.send_at)
+            .min();
+        let send_at = match (send_at, self.last_release) {
+            (Some(send_at), Some(last))
+                if !self.min_spacing.is_zero() =>
+            {
+                Some(send_at.max(last + self.min_spacing))
+            }
+            (send_at, _) => send_at,
+        };
+        let scheduled = self
+            .scheduled
+            .iter()
+            .filter(|it| !it.delete)
+            .map(|it| it.send_at)
+            .next();
+        let send_at = send_at.into_iter().chain(scheduled).min()?;
+        Some((send_at - now).to_std().unwrap_or_default())
+    }
+
+    /// Pending messages, oldest first. Ones marked for deletion are left
+    /// out.
+    pub fn snapshot(&self) -> QueueSnapshot {
+        let queued = self
+            .message
+            .iter()
+            .filter(|it| !it.delete)
+            .map(PendingMessage::snapshot_entry);
+        let waiting =
+            self.message_waiting.iter().map(|it| SnapshotEntry {
+                msg: it.msg.clone(),
+                arrive_at: None,
+                send_at: None,
+                delay_secs: it.delay_secs,
+                scheduled: false,
+            });
+        let scheduled =
+            self.scheduled.iter().filter(|it| !it.delete).map(|it| {
+                SnapshotEntry {
+                    scheduled: true,
+                    ..it.snapshot_entry()
+                }
+            });
+        QueueSnapshot {
+            saved_at: self.clock.now_utc(),
+            entries: queued.chain(waiting).chain(scheduled).collect(),
+        }
+    }
+
+    /// Adds the snapshot's messages with their deadlines. Messages
+    /// whose deadline passed while the app was down go back to waiting
+    /// for a full delay rather than going out unreviewed, scheduled ones
+    /// too. Meant for an empty queue at startup, or putting back what
+    /// was deleted or purged.
+    pub fn restore(&mut self, snapshot: QueueSnapshot) {
+        let now = self.clock.now();
+        let now_utc = self.clock.now_utc();
+        for entry in snapshot.entries {
+            match (entry.arrive_at, entry.send_at) {
+                (Some(_), Some(send_at))
+                    if entry.scheduled && send_at > now_utc =>
+                {
+                    self.schedule(entry.msg, send_at);
+                }
+                (Some(arrive_at), Some(send_at)) if send_at > now_utc => {
+                    let idx = self
+                        .message
+                        .partition_point(|it| it.arrive_at <= arrive_at);
+                    self.message.insert(
+                        idx,
+                        PendingMessage {
+                            msg: entry.msg,
+                            received_at: now,
+                            arrive_at,
+                            send_at,
+                            delete: false,
+                            forced: false,
+                        },
+                    );
+                }
+                _ => self.message_waiting.push_back(WaitingMessage {
+                    msg: entry.msg,
+                    received_at: now,
+                    delay_secs: entry.delay_secs,
+                }),
+            }
+        }
+    }
+
+    /// Empties the queue, the waiting list and the scheduled messages,
+    /// returning every message that was in them, oldest first.
+    pub fn purge(&mut self) -> Vec<Message> {
+        let queued = self.message.drain(..).map(|it| it.msg);
+        let waiting = self.message_waiting.drain(..).map(|it| it.msg);
+        let scheduled = self.scheduled.drain(..).map(|it| it.msg);
+        queued.chain(waiting).chain(scheduled).collect()
+    }
+
+    /// Removes messages marked for deletion and returns them.
+    pub fn take_deleted(&mut self) -> Vec<Message> {
+        self.take_deleted_entries()
+            .into_iter()
+            .map(|it| it.msg)
+            .collect()
+    }
+
+    /// [`Self::take_deleted`] with their deadlines, to [`Self::restore`]
+    /// them with.
+    pub fn take_deleted_entries(&mut self) -> Vec<SnapshotEntry> {
+        let (deleted, kept): (VecDeque<_>, _) =
+            std::mem::take(&mut self.message)
+                .into_iter()
+                .partition(|it| it.delete);
+        self.message = kept;
+        let (scheduled, kept): (Vec<_>, _) =
+            std::mem::take(&mut self.scheduled)
+                .into_iter()
+                .partition(|it| it.delete);
+        self.scheduled = kept;
+        let deleted = deleted.iter().map(PendingMessage::snapshot_entry);
+        let scheduled = scheduled.iter().map(|it| SnapshotEntry {
+            scheduled: true,
+            ..it.snapshot_entry()
+        });
+        deleted.chain(scheduled).collect()
+    }
+
+    /// Takes the oldest pending message equal to `msg` off right away,
+    /// queued, waiting or scheduled, and returns it.
+    pub fn remove(&mut self, msg: &Message) -> Option<SnapshotEntry> {
+        if let Some(idx) = self
+            .message
+            .iter()
+            .position(|it| !it.delete && !it.forced && it.msg == *msg)
+        {
+            return self
+                .message
+                .remove(idx)
+                .map(|it| it.snapshot_entry());
+        }
+        if let Some(idx) =
+            self.message_waiting.iter().position(|it| it.msg == *msg)
+        {
+            return self.message_waiting.remove(idx).map(|it| {
+                SnapshotEntry {
+                    msg: it.msg,
+                    arrive_at: None,
+                    send_at: None,
+                    delay_secs: it.delay_secs,
+                    scheduled: false,
+                }
+            });
+        }
+        let idx = self
+            .scheduled
+            .iter()
+            .position(|it| !it.delete && it.msg == *msg)?;
+        Some(SnapshotEntry {
+            scheduled: true,
+            ..self.scheduled.remove(idx).snapshot_entry()
+        })
+    }
+}
+
+/// [`MessageQueue`] shared between the frontend and the task releasing
+/// from it. A lock through [`SharedQueue::lock`] that changed the queue
+/// wakes the task up when dropped, as the deadlines may have moved.
+#[derive(Clone)]
+pub struct SharedQueue {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    queue: Mutex<MessageQueue>,
+    changed: Notify,
+}
+
+impl SharedQueue {
+    pub fn new(queue: MessageQueue) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                queue: Mutex::new(queue),
+                changed: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn lock(&self) -> QueueGuard<'_> {
+        QueueGuard {
+            guard: self.lock_quiet(),
+            changed: &self.shared.changed,
+            dirty: false,
+        }
+    }
+
+    /// Lock that doesn't wake the releasing task, for the task itself
+    /// and changes that can't bring a deadline forward.
+    pub fn lock_quiet(&self) -> MutexGuard<'_, MessageQueue> {
+        self.shared.queue.lock().unwrap()
+    }
+
+    /// Resolves after the next changed [`QueueGuard`] is dropped, or
+    /// right away if one was since the last call.
+    pub async fn changed(&self) {
+        self.shared.changed.notified().await;
+    }
+}
+
+pub struct QueueGuard<'a> {
+    guard: MutexGuard<'a, MessageQueue>,
+    changed: &'a Notify,
+    /// Borrowed mutably, assumed changed.
+    dirty: bool,
+}
+
+impl Deref for QueueGuard<'_> {
+    type Target = MessageQueue;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl DerefMut for QueueGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.dirty = true;
+        &mut self.guard
+    }
+}
+
+impl Drop for QueueGuard<'_> {
+    fn drop(&mut self) {
+        if self.dirty {
+            self.changed.notify_one();
+        }
+    }
+}
+
+/// On-disk copy of a [`MessageQueue`], so pending messages survive a
+/// crash or restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub saved_at: DateTime<Utc>,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub msg: Message,
+    /// Both `None` if still waiting to enter the queue.
+    #[serde(default)]
+    pub arrive_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub send_at: Option<DateTime<Utc>>,
+    /// Own delay of the message's source, for waiting messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_secs: Option<f64>,
+    /// Sent at `send_at` by [`MessageQueue::schedule`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub scheduled: bool,
+}
+
+impl QueueSnapshot {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Time since the snapshot was taken.
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.saved_at).to_std().unwrap_or_default()
+    }
+
+    /// Returns `None` if there is no snapshot at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read {}", path.display())
+                });
+            }
+        };
+        serde_json::from_slice(&data).map(Some).with_context(|| {
+            format!("invalid snapshot {}", path.display())
+        })
+    }
+
+    /// Writes to a temporary file first so a crash mid-write leaves the
+    /// previous snapshot intact. An empty snapshot removes the file.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return match fs::remove_file(path) {
+                Err(err)
+                    if err.kind() != std::io::ErrorKind::NotFound =>
+                {
+                    Err(err).with_context(|| {
+                        format!("failed to remove {}", path.display())
+                    })
+                }
+                _ => Ok(()),
+            };
+        }
+        let tmp = path.with_extension("json.tmp");
+        let data = serde_json::to_vec(self)
+            .context("failed to serialize snapshot")?;
+        fs::write(&tmp, data).with_context(|| {
+            format!("failed to write {}", tmp.display())
+        })?;
+        fs::rename(&tmp, path).with_context(|| {
+            format!("failed to replace {}", path.display())
+        })
+    }
+}
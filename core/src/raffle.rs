@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use rand::seq::SliceRandom;
+
+use crate::message::{Message, MessageKind};
+
+/// Enters chatters who type a keyword until it closes, then draws
+/// winners among them at random.
+#[derive(Debug, Clone)]
+pub struct Raffle {
+    /// Matched against the whole text, in any case.
+    pub keyword: String,
+    closes_at: DateTime<Utc>,
+    /// In the order they entered, once each.
+    entrants: Vec<String>,
+    /// Oldest first, none drawn twice.
+    winners: Vec<String>,
+}
+
+impl Raffle {
+    pub fn new(
+        keyword: impl Into<String>,
+        closes_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            keyword: keyword.into(),
+            closes_at,
+            entrants: vec![],
+            winners: vec![],
+        }
+    }
+
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        now < self.closes_at
+    }
+
+    pub fn closes_at(&self) -> DateTime<Utc> {
+        self.closes_at
+    }
+
+    /// Takes no more entries from `now` on.
+    pub fn close(&mut self, now: DateTime<Utc>) {
+        self.closes_at = self.closes_at.min(now);
+    }
+
+    /// Enters the sender of `msg` if it's the keyword and the raffle is
+    /// open. Returns whether it was an entry, even a repeated one.
+    pub fn enter(&mut self, msg: &Message, now: DateTime<Utc>) -> bool {
+        if !self.is_open(now)
+            || msg.kind != MessageKind::Chat
+            || msg.text.trim().to_lowercase()
+                != self.keyword.trim().to_lowercase()
+        {
+            return false;
+        }
+        let Some(ref username) = msg.username else {
+            return false;
+        };
+        if !self.entrants.contains(username) {
+            self.entrants.push(username.clone());
+        }
+        true
+    }
+
+    pub fn entrants(&self) -> &[String] {
+        &self.entrants
+    }
+
+    pub fn winners(&self) -> &[String] {
+        &self.winners
+    }
+
+    /// Picks an entrant not drawn before and not `excluded`, like a
+    /// user timed out since entering. Re-rolls are drawn the same way.
+    pub fn draw(
+        &mut self,
+        excluded: impl Fn(&str) -> bool,
+    ) -> Option<&str> {
+        let candidates = self
+            .entrants
+            .iter()
+            .filter(|it| !self.winners.contains(it) && !excluded(it))
+            .collect::<Vec<_>>();
+        let winner =
+            (*candidates.choose(&mut rand::thread_rng())?).clone();
+        self.winners.push(winner);
+        self.winners.last().map(String::as_str)
+    }
+}
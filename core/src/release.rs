@@ -0,0 +1,166 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+
+use crate::{
+    alert::AlertConfig,
+    combo::{ComboCounter, ComboUpdate},
+    message::Message,
+    queue::{MessageQueue, PendingMessage},
+    schedule::PauseSchedule,
+    superchat::PinDurations,
+    text::{
+        overlay_messages, LengthLimit, OverlayMarkup, UrlFilter, UrlHit,
+    },
+};
+
+/// Everything the frontend decides about releasing, handed over to the
+/// releasing task whenever it changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseConfig {
+    /// Send delay for messages without one of their own.
+    pub delay_secs: f64,
+    /// Held by hand, e.g. while hovering the delete buttons. The pause
+    /// schedule is checked on top of this.
+    pub paused: bool,
+    pub pause_schedule: PauseSchedule,
+    pub url_filter: UrlFilter,
+    pub pin_durations: PinDurations,
+    pub length_limit: LengthLimit,
+    pub overlay_markup: OverlayMarkup,
+    /// Zero turns combos off.
+    pub combo_window: Duration,
+    /// Fired by the releasing task as messages go out.
+    pub alerts: AlertConfig,
+}
+
+impl ReleaseConfig {
+    pub fn is_paused(&self, now: DateTime<Utc>) -> bool {
+        self.paused
+            || self
+                .pause_schedule
+                .state(now.with_timezone(&Local).time())
+                .is_paused()
+    }
+}
+
+/// One JSON frame broadcast to overlays.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum OverlayFrame {
+    Message(Message),
+    Combo(ComboUpdate),
+}
+
+/// A message taken off the queue, with what goes out for it.
+#[derive(Debug, Clone)]
+pub struct Released {
+    /// As it was queued, for the log.
+    pub msg: Message,
+    /// With links filtered and the pin duration filled in.
+    pub filtered: Message,
+    pub urls: Vec<UrlHit>,
+    /// Escaped and split to the length limit, or a single combo frame.
+    pub frames: Vec<OverlayFrame>,
+    /// Place in a running combo, 1 if it isn't part of one.
+    pub combo: u32,
+    pub received_at: Instant,
+    pub released_at: Instant,
+    /// Whether any overlay client got a frame, set once broadcast.
+    pub sent: bool,
+}
+
+/// Turns due messages into overlay frames, keeping track of combos
+/// across releases.
+#[derive(Debug, Default)]
+pub struct Releaser {
+    combo_counter: ComboCounter,
+}
+
+impl Releaser {
+    pub fn release(
+        &mut self,
+        queue: &mut MessageQueue,
+        config: &ReleaseConfig,
+    ) -> Vec<Released> {
+        let paused = config.is_paused(queue.now_utc());
+        let now = queue.now();
+        queue
+            .update(paused, config.delay_secs)
+            .into_iter()
+            .map(
+                |PendingMessage {
+                     msg, received_at, ..
+                 }| {
+                    self.release_one(msg, received_at, now, config)
+                },
+            )
+            .collect()
+    }
+
+    fn release_one(
+        &mut self,
+        msg: Message,
+        received_at: Instant,
+        now: Instant,
+        config: &ReleaseConfig,
+    ) -> Released {
+        let (text, urls) = config.url_filter.apply(&msg.text);
+        let mut filtered = Message {
+            text,
+            ..msg.clone()
+        };
+        config.pin_durations.apply(&mut filtered);
+        let combo =
+            self.combo_counter
+                .count(&filtered, now, config.combo_window);
+        let frames = if combo > 1 {
+            let text = config
+                .overlay_markup
+                .escape(&config.length_limit.truncate(&filtered.text));
+            vec![OverlayFrame::Combo(ComboUpdate::new(text, combo))]
+        } else {
+            overlay_messages(
+                &filtered,
+                &config.length_limit,
+                &config.overlay_markup,
+            )
+            .into_iter()
+            .map(OverlayFrame::Message)
+            .collect()
+        };
+        Released {
+            msg,
+            filtered,
+            urls,
+            frames,
+            combo,
+            received_at,
+            released_at: now,
+            sent: false,
+        }
+    }
+}
+
+/// How long the releasing task can sleep before it has to look at the
+/// queue again, unless the queue or config changes first. `None` to
+/// sleep until then.
+pub fn next_wake(
+    queue: &MessageQueue,
+    config: &ReleaseConfig,
+) -> Option<Duration> {
+    let now = queue.now_utc();
+    let schedule = config
+        .pause_schedule
+        .state(now.with_timezone(&Local).time())
+        .next_change()
+        .map(|it| it.to_std().unwrap_or_default());
+    if config.is_paused(now) {
+        return schedule;
+    }
+    match (schedule, queue.next_release_in()) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
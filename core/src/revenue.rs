@@ -0,0 +1,145 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Write};
+
+use crate::message::{Message, MessageKind};
+
+/// What gifts and paid messages brought in, from one user or everyone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Takings {
+    pub gifts: u64,
+    /// In the platform's own unit, see [`crate::message::Gift::value`].
+    pub gift_value: u64,
+    pub paid_messages: usize,
+    /// In the platform's currency.
+    pub paid: f64,
+}
+
+impl Takings {
+    fn add(&mut self, msg: &Message) {
+        if let Some(ref gift) = msg.gift {
+            self.gifts += u64::from(gift.count);
+            self.gift_value += gift.value;
+        }
+        if let Some(ref paid) = msg.paid {
+            self.paid_messages += 1;
+            self.paid += paid.amount;
+        }
+    }
+
+    fn sub(&mut self, msg: &Message) {
+        if let Some(ref gift) = msg.gift {
+            self.gifts = self.gifts.saturating_sub(u64::from(gift.count));
+            self.gift_value = self.gift_value.saturating_sub(gift.value);
+        }
+        if let Some(ref paid) = msg.paid {
+            self.paid_messages = self.paid_messages.saturating_sub(1);
+            self.paid = (self.paid - paid.amount).max(0.0);
+        }
+    }
+}
+
+/// Running totals of gifts and paid messages like SuperChats, overall
+/// and per user.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Revenue {
+    total: Takings,
+    users: HashMap<String, Takings>,
+}
+
+impl Revenue {
+    /// Counts `msg` in if it's a gift or paid for, returns whether it
+    /// was. Paid messages without a sender only count towards the total.
+    pub fn record(&mut self, msg: &Message) -> bool {
+        if !brings_in(msg) {
+            return false;
+        }
+        self.total.add(msg);
+        if let Some(ref username) = msg.username {
+            self.users.entry(username.clone()).or_default().add(msg);
+        }
+        true
+    }
+
+    /// Takes a [`Revenue::record`]ed `msg` back out, e.g. one folded
+    /// into a summary that is counted instead.
+    pub fn unrecord(&mut self, msg: &Message) {
+        if !brings_in(msg) {
+            return;
+        }
+        self.total.sub(msg);
+        if let Some(ref username) = msg.username {
+            if let Some(takings) = self.users.get_mut(username) {
+                takings.sub(msg);
+            }
+        }
+    }
+
+    pub fn total(&self) -> &Takings {
+        &self.total
+    }
+
+    /// Most paid first, then by gift value, ties by name.
+    pub fn by_user(&self) -> Vec<(&str, &Takings)> {
+        let mut users = self
+            .users
+            .iter()
+            .filter(|(_, it)| it.gifts > 0 || it.paid_messages > 0)
+            .map(|(name, it)| (name.as_str(), it))
+            .collect::<Vec<_>>();
+        users.sort_by(|a, b| {
+            b.1.paid
+                .total_cmp(&a.1.paid)
+                .then_with(|| b.1.gift_value.cmp(&a.1.gift_value))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        users
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == Takings::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// A row per user as [`Revenue::by_user`] orders them, with a header
+    /// and the total last.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "username,gifts,gift_value,paid_messages,paid\n".to_owned();
+        let total = [("(total)", &self.total)];
+        for (name, it) in self.by_user().into_iter().chain(total) {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{:.2}",
+                csv_field(name),
+                it.gifts,
+                it.gift_value,
+                it.paid_messages,
+                it.paid
+            );
+        }
+        csv
+    }
+}
+
+fn brings_in(msg: &Message) -> bool {
+    (msg.kind == MessageKind::Gift && msg.gift.is_some())
+        || msg.paid.is_some()
+}
+
+/// Quoted if it would break the row. Usernames are up to chatters, so
+/// one a spreadsheet would take for a formula, e.g. `=HYPERLINK(..)`,
+/// is prefixed with `'` to be kept as text.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{field}"))
+    } else {
+        Cow::Borrowed(field)
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into_owned()
+    }
+}
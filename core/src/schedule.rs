@@ -0,0 +1,105 @@
+use chrono::{NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+/// Daily time window during which forwarding pauses by itself, e.g. for
+/// a sponsor segment. Windows with `end` before `start` span midnight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PauseWindow {
+    pub enabled: bool,
+    pub label: String,
+    /// Local time.
+    pub start: NaiveTime,
+    /// Local time, exclusive.
+    pub end: NaiveTime,
+}
+
+impl Default for PauseWindow {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            label: String::new(),
+            start: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(20, 5, 0).unwrap(),
+        }
+    }
+}
+
+impl PauseWindow {
+    pub fn contains(&self, now: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= now && now < self.end
+        } else {
+            now >= self.start || now < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PauseSchedule {
+    pub windows: Vec<PauseWindow>,
+}
+
+/// Where the schedule stands at some time of day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleState<'a> {
+    /// Inside `window`, pausing for another `left`.
+    Paused {
+        window: &'a PauseWindow,
+        left: TimeDelta,
+    },
+    /// `window` is the next one to start, in `until`.
+    Upcoming {
+        window: &'a PauseWindow,
+        until: TimeDelta,
+    },
+    /// No enabled windows.
+    Idle,
+}
+
+impl ScheduleState<'_> {
+    pub fn is_paused(&self) -> bool {
+        matches!(self, ScheduleState::Paused { .. })
+    }
+
+    /// Time until the state changes, `None` if it never does.
+    pub fn next_change(&self) -> Option<TimeDelta> {
+        match *self {
+            ScheduleState::Paused { left, .. } => Some(left),
+            ScheduleState::Upcoming { until, .. } => Some(until),
+            ScheduleState::Idle => None,
+        }
+    }
+}
+
+impl PauseSchedule {
+    pub fn state(&self, now: NaiveTime) -> ScheduleState<'_> {
+        let enabled = self.windows.iter().filter(|it| it.enabled);
+        // overlapping windows pause until the last of them ends
+        let paused = enabled
+            .clone()
+            .filter(|it| it.contains(now))
+            .map(|it| (it, until(now, it.end)))
+            .max_by_key(|(_, left)| *left);
+        if let Some((window, left)) = paused {
+            return ScheduleState::Paused { window, left };
+        }
+        enabled
+            .map(|it| (it, until(now, it.start)))
+            .min_by_key(|(_, until)| *until)
+            .map_or(ScheduleState::Idle, |(window, until)| {
+                ScheduleState::Upcoming { window, until }
+            })
+    }
+}
+
+/// Time from `now` until the next time the clock reads `at`.
+fn until(now: NaiveTime, at: NaiveTime) -> TimeDelta {
+    let delta = at - now;
+    if delta <= TimeDelta::zero() {
+        delta + TimeDelta::days(1)
+    } else {
+        delta
+    }
+}
@@ -0,0 +1,83 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    clock::ManualClock,
+    log::{LogEntry, LogEvent},
+    message::Message,
+    queue::MessageQueue,
+    release::{OverlayFrame, ReleaseConfig, Releaser},
+};
+
+/// Deterministic driver for the forwarding pipeline.
+///
+/// Each [`Simulation::step`] mirrors one wake of the releasing task: the
+/// [`Releaser`] takes due messages off the queue under `config`, their
+/// frames are "broadcast" and they are logged as forwarded, and deleted
+/// ones are logged. Alerts aren't fired. Time only moves through
+/// [`Simulation::advance`].
+pub struct Simulation {
+    pub clock: ManualClock,
+    pub queue: MessageQueue,
+    pub config: ReleaseConfig,
+    releaser: Releaser,
+
+    pub broadcast: Vec<OverlayFrame>,
+    pub log: Vec<LogEntry>,
+}
+
+impl Simulation {
+    pub fn new(delay_secs: f64) -> Self {
+        let clock = ManualClock::new();
+        Self {
+            queue: MessageQueue::with_clock(Arc::new(clock.clone())),
+            clock,
+            config: ReleaseConfig {
+                delay_secs,
+                ..ReleaseConfig::default()
+            },
+            releaser: Releaser::default(),
+
+            broadcast: vec![],
+            log: vec![],
+        }
+    }
+
+    pub fn push(&mut self, msg: impl Into<Message>) {
+        self.queue.push(msg.into());
+    }
+
+    pub fn step(&mut self) {
+        let released =
+            self.releaser.release(&mut self.queue, &self.config);
+        for it in released {
+            self.broadcast.extend(it.frames);
+            let entry = LogEntry::new(it.msg, LogEvent::Forward)
+                .with_urls(it.urls);
+            self.log.push(entry);
+        }
+        for msg in self.queue.take_deleted() {
+            self.log.push(LogEntry::new(msg, LogEvent::Delete));
+        }
+    }
+
+    /// Moves the clock forward, then runs one step.
+    pub fn advance(&mut self, secs: f64) {
+        self.clock.advance(Duration::from_secs_f64(secs));
+        self.step();
+    }
+
+    /// Marks the oldest pending message with text `text` for deletion,
+    /// like clicking its "Delete" button. Takes effect on the next step.
+    pub fn delete(&mut self, text: &str) -> bool {
+        let Some(pending) = self
+            .queue
+            .iter_mut()
+            .filter(|it| it.msg.text == text)
+            .last()
+        else {
+            return false;
+        };
+        pending.delete = true;
+        true
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Rolling window of end-to-end latencies, from the source delivering a
+/// message to it being handed to the overlay server.
+#[derive(Debug, Clone)]
+pub struct LatencyStats {
+    samples: VecDeque<Duration>,
+    window: usize,
+}
+
+impl Default for LatencyStats {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl LatencyStats {
+    /// Keeps the latest `window` samples.
+    pub fn new(window: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(window),
+            window,
+        }
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Nearest-rank percentile, `q` in `0..=1`. `None` without samples.
+    pub fn percentile(&self, q: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = Vec::from_iter(self.samples.iter().copied());
+        sorted.sort_unstable();
+        let rank = (q.clamp(0.0, 1.0) * sorted.len() as f64).ceil();
+        Some(sorted[(rank as usize).saturating_sub(1)])
+    }
+}
+
+/// Events per second over a trailing window.
+#[derive(Debug, Clone)]
+pub struct RateMeter {
+    events: VecDeque<Instant>,
+    window: Duration,
+}
+
+impl Default for RateMeter {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+impl RateMeter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            events: VecDeque::new(),
+            window,
+        }
+    }
+
+    pub fn record(&mut self, now: Instant) {
+        self.events.push_back(now);
+        self.expire(now);
+    }
+
+    /// Averaged over the window ending at `now`.
+    pub fn rate(&mut self, now: Instant) -> f64 {
+        self.expire(now);
+        self.events.len() as f64 / self.window.as_secs_f64()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(&first) = self.events.front() {
+            if now.saturating_duration_since(first) < self.window {
+                break;
+            }
+            self.events.pop_front();
+        }
+    }
+}
+
+/// Something done to the queue that explains a dip or spike in
+/// [`RateHistory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateMark {
+    Paused,
+    Resumed,
+    Purged,
+}
+
+/// Messages per minute over the whole session, incoming and forwarded
+/// counted apart, with [`RateMark`]s where they happened.
+#[derive(Debug, Clone)]
+pub struct RateHistory {
+    start: Instant,
+    incoming: Vec<u32>,
+    forwarded: Vec<u32>,
+    marks: Vec<(Duration, RateMark)>,
+}
+
+impl RateHistory {
+    /// Minute 0 starts at `start`.
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            incoming: vec![],
+            forwarded: vec![],
+            marks: vec![],
+        }
+    }
+
+    pub fn record_incoming(&mut self, now: Instant) {
+        let minute = self.minute(now);
+        bump(&mut self.incoming, minute);
+    }
+
+    pub fn record_forwarded(&mut self, now: Instant) {
+        let minute = self.minute(now);
+        bump(&mut self.forwarded, minute);
+    }
+
+    pub fn mark(&mut self, now: Instant, mark: RateMark) {
+        self.marks
+            .push((now.saturating_duration_since(self.start), mark));
+    }
+
+    /// Counts per minute, up to the last one with an event.
+    pub fn incoming(&self) -> &[u32] {
+        &self.incoming
+    }
+
+    pub fn forwarded(&self) -> &[u32] {
+        &self.forwarded
+    }
+
+    /// Since the start, in the order marked.
+    pub fn marks(&self) -> &[(Duration, RateMark)] {
+        &self.marks
+    }
+
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.start)
+    }
+
+    fn minute(&self, now: Instant) -> usize {
+        (self.elapsed(now).as_secs() / 60) as usize
+    }
+}
+
+fn bump(counts: &mut Vec<u32>, minute: usize) {
+    if counts.len() <= minute {
+        counts.resize(minute + 1, 0);
+    }
+    counts[minute] += 1;
+}
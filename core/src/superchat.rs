@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// Paid messages at or above `min_amount` stay pinned for `secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PinTier {
+    pub min_amount: f64,
+    pub secs: f64,
+}
+
+/// How long a paid message stays pinned on the overlay, by amount.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PinDurations {
+    pub tiers: Vec<PinTier>,
+}
+
+impl Default for PinDurations {
+    /// bilibili's SuperChat tiers, in CNY.
+    fn default() -> Self {
+        let tier = |min_amount, secs| PinTier { min_amount, secs };
+        Self {
+            tiers: vec![
+                tier(30.0, 60.0),
+                tier(50.0, 120.0),
+                tier(100.0, 300.0),
+                tier(500.0, 1800.0),
+                tier(1000.0, 3600.0),
+                tier(2000.0, 7200.0),
+            ],
+        }
+    }
+}
+
+impl PinDurations {
+    /// Duration of the highest tier `amount` reaches, `None` below all of
+    /// them.
+    pub fn secs_for(&self, amount: f64) -> Option<f64> {
+        self.tiers
+            .iter()
+            .filter(|it| amount >= it.min_amount)
+            .max_by(|a, b| a.min_amount.total_cmp(&b.min_amount))
+            .map(|it| it.secs)
+    }
+
+    /// Fills in the pinned duration of a paid message.
+    pub fn apply(&self, msg: &mut Message) {
+        if let Some(paid) = &mut msg.paid {
+            paid.pin_secs = self.secs_for(paid.amount);
+        }
+    }
+
+    /// `amount=secs` pairs, comma separated.
+    pub fn format(&self) -> String {
+        self.tiers
+            .iter()
+            .map(|it| format!("{}={}", it.min_amount, it.secs))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Reads [`Self::format`] back, `None` if any pair is invalid.
+    pub fn parse(tiers: &str) -> Option<Self> {
+        let tiers = tiers
+            .split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .map(|it| {
+                let (amount, secs) = it.split_once('=')?;
+                let min_amount = amount.trim().parse::<f64>().ok()?;
+                let secs = secs.trim().parse::<f64>().ok()?;
+                (min_amount >= 0.0 && secs > 0.0)
+                    .then_some(PinTier { min_amount, secs })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { tiers })
+    }
+}
+
+/// A forwarded paid message still pinned on the overlay.
+#[derive(Debug, Clone)]
+pub struct ActiveSuperChat {
+    pub msg: Message,
+    pub until: Instant,
+}
+
+/// Paid messages currently pinned, most recent first.
+#[derive(Debug, Default)]
+pub struct ActiveSuperChats {
+    active: Vec<ActiveSuperChat>,
+}
+
+impl ActiveSuperChats {
+    /// Tracks `msg` if it was sent pinned.
+    pub fn push(&mut self, msg: &Message, now: Instant) {
+        let Some(secs) = msg.paid.as_ref().and_then(|it| it.pin_secs)
+        else {
+            return;
+        };
+        self.active.insert(
+            0,
+            ActiveSuperChat {
+                msg: msg.clone(),
+                until: now + Duration::from_secs_f64(secs),
+            },
+        );
+    }
+
+    pub fn expire(&mut self, now: Instant) {
+        self.active.retain(|it| it.until > now);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ActiveSuperChat> {
+        self.active.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+}
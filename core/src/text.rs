@@ -0,0 +1,451 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{
+    char::is_combining_mark, UnicodeNormalization,
+};
+
+use crate::message::{Attachment, Message};
+
+/// What happens to messages longer than [`LengthLimit::max_chars`].
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum LongMessage {
+    /// Cut off with an ellipsis.
+    #[default]
+    Truncate,
+    /// Sent as several overlay messages, broken at whitespace where
+    /// possible.
+    Split,
+}
+
+impl LongMessage {
+    pub const ALL: [LongMessage; 2] =
+        [LongMessage::Truncate, LongMessage::Split];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LongMessage::Truncate => "Truncate",
+            LongMessage::Split => "Split",
+        }
+    }
+}
+
+/// Keeps wall-of-text messages from breaking the overlay layout. Lengths
+/// are in chars.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LengthLimit {
+    /// 0 for no limit.
+    pub max_chars: usize,
+    pub long_message: LongMessage,
+}
+
+impl LengthLimit {
+    pub fn exceeded_by(&self, text: &str) -> bool {
+        self.max_chars > 0 && text.chars().count() > self.max_chars
+    }
+
+    /// `text` cut to `max_chars` including the ellipsis.
+    pub fn truncate<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.exceeded_by(text) {
+            return Cow::Borrowed(text);
+        }
+        let mut text: String =
+            text.chars().take(self.max_chars - 1).collect();
+        text.push('…');
+        Cow::Owned(text)
+    }
+
+    /// The overlay messages to send for `msg`, just `msg` itself if it
+    /// fits.
+    pub fn apply(&self, msg: &Message) -> Vec<Message> {
+        if !self.exceeded_by(&msg.text) {
+            return vec![msg.clone()];
+        }
+        let parts = match self.long_message {
+            LongMessage::Truncate => {
+                vec![self.truncate(&msg.text).into_owned()]
+            }
+            LongMessage::Split => split(&msg.text, self.max_chars),
+        };
+        // attachments go with the first part only
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, text)| Message {
+                text,
+                attachments: match idx {
+                    0 => msg.attachments.clone(),
+                    _ => vec![],
+                },
+                ..msg.clone()
+            })
+            .collect()
+    }
+}
+
+/// Splits into parts of at most `max_chars`, at the last whitespace of a
+/// part unless that would leave it less than half full.
+fn split(text: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = vec![];
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let Some((limit, next)) = rest.char_indices().nth(max_chars)
+        else {
+            parts.push(rest.to_owned());
+            break;
+        };
+        let min = rest
+            .char_indices()
+            .nth(max_chars / 2)
+            .map_or(0, |(idx, _)| idx);
+        // the char right after the part counts, breaking there is fine
+        let end = rest[..limit + next.len_utf8()]
+            .rfind(char::is_whitespace)
+            .filter(|idx| *idx >= min)
+            .unwrap_or(limit);
+        parts.push(rest[..end].trim_end().to_owned());
+        rest = rest[end..].trim_start();
+    }
+    parts
+}
+
+/// Cleans up incoming text before it's shown or broadcast: normalizes to
+/// NFC, strips invisible and bidi control characters that can be used
+/// for spoofing, and caps stacked combining marks ("zalgo" text).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sanitizer {
+    pub enabled: bool,
+    /// Combining marks kept per base character.
+    pub max_combining_marks: usize,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_combining_marks: 2,
+        }
+    }
+}
+
+impl Sanitizer {
+    pub fn apply(&self, msg: Message) -> Message {
+        if !self.enabled {
+            return msg;
+        }
+        Message {
+            username: msg.username.map(|it| self.sanitize(&it)),
+            text: self.sanitize(&msg.text),
+            ..msg
+        }
+    }
+
+    pub fn sanitize(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut marks = 0;
+        let mut prev = None;
+        let mut chars = text.nfc().peekable();
+        while let Some(ch) = chars.next() {
+            if is_combining_mark(ch) {
+                marks += 1;
+                if marks > self.max_combining_marks {
+                    continue;
+                }
+            } else {
+                marks = 0;
+            }
+            // joiners hold emoji sequences together, keep those
+            let joins_emoji = ch == '\u{200d}'
+                && prev.is_some_and(is_emoji_part)
+                && chars.peek().copied().is_some_and(is_emoji_part);
+            if is_invisible_control(ch) && !joins_emoji {
+                continue;
+            }
+            out.push(ch);
+            prev = Some(ch);
+        }
+        out
+    }
+}
+
+fn is_invisible_control(ch: char) -> bool {
+    matches!(
+        ch,
+        // zero width space, non-joiner, joiner
+        '\u{200b}'..='\u{200d}'
+        // bidi marks and embeddings, overrides, isolates
+        | '\u{200e}' | '\u{200f}' | '\u{061c}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2066}'..='\u{2069}'
+        // word joiner, invisible operators
+        | '\u{2060}'..='\u{2064}'
+        | '\u{180e}' | '\u{feff}'
+    )
+}
+
+fn is_emoji_part(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{2600}'..='\u{27bf}'
+            | '\u{fe0f}'
+            | '\u{1f000}'..='\u{1faff}'
+    )
+}
+
+/// Overlay pages may put the text straight into their markup, so it's
+/// always sent HTML-escaped. Only bare tags from `allowed_tags`, like
+/// `<b>`, survive, never with attributes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayMarkup {
+    /// Lowercase tag names, e.g. `b`, `i`.
+    pub allowed_tags: Vec<String>,
+}
+
+impl OverlayMarkup {
+    pub fn escape(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(ch) = rest.chars().next() {
+            if ch == '<' {
+                if let Some((tag, len)) = self.allowed_tag(rest) {
+                    out += &tag;
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            match ch {
+                '&' => out += "&amp;",
+                '<' => out += "&lt;",
+                '>' => out += "&gt;",
+                '"' => out += "&quot;",
+                '\'' => out += "&#39;",
+                _ => out.push(ch),
+            }
+            rest = &rest[ch.len_utf8()..];
+        }
+        out
+    }
+
+    /// Normalized tag and its length in `text` if `text` starts with an
+    /// allowed opening or closing tag.
+    fn allowed_tag(&self, text: &str) -> Option<(String, usize)> {
+        let end = text.find('>')?;
+        let inner = &text[1..end];
+        let (slash, name) = match inner.strip_prefix('/') {
+            Some(name) => ("/", name),
+            None => ("", inner),
+        };
+        let name = name.to_ascii_lowercase();
+        let valid = !name.is_empty()
+            && name.chars().all(|it| it.is_ascii_alphanumeric())
+            && self.allowed_tags.contains(&name);
+        valid.then(|| (format!("<{slash}{name}>"), end + 1))
+    }
+}
+
+/// What [`UrlFilter`] does with links outside its allowed domains.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum UrlAction {
+    #[default]
+    Keep,
+    Strip,
+    /// Replaced with `[link]`.
+    Replace,
+}
+
+impl UrlAction {
+    pub const ALL: [UrlAction; 3] =
+        [UrlAction::Keep, UrlAction::Strip, UrlAction::Replace];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UrlAction::Keep => "Keep",
+            UrlAction::Strip => "Strip",
+            UrlAction::Replace => "Replace with [link]",
+        }
+    }
+}
+
+/// A link found in a message and what was done with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlHit {
+    pub url: String,
+    pub action: UrlAction,
+}
+
+/// Handles links in forwarded messages. Words with a scheme (`x://`) or
+/// starting with `www.` count as links.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlFilter {
+    pub action: UrlAction,
+    /// Links to these domains and their subdomains are always kept.
+    pub allowed_domains: Vec<String>,
+}
+
+impl UrlFilter {
+    /// The text to send and every link found in it, with the action
+    /// taken.
+    pub fn apply(&self, text: &str) -> (String, Vec<UrlHit>) {
+        let mut out = String::with_capacity(text.len());
+        let mut hits = vec![];
+        for piece in text.split_inclusive(char::is_whitespace) {
+            let word = piece.trim_end();
+            if !is_url(word) {
+                out += piece;
+                continue;
+            }
+            let action = if self.allows(word) {
+                UrlAction::Keep
+            } else {
+                self.action
+            };
+            match action {
+                UrlAction::Keep => out += piece,
+                UrlAction::Strip => {}
+                UrlAction::Replace => {
+                    out += "[link]";
+                    out += &piece[word.len()..];
+                }
+            }
+            hits.push(UrlHit {
+                url: word.to_owned(),
+                action,
+            });
+        }
+        if hits.iter().any(|it| it.action == UrlAction::Strip) {
+            out.truncate(out.trim_end().len());
+        }
+        (out, hits)
+    }
+
+    fn allows(&self, url: &str) -> bool {
+        let host = url_host(url);
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        self.allowed_domains.iter().any(|domain| {
+            let domain = domain.trim().to_ascii_lowercase();
+            !domain.is_empty()
+                && (host == domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|it| it.ends_with('.')))
+        })
+    }
+}
+
+fn is_url(word: &str) -> bool {
+    let word = word.to_ascii_lowercase();
+    word.contains("://") || word.starts_with("www.")
+}
+
+fn url_host(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority =
+        rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    host.split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// What happens to messages carrying images or stickers.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageAction {
+    #[default]
+    Keep,
+    /// Forwarded without the images, dropped if that leaves no text.
+    Strip,
+    Drop,
+}
+
+impl ImageAction {
+    pub const ALL: [ImageAction; 3] =
+        [ImageAction::Keep, ImageAction::Strip, ImageAction::Drop];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageAction::Keep => "Keep",
+            ImageAction::Strip => "Strip images",
+            ImageAction::Drop => "Drop message",
+        }
+    }
+
+    /// Whether `msg` shouldn't be queued at all.
+    pub fn drops(self, msg: &Message) -> bool {
+        msg.has_image()
+            && match self {
+                ImageAction::Keep => false,
+                ImageAction::Strip => msg.text.trim().is_empty(),
+                ImageAction::Drop => true,
+            }
+    }
+
+    pub fn apply(self, msg: Message) -> Message {
+        match self {
+            ImageAction::Strip => Message {
+                attachments: vec![],
+                ..msg
+            },
+            _ => msg,
+        }
+    }
+}
+
+/// Turns a released message into the messages broadcast to the overlay:
+/// split or truncated to the length limit, then escaped. Attachments the
+/// overlay shouldn't load are dropped.
+pub fn overlay_messages(
+    msg: &Message,
+    limit: &LengthLimit,
+    markup: &OverlayMarkup,
+) -> Vec<Message> {
+    limit
+        .apply(msg)
+        .into_iter()
+        .map(|it| Message {
+            username: it.username.map(|it| markup.escape(&it)),
+            text: markup.escape(&it.text),
+            attachments: it
+                .attachments
+                .into_iter()
+                .filter(is_safe_attachment)
+                .collect(),
+            ..it
+        })
+        .collect()
+}
+
+/// Only http(s) links and inlined base64 images, never `javascript:` and
+/// the like.
+fn is_safe_attachment(attachment: &Attachment) -> bool {
+    match (&attachment.url, &attachment.data) {
+        (Some(url), _) => {
+            let url = url.to_ascii_lowercase();
+            url.starts_with("http://") || url.starts_with("https://")
+        }
+        (None, Some(data)) => {
+            let mime = attachment.mime.as_deref().unwrap_or("image/png");
+            mime.starts_with("image/")
+                && mime[6..].bytes().all(|it| {
+                    it.is_ascii_alphanumeric() || b"+-.".contains(&it)
+                })
+                && !data.is_empty()
+                && data.bytes().all(|it| {
+                    it.is_ascii_alphanumeric() || b"+/=".contains(&it)
+                })
+        }
+        (None, None) => false,
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// Offered as one click timeouts.
+pub const TIMEOUT_PRESETS: [(&str, TimeDelta); 2] = [
+    ("5 min", TimeDelta::minutes(5)),
+    ("30 min", TimeDelta::minutes(30)),
+];
+
+/// Users whose messages are dropped until a deadline, by username. Wall
+/// clock deadlines, so they keep running across restarts.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct Timeouts(HashMap<String, DateTime<Utc>>);
+
+impl Timeouts {
+    /// Mutes `username` until `until`, replacing any timeout it had.
+    pub fn add(&mut self, username: &str, until: DateTime<Utc>) {
+        let username = username.trim();
+        if !username.is_empty() {
+            self.0.insert(username.to_owned(), until);
+        }
+    }
+
+    pub fn remove(&mut self, username: &str) {
+        self.0.remove(username);
+    }
+
+    /// Whether the sender of `msg` is timed out at `now`.
+    pub fn mutes(&self, msg: &Message, now: DateTime<Utc>) -> bool {
+        msg.username
+            .as_deref()
+            .is_some_and(|it| self.is_muted(it, now))
+    }
+
+    pub fn is_muted(&self, username: &str, now: DateTime<Utc>) -> bool {
+        self.0.get(username).is_some_and(|until| now < *until)
+    }
+
+    /// Forgets timeouts over at `now`, returning whose.
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut expired = vec![];
+        self.0.retain(|username, until| {
+            let active = now < *until;
+            if !active {
+                expired.push(username.clone());
+            }
+            active
+        });
+        expired.sort();
+        expired
+    }
+
+    /// Ending soonest first.
+    pub fn active(&self) -> Vec<(&str, DateTime<Utc>)> {
+        let mut active = self
+            .0
+            .iter()
+            .map(|(username, until)| (username.as_str(), *until))
+            .collect::<Vec<_>>();
+        active.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        active
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Frame sent to overlays as a timer is started, stopped or counts down,
+/// e.g. "starting soon in 5:00". The overlay shows it until it stops.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerFrame {
+    /// Always `timer`, tells it apart from message envelopes.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Tells several timers on the same overlay apart.
+    pub id: u32,
+    pub label: String,
+    pub state: TimerState,
+    /// Whole seconds left, rounded up.
+    pub remaining_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerState {
+    Start,
+    Stop,
+    /// Sent every whole second while running.
+    Remaining,
+}
+
+/// A countdown controlled by the operator and rendered by the overlay.
+/// Stopping keeps what's left, so starting again resumes it.
+#[derive(Debug, Clone)]
+pub struct OverlayTimer {
+    pub id: u32,
+    pub label: String,
+    pub duration: Duration,
+    /// When it runs out, while running.
+    ends_at: Option<Instant>,
+    /// Left while stopped.
+    left: Duration,
+    /// Last `remaining_secs` sent, so each second goes out once.
+    last_secs: u64,
+}
+
+impl OverlayTimer {
+    pub fn new(
+        id: u32,
+        label: impl Into<String>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            id,
+            label: label.into(),
+            duration,
+            ends_at: None,
+            left: duration,
+            last_secs: 0,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.ends_at.is_some()
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        match self.ends_at {
+            Some(ends_at) => ends_at.saturating_duration_since(now),
+            None => self.left,
+        }
+    }
+
+    /// Resumes from what's left, from the full duration if it ran out.
+    pub fn start(&mut self, now: Instant) -> TimerFrame {
+        if self.left.is_zero() {
+            self.left = self.duration;
+        }
+        self.ends_at = Some(now + self.left);
+        self.frame(TimerState::Start, now)
+    }
+
+    pub fn stop(&mut self, now: Instant) -> TimerFrame {
+        self.left = self.remaining(now);
+        self.ends_at = None;
+        self.frame(TimerState::Stop, now)
+    }
+
+    /// Stops it and sets it back to the full duration. Returns the stop
+    /// frame if it was running.
+    pub fn reset(&mut self, now: Instant) -> Option<TimerFrame> {
+        let frame = self.is_running().then(|| self.stop(now));
+        self.left = self.duration;
+        frame
+    }
+
+    /// The frame due at `now`, if any: a remaining one as each whole
+    /// second passes, a stop one once it runs out.
+    pub fn tick(&mut self, now: Instant) -> Option<TimerFrame> {
+        self.ends_at?;
+        if self.remaining(now).is_zero() {
+            return Some(self.stop(now));
+        }
+        (secs_ceil(self.remaining(now)) != self.last_secs)
+            .then(|| self.frame(TimerState::Remaining, now))
+    }
+
+    /// Until [`OverlayTimer::tick`] has a frame again, `None` while
+    /// stopped.
+    pub fn next_tick_in(&self, now: Instant) -> Option<Duration> {
+        self.ends_at?;
+        let remaining = self.remaining(now);
+        let subsec = Duration::from_nanos(
+            (remaining.as_nanos() % 1_000_000_000) as u64,
+        );
+        Some(if subsec.is_zero() {
+            Duration::from_secs(1).min(remaining)
+        } else {
+            subsec
+        })
+    }
+
+    fn frame(&mut self, state: TimerState, now: Instant) -> TimerFrame {
+        self.last_secs = secs_ceil(self.remaining(now));
+        TimerFrame {
+            kind: "timer".to_owned(),
+            id: self.id,
+            label: self.label.clone(),
+            state,
+            remaining_secs: self.last_secs,
+        }
+    }
+}
+
+fn secs_ceil(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
@@ -0,0 +1,79 @@
+use anyhow::{anyhow, bail, Context};
+use http_body_util::{BodyExt, Limited};
+use hyper::{body::Bytes, header, Request};
+use serde::Deserialize;
+
+use crate::network::{fetch, proxy::ProxyConfig};
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/Berylsoft/blooming-light/releases/latest";
+/// Release notes can be long, but not this long.
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+/// A published release, as the GitHub API describes it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    /// Its page, with the changelog.
+    pub html_url: String,
+}
+
+impl Release {
+    /// Whether this is a later version than `current`, e.g. `v0.2.0`
+    /// over `0.1.3`. Tags that aren't a version never are.
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        match (parse_version(&self.tag_name), parse_version(current)) {
+            (Some(latest), Some(current)) => latest > current,
+            _ => false,
+        }
+    }
+}
+
+/// `major.minor.patch`, with an optional `v` in front and anything from
+/// a `-` or `+` on ignored.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|it| it.parse().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// The latest release if it's newer than `current`.
+pub async fn check(
+    current: &str,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Option<Release>> {
+    let request = Request::get(LATEST_RELEASE_URL)
+        .header(header::ACCEPT, "application/vnd.github+json")
+        // refused without one
+        .header(header::USER_AGENT, "blooming-light");
+    let res = fetch::send(request, Bytes::new(), proxy)
+        .await
+        .context("failed to ask for the latest release")?;
+    let status = res.status();
+    if !status.is_success() {
+        bail!("asking for the latest release failed with {status}");
+    }
+    let body = Limited::new(res.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|err| anyhow!(err))
+        .context("failed to read the latest release")?
+        .to_bytes();
+    let release = serde_json::from_slice::<Release>(&body)
+        .context("failed to parse the latest release")?;
+    Ok(release.is_newer_than(current).then_some(release))
+}
+
+/// [`check`] on a throwaway runtime, for callers outside of tokio.
+pub fn check_blocking(
+    current: &str,
+    proxy: &ProxyConfig,
+) -> anyhow::Result<Option<Release>> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime")?
+        .block_on(check(current, proxy))
+}
@@ -0,0 +1,60 @@
+use std::net::IpAddr;
+
+use blooming_light_core::network::access::{IpAccess, IpRange};
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+fn range(range: &str) -> IpRange {
+    range.parse().unwrap()
+}
+
+#[test]
+fn ranges_match_by_prefix() {
+    assert!(range("192.168.1.0/24").contains(ip("192.168.1.42")));
+    assert!(!range("192.168.1.0/24").contains(ip("192.168.2.1")));
+    assert!(range("10.0.0.7").contains(ip("10.0.0.7")));
+    assert!(!range("10.0.0.7").contains(ip("10.0.0.8")));
+    assert!(range("0.0.0.0/0").contains(ip("8.8.8.8")));
+    assert!(range("fd00::/8").contains(ip("fd12::1")));
+    assert!(!range("fd00::/8").contains(ip("10.0.0.1")));
+    // from a dual-stack listener
+    assert!(range("192.168.1.0/24").contains(ip("::ffff:192.168.1.5")));
+
+    assert!("192.168.1.0/33".parse::<IpRange>().is_err());
+    assert!("phone".parse::<IpRange>().is_err());
+}
+
+#[test]
+fn allow_and_deny_lists() {
+    let access = IpAccess {
+        allow: IpAccess::parse("192.168.1.0/24, 10.0.0.2").unwrap(),
+        deny: IpAccess::parse("192.168.1.13").unwrap(),
+    };
+    assert!(access.allows(ip("192.168.1.20")));
+    assert!(access.allows(ip("10.0.0.2")));
+    assert!(!access.allows(ip("10.0.0.3")));
+    assert!(!access.allows(ip("192.168.1.13")));
+    // this machine always gets in
+    assert!(access.allows(ip("127.0.0.1")));
+    assert!(access.allows(ip("::1")));
+
+    let open = IpAccess::default();
+    assert!(open.allows(ip("203.0.113.9")));
+}
+
+#[test]
+fn formats_and_parses_lists() {
+    let ranges = IpAccess::parse(" 192.168.1.0/24,,fd00::/8 ").unwrap();
+    assert_eq!(IpAccess::format(&ranges), "192.168.1.0/24, fd00::/8");
+    assert_eq!(IpAccess::parse(""), Some(vec![]));
+    assert_eq!(IpAccess::parse("1.2.3.4, nope"), None);
+
+    let json = serde_json::to_string(&ranges).unwrap();
+    assert_eq!(json, r#"["192.168.1.0/24","fd00::/8"]"#);
+    assert_eq!(
+        serde_json::from_str::<Vec<IpRange>>(&json).unwrap(),
+        ranges
+    );
+}
@@ -0,0 +1,115 @@
+use std::{fs, net::SocketAddr, path::PathBuf};
+
+use blooming_light_core::network::access_log::{
+    read_since, redact, rotated_path, AccessLog, ROTATED_FILES,
+};
+use chrono::{TimeZone, Utc};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("blooming-light-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn addr() -> SocketAddr {
+    "192.168.1.42:50000".parse().unwrap()
+}
+
+#[test]
+fn lines_carry_the_client_and_what_happened() {
+    let dir = temp_dir("access-log-lines");
+    let path = dir.join("access.log");
+    let log = AccessLog::open(&path, 1 << 20).unwrap();
+    log.write(addr(), "GET / 200 1ms").unwrap();
+    log.write(addr(), "ws connected").unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with(" 192.168.1.42:50000 GET / 200 1ms"));
+    assert!(lines[1].ends_with(" 192.168.1.42:50000 ws connected"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rotates_by_size_keeping_a_few() {
+    let dir = temp_dir("access-log-rotate");
+    let path = dir.join("access.log");
+    let log = AccessLog::open(&path, 100).unwrap();
+    for idx in 0..20 {
+        log.write(addr(), &format!("request {idx}")).unwrap();
+    }
+
+    let current = fs::read_to_string(&path).unwrap();
+    assert!(current.len() <= 100);
+    assert!(current.ends_with("request 19\n"));
+    let newest = fs::read_to_string(rotated_path(&path, 1)).unwrap();
+    assert!(!newest.contains("request 19"));
+    assert!(rotated_path(&path, ROTATED_FILES).exists());
+    assert!(!rotated_path(&path, ROTATED_FILES + 1).exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reopening_appends() {
+    let dir = temp_dir("access-log-reopen");
+    let path = dir.join("access.log");
+    AccessLog::open(&path, 1 << 20)
+        .unwrap()
+        .write(addr(), "first")
+        .unwrap();
+    AccessLog::open(&path, 1 << 20)
+        .unwrap()
+        .write(addr(), "second")
+        .unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 2);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reads_back_since_a_time_across_rotations() {
+    let dir = temp_dir("access-log-since");
+    let path = dir.join("access.log");
+    fs::write(rotated_path(&path, 2), "2024-01-01T00:00:00.000Z a old\n")
+        .unwrap();
+    fs::write(
+        rotated_path(&path, 1),
+        "2024-01-01T00:59:59.999Z a old\n\
+         2024-01-01T01:00:00.000Z a first\n",
+    )
+    .unwrap();
+    fs::write(&path, "2024-01-01T02:00:00.000Z a second\n").unwrap();
+
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap();
+    assert_eq!(
+        read_since(&path, since).unwrap(),
+        "2024-01-01T01:00:00.000Z a first\n\
+         2024-01-01T02:00:00.000Z a second\n"
+    );
+    let later = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+    assert_eq!(read_since(&path, later).unwrap(), "");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn redacts_the_token() {
+    assert_eq!(redact("/api/actions/pause"), "/api/actions/pause");
+    assert_eq!(
+        redact("/api/actions/purge?token=secret"),
+        "/api/actions/purge?token=redacted"
+    );
+    assert_eq!(
+        redact("/layouts/chat/?theme=dark&token=secret&x=1"),
+        "/layouts/chat/?theme=dark&token=redacted&x=1"
+    );
+    // only the parameter named so
+    assert_eq!(redact("/?tokens=1"), "/?tokens=1");
+}
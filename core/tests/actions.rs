@@ -0,0 +1,28 @@
+use blooming_light_core::network::{
+    actions::{authorized, generate_token},
+    Action,
+};
+
+#[test]
+fn slugs_round_trip() {
+    for action in Action::ALL {
+        assert_eq!(Action::from_slug(action.slug()), Some(action));
+    }
+    assert_eq!(Action::from_slug("send_next"), None);
+}
+
+#[test]
+fn takes_the_token_from_the_header_or_query() {
+    let token = generate_token();
+    let bearer = format!("Bearer {token}");
+    assert!(authorized(&token, Some(&bearer), None));
+    assert!(authorized(&token, None, Some(&token)));
+    assert!(!authorized(&token, Some(&token), None));
+    assert!(!authorized(&token, None, Some("wrong")));
+    assert!(!authorized(&token, None, None));
+}
+
+#[test]
+fn is_off_without_a_token() {
+    assert!(!authorized("", Some("Bearer "), Some("")));
+}
@@ -0,0 +1,109 @@
+use blooming_light_core::{
+    alert::{obs_auth, obs_request, Alert, AlertConfig},
+    message::MessageKind,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::{
+    accept_async, tungstenite::Message as WsMessage, WebSocketStream,
+};
+
+async fn recv(ws: &mut WebSocketStream<tokio::net::TcpStream>) -> Value {
+    let msg = ws.next().await.unwrap().unwrap();
+    serde_json::from_str(msg.to_text().unwrap()).unwrap()
+}
+
+/// Speaks obs-websocket with `password` and answers one request with
+/// `result`, returns the request's `d`.
+async fn fake_obs(
+    password: &'static str,
+    result: bool,
+) -> (String, tokio::task::JoinHandle<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let hello = json!({"op": 0, "d": {
+            "rpcVersion": 1,
+            "authentication": {"challenge": "c", "salt": "s"},
+        }});
+        ws.send(WsMessage::Text(hello.to_string())).await.unwrap();
+        let identify = recv(&mut ws).await;
+        assert_eq!(identify["op"], 1);
+        assert_eq!(
+            identify["d"]["authentication"],
+            obs_auth(password, "s", "c")
+        );
+        ws.send(WsMessage::Text(json!({"op": 2, "d": {}}).to_string()))
+            .await
+            .unwrap();
+        let request = recv(&mut ws).await;
+        assert_eq!(request["op"], 6);
+        let response = json!({"op": 7, "d": {
+            "requestType": request["d"]["requestType"],
+            "requestId": request["d"]["requestId"],
+            "requestStatus": {"result": result, "code": 600},
+        }});
+        ws.send(WsMessage::Text(response.to_string()))
+            .await
+            .unwrap();
+        request["d"].clone()
+    });
+    (url, handle)
+}
+
+#[tokio::test]
+async fn obs_request_authenticates_and_sends() {
+    let (url, server) = fake_obs("secret", true).await;
+    obs_request(
+        &url,
+        "secret",
+        "SetCurrentProgramScene",
+        Some(json!({"sceneName": "Alert"})),
+    )
+    .await
+    .unwrap();
+    let request = server.await.unwrap();
+    assert_eq!(request["requestType"], "SetCurrentProgramScene");
+    assert_eq!(request["requestData"], json!({"sceneName": "Alert"}));
+}
+
+#[tokio::test]
+async fn obs_request_failure() {
+    let (url, server) = fake_obs("secret", false).await;
+    let err =
+        obs_request(&url, "secret", "Nope", None).await.unwrap_err();
+    assert!(err.to_string().contains("Nope"));
+    assert!(server.await.unwrap().get("requestData").is_none());
+}
+
+#[test]
+fn obs_auth_matches_protocol() {
+    // the example in the obs-websocket 5.x protocol docs
+    assert_eq!(
+        obs_auth(
+            "supersecretpassword",
+            "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+            "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+        ),
+        "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+    );
+}
+
+#[test]
+fn empty_alerts_are_skipped() {
+    let mut config = AlertConfig::default();
+    config.alerts.insert(MessageKind::Follow, Alert::default());
+    config.alerts.insert(
+        MessageKind::Gift,
+        Alert {
+            notify: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(config.alert(MessageKind::Follow), None);
+    assert_eq!(config.alert(MessageKind::Chat), None);
+    assert!(config.alert(MessageKind::Gift).is_some());
+}
@@ -0,0 +1,42 @@
+use blooming_light_core::{
+    alias::{UserAlias, UserAliases},
+    message::Message,
+};
+
+fn alias(alias: &str, note: &str) -> UserAlias {
+    UserAlias {
+        alias: alias.to_owned(),
+        note: note.to_owned(),
+    }
+}
+
+#[test]
+fn aliases_senders() {
+    let mut aliases = UserAliases::default();
+    aliases.set(" bob123 ", alias("Alice", "mod's alt"));
+    aliases.set("", alias("nobody", ""));
+
+    let msg = Message {
+        username: Some("bob123".to_owned()),
+        ..Message::chat("hi")
+    };
+    assert_eq!(aliases.of(&msg), Some(&alias("Alice", "mod's alt")));
+    assert_eq!(aliases.of(&Message::chat("hi")), None);
+    assert_eq!(aliases.iter().count(), 1);
+
+    // cleared by setting nothing
+    aliases.set("bob123", alias(" ", ""));
+    assert!(aliases.is_empty());
+}
+
+#[test]
+fn round_trips_through_json() {
+    let mut aliases = UserAliases::default();
+    aliases.set("bob123", alias("Alice", ""));
+    let json = serde_json::to_string(&aliases).unwrap();
+    assert_eq!(json, r#"{"bob123":{"alias":"Alice","note":""}}"#);
+    assert_eq!(
+        serde_json::from_str::<UserAliases>(&json).unwrap(),
+        aliases
+    );
+}
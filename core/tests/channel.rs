@@ -0,0 +1,77 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use blooming_light_core::{
+    channel::{self, ChannelStats},
+    Notifier,
+};
+
+#[test]
+fn drops_oldest_when_full() {
+    let (tx, rx) = channel::bounded(2);
+    for it in 0..5 {
+        tx.send(it).unwrap();
+    }
+    assert_eq!(
+        rx.stats(),
+        ChannelStats {
+            len: 2,
+            capacity: 2,
+            dropped: 3,
+        }
+    );
+    assert_eq!(rx.try_recv(), Some(3));
+    assert_eq!(rx.try_recv(), Some(4));
+    assert_eq!(rx.try_recv(), None);
+    assert_eq!(tx.stats().dropped, 3);
+}
+
+#[test]
+fn drains_in_batches_and_notifies_on_send() {
+    let notified = Arc::new(AtomicUsize::new(0));
+    let notified_cloned = Arc::clone(&notified);
+    let (tx, rx) = channel::notifying(
+        8,
+        Notifier::new(move || {
+            notified_cloned.fetch_add(1, Ordering::Relaxed);
+        }),
+    );
+    assert!(rx.drain(usize::MAX).is_empty());
+    assert_eq!(notified.load(Ordering::Relaxed), 0);
+
+    for it in 0..3 {
+        tx.send(it).unwrap();
+    }
+    assert_eq!(notified.load(Ordering::Relaxed), 3);
+    assert_eq!(rx.drain(2), [0, 1]);
+    assert_eq!(rx.drain(usize::MAX), [2]);
+}
+
+#[test]
+fn send_fails_without_receiver() {
+    let (tx, rx) = channel::bounded(1);
+    drop(rx);
+    assert_eq!(tx.send(1), Err(1));
+}
+
+#[tokio::test]
+async fn recv_waits_then_ends_with_senders() {
+    let (tx, rx) = channel::bounded(4);
+    let tx_cloned = tx.clone();
+    let handle = tokio::spawn(async move {
+        let mut items = vec![];
+        while let Some(it) = rx.recv().await {
+            items.push(it);
+        }
+        items
+    });
+
+    tx.send(1).unwrap();
+    tokio::task::yield_now().await;
+    tx_cloned.send(2).unwrap();
+    drop(tx);
+    drop(tx_cloned);
+    assert_eq!(handle.await.unwrap(), [1, 2]);
+}
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    combo::{ComboCounter, ComboUpdate},
+    message::{Message, MessageKind},
+};
+
+#[test]
+fn counts_repeats_within_window() {
+    let mut counter = ComboCounter::default();
+    let window = Duration::from_secs(5);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let chant = Message::chat("兰纳真");
+
+    assert_eq!(counter.count(&chant, at(0), window), 1);
+    assert_eq!(counter.count(&Message::chat("hi"), at(1), window), 1);
+    assert_eq!(counter.count(&chant, at(4), window), 2);
+    // each repeat extends the combo
+    assert_eq!(counter.count(&chant, at(8), window), 3);
+    assert_eq!(counter.count(&chant, at(14), window), 1);
+}
+
+#[test]
+fn only_chat_text_and_only_when_enabled() {
+    let mut counter = ComboCounter::default();
+    let now = Instant::now();
+    let window = Duration::from_secs(5);
+    let gift = Message {
+        kind: MessageKind::Gift,
+        ..Message::chat("x x1")
+    };
+    assert_eq!(counter.count(&gift, now, window), 1);
+    assert_eq!(counter.count(&gift, now, window), 1);
+
+    let chant = Message::chat("a");
+    assert_eq!(counter.count(&chant, now, Duration::ZERO), 1);
+    assert_eq!(counter.count(&chant, now, Duration::ZERO), 1);
+}
+
+#[test]
+fn combo_frame() {
+    assert_eq!(
+        serde_json::to_value(ComboUpdate::new("a", 27)).unwrap(),
+        serde_json::json!({"type": "combo", "text": "a", "count": 27})
+    );
+}
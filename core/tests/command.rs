@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    command::{
+        ChatCommand, ChatCommands, CommandCooldowns, CommandHandler,
+        Routed,
+    },
+    message::Message,
+    network::Action,
+};
+
+fn from(username: &str, text: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    }
+}
+
+#[test]
+fn routes_commands() {
+    let mut commands = ChatCommands {
+        enabled: true,
+        commands: vec![
+            ChatCommand {
+                name: "Discord".to_owned(),
+                handler: CommandHandler::Respond(
+                    "@{user} discord.gg/x".to_owned(),
+                ),
+                ..Default::default()
+            },
+            ChatCommand {
+                name: "!pause".to_owned(),
+                handler: CommandHandler::Action(Action::TogglePause),
+                users: "mod, host".to_owned(),
+                ..Default::default()
+            },
+        ],
+        drop_unknown: false,
+    };
+    let discord = from("a", "!discord please");
+    let Routed::Run(command) = commands.route(&discord) else {
+        panic!("not run");
+    };
+    assert_eq!(
+        command.response(&discord),
+        Some(Message::chat("@a discord.gg/x"))
+    );
+    assert!(matches!(
+        commands.route(&from("host", "!PAUSE")),
+        Routed::Run(ChatCommand {
+            handler: CommandHandler::Action(Action::TogglePause),
+            ..
+        })
+    ));
+    assert_eq!(commands.route(&from("a", "!pause")), Routed::Drop);
+    assert_eq!(commands.route(&from("a", "!lurk")), Routed::Queue);
+    assert_eq!(commands.route(&from("a", "hi !discord")), Routed::Queue);
+    assert_eq!(commands.route(&from("a", "!")), Routed::Queue);
+
+    commands.drop_unknown = true;
+    assert_eq!(commands.route(&from("a", "!lurk")), Routed::Drop);
+    commands.enabled = false;
+    assert_eq!(commands.route(&discord), Routed::Queue);
+}
+
+#[test]
+fn actions_need_someone_listed() {
+    let mut purge = ChatCommand {
+        name: "purge".to_owned(),
+        handler: CommandHandler::Action(Action::Purge),
+        ..Default::default()
+    };
+    assert!(!purge.allows(Some("a")));
+    assert!(!purge.allows(None));
+    purge.users = "host".to_owned();
+    assert!(purge.allows(Some("host")));
+    assert!(!purge.allows(Some("a")));
+
+    let respond = ChatCommand {
+        handler: CommandHandler::Respond("hi".to_owned()),
+        ..Default::default()
+    };
+    assert!(respond.allows(Some("a")));
+    assert!(respond.allows(None));
+}
+
+#[test]
+fn responses_cool_down() {
+    let discord = ChatCommand {
+        name: "!Discord".to_owned(),
+        handler: CommandHandler::Respond("discord.gg/x".to_owned()),
+        cooldown_secs: 30.0,
+        ..Default::default()
+    };
+    let lurk = ChatCommand {
+        name: "lurk".to_owned(),
+        handler: CommandHandler::Respond("enjoy".to_owned()),
+        ..Default::default()
+    };
+    let mut cooldowns = CommandCooldowns::default();
+    let now = Instant::now();
+    assert!(cooldowns.respond(&discord, now));
+    assert!(!cooldowns.respond(&discord, now + Duration::from_secs(29)));
+    // others have their own, and none is none
+    assert!(cooldowns.respond(&lurk, now));
+    assert!(cooldowns.respond(&lurk, now));
+    assert!(cooldowns.respond(&discord, now + Duration::from_secs(30)));
+}
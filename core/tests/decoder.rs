@@ -0,0 +1,318 @@
+use blooming_light_core::{
+    message::{
+        Attachment, AttachmentKind, Gift, KindFilter, Message,
+        MessageKind, Paid, PlatformEvent,
+    },
+    network::{
+        decoder::{DecoderConfig, DecoderKind, JsonPaths},
+        emote::{twitch_emotes, Emote, EmoteMode},
+    },
+};
+use serde_json::{json, Value};
+
+fn decoder(kind: DecoderKind) -> DecoderConfig {
+    DecoderConfig {
+        kind,
+        ..Default::default()
+    }
+}
+
+fn decode(decoder: &DecoderConfig, frame: Value) -> Option<Message> {
+    decoder.decode(&frame.to_string()).unwrap()
+}
+
+fn message(kind: MessageKind, username: &str, text: &str) -> Message {
+    Message {
+        kind,
+        username: Some(username.to_owned()),
+        text: text.to_owned(),
+        attachments: vec![],
+        gift: None,
+        paid: None,
+        event: None,
+    }
+}
+
+#[test]
+fn text_and_envelope() {
+    assert_eq!(
+        decoder(DecoderKind::Text).decode("hi").unwrap(),
+        Some(Message::chat("hi"))
+    );
+
+    let envelope = decoder(DecoderKind::Envelope);
+    assert_eq!(
+        decode(
+            &envelope,
+            json!({"type": "gift", "username": "a", "text": "x"})
+        ),
+        Some(message(MessageKind::Gift, "a", "x"))
+    );
+    assert_eq!(
+        decode(
+            &envelope,
+            json!({
+                "type": "raid",
+                "username": "c",
+                "text": "raided",
+                "event": {"viewers": 42},
+            })
+        ),
+        Some(Message {
+            event: Some(PlatformEvent {
+                months: 0,
+                viewers: 42,
+            }),
+            ..message(MessageKind::Raid, "c", "raided")
+        })
+    );
+    assert!(envelope.decode("hi").is_err());
+}
+
+#[test]
+fn json_path() {
+    let decoder = DecoderConfig {
+        kind: DecoderKind::JsonPath,
+        json_paths: JsonPaths {
+            text: "data.content".to_owned(),
+            username: "data.user.0".to_owned(),
+            kind: "data.kind".to_owned(),
+            image: "data.img".to_owned(),
+            amount: "data.price".to_owned(),
+            emotes: String::new(),
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({"data": {
+                "content": "hi",
+                "user": ["a"],
+                "kind": "superchat",
+            }})
+        ),
+        Some(message(MessageKind::SuperChat, "a", "hi"))
+    );
+    assert_eq!(
+        decode(&decoder, json!({"data": {"content": 1, "kind": "x"}})),
+        Some(Message::chat("1"))
+    );
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({"data": {"content": "", "img": "https://a/b.png"}})
+        ),
+        Some(Message {
+            attachments: vec![Attachment::url(
+                AttachmentKind::Image,
+                "https://a/b.png"
+            )],
+            ..Message::chat("")
+        })
+    );
+    assert_eq!(decode(&decoder, json!({"heartbeat": 1})), None);
+}
+
+#[test]
+fn bilibili() {
+    let decoder = decoder(DecoderKind::Bilibili);
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "DANMU_MSG:4:0:2:2:2:0",
+                "info": [[], "hi", [1, "a"]],
+            })
+        ),
+        Some(message(MessageKind::Chat, "a", "hi"))
+    );
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "DANMU_MSG",
+                "info": [
+                    [0, 1, 25, 0, 0, 0, 0, "", 0, 0, 0, "", 1,
+                     {"url": "https://i0.hdslb.com/x.png"}],
+                    "[dog]",
+                    [1, "a"],
+                ],
+            })
+        ),
+        Some(Message {
+            attachments: vec![Attachment::url(
+                AttachmentKind::Sticker,
+                "https://i0.hdslb.com/x.png"
+            )],
+            ..message(MessageKind::Chat, "a", "[dog]")
+        })
+    );
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "SEND_GIFT",
+                "data": {
+                    "uname": "b",
+                    "giftName": "x",
+                    "num": 3,
+                    "total_coin": 300,
+                },
+            })
+        ),
+        Some(Message {
+            gift: Some(Gift {
+                name: "x".to_owned(),
+                count: 3,
+                value: 300,
+            }),
+            ..message(MessageKind::Gift, "b", "x x3")
+        })
+    );
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "SUPER_CHAT_MESSAGE",
+                "data": {
+                    "message": "hi",
+                    "price": 30,
+                    "user_info": {"uname": "c"},
+                },
+            })
+        ),
+        Some(Message {
+            paid: Some(Paid {
+                amount: 30.0,
+                pin_secs: None,
+            }),
+            ..message(MessageKind::SuperChat, "c", "hi")
+        })
+    );
+    assert_eq!(
+        decode(&decoder, json!({"cmd": "ONLINE_RANK_COUNT"})),
+        None
+    );
+    assert!(decoder.decode("not json").is_err());
+}
+
+#[test]
+fn emotes() {
+    let text = "Kappa hi Kappa 草 PogChamp";
+    let emotes = twitch_emotes("25:0-4,9-13/88:17-24/x:99-100", text);
+    assert_eq!(
+        emotes
+            .iter()
+            .map(|it| &text[it.range.clone()])
+            .collect::<Vec<_>>(),
+        ["Kappa", "Kappa", "PogChamp"]
+    );
+    assert_eq!(
+        emotes[2].url.as_deref(),
+        Some("https://static-cdn.jtvnw.net/emoticons/v2/88/default/dark/1.0")
+    );
+
+    let apply = |mode: EmoteMode, emotes: Vec<Emote>| {
+        let mut msg = Message::chat(text);
+        mode.apply(&mut msg, emotes);
+        msg
+    };
+    assert_eq!(apply(EmoteMode::Keep, emotes.clone()).text, text);
+    assert_eq!(apply(EmoteMode::Strip, emotes.clone()).text, "hi 草");
+    assert_eq!(
+        apply(EmoteMode::Placeholder, emotes.clone()).text,
+        "[emote] hi [emote] 草 [emote]"
+    );
+    let mut no_url = emotes.clone();
+    no_url[0].url = None;
+    let msg = apply(EmoteMode::Image, no_url);
+    assert_eq!(msg.text, "Kappa hi 草");
+    assert_eq!(msg.attachments.len(), 2);
+    assert_eq!(msg.attachments[0].kind, AttachmentKind::Emote);
+
+    let decoder = DecoderConfig {
+        kind: DecoderKind::Bilibili,
+        emotes: EmoteMode::Image,
+        ..Default::default()
+    };
+    let extra = json!({"emots": {
+        "[dog]": {"url": "https://i0.hdslb.com/dog.png"},
+    }});
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "DANMU_MSG",
+                "info": [
+                    [0, 1, 25, 0, 0, 0, 0, "", 0, 0, 0, "", 0, "{}", "",
+                     {"extra": extra.to_string()}],
+                    "hi [dog]",
+                    [1, "a"],
+                ],
+            })
+        ),
+        Some(Message {
+            attachments: vec![Attachment::url(
+                AttachmentKind::Emote,
+                "https://i0.hdslb.com/dog.png"
+            )],
+            ..message(MessageKind::Chat, "a", "hi")
+        })
+    );
+}
+
+#[test]
+fn bilibili_events() {
+    let decoder = decoder(DecoderKind::Bilibili);
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "INTERACT_WORD",
+                "data": {"uname": "a", "msg_type": 2},
+            })
+        ),
+        Some(message(MessageKind::Follow, "a", "followed"))
+    );
+    // entering the room
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "INTERACT_WORD",
+                "data": {"uname": "a", "msg_type": 1},
+            })
+        ),
+        None
+    );
+    assert_eq!(
+        decode(
+            &decoder,
+            json!({
+                "cmd": "GUARD_BUY",
+                "data": {"username": "b", "gift_name": "舰长", "num": 3},
+            })
+        ),
+        Some(Message {
+            event: Some(PlatformEvent {
+                months: 3,
+                viewers: 0,
+            }),
+            ..message(MessageKind::Subscription, "b", "舰长 x3")
+        })
+    );
+}
+
+#[test]
+fn kind_filter() {
+    let mut filter = KindFilter::default();
+    let follow = message(MessageKind::Follow, "a", "followed");
+    assert!(!filter.drops(&follow));
+    filter.set_dropped(MessageKind::Follow, true);
+    filter.set_dropped(MessageKind::Follow, true);
+    assert!(filter.drops(&follow));
+    assert!(!filter.drops(&Message::chat("hi")));
+    filter.set_dropped(MessageKind::Follow, false);
+    assert_eq!(filter, KindFilter::default());
+}
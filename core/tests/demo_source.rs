@@ -0,0 +1,149 @@
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use blooming_light_core::{
+    demo_source::{DemoSource, Scenario, Stress, StressConfig},
+    message::{Message, MessageKind},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn loads_jsonl_entries() {
+    let path = std::env::temp_dir().join(format!(
+        "blooming-light-demo-{}.jsonl",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        concat!(
+            r#"{"username": "a", "type": "gift", "text": "x", "weight": 1}"#,
+            "\n\n",
+            r#"{"username": "b", "type": "superchat", "text": "y", "weight": 0}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let mut source = DemoSource::new(path.clone());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(source.data_len(), Some(2));
+
+    // the zero-weight entry is never picked
+    for _ in 0..32 {
+        let msg = source.pull_demo_msg(0.0).unwrap();
+        assert_eq!(msg.kind, MessageKind::Gift);
+        assert_eq!(msg.username.as_deref(), Some("a"));
+        assert_eq!(msg.text, "x");
+    }
+}
+
+#[test]
+fn falls_back_on_invalid_file() {
+    let path = std::env::temp_dir()
+        .join(format!("blooming-light-demo-{}.json", std::process::id()));
+    fs::write(&path, "not json").unwrap();
+
+    let source = DemoSource::new(path.clone());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(source.data_len(), None);
+}
+
+#[test]
+fn plays_scenario_in_order() {
+    let mut scenario = Scenario::parse(
+        "# rehearsal\n\
+         +0.5s: hello\n\
+         +1s: burst of 3\n\
+         +2s: superchat @bob thanks\n\
+         +0s: gift @alice\n",
+    )
+    .unwrap();
+    assert_eq!(scenario.len(), 4);
+
+    assert_eq!(scenario.pull(Duration::ZERO), None);
+    assert_eq!(
+        scenario.pull(Duration::from_millis(500)),
+        Some(Message::chat("hello"))
+    );
+    assert_eq!(scenario.pull(Duration::from_millis(1499)), None);
+
+    let burst =
+        std::iter::from_fn(|| scenario.pull(Duration::from_millis(1500)))
+            .collect::<Vec<_>>();
+    assert_eq!(burst.len(), 3);
+    assert_eq!(scenario.next_at(), Some(Duration::from_millis(3500)));
+
+    let rest =
+        std::iter::from_fn(|| scenario.pull(Duration::from_secs(4)))
+            .collect::<Vec<_>>();
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest[0].kind, MessageKind::SuperChat);
+    assert_eq!(rest[0].username.as_deref(), Some("bob"));
+    assert_eq!(rest[0].text, "thanks");
+    assert_eq!(rest[1].kind, MessageKind::Gift);
+    assert!(!rest[1].text.is_empty());
+    assert!(scenario.is_finished());
+
+    scenario.restart();
+    let replay =
+        std::iter::from_fn(|| scenario.pull(Duration::from_secs(4)))
+            .collect::<Vec<_>>();
+    assert_eq!(replay[1..4], burst);
+}
+
+#[test]
+fn rejects_malformed_scenario() {
+    assert!(Scenario::parse("hello").is_err());
+    assert!(Scenario::parse("+1: hello").is_err());
+    assert!(Scenario::parse("+1s: burst of many").is_err());
+}
+
+#[test]
+fn stress_keeps_rate_and_length() {
+    let mut stress = Stress::new(StressConfig {
+        rate: 100.0,
+        min_len: 3,
+        max_len: 5,
+        duration_secs: 1.0,
+    });
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let first = std::iter::from_fn(|| {
+        stress.pull(Duration::from_millis(500), &mut rng)
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(first.len(), 50);
+    assert!(first
+        .iter()
+        .all(|it| (3..=5).contains(&it.text.chars().count())));
+    assert!(!stress.is_finished());
+
+    while stress.pull(Duration::from_secs(5), &mut rng).is_some() {}
+    assert_eq!(stress.sent(), 100);
+    assert!(stress.is_finished());
+}
+
+#[test]
+fn reloads_changed_file() {
+    let dir = std::env::temp_dir()
+        .join(format!("blooming-light-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("demo.txt");
+    fs::write(&path, "a\n").unwrap();
+
+    let mut source = DemoSource::new(path.clone());
+    assert_eq!(source.data_len(), Some(1));
+
+    fs::write(&path, "a\nb\nc\n").unwrap();
+    let start = Instant::now();
+    // a write can show up as several events, keep polling until the
+    // final content is seen
+    while source.data_len() != Some(3) && start.elapsed().as_secs() < 5 {
+        source.poll_changes();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(source.data_len(), Some(3));
+}
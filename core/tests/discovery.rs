@@ -0,0 +1,73 @@
+use std::net::SocketAddr;
+
+use blooming_light_core::network::{
+    discovery::lan_ips, theme::Layout, ServerConfig,
+};
+
+fn addr(addr: &str) -> SocketAddr {
+    addr.parse().unwrap()
+}
+
+#[test]
+fn lan_ips_of_listen_address() {
+    assert!(lan_ips(addr("127.0.0.1:8081")).is_empty());
+    assert!(lan_ips(addr("[::1]:8081")).is_empty());
+    assert_eq!(
+        lan_ips(addr("192.168.1.5:8081")),
+        [addr("192.168.1.5:0").ip()]
+    );
+
+    for ip in lan_ips(addr("0.0.0.0:8081")) {
+        assert!(ip.is_ipv4() && !ip.is_loopback(), "{ip}");
+    }
+    for ip in lan_ips(addr("[::]:8081")) {
+        assert!(ip.is_ipv6() && !ip.is_loopback(), "{ip}");
+    }
+}
+
+#[test]
+fn overlay_urls_follow_listen_address() {
+    let config = |addrs: &str| ServerConfig {
+        addrs: ServerConfig::parse_addrs(addrs).unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(
+        config("127.0.0.1:8081").lan_url(),
+        "http://127.0.0.1:8081/"
+    );
+    assert_eq!(
+        config("192.168.1.5:9000").lan_url(),
+        "http://192.168.1.5:9000/"
+    );
+    assert!(config("127.0.0.1:8081").lan_urls().is_empty());
+    for url in config("0.0.0.0:9000").lan_urls() {
+        assert!(url.starts_with("http://") && url.ends_with(":9000/"));
+        assert!(!url.contains("127.0.0.1"), "{url}");
+    }
+    // this machine reaches a wildcard listener over loopback
+    assert_eq!(
+        config("0.0.0.0:9000").layout_url(Layout::Ticker),
+        "http://127.0.0.1:9000/layouts/ticker/"
+    );
+    assert_eq!(
+        config("[::]:9000").preview_url(),
+        "http://[::1]:9000/layouts/danmaku/"
+    );
+}
+
+#[test]
+fn parses_listen_addresses() {
+    let addrs =
+        ServerConfig::parse_addrs(" 0.0.0.0:8081, [::]:8081,").unwrap();
+    assert_eq!(addrs, [addr("0.0.0.0:8081"), addr("[::]:8081")]);
+    assert_eq!(
+        ServerConfig::format_addrs(&addrs),
+        "0.0.0.0:8081, [::]:8081"
+    );
+    assert_eq!(ServerConfig::parse_addrs(""), None);
+    assert_eq!(ServerConfig::parse_addrs("localhost:8081"), None);
+    assert_eq!(
+        ServerConfig::default().preview_url(),
+        "http://127.0.0.1:8081/layouts/danmaku/"
+    );
+}
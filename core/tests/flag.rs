@@ -0,0 +1,90 @@
+use blooming_light_core::{
+    flag::{
+        word_list::{parse_words, WordList, WordListSource},
+        FlagAction, FlagWords,
+    },
+    message::Message,
+};
+use chrono::{TimeDelta, Utc};
+
+#[test]
+fn matches_text_and_sender_ignoring_case() {
+    let words = FlagWords {
+        words: vec![" ".to_owned(), "Spoiler".to_owned()],
+        ..Default::default()
+    };
+    assert_eq!(
+        words.matches(&Message::chat("no SPOILERS pls")),
+        Some("Spoiler")
+    );
+    let msg = Message {
+        username: Some("spoiler_bot".to_owned()),
+        ..Message::chat("hi")
+    };
+    assert_eq!(words.matches(&msg), Some("Spoiler"));
+    assert_eq!(words.matches(&Message::chat("hello")), None);
+}
+
+#[test]
+fn reads_clicked_actions() {
+    assert_eq!(FlagAction::from_key("delete"), Some(FlagAction::Delete));
+    assert_eq!(
+        FlagAction::from_key("approve"),
+        Some(FlagAction::Approve)
+    );
+    // closed without a click
+    assert_eq!(FlagAction::from_key(""), None);
+}
+
+#[test]
+fn reads_word_lists() {
+    let text = "# shared list\nSpoiler\n\n  leak  \n";
+    assert_eq!(parse_words(text, false), ["spoiler", "leak"]);
+    let csv = "word,severity\nspoiler,high\n\"end, game\",low\n\
+               \"say \"\"hi\"\"\",low\n";
+    assert_eq!(
+        parse_words(csv, true),
+        ["spoiler", "end, game", "say \"hi\""]
+    );
+
+    let source =
+        WordListSource::parse("https://example.com/lists/a.csv?v=2");
+    assert_eq!(
+        source,
+        WordListSource::Url(
+            "https://example.com/lists/a.csv?v=2".to_owned()
+        )
+    );
+    assert_eq!(source.name(), "a.csv");
+}
+
+#[test]
+fn matches_enabled_lists() {
+    let mut list = WordList::new(WordListSource::parse("list.txt"));
+    list.words = parse_words("leak", false);
+    let now = Utc::now();
+    assert!(list.resync_due(now));
+    list.synced_at = Some(now);
+    assert!(!list.resync_due(now + TimeDelta::minutes(59)));
+    assert!(list.resync_due(now + TimeDelta::minutes(60)));
+    let mut words = FlagWords {
+        lists: vec![list],
+        ..Default::default()
+    };
+    assert_eq!(
+        words.matches(&Message::chat("the LEAK is out")),
+        Some("leak")
+    );
+    words.words = vec!["Out".to_owned()];
+    let found = words
+        .matching(&Message::chat("the LEAK is out"))
+        .map(|it| (it.word, it.list.map(WordListSource::name)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [("Out", None), ("leak", Some("list.txt".to_owned()))]
+    );
+    words.words.clear();
+    words.lists[0].enabled = false;
+    assert_eq!(words.matches(&Message::chat("the LEAK is out")), None);
+}
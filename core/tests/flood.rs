@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    flood::{FloodCollapser, FloodLimit},
+    message::Message,
+};
+
+fn from(username: &str, text: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    }
+}
+
+#[test]
+fn collapses_messages_over_limit() {
+    let start = Instant::now();
+    let secs = Duration::from_secs;
+    let limit = FloodLimit {
+        max_messages: 2,
+        window_secs: 10.0,
+    };
+    let mut flood = FloodCollapser::default();
+
+    let mut passed = vec![];
+    for (at, msg) in [
+        (0, from("a", "1")),
+        (1, from("a", "2")),
+        (2, from("a", "3")),
+        (3, from("b", "hi")),
+        (4, from("a", "4")),
+        (5, Message::chat("no sender")),
+    ] {
+        passed.extend(flood.push(msg, start + secs(at), limit));
+    }
+    let passed = passed.iter().map(|it| &*it.text).collect::<Vec<_>>();
+    assert_eq!(passed, ["1", "2", "hi", "no sender"]);
+
+    assert!(flood.take_due(start + secs(5), limit).is_empty());
+    assert_eq!(flood.next_due(limit), Some(start + secs(12)));
+    let [summary] =
+        flood.take_due(start + secs(12), limit).try_into().unwrap();
+    assert_eq!(summary.msg.text, "…and 2 more from a");
+    assert_eq!(summary.msg.username.as_deref(), Some("a"));
+    assert_eq!(summary.received_at, start + secs(2));
+    assert_eq!(summary.messages.len(), 2);
+
+    // the first two have left the window
+    assert!(flood
+        .push(from("a", "5"), start + secs(12), limit)
+        .is_some());
+}
+
+#[test]
+fn lets_everything_through_when_off() {
+    let now = Instant::now();
+    let mut flood = FloodCollapser::default();
+    for _ in 0..100 {
+        assert!(flood
+            .push(from("a", "spam"), now, FloodLimit::default())
+            .is_some());
+    }
+}
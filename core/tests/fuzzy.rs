@@ -0,0 +1,21 @@
+use blooming_light_core::fuzzy::score;
+
+#[test]
+fn matches_in_order_ignoring_case() {
+    assert_eq!(score("", "Purge queue"), Some(0));
+    assert!(score("PQ", "Purge queue").is_some());
+    assert!(score("purge queue", "Purge queue").is_some());
+    assert_eq!(score("qp", "Purge queue"), None);
+    assert_eq!(score("purges", "Purge queue"), None);
+}
+
+#[test]
+fn prefers_word_starts_and_runs() {
+    let restart = score("rs", "Restart server").unwrap();
+    let redact = score("rs", "Redact users").unwrap();
+    assert!(restart > redact);
+
+    let source = score("ss", "Open Source Settings").unwrap();
+    let stats = score("ss", "Open Stats").unwrap();
+    assert!(source > stats);
+}
@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    gift::GiftAggregator,
+    message::{Gift, Message, MessageKind},
+};
+
+fn gift(username: &str, name: &str, count: u32, value: u64) -> Message {
+    Message {
+        kind: MessageKind::Gift,
+        username: Some(username.to_owned()),
+        gift: Some(Gift {
+            name: name.to_owned(),
+            count,
+            value,
+        }),
+        ..Message::chat(format!("{name} x{count}"))
+    }
+}
+
+#[test]
+fn summarizes_per_user_and_gift_within_window() {
+    let mut gifts = GiftAggregator::default();
+    let window = Duration::from_secs(3);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+
+    assert!(gifts.push(gift("a", "x", 1, 100), at(0), window).is_none());
+    assert!(gifts.push(gift("b", "x", 2, 200), at(1), window).is_none());
+    assert!(gifts.push(gift("a", "x", 5, 500), at(2), window).is_none());
+    assert!(gifts.push(gift("a", "y", 1, 10), at(2), window).is_none());
+    assert_eq!(gifts.next_due(window), Some(at(3)));
+    assert!(gifts.take_due(at(2), window).is_empty());
+
+    let due = gifts.take_due(at(3), window);
+    let [summary] = due.as_slice() else {
+        panic!("expected one summary, got {due:?}");
+    };
+    assert_eq!(summary.msg.text, "x x6");
+    assert_eq!(summary.msg.gift, gift("a", "x", 6, 600).gift);
+    assert_eq!(summary.received_at, at(0));
+    assert_eq!(summary.gifts.len(), 2);
+
+    let texts: Vec<_> = gifts
+        .take_due(at(10), window)
+        .into_iter()
+        .map(|it| it.msg.text)
+        .collect();
+    assert_eq!(texts, ["x x2", "y x1"]);
+    assert!(gifts.is_empty());
+}
+
+#[test]
+fn passes_through_other_messages() {
+    let mut gifts = GiftAggregator::default();
+    let now = Instant::now();
+    let window = Duration::from_secs(3);
+    let chat = Message::chat("hi");
+    assert_eq!(gifts.push(chat.clone(), now, window), Some(chat));
+
+    let msg = gift("a", "x", 1, 0);
+    assert_eq!(
+        gifts.push(msg.clone(), now, Duration::ZERO),
+        Some(msg.clone())
+    );
+
+    // gifts without details count once under their text
+    let plain = Message { gift: None, ..msg };
+    gifts.push(plain.clone(), now, window);
+    gifts.push(plain, now, window);
+    let summaries = gifts.take_all();
+    assert_eq!(summaries[0].msg.text, "x x1 x2");
+}
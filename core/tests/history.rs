@@ -0,0 +1,39 @@
+use blooming_light_core::{
+    history::{Moderation, ModerationHistory},
+    message::Message,
+    queue::SnapshotEntry,
+};
+use chrono::Utc;
+
+fn delete(text: &str) -> Moderation {
+    Moderation::Delete(SnapshotEntry {
+        msg: Message::chat(text),
+        arrive_at: None,
+        send_at: None,
+        delay_secs: None,
+        scheduled: false,
+    })
+}
+
+#[test]
+fn undo_skips_approvals_and_redo_follows_undo() {
+    let mut history = ModerationHistory::default();
+    assert!(!history.can_undo());
+    history.push(delete("a"), Utc::now());
+    history.push(delete("b"), Utc::now());
+    history.push(Moderation::Approve(Message::chat("c")), Utc::now());
+
+    assert_eq!(history.undo().cloned(), Some(delete("b")));
+    assert_eq!(history.undo().cloned(), Some(delete("a")));
+    assert_eq!(history.undo(), None);
+    assert_eq!(history.entries()[2].action.name(), "Approve");
+    assert!(!history.entries()[2].undone);
+
+    assert_eq!(history.redo().cloned(), Some(delete("a")));
+    assert!(history.can_redo());
+    // something new drops what's left to redo
+    history.push(delete("d"), Utc::now());
+    assert!(!history.can_redo());
+    assert_eq!(history.redo(), None);
+    assert_eq!(history.undo().cloned(), Some(delete("d")));
+}
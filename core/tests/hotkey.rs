@@ -0,0 +1,81 @@
+use blooming_light_core::hotkey::{Hotkey, HotkeyAction, HotkeyBindings};
+
+fn hotkey(s: &str) -> Hotkey {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_and_display() {
+    assert_eq!(
+        hotkey("ctrl + shift + p"),
+        Hotkey {
+            ctrl: true,
+            shift: true,
+            alt: false,
+            key: "P".to_owned(),
+        }
+    );
+    assert_eq!(hotkey("Shift+Alt+Space").to_string(), "Shift+Alt+Space");
+    assert_eq!(hotkey("F9").to_string(), "F9");
+
+    // would steal typing
+    assert!("P".parse::<Hotkey>().is_err());
+    assert!("Shift+P".parse::<Hotkey>().is_err());
+    assert!("Ctrl+Ctrl+P".parse::<Hotkey>().is_err());
+    assert!("Meta+P".parse::<Hotkey>().is_err());
+    assert!("Ctrl+".parse::<Hotkey>().is_err());
+}
+
+#[test]
+fn conflicts() {
+    let mut bindings = HotkeyBindings::default();
+    bindings.set(HotkeyAction::TogglePause, Some(hotkey("Ctrl+Shift+P")));
+
+    assert_eq!(
+        bindings.conflict(HotkeyAction::Purge, &hotkey("ctrl+shift+p")),
+        Some("Ctrl+Shift+P already runs Pause/Resume".to_owned())
+    );
+    // rebinding the same action to it is fine
+    assert_eq!(
+        bindings
+            .conflict(HotkeyAction::TogglePause, &hotkey("Ctrl+Shift+P")),
+        None
+    );
+    assert!(bindings
+        .conflict(HotkeyAction::Purge, &hotkey("Alt+F4"))
+        .is_some());
+    assert_eq!(
+        bindings.conflict(HotkeyAction::Purge, &hotkey("ctrl+p")),
+        Some("Ctrl+P opens the command palette".to_owned())
+    );
+    assert_eq!(
+        bindings.conflict(HotkeyAction::Purge, &hotkey("F12")),
+        None
+    );
+
+    bindings.set(HotkeyAction::TogglePause, None);
+    assert_eq!(bindings, HotkeyBindings::default());
+}
+
+#[test]
+fn serialized_as_written() {
+    let mut bindings = HotkeyBindings::default();
+    bindings.set(HotkeyAction::Purge, Some(hotkey("alt+F12")));
+    let json = serde_json::to_string(&bindings).unwrap();
+    assert_eq!(
+        json,
+        r#"{"bindings":[["Purge","Alt+F12"]],"global":false}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<HotkeyBindings>(&json).unwrap(),
+        bindings
+    );
+    // saved before system-wide grabs
+    assert_eq!(
+        serde_json::from_str::<HotkeyBindings>(
+            r#"{"bindings":[["Purge","Alt+F12"]]}"#
+        )
+        .unwrap(),
+        bindings
+    );
+}
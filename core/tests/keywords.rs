@@ -0,0 +1,36 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::keywords::{tokenize, Keywords};
+
+#[test]
+fn splits_words_and_cjk_runs() {
+    assert_eq!(
+        tokenize("When is the STREAM, stream again?"),
+        ["when", "stream", "again"]
+    );
+    assert_eq!(
+        tokenize("今天唱歌吗 ok"),
+        ["今天", "天唱", "唱歌", "歌吗", "ok"]
+    );
+    assert_eq!(tokenize("歌abc好"), ["歌", "abc", "好"]);
+    assert_eq!(tokenize("88888 x"), ["88888"]);
+}
+
+#[test]
+fn counts_messages_within_window() {
+    let start = Instant::now();
+    let secs = Duration::from_secs;
+    let mut keywords = Keywords::new(secs(60));
+    keywords.record(start, "song song please");
+    keywords.record(start + secs(30), "what song is this");
+    keywords.record(start + secs(40), "please");
+    assert_eq!(
+        keywords.top(start + secs(45), 2),
+        [("please".to_owned(), 2), ("song".to_owned(), 2)]
+    );
+    // the first message has left the window
+    assert_eq!(
+        keywords.top(start + secs(70), 3),
+        [("please".to_owned(), 1), ("song".to_owned(), 1)]
+    );
+}
@@ -0,0 +1,49 @@
+use blooming_light_core::{
+    lang::{Language, LanguageFilter},
+    message::Message,
+};
+
+#[test]
+fn detects_by_script() {
+    let detect = Language::detect;
+    assert_eq!(detect("今天唱什么歌"), Some(Language::Chinese));
+    assert_eq!(detect("今日は何を歌いますか"), Some(Language::Japanese));
+    assert_eq!(detect("오늘 무슨 노래"), Some(Language::Korean));
+    assert_eq!(detect("what song is this"), Some(Language::Latin));
+    assert_eq!(detect("какая песня"), Some(Language::Cyrillic));
+    assert_eq!(detect("ok 好的好的"), Some(Language::Chinese));
+    assert_eq!(detect("8888 😂"), None);
+}
+
+#[test]
+fn loanwords_dont_outweigh_han() {
+    let detect = Language::detect;
+    assert_eq!(detect("打call"), Some(Language::Chinese));
+    assert_eq!(detect("awsl草"), Some(Language::Chinese));
+    assert_eq!(detect("笑死 www"), Some(Language::Chinese));
+    assert_eq!(detect("这首歌好好听 i love it"), Some(Language::Chinese));
+    assert_eq!(
+        detect("this song is so good 好听"),
+        Some(Language::Latin)
+    );
+    assert_eq!(detect("awsl"), Some(Language::Latin));
+    // short words still count alone
+    assert_eq!(detect("ok"), Some(Language::Latin));
+    assert_eq!(detect("xD 好"), Some(Language::Chinese));
+}
+
+#[test]
+fn drops_and_tags() {
+    let filter = LanguageFilter {
+        tagged: vec![Language::Latin],
+        dropped: vec![Language::Cyrillic],
+    };
+    assert!(filter.drops(&Message::chat("привет")));
+    assert!(!filter.drops(&Message::chat("hello")));
+    assert_eq!(
+        filter.tag(&Message::chat("hello")),
+        Some(Language::Latin)
+    );
+    assert_eq!(filter.tag(&Message::chat("你好")), None);
+    assert_eq!(filter.tag(&Message::chat("233")), None);
+}
@@ -0,0 +1,60 @@
+use blooming_light_core::{
+    leaderboard::{Leaderboard, LeaderboardSort},
+    message::{Gift, Message, MessageKind, Paid},
+};
+use chrono::Utc;
+
+fn from(username: &str, text: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    }
+}
+
+fn names(board: &Leaderboard, by: LeaderboardSort) -> Vec<&str> {
+    board.sorted(by).iter().map(|it| &*it.username).collect()
+}
+
+#[test]
+fn ranks_chatters() {
+    let now = Utc::now();
+    let mut board = Leaderboard::default();
+    board.record(&from("a", "hi"), now);
+    board.record(&from("a", "again"), now);
+    board.record(&from("b", "hello"), now);
+    board.record(
+        &Message {
+            kind: MessageKind::Gift,
+            gift: Some(Gift {
+                name: "Rose".to_owned(),
+                count: 2,
+                value: 200,
+            }),
+            ..from("c", "sent Rose x2")
+        },
+        now,
+    );
+    board.record(
+        &Message {
+            kind: MessageKind::SuperChat,
+            paid: Some(Paid {
+                amount: 30.0,
+                pin_secs: None,
+            }),
+            ..from("b", "thanks")
+        },
+        now,
+    );
+    board.record(&Message::chat("no sender"), now);
+
+    assert_eq!(board.len(), 3);
+    assert_eq!(names(&board, LeaderboardSort::Messages), ["a", "b", "c"]);
+    assert_eq!(names(&board, LeaderboardSort::Gifts), ["c", "a", "b"]);
+    assert_eq!(names(&board, LeaderboardSort::Paid), ["b", "a", "c"]);
+
+    let c = board.get("c").unwrap();
+    assert_eq!((c.messages, c.gift_value), (0, 200));
+    let a = board.get("a").unwrap();
+    let recent = a.recent().map(|it| &*it.1.text).collect::<Vec<_>>();
+    assert_eq!(recent, ["again", "hi"]);
+}
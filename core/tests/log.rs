@@ -0,0 +1,261 @@
+use std::io::Write;
+
+use blooming_light_core::{
+    channel,
+    log::{self, LogConfig, LogEntry, LogEvent, SyncPolicy},
+    message::Message,
+};
+
+fn write_log(name: &str, entries: &[LogEntry]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "blooming-light-{name}-{}.jsonl",
+        std::process::id()
+    ));
+    let mut file = std::fs::File::create(&path).unwrap();
+    for entry in entries {
+        serde_json::to_writer(&mut file, entry).unwrap();
+        file.write_all(b"\n").unwrap();
+    }
+    path
+}
+
+fn entry(text: &str, event: LogEvent) -> LogEntry {
+    LogEntry::new(Message::chat(text), event)
+}
+
+#[test]
+fn finds_unfinished_messages_of_crashed_session() {
+    let path = write_log(
+        "crashed",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("old", LogEvent::Receive),
+            LogEntry::marker(LogEvent::Start),
+            entry("a", LogEvent::Receive),
+            entry("a", LogEvent::Receive),
+            entry("b", LogEvent::Receive),
+            entry("c", LogEvent::Receive),
+            entry("a", LogEvent::Forward),
+            entry("c", LogEvent::Delete),
+        ],
+    );
+    assert_eq!(
+        log::unfinished_messages(&path).unwrap(),
+        [Message::chat("a"), Message::chat("b")]
+    );
+}
+
+#[test]
+fn anonymized_session_leaves_nothing() {
+    let path = write_log(
+        "crashed-anonymized",
+        &[
+            LogEntry {
+                anonymized: true,
+                ..LogEntry::marker(LogEvent::Start)
+            },
+            LogEntry::new(
+                Message {
+                    username: Some("user-0123456789".to_owned()),
+                    ..Message::chat("a")
+                },
+                LogEvent::Receive,
+            ),
+        ],
+    );
+    assert!(log::unfinished_messages(&path).unwrap().is_empty());
+}
+
+#[test]
+fn clean_exit_leaves_nothing() {
+    let path = write_log(
+        "clean",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("a", LogEvent::Receive),
+            LogEntry::marker(LogEvent::End),
+        ],
+    );
+    assert!(log::unfinished_messages(&path).unwrap().is_empty());
+}
+
+#[test]
+fn reads_entries_predating_events() {
+    let legacy = serde_json::json!({
+        "msg": "a",
+        "type": "chat",
+        "is_delete": true,
+        "ts": "2024-01-01T00:00:00Z",
+    });
+    let legacy: LogEntry = serde_json::from_value(legacy).unwrap();
+    assert_eq!(legacy.event, LogEvent::Forward);
+    assert!(legacy.is_delete);
+
+    // counts as a delete
+    let path = write_log(
+        "legacy",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("a", LogEvent::Receive),
+            legacy,
+        ],
+    );
+    assert!(log::unfinished_messages(&path).unwrap().is_empty());
+}
+
+#[test]
+fn purged_messages_are_settled() {
+    let purged = entry("a", LogEvent::Purge)
+        .with_reason(Some("leaked address".to_owned()));
+    let value = serde_json::to_value(&purged).unwrap();
+    assert_eq!(value["event"], "purge");
+    assert_eq!(value["reason"], "leaked address");
+    assert_eq!(value["is_delete"], true);
+
+    let path = write_log(
+        "purge",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("a", LogEvent::Receive),
+            entry("b", LogEvent::Receive),
+            purged,
+        ],
+    );
+    assert_eq!(
+        log::unfinished_messages(&path).unwrap(),
+        [Message::chat("b")]
+    );
+}
+
+#[test]
+fn merged_gifts_are_settled_by_their_summary() {
+    let path = write_log(
+        "merge",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("x x1", LogEvent::Receive),
+            entry("x x1", LogEvent::Receive),
+            entry("x x1", LogEvent::Merge),
+            entry("x x1", LogEvent::Merge),
+            entry("x x2", LogEvent::Receive),
+        ],
+    );
+    assert_eq!(
+        log::unfinished_messages(&path).unwrap(),
+        [Message::chat("x x2")]
+    );
+}
+
+#[tokio::test]
+async fn writer_frames_session_with_markers() {
+    let path = std::env::temp_dir().join(format!(
+        "blooming-light-writer-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let (log_tx, log_rx) = channel::bounded(16);
+    let config = LogConfig {
+        sync: SyncPolicy::Interval(10),
+        ..Default::default()
+    };
+    let (_redact_tx, redact_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (stop_token, fut) =
+        log::run_writer(path.clone(), log_rx, redact_rx, config);
+    let handle = tokio::spawn(fut);
+
+    log_tx.send(entry("a", LogEvent::Receive)).unwrap();
+    log_tx.send(entry("b", LogEvent::Receive)).unwrap();
+    tokio::task::yield_now().await;
+    log_tx.send(entry("a", LogEvent::Forward)).unwrap();
+    stop_token.cancel();
+    handle.await.unwrap().unwrap();
+
+    let events = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|it| serde_json::from_str::<LogEntry>(it).unwrap())
+        .map(|it| (it.msg, it.event))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            (String::new(), LogEvent::Start),
+            ("a".to_owned(), LogEvent::Receive),
+            ("b".to_owned(), LogEvent::Receive),
+            ("a".to_owned(), LogEvent::Forward),
+            (String::new(), LogEvent::End),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn writer_anonymizes_usernames() {
+    let path = std::env::temp_dir().join(format!(
+        "blooming-light-anonymized-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let (log_tx, log_rx) = channel::bounded(16);
+    let config = LogConfig {
+        anonymize: true,
+        ..Default::default()
+    };
+    let (_redact_tx, redact_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (stop_token, fut) =
+        log::run_writer(path.clone(), log_rx, redact_rx, config);
+    let handle = tokio::spawn(fut);
+
+    for username in ["alice", "bob", "alice"] {
+        let msg = Message {
+            username: Some(username.to_owned()),
+            ..Message::chat("hi")
+        };
+        log_tx.send(LogEntry::new(msg, LogEvent::Receive)).unwrap();
+    }
+    stop_token.cancel();
+    handle.await.unwrap().unwrap();
+
+    let entries = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|it| serde_json::from_str::<LogEntry>(it).unwrap())
+        .collect::<Vec<_>>();
+    assert!(entries[0].anonymized);
+    let usernames = entries
+        .into_iter()
+        .filter_map(|it| it.username)
+        .collect::<Vec<_>>();
+    assert_eq!(usernames.len(), 3);
+    assert!(usernames[0].starts_with("user-"));
+    assert_eq!(usernames[0], usernames[2]);
+    assert_ne!(usernames[0], usernames[1]);
+}
+
+#[test]
+fn pseudonyms_differ_across_instances() {
+    let a = log::Pseudonyms::default();
+    let b = log::Pseudonyms::default();
+    assert_eq!(a.of("alice"), a.of("alice"));
+    assert_ne!(a.of("alice"), b.of("alice"));
+}
+
+#[test]
+fn tells_logs_from_demo_files() {
+    let path = write_log(
+        "is-log",
+        &[
+            LogEntry::marker(LogEvent::Start),
+            entry("a", LogEvent::Receive),
+        ],
+    );
+    assert!(log::is_log(&path).unwrap());
+    assert_eq!(log::read_entries(&path).unwrap().len(), 2);
+
+    let demo = std::env::temp_dir().join(format!(
+        "blooming-light-demo-{}.jsonl",
+        std::process::id()
+    ));
+    std::fs::write(&demo, "\n{\"text\": \"a\", \"weight\": 2}\n")
+        .unwrap();
+    assert!(!log::is_log(&demo).unwrap());
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use blooming_light_core::log::archive;
+use flate2::read::GzDecoder;
+
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "blooming-light-archive-{name}-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn names(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|it| it.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+fn gunzip(path: &Path) -> String {
+    let mut text = String::new();
+    GzDecoder::new(fs::File::open(path).unwrap())
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+#[test]
+fn rotates_only_past_limit_then_compresses() {
+    let dir = dir("rotate");
+    let log = dir.join("log.jsonl");
+    assert_eq!(archive::rotate(&log, 4).unwrap(), None);
+
+    fs::write(&log, "1234").unwrap();
+    assert_eq!(archive::rotate(&log, 4).unwrap(), None);
+
+    fs::write(&log, "12345\n").unwrap();
+    let rotated = archive::rotate(&log, 4).unwrap().unwrap();
+    assert!(!log.exists());
+    let name = rotated.file_name().unwrap().to_string_lossy();
+    assert!(name.starts_with("log-") && name.ends_with(".jsonl"));
+
+    assert!(archive::tidy(&log, 0).unwrap().is_empty());
+    let archives = archive::archives(&log).unwrap();
+    assert_eq!(archives.len(), 1);
+    assert!(names(&archives)[0].ends_with(".jsonl.gz"));
+    assert!(!rotated.exists());
+    assert_eq!(gunzip(&archives[0]), "12345\n");
+}
+
+#[test]
+fn prunes_oldest_archives_past_total() {
+    let dir = dir("prune");
+    let log = dir.join("log.jsonl");
+    fs::write(&log, [0; 10]).unwrap();
+    for stamp in ["20240101T000000", "20240102T000000", "20240103T000000"]
+    {
+        fs::write(dir.join(format!("log-{stamp}.jsonl.gz")), [0; 10])
+            .unwrap();
+    }
+    fs::write(dir.join("access.log"), [0; 100]).unwrap();
+
+    let deleted = archive::tidy(&log, 25).unwrap();
+    assert_eq!(
+        names(&deleted),
+        [
+            "log-20240101T000000.jsonl.gz",
+            "log-20240102T000000.jsonl.gz"
+        ]
+    );
+    assert_eq!(
+        names(&archive::archives(&log).unwrap()),
+        ["log-20240103T000000.jsonl.gz"]
+    );
+    assert!(dir.join("access.log").exists());
+}
+
+#[test]
+fn format_is_told_by_content() {
+    let dir = dir("format");
+    let log = dir.join("log.jsonl");
+    fs::write(&log, "12345\n").unwrap();
+    assert_eq!(
+        archive::Format::of(&log).unwrap(),
+        archive::Format::Plain
+    );
+
+    let compressed = archive::compress(&log).unwrap();
+    // named by extension, but read by what's in it
+    let renamed = dir.join("log-20240101T000000.jsonl");
+    fs::rename(&compressed, &renamed).unwrap();
+    let format = archive::Format::of(&renamed).unwrap();
+    assert_eq!(format, archive::Format::NEW);
+    let file = fs::File::open(&renamed).unwrap();
+    assert_eq!(format.read_to_string(file).unwrap(), "12345\n");
+}
@@ -0,0 +1,178 @@
+use std::io::Write;
+
+use blooming_light_core::{
+    channel,
+    log::{
+        self,
+        redact::{self, RedactMode, Redaction, REDACTED},
+        LogConfig, LogEntry, LogEvent,
+    },
+    message::Message,
+};
+use flate2::{write::GzEncoder, Compression};
+
+fn entry(username: &str, text: &str) -> LogEntry {
+    let msg = Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    };
+    LogEntry::new(msg, LogEvent::Receive)
+}
+
+fn lines(entries: &[LogEntry]) -> String {
+    entries
+        .iter()
+        .map(|it| serde_json::to_string(it).unwrap() + "\n")
+        .collect()
+}
+
+fn read(path: &std::path::Path) -> Vec<(Option<String>, String)> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|it| serde_json::from_str::<LogEntry>(it).unwrap())
+        .map(|it| (it.username, it.msg))
+        .collect()
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("blooming-light-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn redaction(mode: RedactMode) -> Redaction {
+    Redaction {
+        username: "eve".to_owned(),
+        mode,
+    }
+}
+
+#[test]
+fn redacts_log_and_archives() {
+    let dir = temp_dir("redact");
+    let path = dir.join("log.jsonl");
+    let archive = dir.join("log-20240101T000000.jsonl.gz");
+    let mut encoder = GzEncoder::new(
+        std::fs::File::create(&archive).unwrap(),
+        Compression::default(),
+    );
+    encoder
+        .write_all(
+            lines(&[entry("eve", "old"), entry("bob", "b")]).as_bytes(),
+        )
+        .unwrap();
+    encoder.finish().unwrap();
+    let previous = lines(&[entry("eve", "x"), entry("bob", "y")]);
+    let session = lines(&[
+        LogEntry::marker(LogEvent::Start),
+        entry("eve", "z"),
+        entry("bob", "w"),
+    ]);
+    std::fs::write(&path, previous.clone() + &session).unwrap();
+
+    let found = redact::preview(&path, &redaction(RedactMode::Delete))
+        .unwrap()
+        .into_iter()
+        .map(|it| (it.path, it.entries))
+        .collect::<Vec<_>>();
+    assert_eq!(found, [(archive.clone(), 1), (path.clone(), 2)]);
+    // nothing changed
+    assert_eq!(read(&path).len(), 5);
+
+    let (found, mark) = redact::apply(
+        &path,
+        &redaction(RedactMode::Redact),
+        previous.len() as u64,
+    )
+    .unwrap();
+    assert_eq!(found.len(), 2);
+    let redacted = read(&path);
+    assert_eq!(
+        redacted,
+        [
+            (None, REDACTED.to_owned()),
+            (Some("bob".to_owned()), "y".to_owned()),
+            (None, String::new()),
+            (None, REDACTED.to_owned()),
+            (Some("bob".to_owned()), "w".to_owned()),
+        ]
+    );
+    // still points at the start marker
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(log[mark as usize..].starts_with(r#"{"msg":"""#));
+    assert_eq!(log[mark as usize..].lines().count(), 3);
+
+    let (found, mark) =
+        redact::apply(&path, &redaction(RedactMode::Delete), mark)
+            .unwrap();
+    // the archive was redacted before, its entry has no name anymore
+    assert!(found.is_empty());
+    assert_eq!(read(&path).len(), 5);
+    assert!(redact::preview(&path, &redaction(RedactMode::Delete))
+        .unwrap()
+        .is_empty());
+    assert!(mark > 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn writer_applies_redaction_and_keeps_writing() {
+    let dir = temp_dir("redact-writer");
+    let path = dir.join("log.jsonl");
+    std::fs::write(&path, lines(&[entry("eve", "before")])).unwrap();
+    let (log_tx, log_rx) = channel::bounded(16);
+    let (redact_tx, redact_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (stop_token, fut) = log::run_writer(
+        path.clone(),
+        log_rx,
+        redact_rx,
+        LogConfig::default(),
+    );
+    let handle = tokio::spawn(fut);
+
+    log_tx.send(entry("eve", "queued")).unwrap();
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+    redact_tx
+        .send((redaction(RedactMode::Delete), done_tx))
+        .unwrap();
+    let found = done_rx.await.unwrap().unwrap();
+    assert_eq!(found[0].entries, 2);
+    log_tx.send(entry("bob", "after")).unwrap();
+    stop_token.cancel();
+    let session_start = handle.await.unwrap().unwrap();
+
+    assert_eq!(
+        read(&path),
+        [
+            (None, String::new()),
+            (Some("bob".to_owned()), "after".to_owned()),
+            (None, String::new()),
+        ]
+    );
+    assert_eq!(session_start, 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn counts_unreadable_lines() {
+    let dir = temp_dir("redact-unreadable");
+    let path = dir.join("log.jsonl");
+    let text =
+        lines(&[entry("bob", "b")]) + "{\"msg\":\"eve's, cut off\n\n";
+    std::fs::write(&path, &text).unwrap();
+
+    let found =
+        redact::preview(&path, &redaction(RedactMode::Delete)).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!((found[0].entries, found[0].unreadable), (0, 1));
+
+    let (found, _) =
+        redact::apply(&path, &redaction(RedactMode::Delete), 0).unwrap();
+    assert_eq!(found[0].unreadable, 1);
+    // left as is
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,104 @@
+use blooming_light_core::{
+    log::{
+        report::{self, SessionReport},
+        LogEntry, LogEvent,
+    },
+    message::{Message, MessageKind},
+};
+use chrono::{Duration, TimeZone, Utc};
+
+fn at(secs: i64, msg: Message, event: LogEvent) -> LogEntry {
+    let mut entry = LogEntry::new(msg, event);
+    entry.ts = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap()
+        + Duration::seconds(secs);
+    entry
+}
+
+fn from(username: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat("hi")
+    }
+}
+
+#[test]
+fn summarizes_session() {
+    let entries = [
+        at(0, Message::chat(""), LogEvent::Start),
+        at(10, from("a"), LogEvent::Receive),
+        at(20, from("b"), LogEvent::Receive),
+        at(30, from("a"), LogEvent::Receive),
+        at(40, from("a"), LogEvent::Forward),
+        at(50, from("b"), LogEvent::Delete),
+        at(
+            130,
+            Message::test(MessageKind::SuperChat),
+            LogEvent::Receive,
+        ),
+        at(
+            140,
+            Message::test(MessageKind::SuperChat),
+            LogEvent::Receive,
+        ),
+        at(150, from("c"), LogEvent::Purge),
+        at(160, from("c"), LogEvent::Overflow),
+        at(200, Message::chat(""), LogEvent::End),
+    ];
+    let report = SessionReport::new(&entries);
+
+    assert_eq!(report.start, Some(entries[0].ts));
+    assert_eq!(report.end, Some(entries[10].ts));
+    assert_eq!(report.received, 5);
+    assert_eq!(report.forwarded, 1);
+    assert_eq!(report.deleted, 2);
+    assert_eq!(report.overflowed, 1);
+    assert_eq!(report.superchats, 2);
+    assert_eq!(report.superchat_total, 60.0);
+    assert_eq!(
+        report.top_chatters,
+        [
+            ("Blooming Light".to_owned(), 2),
+            ("a".to_owned(), 2),
+            ("b".to_owned(), 1),
+        ]
+    );
+    assert_eq!(report.bucket, Duration::minutes(1));
+    assert_eq!(
+        report.volume.iter().map(|it| it.1).collect::<Vec<_>>(),
+        [3, 0, 2]
+    );
+
+    let md = report.to_markdown();
+    assert!(md.contains("| Received | 5 |"));
+    assert!(md.contains("| SuperChat total | 60.00 |"));
+    assert!(md.contains("20:02      2 ####"));
+    assert_eq!(report.revenue.total().paid, 60.0);
+    assert!(md.contains("| Blooming Light | 0 | 0 | 60.00 |"));
+}
+
+#[test]
+fn widens_buckets_of_long_sessions() {
+    let entries = (0..=180)
+        .map(|min| at(min * 60, from("a"), LogEvent::Receive))
+        .collect::<Vec<_>>();
+    let report = SessionReport::new(&entries);
+    assert_eq!(report.bucket, Duration::minutes(4));
+    assert!(report.volume.len() <= 60);
+    assert_eq!(
+        report.volume.iter().map(|it| it.1).sum::<usize>(),
+        entries.len()
+    );
+}
+
+#[test]
+fn activity_stays_per_minute() {
+    let mut entries = (0..=180)
+        .map(|min| at(min * 60 + 30, from("a"), LogEvent::Receive))
+        .collect::<Vec<_>>();
+    entries.push(at(45, from("a"), LogEvent::Forward));
+    entries.push(at(50, from("b"), LogEvent::Receive));
+    let activity = report::activity(&entries);
+    assert_eq!(activity.len(), 181);
+    assert_eq!(activity[0], (entries[0].ts - Duration::seconds(30), 2));
+    assert!(activity[1..].iter().all(|it| it.1 == 1));
+}
@@ -0,0 +1,24 @@
+use blooming_light_core::{mention::Mentions, message::Message};
+
+#[test]
+fn matches_whole_names() {
+    let mentions = Mentions {
+        names: vec![
+            " ".to_owned(),
+            "@Beryl".to_owned(),
+            "小明".to_owned(),
+        ],
+        fast_track_secs: Some(2.0),
+    };
+    let matches = |text| mentions.matches(&Message::chat(text));
+    assert_eq!(matches("hi @beryl!"), Some("Beryl"));
+    assert_eq!(matches("BERYL, look"), Some("Beryl"));
+    assert_eq!(matches("berylsoft"), None);
+    assert_eq!(matches("你好小明"), Some("小明"));
+
+    assert_eq!(
+        mentions.delay_secs(&Message::chat("beryl"), Some(10.0)),
+        Some(2.0)
+    );
+    assert_eq!(mentions.delay_secs(&Message::chat("hi"), None), None);
+}
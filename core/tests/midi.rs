@@ -0,0 +1,92 @@
+use blooming_light_core::midi::{
+    MidiAction, MidiBindings, MidiControl, MidiInput, MidiParser,
+};
+
+fn parse(bytes: &[u8]) -> Vec<MidiInput> {
+    let mut parser = MidiParser::default();
+    bytes.iter().filter_map(|&it| parser.push(it)).collect()
+}
+
+fn note(channel: u8, note: u8) -> MidiInput {
+    MidiInput {
+        channel,
+        control: MidiControl::Note(note),
+    }
+}
+
+#[test]
+fn note_ons_are_presses() {
+    // running status, a zero velocity note on is a note off
+    assert_eq!(
+        parse(&[0x90, 36, 100, 36, 0, 0xf8, 38, 90, 0x81, 36, 0]),
+        [note(1, 36), note(1, 38)]
+    );
+    assert_eq!(parse(&[0x99, 40, 1]), [note(10, 40)]);
+}
+
+#[test]
+fn control_changes_press_on_the_way_up() {
+    let cc = MidiInput {
+        channel: 1,
+        control: MidiControl::Cc(20),
+    };
+    assert_eq!(parse(&[0xb0, 20, 127, 20, 0, 20, 127]), [cc, cc]);
+    // a knob turned up only presses once
+    assert_eq!(parse(&[0xb0, 20, 10, 20, 70, 20, 90, 20, 127]), [cc]);
+}
+
+#[test]
+fn skips_other_messages() {
+    assert!(parse(&[0xc0, 5, 0xf0, 1, 2, 3, 0xf7, 36, 100]).is_empty());
+}
+
+#[test]
+fn controls_run_one_action() {
+    let mut bindings = MidiBindings::default();
+    bindings.set(MidiAction::TogglePause, Some(note(1, 36)));
+    bindings.set(MidiAction::DeleteNext, Some(note(1, 36)));
+    assert_eq!(bindings.get(MidiAction::TogglePause), None);
+    assert_eq!(
+        bindings.action(note(1, 36)),
+        Some(MidiAction::DeleteNext)
+    );
+
+    bindings.set(MidiAction::DeleteNext, None);
+    assert_eq!(bindings.action(note(1, 36)), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn listener_closes_port_on_drop() {
+    use std::{
+        fs::OpenOptions,
+        io::{ErrorKind, Write},
+        time::{Duration, Instant},
+    };
+
+    use blooming_light_core::{midi::MidiListener, Notifier};
+    use nix::{sys::stat::Mode, unistd::mkfifo};
+
+    let path = std::env::temp_dir()
+        .join(format!("blooming-light-midi-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    mkfifo(&path, Mode::S_IRWXU).unwrap();
+    let listener =
+        MidiListener::open(&path, Notifier::new(|| {})).unwrap();
+    let mut port = OpenOptions::new().write(true).open(&path).unwrap();
+    port.write_all(&[0x90, 60, 100]).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut inputs = vec![];
+    while inputs.is_empty() && Instant::now() < deadline {
+        inputs = listener.pull();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(inputs, [note(1, 60)]);
+
+    drop(listener);
+    // nothing reads the port anymore
+    let err = port.write_all(&[0x90]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,28 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use blooming_light_core::network::operator::OperatorLogin;
+
+#[test]
+fn takes_basic_credentials() {
+    let login = OperatorLogin {
+        username: "mod".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+    let basic =
+        |it: &str| format!("Basic {}", BASE64_STANDARD.encode(it));
+    assert!(login.authorized(Some(&basic("mod:hunter2"))));
+    assert!(!login.authorized(Some(&basic("mod:hunter3"))));
+    assert!(!login.authorized(Some(&basic("admin:hunter2"))));
+    assert!(!login.authorized(Some("Bearer hunter2")));
+    assert!(!login.authorized(None));
+}
+
+#[test]
+fn is_off_without_a_password() {
+    let login = OperatorLogin {
+        username: "mod".to_owned(),
+        password: String::new(),
+    };
+    assert!(!login.is_on());
+    let basic = format!("Basic {}", BASE64_STANDARD.encode("mod:"));
+    assert!(!login.authorized(Some(&basic)));
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use blooming_light_core::{
+    release::OverlayFrame,
+    sim::Simulation,
+    text::{UrlAction, UrlFilter},
+};
+
+fn texts(frames: &[OverlayFrame]) -> Vec<String> {
+    frames
+        .iter()
+        .map(|it| match it {
+            OverlayFrame::Message(msg) => msg.text.clone(),
+            OverlayFrame::Combo(combo) => {
+                format!("{} x{}", combo.text, combo.count)
+            }
+        })
+        .collect()
+}
+
+fn log(sim: &Simulation) -> Vec<(&str, bool)> {
+    sim.log
+        .iter()
+        .map(|it| (it.msg.as_str(), it.is_delete))
+        .collect()
+}
+
+#[test]
+fn forwards_after_delay() {
+    let mut sim = Simulation::new(10.0);
+    sim.push("a");
+    sim.step();
+    assert!(sim.broadcast.is_empty());
+
+    sim.advance(9.9);
+    assert!(sim.broadcast.is_empty());
+
+    sim.advance(0.1);
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+    assert_eq!(log(&sim), [("a", false)]);
+}
+
+#[test]
+fn keeps_arrival_order() {
+    let mut sim = Simulation::new(1.0);
+    sim.push("a");
+    sim.step();
+    sim.advance(0.5);
+    sim.push("b");
+    sim.push("c");
+    sim.step();
+
+    sim.advance(0.5);
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+    sim.advance(0.5);
+    assert_eq!(texts(&sim.broadcast), ["a", "b", "c"]);
+}
+
+#[test]
+fn pause_holds_messages_and_delays_from_resume() {
+    let mut sim = Simulation::new(2.0);
+    sim.config.paused = true;
+    sim.push("a");
+    sim.advance(5.0);
+    assert!(sim.broadcast.is_empty());
+    assert_eq!(sim.queue.waiting_len(), 1);
+    assert!(sim.queue.is_empty());
+
+    sim.config.paused = false;
+    sim.step();
+    assert_eq!(sim.queue.waiting_len(), 0);
+    assert_eq!(sim.queue.len(), 1);
+
+    sim.advance(1.0);
+    assert!(sim.broadcast.is_empty());
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+}
+
+#[test]
+fn pause_freezes_release() {
+    let mut sim = Simulation::new(1.0);
+    sim.push("a");
+    sim.step();
+    sim.config.paused = true;
+    sim.advance(3.0);
+    assert!(sim.broadcast.is_empty());
+
+    sim.config.paused = false;
+    sim.step();
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+}
+
+#[test]
+fn deleted_messages_are_logged_not_broadcast() {
+    let mut sim = Simulation::new(1.0);
+    sim.push("a");
+    sim.push("b");
+    sim.step();
+
+    assert!(sim.delete("a"));
+    assert!(!sim.delete("missing"));
+    sim.step();
+    assert_eq!(log(&sim), [("a", true)]);
+
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["b"]);
+    assert_eq!(log(&sim), [("a", true), ("b", false)]);
+}
+
+#[test]
+fn slow_mode_spaces_out_backlog() {
+    let mut sim = Simulation::new(1.0);
+    sim.queue.set_min_spacing(std::time::Duration::from_secs(2));
+    sim.config.paused = true;
+    sim.push("a");
+    sim.push("b");
+    sim.push("c");
+    sim.step();
+
+    sim.config.paused = false;
+    sim.step();
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["a"]);
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["a", "b"]);
+    sim.advance(2.0);
+    assert_eq!(texts(&sim.broadcast), ["a", "b", "c"]);
+}
+
+#[test]
+fn source_delay_overrides_global_delay() {
+    let mut sim = Simulation::new(10.0);
+    let now = sim.queue.now();
+    sim.queue.push_with_delay("fast".into(), now, Some(0.0));
+    sim.queue.push_with_delay("slow".into(), now, Some(30.0));
+    sim.push("default");
+    sim.step();
+    assert_eq!(texts(&sim.broadcast), ["fast"]);
+
+    sim.advance(10.0);
+    assert_eq!(texts(&sim.broadcast), ["fast", "default"]);
+    sim.advance(20.0);
+    assert_eq!(texts(&sim.broadcast), ["fast", "default", "slow"]);
+}
+
+#[test]
+fn next_release_is_the_nearest_deadline() {
+    let mut sim = Simulation::new(2.0);
+    assert_eq!(sim.queue.next_release_in(), None);
+
+    sim.push("a");
+    sim.step();
+    sim.advance(0.5);
+    sim.push("b");
+    sim.step();
+    assert_eq!(
+        sim.queue.next_release_in(),
+        Some(Duration::from_millis(1500))
+    );
+
+    assert!(sim.delete("a"));
+    assert_eq!(sim.queue.next_release_in(), Some(Duration::from_secs(2)));
+
+    sim.advance(2.0);
+    assert_eq!(sim.queue.next_release_in(), None);
+}
+
+#[test]
+fn releases_through_filters_and_combos() {
+    let mut sim = Simulation::new(1.0);
+    sim.config.url_filter = UrlFilter {
+        action: UrlAction::Replace,
+        ..Default::default()
+    };
+    sim.config.combo_window = Duration::from_secs(10);
+    sim.push("see https://a.test");
+    sim.push("gg");
+    sim.push("gg");
+    sim.step();
+
+    sim.advance(1.0);
+    assert_eq!(texts(&sim.broadcast), ["see [link]", "gg", "gg x2"]);
+    // logged as queued, with the links found
+    assert_eq!(sim.log[0].msg, "see https://a.test");
+    assert_eq!(sim.log[0].urls[0].url, "https://a.test");
+    assert_eq!(sim.log.len(), 3);
+}
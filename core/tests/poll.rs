@@ -0,0 +1,48 @@
+use blooming_light_core::{
+    message::{Message, MessageKind},
+    poll::{Poll, PollResult},
+};
+
+fn from(username: &str, text: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    }
+}
+
+#[test]
+fn counts_one_vote_per_user() {
+    let mut poll = Poll::new(
+        "Next game?",
+        vec!["Tetris".to_owned(), "Go".to_owned()],
+    );
+    assert_eq!(poll.option_of(" 2 "), Some(1));
+    assert_eq!(poll.option_of("3"), None);
+    assert_eq!(poll.option_of("0"), None);
+    assert_eq!(poll.option_of("tetris"), Some(0));
+    assert_eq!(poll.option_of("tetris please"), None);
+
+    assert!(poll.vote(&from("a", "1")));
+    // counted as a vote, but not again
+    assert!(poll.vote(&from("a", "2")));
+    assert!(poll.vote(&from("b", "go")));
+    assert!(!poll.vote(&from("c", "hi")));
+    assert!(!poll.vote(&Message {
+        username: Some("d".to_owned()),
+        ..Message::test(MessageKind::Gift)
+    }));
+    assert_eq!(poll.counts(), [1, 1]);
+
+    let frame = poll.close();
+    assert!(!poll.is_open());
+    assert!(!poll.vote(&from("e", "1")));
+    assert_eq!(frame.kind, "poll");
+    assert_eq!(frame.total, 2);
+    assert_eq!(
+        frame.results[0],
+        PollResult {
+            option: "Tetris".to_owned(),
+            votes: 1,
+        }
+    );
+}
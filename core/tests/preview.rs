@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    combo::ComboUpdate,
+    message::{Message, MessageKind, Paid},
+    preview::{OverlayPreview, PreviewFrame},
+};
+
+const FRAME: PreviewFrame = PreviewFrame {
+    width: 100.0,
+    height: 30.0,
+    row_height: 10.0,
+    speed: 0.5,
+    spacing: 5.0,
+};
+
+fn labels(preview: &OverlayPreview) -> Vec<Vec<String>> {
+    preview
+        .rows()
+        .map(|row| row.iter().map(|it| it.label.clone()).collect())
+        .collect()
+}
+
+#[test]
+fn fills_rows_and_scrolls() {
+    let mut preview = OverlayPreview::default();
+    let now = Instant::now();
+    for text in ["a", "b", "c", "d"] {
+        preview.push(&Message::chat(text), 20.0, now);
+    }
+    preview.step(0.0, &FRAME, now);
+    assert_eq!(labels(&preview), [["a"], ["b"], ["c"]]);
+    assert_eq!(preview.pending_len(), 1);
+
+    // not enough room behind `a` yet
+    preview.step(0.1, &FRAME, now);
+    assert_eq!(preview.pending_len(), 1);
+    preview.step(0.5, &FRAME, now);
+    preview.step(0.0, &FRAME, now);
+    assert_eq!(labels(&preview), [vec!["a", "d"], vec!["b"], vec!["c"]]);
+
+    // everything scrolls out eventually
+    for _ in 0..10 {
+        preview.step(0.5, &FRAME, now);
+    }
+    assert!(preview.is_idle());
+}
+
+#[test]
+fn labels_like_the_overlay() {
+    let gift = Message {
+        kind: MessageKind::Gift,
+        username: Some("alice".to_owned()),
+        ..Message::chat("flower x2")
+    };
+    assert_eq!(OverlayPreview::label(&gift), "alice: flower x2");
+    let chat = Message {
+        username: Some("alice".to_owned()),
+        ..Message::chat("hi")
+    };
+    assert_eq!(OverlayPreview::label(&chat), "hi");
+}
+
+#[test]
+fn combos_update_in_place() {
+    let mut preview = OverlayPreview::default();
+    let now = Instant::now();
+    preview.push(&Message::chat("chant"), 20.0, now);
+    preview.step(0.0, &FRAME, now);
+    preview.push_combo(&ComboUpdate::new("chant", 2), 30.0);
+    assert_eq!(labels(&preview), [vec!["chant ×2"], vec![], vec![]]);
+
+    preview.push_combo(&ComboUpdate::new("other", 2), 30.0);
+    assert_eq!(preview.pending_len(), 1);
+}
+
+#[test]
+fn pins_paid_messages_at_the_bottom() {
+    let mut preview = OverlayPreview::default();
+    let now = Instant::now();
+    let paid = |text, secs| Message {
+        kind: MessageKind::SuperChat,
+        paid: Some(Paid {
+            amount: 30.0,
+            pin_secs: Some(secs),
+        }),
+        ..Message::chat(text)
+    };
+    preview.push(&paid("long", 60.0), 20.0, now);
+    preview.push(&paid("short", 10.0), 20.0, now);
+    preview.push(&Message::chat("a"), 20.0, now);
+    preview.step(0.0, &FRAME, now);
+
+    let pinned = preview
+        .pinned(&FRAME)
+        .map(|it| it.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(pinned, ["long", "short"]);
+    // one row left for scrolling
+    assert_eq!(labels(&preview), [["a"]]);
+
+    preview.step(0.0, &FRAME, now + Duration::from_secs(30));
+    assert_eq!(preview.pinned(&FRAME).count(), 1);
+}
@@ -0,0 +1,73 @@
+use blooming_light_core::network::proxy::{self, ProxyConfig, ProxyKind};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Accepts one CONNECT request, records it, then echoes the tunnel.
+async fn fake_http_proxy(
+    reply: &'static str,
+) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        while !request.ends_with(b"\r\n\r\n") {
+            request.push(stream.read_u8().await.unwrap());
+        }
+        stream.write_all(reply.as_bytes()).await.unwrap();
+
+        let mut buf = [0; 4];
+        if stream.read_exact(&mut buf).await.is_ok() {
+            stream.write_all(&buf).await.unwrap();
+        }
+        String::from_utf8(request).unwrap()
+    });
+    (addr, handle)
+}
+
+#[tokio::test]
+async fn tunnels_through_http_proxy() {
+    let (addr, handle) =
+        fake_http_proxy("HTTP/1.1 200 Connection established\r\n\r\n")
+            .await;
+    let config = ProxyConfig {
+        kind: ProxyKind::Http,
+        addr,
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+    };
+
+    let mut stream =
+        proxy::connect(&config, "example.com", 8082).await.unwrap();
+    stream.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    let request = handle.await.unwrap();
+    assert!(request.starts_with("CONNECT example.com:8082 HTTP/1.1\r\n"));
+    // base64("user:pass")
+    assert!(
+        request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n")
+    );
+}
+
+#[tokio::test]
+async fn reports_refused_connect() {
+    let (addr, _handle) = fake_http_proxy(
+        "HTTP/1.1 407 Proxy Authentication Required\r\n\r\n",
+    )
+    .await;
+    let config = ProxyConfig {
+        kind: ProxyKind::Http,
+        addr,
+        ..Default::default()
+    };
+
+    let err = proxy::connect(&config, "example.com", 8082)
+        .await
+        .unwrap_err();
+    assert!(format!("{err}").contains("407"));
+}
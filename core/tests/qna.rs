@@ -0,0 +1,40 @@
+use blooming_light_core::{
+    message::{Message, MessageKind},
+    qna::{QnaConfig, Questions},
+};
+use chrono::Utc;
+
+#[test]
+fn routes_questions() {
+    let mut config = QnaConfig {
+        enabled: false,
+        prefix: "Q:".to_owned(),
+    };
+    assert!(!config.is_question(&Message::chat("why?")));
+    config.enabled = true;
+    assert!(config.is_question(&Message::chat("why? ")));
+    assert!(config.is_question(&Message::chat("为什么？")));
+    assert!(config.is_question(&Message::chat("q: favourite food")));
+    assert!(!config.is_question(&Message::chat("hi")));
+    assert!(!config.is_question(&Message::chat("草")));
+    assert!(!config.is_question(&Message {
+        text: "why?".to_owned(),
+        ..Message::test(MessageKind::SuperChat)
+    }));
+}
+
+#[test]
+fn holds_questions_in_order() {
+    let mut questions = Questions::default();
+    for text in ["a?", "b?", "c?"] {
+        assert_eq!(questions.push(Message::chat(text), Utc::now()), None);
+    }
+    assert_eq!(questions.take(1), Some(Message::chat("b?")));
+    assert_eq!(questions.take(5), None);
+    assert_eq!(
+        questions.iter().map(|it| &*it.1.text).collect::<Vec<_>>(),
+        ["a?", "c?"]
+    );
+    assert_eq!(questions.take_all().len(), 2);
+    assert!(questions.is_empty());
+}
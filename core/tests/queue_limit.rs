@@ -0,0 +1,67 @@
+use std::{sync::Arc, time::Duration};
+
+use blooming_light_core::{
+    clock::ManualClock,
+    message::Message,
+    queue::{MessageQueue, OverflowPolicy, QueueLimit},
+};
+
+fn queue(policy: OverflowPolicy) -> (ManualClock, MessageQueue) {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.set_limit(QueueLimit { max_len: 2, policy });
+    (clock, queue)
+}
+
+fn texts(queue: &mut MessageQueue) -> Vec<String> {
+    let mut texts: Vec<_> =
+        queue.iter_mut().map(|it| it.msg.text.clone()).collect();
+    texts.reverse();
+    texts
+}
+
+#[test]
+fn drop_oldest_makes_room() {
+    let (_, mut queue) = queue(OverflowPolicy::DropOldest);
+    queue.push("a".into());
+    queue.push("b".into());
+    queue.update(false, 10.0);
+    assert!(queue.is_full());
+
+    queue.push("c".into());
+    queue.update(false, 10.0);
+    assert_eq!(texts(&mut queue), ["b", "c"]);
+    assert_eq!(queue.take_overflowed(), [Message::chat("a")]);
+    assert_eq!(queue.overflow_count(), 1);
+    assert!(queue.take_overflowed().is_empty());
+}
+
+#[test]
+fn drop_newest_keeps_queue() {
+    let (_, mut queue) = queue(OverflowPolicy::DropNewest);
+    queue.push("a".into());
+    queue.push("b".into());
+    queue.push("c".into());
+    queue.update(false, 10.0);
+    assert_eq!(texts(&mut queue), ["a", "b"]);
+    assert_eq!(queue.take_overflowed(), [Message::chat("c")]);
+}
+
+#[test]
+fn pause_sources_only_reports_full() {
+    let (clock, mut queue) = queue(OverflowPolicy::PauseSources);
+    assert_eq!(queue.source_room(), 2);
+    queue.push("a".into());
+    queue.push("b".into());
+    assert!(queue.pauses_sources());
+    assert_eq!(queue.source_room(), 0);
+    queue.push("c".into());
+    assert!(queue.take_overflowed().is_empty());
+    assert_eq!(queue.waiting_len(), 3);
+
+    queue.update(false, 1.0);
+    clock.advance(Duration::from_secs(1));
+    queue.update(false, 1.0);
+    assert!(!queue.pauses_sources());
+    assert_eq!(queue.source_room(), 2);
+}
@@ -0,0 +1,70 @@
+use std::{sync::Arc, time::Duration};
+
+use blooming_light_core::{
+    clock::{Clock, ManualClock},
+    message::Message,
+    queue::MessageQueue,
+};
+
+fn texts(
+    released: &[blooming_light_core::queue::PendingMessage],
+) -> Vec<&str> {
+    released.iter().map(|it| it.msg.text.as_str()).collect()
+}
+
+#[test]
+fn releases_scheduled_at_their_time() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    // slow mode doesn't hold scheduled messages back
+    queue.set_min_spacing(Duration::from_secs(60));
+    let now = clock.now_utc();
+    queue.schedule("later".into(), now + Duration::from_secs(20));
+    queue.schedule("sooner".into(), now + Duration::from_secs(10));
+    queue.schedule("deleted".into(), now + Duration::from_secs(15));
+    assert_eq!(queue.scheduled_len(), 3);
+    assert_eq!(
+        queue
+            .scheduled_mut()
+            .map(|it| it.msg.text.clone())
+            .collect::<Vec<_>>(),
+        ["sooner", "deleted", "later"]
+    );
+    queue.scheduled_mut().nth(1).unwrap().delete = true;
+    assert_eq!(queue.take_deleted(), [Message::chat("deleted")]);
+    assert_eq!(queue.next_release_in(), Some(Duration::from_secs(10)));
+
+    clock.advance(Duration::from_secs(12));
+    // held back while paused
+    assert!(queue.update(true, 5.0).is_empty());
+    queue.push("chat".into());
+    assert_eq!(texts(&queue.update(false, 0.0)), ["sooner", "chat"]);
+    assert_eq!(queue.next_release_in(), Some(Duration::from_secs(8)));
+
+    clock.advance(Duration::from_secs(8));
+    assert_eq!(texts(&queue.update(false, 0.0)), ["later"]);
+    assert_eq!(queue.scheduled_len(), 0);
+    assert_eq!(queue.next_release_in(), None);
+}
+
+#[test]
+fn snapshot_keeps_scheduled() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    let now = clock.now_utc();
+    queue.schedule("soon".into(), now + Duration::from_secs(5));
+    queue.schedule("later".into(), now + Duration::from_secs(60));
+    let snapshot = queue.snapshot();
+
+    // back 10s later, the one missed waits for review like the rest
+    let clock = ManualClock::starting_at(
+        snapshot.saved_at + Duration::from_secs(10),
+    );
+    let mut restored = MessageQueue::with_clock(Arc::new(clock.clone()));
+    restored.restore(snapshot);
+    assert_eq!(restored.scheduled_len(), 1);
+    assert_eq!(restored.waiting_len(), 1);
+    assert_eq!(texts(&restored.update(false, 0.0)), ["soon"]);
+    clock.advance(Duration::from_secs(50));
+    assert_eq!(texts(&restored.update(false, 0.0)), ["later"]);
+}
@@ -0,0 +1,159 @@
+use std::{sync::Arc, time::Duration};
+
+use blooming_light_core::{
+    clock::{Clock, ManualClock},
+    message::Message,
+    queue::{MessageQueue, QueueSnapshot},
+};
+
+#[test]
+fn snapshot_round_trip_keeps_deadlines() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.push("a".into());
+    queue.push("deleted".into());
+    queue.update(false, 10.0);
+    queue.iter_mut().next().unwrap().delete = true;
+    clock.advance(Duration::from_secs(4));
+    queue.push("b".into());
+
+    let snapshot = queue.snapshot();
+    assert_eq!(snapshot.len(), 2);
+
+    let path = std::env::temp_dir().join(format!(
+        "blooming-light-queue-{}.json",
+        std::process::id()
+    ));
+    snapshot.save(&path).unwrap();
+    let loaded = QueueSnapshot::load(&path).unwrap().unwrap();
+    assert_eq!(loaded, snapshot);
+
+    // restart 2s later, "a" is due 4s after that
+    let clock = ManualClock::starting_at(
+        snapshot.saved_at + Duration::from_secs(2),
+    );
+    let mut restored = MessageQueue::with_clock(Arc::new(clock.clone()));
+    restored.restore(loaded);
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored.waiting_len(), 1);
+
+    clock.advance(Duration::from_secs(3));
+    assert!(restored.update(false, 10.0).is_empty());
+    clock.advance(Duration::from_secs(1));
+    let released = restored.update(false, 10.0);
+    let texts: Vec<_> = released.iter().map(|it| &it.msg).collect();
+    assert_eq!(texts, [&Message::chat("a")]);
+
+    // empty snapshots remove the file
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(restored.update(false, 10.0).len(), 1);
+    restored.snapshot().save(&path).unwrap();
+    assert_eq!(QueueSnapshot::load(&path).unwrap(), None);
+}
+
+#[test]
+fn deadlines_passed_while_down_wait_again() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.push("a".into());
+    queue.update(false, 10.0);
+    let snapshot = queue.snapshot();
+
+    let clock = ManualClock::starting_at(
+        snapshot.saved_at + Duration::from_secs(60),
+    );
+    let mut restored = MessageQueue::with_clock(Arc::new(clock.clone()));
+    restored.restore(snapshot);
+    assert_eq!(restored.len(), 0);
+    assert_eq!(restored.waiting_len(), 1);
+
+    // not released on the first update but after a full delay
+    assert!(restored.update(false, 10.0).is_empty());
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(restored.update(false, 10.0).len(), 1);
+}
+
+#[test]
+fn delay_changes_keep_existing_deadlines() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.push("a".into());
+    queue.update(false, 10.0);
+    let pending = queue.iter_mut().next().unwrap();
+    assert_eq!(pending.progress(clock.now_utc()), 0.0);
+
+    clock.advance(Duration::from_secs(5));
+    queue.push("b".into());
+    assert!(queue.update(false, 1.0).is_empty());
+    let pending = queue.iter_mut().last().unwrap();
+    assert_eq!(pending.progress(clock.now_utc()), 0.5);
+
+    clock.advance(Duration::from_secs(1));
+    let released = queue.update(false, 1.0);
+    let texts: Vec<_> = released.iter().map(|it| &it.msg).collect();
+    assert_eq!(texts, [&Message::chat("b")]);
+
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(queue.update(false, 60.0).len(), 1);
+}
+
+#[test]
+fn purge_empties_queue_and_waiting() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.push("a".into());
+    queue.update(false, 10.0);
+    queue.push("b".into());
+
+    assert_eq!(queue.purge(), [Message::chat("a"), Message::chat("b")]);
+    assert!(queue.is_empty());
+    assert_eq!(queue.waiting_len(), 0);
+    assert!(queue.snapshot().is_empty());
+}
+
+#[test]
+fn pending_entries_carry_their_deadlines() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    queue.push("a".into());
+    queue.update(false, 10.0);
+    let entry = queue.iter_mut().next().unwrap().snapshot_entry();
+    assert_eq!(entry.arrive_at, Some(clock.now_utc()));
+    assert_eq!(
+        entry.send_at,
+        Some(clock.now_utc() + Duration::from_secs(10))
+    );
+    assert_eq!(queue.snapshot().entries, [entry]);
+}
+
+#[test]
+fn deleted_messages_go_back_in_order() {
+    let clock = ManualClock::new();
+    let mut queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    for text in ["a", "b", "c"] {
+        queue.push(text.into());
+        queue.update(false, 10.0);
+        clock.advance(Duration::from_secs(1));
+    }
+    // newest first
+    queue.iter_mut().nth(1).unwrap().delete = true;
+    let deleted = queue.take_deleted_entries();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].msg, Message::chat("b"));
+
+    let snapshot = QueueSnapshot {
+        saved_at: clock.now_utc(),
+        entries: deleted,
+    };
+    queue.restore(snapshot);
+    let texts = queue
+        .iter_mut()
+        .map(|it| it.msg.text.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["c", "b", "a"]);
+
+    let removed = queue.remove(&Message::chat("a")).unwrap();
+    assert!(removed.send_at.is_some());
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.remove(&Message::chat("a")), None);
+}
@@ -0,0 +1,33 @@
+use blooming_light_core::{message::Message, raffle::Raffle};
+use chrono::{TimeDelta, TimeZone, Utc};
+
+fn from(username: &str, text: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat(text)
+    }
+}
+
+#[test]
+fn draws_each_entrant_once() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+    let mut raffle = Raffle::new("!Join", start + TimeDelta::minutes(1));
+    assert!(raffle.enter(&from("a", "!join"), start));
+    assert!(raffle.enter(&from("a", " !JOIN "), start));
+    assert!(!raffle.enter(&from("b", "!join me"), start));
+    assert!(!raffle.enter(&Message::chat("!join"), start));
+    assert!(raffle.enter(&from("b", "!join"), start));
+    assert!(raffle.enter(&from("c", "!join"), start));
+    raffle.close(start + TimeDelta::seconds(30));
+    assert!(!raffle
+        .enter(&from("d", "!join"), start + TimeDelta::seconds(40)));
+    assert_eq!(raffle.entrants(), ["a", "b", "c"]);
+
+    let mut drawn = vec![];
+    while let Some(winner) = raffle.draw(|it| it == "b") {
+        drawn.push(winner.to_owned());
+    }
+    drawn.sort();
+    assert_eq!(drawn, ["a", "c"]);
+    assert_eq!(raffle.winners().len(), 2);
+}
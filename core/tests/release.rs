@@ -0,0 +1,132 @@
+use std::{sync::Arc, time::Duration};
+
+use blooming_light_core::{
+    clock::ManualClock,
+    message::Message,
+    queue::{MessageQueue, SharedQueue},
+    release::{next_wake, OverlayFrame, ReleaseConfig, Releaser},
+};
+
+fn queue() -> (ManualClock, MessageQueue) {
+    let clock = ManualClock::new();
+    let queue = MessageQueue::with_clock(Arc::new(clock.clone()));
+    (clock, queue)
+}
+
+fn config(delay_secs: f64) -> ReleaseConfig {
+    ReleaseConfig {
+        delay_secs,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn releases_due_messages_as_frames() {
+    let (clock, mut queue) = queue();
+    let mut releaser = Releaser::default();
+    let config = config(1.0);
+    queue.push("a".into());
+    assert!(releaser.release(&mut queue, &config).is_empty());
+
+    clock.advance(Duration::from_secs(1));
+    let released = releaser.release(&mut queue, &config);
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].combo, 1);
+    assert_eq!(
+        released[0].frames,
+        [OverlayFrame::Message(Message::chat("a"))]
+    );
+    assert!(!released[0].sent);
+}
+
+#[test]
+fn counts_combos_across_releases() {
+    let (clock, mut queue) = queue();
+    let mut releaser = Releaser::default();
+    let config = ReleaseConfig {
+        combo_window: Duration::from_secs(5),
+        ..config(0.0)
+    };
+    for _ in 0..2 {
+        queue.push("a".into());
+        releaser.release(&mut queue, &config);
+        clock.advance(Duration::from_secs(1));
+    }
+    queue.push("a".into());
+    let released = releaser.release(&mut queue, &config);
+    assert_eq!(released[0].combo, 3);
+    assert!(matches!(released[0].frames[..], [OverlayFrame::Combo(_)]));
+}
+
+#[test]
+fn wakes_at_next_deadline_unless_paused() {
+    let (clock, mut queue) = queue();
+    let mut releaser = Releaser::default();
+    let config = config(2.0);
+    assert_eq!(next_wake(&queue, &config), None);
+
+    queue.push("a".into());
+    assert_eq!(next_wake(&queue, &config), Some(Duration::ZERO));
+    releaser.release(&mut queue, &config);
+    clock.advance(Duration::from_millis(500));
+    assert_eq!(
+        next_wake(&queue, &config),
+        Some(Duration::from_millis(1500))
+    );
+
+    let paused = ReleaseConfig {
+        paused: true,
+        ..config
+    };
+    assert_eq!(next_wake(&queue, &paused), None);
+    assert!(releaser.release(&mut queue, &paused).is_empty());
+}
+
+#[tokio::test]
+async fn changed_only_after_mutable_lock() {
+    let queue = SharedQueue::new(MessageQueue::new());
+    let _ = queue.lock().len();
+    let changed =
+        tokio::time::timeout(Duration::from_millis(10), queue.changed());
+    assert!(changed.await.is_err());
+
+    queue.lock().push("a".into());
+    queue.changed().await;
+}
+
+#[test]
+fn sends_next_while_paused() {
+    let (_clock, mut queue) = queue();
+    let mut releaser = Releaser::default();
+    let config = ReleaseConfig {
+        paused: true,
+        ..config(10.0)
+    };
+    queue.push("a".into());
+    queue.push("b".into());
+    assert!(releaser.release(&mut queue, &config).is_empty());
+
+    assert!(queue.send_next());
+    let released = releaser.release(&mut queue, &config);
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].msg, Message::chat("a"));
+    assert!(releaser.release(&mut queue, &config).is_empty());
+    assert_eq!(queue.waiting_len(), 1);
+}
+
+#[test]
+fn sends_a_given_message_now() {
+    let (_clock, mut queue) = queue();
+    let mut releaser = Releaser::default();
+    let config = config(10.0);
+    queue.push("a".into());
+    queue.push("b".into());
+    assert!(releaser.release(&mut queue, &config).is_empty());
+
+    assert!(queue.send_now(&Message::chat("b")));
+    let released = releaser.release(&mut queue, &config);
+    assert_eq!(released.len(), 1);
+    assert_eq!(released[0].msg, Message::chat("b"));
+    assert!(!queue.send_now(&Message::chat("b")));
+    assert_eq!(queue.len(), 1);
+}
@@ -0,0 +1,71 @@
+use blooming_light_core::{
+    message::{Gift, Message, MessageKind},
+    revenue::{Revenue, Takings},
+};
+
+fn gift(username: &str, count: u32, value: u64) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        gift: Some(Gift {
+            name: "rose".to_owned(),
+            count,
+            value,
+        }),
+        ..Message::test(MessageKind::Gift)
+    }
+}
+
+#[test]
+fn totals_by_user() {
+    let mut revenue = Revenue::default();
+    assert!(!revenue.record(&Message::chat("hi")));
+    assert!(revenue.record(&gift("a", 2, 200)));
+    assert!(revenue.record(&gift("b,c", 1, 100)));
+    let superchat = Message {
+        username: Some("b,c".to_owned()),
+        ..Message::test(MessageKind::SuperChat)
+    };
+    assert!(revenue.record(&superchat));
+    assert_eq!(
+        *revenue.total(),
+        Takings {
+            gifts: 3,
+            gift_value: 300,
+            paid_messages: 1,
+            paid: 30.0,
+        }
+    );
+    assert_eq!(
+        revenue
+            .by_user()
+            .into_iter()
+            .map(|it| it.0)
+            .collect::<Vec<_>>(),
+        ["b,c", "a"]
+    );
+    assert_eq!(
+        revenue.to_csv(),
+        "username,gifts,gift_value,paid_messages,paid\n\
+         \"b,c\",1,100,1,30.00\n\
+         a,2,200,0,0.00\n\
+         (total),3,300,1,30.00\n"
+    );
+
+    revenue.unrecord(&gift("a", 2, 200));
+    assert_eq!(revenue.total().gift_value, 100);
+    assert_eq!(revenue.by_user().len(), 1);
+    revenue.clear();
+    assert!(revenue.is_empty());
+}
+
+#[test]
+fn csv_keeps_formulas_as_text() {
+    let mut revenue = Revenue::default();
+    revenue.record(&gift("=HYPERLINK(\"x\")", 1, 100));
+    revenue.record(&gift("-1", 1, 100));
+    revenue.record(&gift("a-b", 1, 100));
+    let csv = revenue.to_csv();
+    assert!(csv.contains("\n\"'=HYPERLINK(\"\"x\"\")\",1,"));
+    assert!(csv.contains("\n'-1,1,"));
+    assert!(csv.contains("\na-b,1,"));
+}
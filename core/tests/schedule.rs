@@ -0,0 +1,73 @@
+use blooming_light_core::schedule::{
+    PauseSchedule, PauseWindow, ScheduleState,
+};
+use chrono::{NaiveTime, TimeDelta};
+
+fn at(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn window(start: NaiveTime, end: NaiveTime) -> PauseWindow {
+    PauseWindow {
+        start,
+        end,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pauses_inside_window() {
+    let schedule = PauseSchedule {
+        windows: vec![window(at(20, 0), at(20, 5))],
+    };
+    let state = schedule.state(at(19, 58));
+    assert!(!state.is_paused());
+    assert_eq!(state.next_change(), Some(TimeDelta::minutes(2)));
+
+    let state = schedule.state(at(20, 0));
+    assert!(state.is_paused());
+    assert_eq!(state.next_change(), Some(TimeDelta::minutes(5)));
+
+    // end is exclusive, the next start is tomorrow
+    let state = schedule.state(at(20, 5));
+    assert!(!state.is_paused());
+    assert_eq!(
+        state.next_change(),
+        Some(TimeDelta::hours(24) - TimeDelta::minutes(5))
+    );
+}
+
+#[test]
+fn windows_can_span_midnight() {
+    let schedule = PauseSchedule {
+        windows: vec![window(at(23, 50), at(0, 10))],
+    };
+    assert!(schedule.state(at(23, 55)).is_paused());
+    let state = schedule.state(at(0, 5));
+    assert!(state.is_paused());
+    assert_eq!(state.next_change(), Some(TimeDelta::minutes(5)));
+    assert!(!schedule.state(at(0, 10)).is_paused());
+}
+
+#[test]
+fn overlapping_windows_pause_until_last_ends() {
+    let schedule = PauseSchedule {
+        windows: vec![
+            window(at(20, 0), at(20, 5)),
+            window(at(20, 3), at(20, 10)),
+            PauseWindow {
+                enabled: false,
+                ..window(at(20, 0), at(21, 0))
+            },
+        ],
+    };
+    let state = schedule.state(at(20, 4));
+    assert_eq!(state.next_change(), Some(TimeDelta::minutes(6)));
+    assert!(!schedule.state(at(20, 30)).is_paused());
+}
+
+#[test]
+fn empty_schedule_is_idle() {
+    let schedule = PauseSchedule::default();
+    assert_eq!(schedule.state(at(12, 0)), ScheduleState::Idle);
+}
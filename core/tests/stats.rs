@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    queue::PendingMessage,
+    sim::Simulation,
+    stats::{LatencyStats, RateHistory, RateMark, RateMeter},
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn percentiles() {
+    let mut stats = LatencyStats::default();
+    assert_eq!(stats.percentile(0.5), None);
+
+    for it in (1..=100).rev() {
+        stats.record(ms(it));
+    }
+    assert_eq!(stats.percentile(0.5), Some(ms(50)));
+    assert_eq!(stats.percentile(0.95), Some(ms(95)));
+    assert_eq!(stats.percentile(0.0), Some(ms(1)));
+    assert_eq!(stats.percentile(1.0), Some(ms(100)));
+}
+
+#[test]
+fn keeps_latest_window() {
+    let mut stats = LatencyStats::new(2);
+    stats.record(ms(100));
+    stats.record(ms(1));
+    stats.record(ms(2));
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats.percentile(1.0), Some(ms(2)));
+}
+
+#[test]
+fn latency_includes_pause_and_delay() {
+    let mut sim = Simulation::new(1.0);
+    sim.config.paused = true;
+    sim.push("a");
+    sim.advance(2.0);
+    sim.config.paused = false;
+    sim.step();
+
+    sim.clock.advance(Duration::from_secs(1));
+    let now = sim.queue.now();
+    let released = sim.queue.update(false, sim.config.delay_secs);
+    let [PendingMessage { received_at, .. }] = released.as_slice() else {
+        panic!("expected one message");
+    };
+    assert_eq!(
+        now.saturating_duration_since(*received_at),
+        Duration::from_secs(3)
+    );
+}
+
+#[test]
+fn rate_over_trailing_window() {
+    let start = Instant::now();
+    let mut rate = RateMeter::new(Duration::from_secs(2));
+    assert_eq!(rate.rate(start), 0.0);
+
+    for it in 0..10 {
+        rate.record(start + ms(it * 100));
+    }
+    assert_eq!(rate.rate(start + ms(1000)), 5.0);
+    // the first half has left the window
+    assert_eq!(rate.rate(start + ms(2500)), 2.0);
+    assert_eq!(rate.rate(start + ms(5000)), 0.0);
+}
+
+#[test]
+fn rate_history_per_minute() {
+    let start = Instant::now();
+    let secs = Duration::from_secs;
+    let mut history = RateHistory::new(start);
+    history.record_incoming(start + secs(1));
+    history.record_incoming(start + secs(59));
+    history.record_incoming(start + secs(150));
+    history.record_forwarded(start + secs(61));
+    history.mark(start + secs(90), RateMark::Paused);
+
+    assert_eq!(history.incoming(), [2, 0, 1]);
+    // gaps are minutes without a message
+    assert_eq!(history.forwarded(), [0, 1]);
+    assert_eq!(history.marks(), [(secs(90), RateMark::Paused)]);
+}
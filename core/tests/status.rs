@@ -0,0 +1,30 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::network::{status::SourceState, SourceStatus};
+
+#[test]
+fn tracks_status_changes_and_message_age() {
+    let start = Instant::now();
+    let secs = |n| start + Duration::from_secs(n);
+
+    let mut state = SourceState::new(start);
+    assert_eq!(state.status(), &SourceStatus::Connecting);
+    assert_eq!(state.last_message_age(secs(1)), None);
+
+    state.set_status(SourceStatus::Connected, secs(2));
+    state.on_message(secs(3));
+    assert_eq!(state.elapsed(secs(5)), Duration::from_secs(3));
+    assert_eq!(
+        state.last_message_age(secs(5)),
+        Some(Duration::from_secs(2))
+    );
+
+    // repeated status keeps the uptime
+    state.set_status(SourceStatus::Connected, secs(6));
+    assert_eq!(state.elapsed(secs(7)), Duration::from_secs(5));
+
+    let err = SourceStatus::Disconnected(Some("refused".to_owned()));
+    assert_eq!(err.name(), "Error");
+    state.set_status(err, secs(8));
+    assert_eq!(state.elapsed(secs(8)), Duration::ZERO);
+}
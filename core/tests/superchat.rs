@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    message::{Message, MessageKind, Paid},
+    superchat::{ActiveSuperChats, PinDurations},
+};
+
+fn superchat(amount: f64) -> Message {
+    Message {
+        kind: MessageKind::SuperChat,
+        paid: Some(Paid {
+            amount,
+            pin_secs: None,
+        }),
+        ..Message::chat("hi")
+    }
+}
+
+#[test]
+fn pins_by_highest_tier_reached() {
+    let tiers = PinDurations::default();
+    assert_eq!(tiers.secs_for(29.9), None);
+    assert_eq!(tiers.secs_for(30.0), Some(60.0));
+    assert_eq!(tiers.secs_for(499.0), Some(300.0));
+    assert_eq!(tiers.secs_for(1e6), Some(7200.0));
+
+    let mut msg = superchat(100.0);
+    tiers.apply(&mut msg);
+    assert_eq!(msg.paid.unwrap().pin_secs, Some(300.0));
+}
+
+#[test]
+fn tiers_round_trip_through_text() {
+    let tiers = PinDurations::default();
+    assert_eq!(PinDurations::parse(&tiers.format()), Some(tiers));
+    assert_eq!(
+        PinDurations::parse(" 10=5 ,").unwrap().secs_for(10.0),
+        Some(5.0)
+    );
+    assert_eq!(PinDurations::parse("10"), None);
+    assert_eq!(PinDurations::parse("10=0"), None);
+}
+
+#[test]
+fn tracks_pinned_until_expiry() {
+    let tiers = PinDurations::default();
+    let now = Instant::now();
+    let mut active = ActiveSuperChats::default();
+
+    active.push(&Message::chat("hi"), now);
+    let mut unpinned = superchat(1.0);
+    tiers.apply(&mut unpinned);
+    active.push(&unpinned, now);
+    assert!(active.is_empty());
+
+    for amount in [30.0, 50.0] {
+        let mut msg = superchat(amount);
+        tiers.apply(&mut msg);
+        active.push(&msg, now);
+    }
+    assert_eq!(active.len(), 2);
+
+    active.expire(now + Duration::from_secs(60));
+    let left: Vec<_> = active.iter().map(|it| it.until - now).collect();
+    assert_eq!(left, [Duration::from_secs(120)]);
+}
@@ -0,0 +1,39 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::network::supervisor::Backoff;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn backs_off_then_gives_up() {
+    let start = Instant::now();
+    let mut backoff = Backoff::new(secs(1), secs(5), 4, secs(60));
+
+    let mut delays = vec![];
+    while let Some(delay) = backoff.on_exit(start) {
+        delays.push(delay);
+        backoff.on_start(start);
+    }
+    assert_eq!(delays, [secs(1), secs(2), secs(4), secs(5)]);
+    assert_eq!(backoff.failures(), 5);
+
+    backoff.reset();
+    assert_eq!(backoff.on_exit(start), Some(secs(1)));
+}
+
+#[test]
+fn healthy_run_resets_failures() {
+    let start = Instant::now();
+    let mut backoff = Backoff::new(secs(1), secs(30), 5, secs(10));
+
+    backoff.on_start(start);
+    assert_eq!(backoff.on_exit(start + secs(1)), Some(secs(1)));
+    backoff.on_start(start + secs(2));
+    assert_eq!(backoff.on_exit(start + secs(3)), Some(secs(2)));
+
+    backoff.on_start(start + secs(5));
+    assert_eq!(backoff.on_exit(start + secs(20)), Some(secs(1)));
+    assert_eq!(backoff.failures(), 1);
+}
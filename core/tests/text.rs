@@ -0,0 +1,242 @@
+use blooming_light_core::{
+    message::{Attachment, AttachmentKind, Message, MessageKind},
+    text::{
+        overlay_messages, ImageAction, LengthLimit, LongMessage,
+        OverlayMarkup, Sanitizer, UrlAction, UrlFilter,
+    },
+};
+
+fn limit(max_chars: usize, long_message: LongMessage) -> LengthLimit {
+    LengthLimit {
+        max_chars,
+        long_message,
+    }
+}
+
+fn texts(msgs: &[Message]) -> Vec<&str> {
+    msgs.iter().map(|it| it.text.as_str()).collect()
+}
+
+#[test]
+fn short_messages_pass() {
+    let msg = Message::chat("hello");
+    assert_eq!(
+        texts(&limit(5, LongMessage::Truncate).apply(&msg)),
+        ["hello"]
+    );
+    assert_eq!(
+        texts(&limit(0, LongMessage::Split).apply(&msg)),
+        ["hello"]
+    );
+}
+
+#[test]
+fn truncates_with_ellipsis() {
+    let limit = limit(5, LongMessage::Truncate);
+    assert_eq!(limit.truncate("hello world"), "hell…");
+    // counts chars, not bytes
+    assert_eq!(limit.truncate("你好你好你好"), "你好你好…");
+    assert_eq!(
+        texts(&limit.apply(&Message::chat("hello world"))),
+        ["hell…"]
+    );
+}
+
+#[test]
+fn splits_at_whitespace() {
+    let limit = limit(10, LongMessage::Split);
+    let msg = Message {
+        username: Some("user".to_owned()),
+        ..Message::chat("the quick brown fox jumps over")
+    };
+    let parts = limit.apply(&msg);
+    assert_eq!(texts(&parts), ["the quick", "brown fox", "jumps over"]);
+    assert!(parts.iter().all(|it| it.username == msg.username));
+
+    // no whitespace to break at
+    assert_eq!(
+        texts(&limit.apply(&Message::chat("aaaaaaaaaaaaaaaaaaaaaaaaa"))),
+        ["aaaaaaaaaa", "aaaaaaaaaa", "aaaaa"]
+    );
+    assert_eq!(
+        texts(&limit.apply(&Message::chat("你好".repeat(6)))),
+        ["你好你好你好你好你好", "你好"]
+    );
+}
+
+#[test]
+fn sanitizer_normalizes_and_strips_controls() {
+    let sanitizer = Sanitizer::default();
+    // e + combining acute composes to é
+    assert_eq!(sanitizer.sanitize("cafe\u{301}"), "café");
+    assert_eq!(sanitizer.sanitize("a\u{200b}b\u{feff}c"), "abc");
+    // right-to-left override used to disguise file names
+    assert_eq!(sanitizer.sanitize("evil\u{202e}txt.exe"), "eviltxt.exe");
+    assert_eq!(
+        sanitizer.sanitize("z\u{336}\u{337}\u{338}\u{339}a"),
+        "z\u{336}\u{337}a"
+    );
+
+    let family = "👨\u{200d}👩\u{200d}👧";
+    assert_eq!(sanitizer.sanitize(family), family);
+    assert_eq!(sanitizer.sanitize("a\u{200d}b"), "ab");
+}
+
+#[test]
+fn sanitizer_covers_username_and_can_be_disabled() {
+    let msg = Message {
+        username: Some("ad\u{200b}min".to_owned()),
+        ..Message::chat("hi\u{2066}")
+    };
+    let sanitized = Sanitizer::default().apply(msg.clone());
+    assert_eq!(sanitized.username.as_deref(), Some("admin"));
+    assert_eq!(sanitized.text, "hi");
+
+    let disabled = Sanitizer {
+        enabled: false,
+        ..Default::default()
+    };
+    assert_eq!(disabled.apply(msg.clone()), msg);
+}
+
+#[test]
+fn overlay_text_is_escaped() {
+    let markup = OverlayMarkup::default();
+    assert_eq!(
+        markup.escape("<script>alert('x')</script> & \"q\""),
+        "&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; \
+         &amp; &quot;q&quot;"
+    );
+
+    let markup = OverlayMarkup {
+        allowed_tags: vec!["b".to_owned()],
+    };
+    assert_eq!(markup.escape("<B>bold</b>"), "<b>bold</b>");
+    assert_eq!(
+        markup.escape("<b onclick=x>no</b>"),
+        "&lt;b onclick=x&gt;no</b>"
+    );
+    assert_eq!(markup.escape("<i>no</i>"), "&lt;i&gt;no&lt;/i&gt;");
+}
+
+#[test]
+fn overlay_messages_limit_then_escape() {
+    let limit = LengthLimit {
+        max_chars: 5,
+        long_message: LongMessage::Truncate,
+    };
+    let msg = Message {
+        username: Some("<u>".to_owned()),
+        ..Message::chat("a<bcdef")
+    };
+    // the limit counts the text as written, not its escaped form
+    let msgs = overlay_messages(&msg, &limit, &OverlayMarkup::default());
+    assert_eq!(texts(&msgs), ["a&lt;bc…"]);
+    assert_eq!(msgs[0].username.as_deref(), Some("&lt;u&gt;"));
+}
+
+#[test]
+fn url_filter_applies_action_outside_allowed_domains() {
+    let filter = UrlFilter {
+        action: UrlAction::Replace,
+        allowed_domains: vec!["example.com".to_owned()],
+    };
+    let (text, hits) = filter.apply(
+        "see https://evil.test/x  and www.example.com/a or \
+         http://user@sub.example.com:8080 badexample.com",
+    );
+    assert_eq!(
+        text,
+        "see [link]  and www.example.com/a or \
+         http://user@sub.example.com:8080 badexample.com"
+    );
+    let actions: Vec<_> = hits.iter().map(|it| it.action).collect();
+    assert_eq!(
+        actions,
+        [UrlAction::Replace, UrlAction::Keep, UrlAction::Keep]
+    );
+    assert_eq!(hits[0].url, "https://evil.test/x");
+
+    let filter = UrlFilter {
+        action: UrlAction::Strip,
+        ..Default::default()
+    };
+    assert_eq!(filter.apply("hi https://a.test").0, "hi");
+    assert_eq!(filter.apply("https://a.test hi").0, "hi");
+    assert_eq!(filter.apply("no links").1, []);
+}
+
+#[test]
+fn overlay_keeps_only_safe_attachments_on_first_part() {
+    let inline = |data: &str, mime: &str| Attachment {
+        data: Some(data.to_owned()),
+        mime: Some(mime.to_owned()),
+        ..Default::default()
+    };
+    let msg = Message {
+        attachments: vec![
+            Attachment::url(AttachmentKind::Sticker, "https://a.test/x"),
+            Attachment::url(AttachmentKind::Image, "javascript:alert(1)"),
+            inline("iVBORw0KGgo=", "image/png"),
+            inline("iVBORw0KGgo=", "text/html"),
+            inline("\"><script>", "image/png"),
+        ],
+        ..Message::chat("aaaa bbbb")
+    };
+    let msgs = overlay_messages(
+        &msg,
+        &limit(4, LongMessage::Split),
+        &OverlayMarkup::default(),
+    );
+    assert_eq!(texts(&msgs), ["aaaa", "bbbb"]);
+    assert_eq!(
+        msgs[0].attachments,
+        [msg.attachments[0].clone(), msg.attachments[2].clone()]
+    );
+    assert_eq!(msgs[1].attachments, []);
+}
+
+#[test]
+fn image_action() {
+    let image = Message {
+        attachments: vec![Attachment::url(
+            AttachmentKind::Image,
+            "https://a.test/x.png",
+        )],
+        ..Message::chat("")
+    };
+    let captioned = Message {
+        text: "look".to_owned(),
+        ..image.clone()
+    };
+
+    assert!(!ImageAction::Keep.drops(&image));
+    assert_eq!(ImageAction::Keep.apply(image.clone()), image);
+
+    assert!(ImageAction::Strip.drops(&image));
+    assert!(!ImageAction::Strip.drops(&captioned));
+    assert_eq!(
+        ImageAction::Strip.apply(captioned.clone()),
+        Message::chat("look")
+    );
+
+    assert!(ImageAction::Drop.drops(&captioned));
+    assert!(!ImageAction::Drop.drops(&Message::chat("look")));
+}
+
+#[test]
+fn test_messages_reach_the_overlay_marked() {
+    for kind in MessageKind::ALL {
+        let msg = Message::test(kind);
+        let parts = overlay_messages(
+            &msg,
+            &LengthLimit::default(),
+            &OverlayMarkup::default(),
+        );
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].kind, kind);
+        assert!(parts[0].text.starts_with("[TEST]"));
+    }
+    assert!(Message::test(MessageKind::Gift).gift.is_some());
+    assert!(Message::test(MessageKind::SuperChat).paid.is_some());
+}
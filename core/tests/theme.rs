@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use blooming_light_core::network::theme::{self, Layout, ThemeVars};
+
+fn themes_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "blooming-light-themes-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("dark")).unwrap();
+    std::fs::create_dir_all(dir.join("not-a-theme")).unwrap();
+    std::fs::write(
+        dir.join("dark/index.html"),
+        "<body style=\"color: {{text_color}}\">",
+    )
+    .unwrap();
+    std::fs::write(dir.join("dark/logo.png"), b"{{text_color}}").unwrap();
+    dir
+}
+
+fn body(file: Option<theme::ThemeFile>) -> String {
+    String::from_utf8(file.expect("file should load").body).unwrap()
+}
+
+#[test]
+fn renders_variables() {
+    let vars = ThemeVars {
+        text_color: "#123456".to_owned(),
+        font_size: 2.5,
+        ..Default::default()
+    };
+    assert_eq!(
+        vars.render("{{text_color}} {{font_size}}vw {{unknown}}"),
+        "#123456 2.5vw {{unknown}}"
+    );
+
+    for layout in Layout::ALL {
+        let html =
+            body(theme::load_builtin(layout, "/", &ThemeVars::default()));
+        assert!(html.contains("font-size: 1.5vw"), "{layout:?}");
+        assert!(!html.contains("{{"), "{layout:?}");
+    }
+}
+
+#[test]
+fn serves_builtin_layouts() {
+    let vars = ThemeVars::default();
+    for layout in Layout::ALL {
+        assert_eq!(Layout::from_slug(layout.slug()), Some(layout));
+        let index = theme::load_builtin(layout, "", &vars).unwrap();
+        assert_eq!(index.content_type, "text/html; charset=utf-8");
+        assert!(body(theme::load_builtin(layout, "overlay.js", &vars))
+            .contains("WebSocket"));
+        assert!(theme::load_builtin(layout, "logo.png", &vars).is_none());
+    }
+    assert_eq!(Layout::from_slug("unknown"), None);
+    assert_ne!(
+        body(theme::load_builtin(Layout::List, "", &vars)),
+        body(theme::load_builtin(Layout::Ticker, "", &vars))
+    );
+}
+
+#[test]
+fn serves_theme_files() {
+    let dir = themes_dir("serve");
+    let vars = ThemeVars::default();
+    assert_eq!(theme::list(&dir), ["dark"]);
+
+    let index = theme::load(&dir, "dark", "", &vars).unwrap();
+    assert_eq!(index.content_type, "text/html; charset=utf-8");
+    assert_eq!(
+        String::from_utf8(index.body).unwrap(),
+        "<body style=\"color: #ffffff\">"
+    );
+    // only text files are templates
+    assert_eq!(
+        body(theme::load(&dir, "dark", "logo.png", &vars)),
+        "{{text_color}}"
+    );
+    // falls back to the built-in script
+    assert!(body(theme::load(&dir, "dark", "index.js", &vars))
+        .contains("WebSocket"));
+
+    assert!(theme::load(&dir, "dark", "missing.css", &vars).is_none());
+    assert!(
+        theme::load(&dir, "dark", "../dark/index.html", &vars).is_none()
+    );
+    assert!(theme::load(&dir, "..", "index.html", &vars).is_none());
+    assert!(theme::load(&dir, "", "dark/index.html", &vars).is_none());
+}
+
+#[test]
+fn dev_mode_reads_from_disk() {
+    let dir = themes_dir("dev");
+    std::fs::create_dir_all(dir.join("layouts")).unwrap();
+    std::fs::write(dir.join("layouts/ticker.html"), "{{font_size}}")
+        .unwrap();
+    let vars = ThemeVars::default();
+    assert_eq!(
+        body(theme::load_builtin_from(&dir, Layout::Ticker, "/", &vars)),
+        "1.5"
+    );
+    assert!(theme::load_builtin_from(&dir, Layout::List, "/", &vars)
+        .is_none());
+    assert!(theme::load_builtin_from(
+        &dir,
+        Layout::Ticker,
+        "x.css",
+        &vars
+    )
+    .is_none());
+}
@@ -0,0 +1,36 @@
+use blooming_light_core::{message::Message, timeout::Timeouts};
+use chrono::{TimeDelta, TimeZone, Utc};
+
+fn from(username: &str) -> Message {
+    Message {
+        username: Some(username.to_owned()),
+        ..Message::chat("hi")
+    }
+}
+
+#[test]
+fn mutes_until_expiry() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+    let mins = TimeDelta::minutes;
+    let mut timeouts = Timeouts::default();
+    timeouts.add("a", start + mins(5));
+    timeouts.add(" b ", start + mins(30));
+    timeouts.add("", start + mins(30));
+
+    assert!(timeouts.mutes(&from("a"), start));
+    assert!(timeouts.mutes(&from("b"), start + mins(10)));
+    assert!(!timeouts.mutes(&from("c"), start));
+    assert!(!timeouts.mutes(&Message::chat("hi"), start));
+    assert_eq!(
+        timeouts.active(),
+        [("a", start + mins(5)), ("b", start + mins(30))]
+    );
+
+    // messages flow again once it's over, before it's expired too
+    assert!(!timeouts.mutes(&from("a"), start + mins(5)));
+    assert_eq!(timeouts.expire(start + mins(5)), ["a"]);
+    assert_eq!(timeouts.active(), [("b", start + mins(30))]);
+
+    timeouts.remove("b");
+    assert!(timeouts.is_empty());
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::timer::{OverlayTimer, TimerFrame, TimerState};
+
+fn state(frame: Option<TimerFrame>) -> Option<(TimerState, u64)> {
+    frame.map(|it| (it.state, it.remaining_secs))
+}
+
+#[test]
+fn counts_down_each_second_then_stops() {
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+    let mut timer =
+        OverlayTimer::new(1, "Starting soon", Duration::from_secs(3));
+
+    assert_eq!(state(timer.tick(at(0))), None);
+    let frame = timer.start(at(0));
+    assert_eq!(
+        (frame.state, frame.remaining_secs),
+        (TimerState::Start, 3)
+    );
+    assert_eq!(state(timer.tick(at(500))), None);
+    assert_eq!(
+        timer.next_tick_in(at(500)),
+        Some(Duration::from_millis(500))
+    );
+    assert_eq!(
+        state(timer.tick(at(1000))),
+        Some((TimerState::Remaining, 2))
+    );
+    assert_eq!(state(timer.tick(at(1200))), None);
+    assert_eq!(
+        state(timer.tick(at(2100))),
+        Some((TimerState::Remaining, 1))
+    );
+    assert_eq!(state(timer.tick(at(3500))), Some((TimerState::Stop, 0)));
+    assert!(!timer.is_running());
+    assert_eq!(timer.next_tick_in(at(3500)), None);
+
+    // ran out, so it starts over
+    let frame = timer.start(at(4000));
+    assert_eq!(frame.remaining_secs, 3);
+}
+
+#[test]
+fn stop_keeps_what_is_left() {
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+    let mut timer = OverlayTimer::new(1, "", Duration::from_secs(300));
+
+    timer.start(at(0));
+    let frame = timer.stop(at(60_000));
+    assert_eq!(
+        (frame.state, frame.remaining_secs),
+        (TimerState::Stop, 240)
+    );
+    assert_eq!(timer.remaining(at(120_000)), Duration::from_secs(240));
+    assert_eq!(timer.start(at(120_000)).remaining_secs, 240);
+
+    assert!(timer.reset(at(130_000)).is_some());
+    assert_eq!(timer.remaining(at(130_000)), Duration::from_secs(300));
+    assert!(timer.reset(at(130_000)).is_none());
+}
+
+#[test]
+fn timer_frame() {
+    let mut timer =
+        OverlayTimer::new(2, "BRB", Duration::from_millis(90_500));
+    assert_eq!(
+        serde_json::to_value(timer.start(Instant::now())).unwrap(),
+        serde_json::json!({
+            "type": "timer",
+            "id": 2,
+            "label": "BRB",
+            "state": "start",
+            "remaining_secs": 91,
+        })
+    );
+}
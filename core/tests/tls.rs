@@ -0,0 +1,35 @@
+use blooming_light_core::network::tls::TlsConfig;
+
+fn pinned(pin: &str) -> TlsConfig {
+    TlsConfig {
+        pinned_sha256: pin.to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parses_pinned_fingerprint() {
+    assert_eq!(pinned("").pinned_fingerprint().unwrap(), None);
+
+    let colons = ["ab"; 32].join(":");
+    assert_eq!(
+        pinned(&colons).pinned_fingerprint().unwrap(),
+        Some([0xab; 32])
+    );
+    assert_eq!(
+        pinned(&"AB".repeat(32)).pinned_fingerprint().unwrap(),
+        Some([0xab; 32])
+    );
+
+    assert!(pinned("abcd").pinned_fingerprint().is_err());
+    assert!(pinned(&"zz".repeat(32)).pinned_fingerprint().is_err());
+}
+
+#[test]
+fn rejects_missing_ca_file() {
+    let config = TlsConfig {
+        ca_file: "/nonexistent/ca.pem".to_owned(),
+        ..Default::default()
+    };
+    assert!(config.connector().is_err());
+}
@@ -0,0 +1,24 @@
+use blooming_light_core::update::Release;
+
+fn release(tag: &str) -> Release {
+    Release {
+        tag_name: tag.to_owned(),
+        html_url: String::new(),
+    }
+}
+
+#[test]
+fn compares_versions() {
+    assert!(release("v0.2.0").is_newer_than("0.1.9"));
+    assert!(release("0.10.0").is_newer_than("0.9.0"));
+    assert!(release("v1.0.0-rc.1").is_newer_than("0.1.0"));
+    assert!(!release("v0.1.0").is_newer_than("0.1.0"));
+    assert!(!release("v0.0.9").is_newer_than("0.1.0"));
+}
+
+#[test]
+fn ignores_other_tags() {
+    assert!(!release("nightly").is_newer_than("0.1.0"));
+    assert!(!release("v1.0").is_newer_than("0.1.0"));
+    assert!(!release("v1.0.0.0").is_newer_than("0.1.0"));
+}
@@ -6,20 +6,173 @@ const canvas = document.querySelector("#canvas");
 let speedPPS = 15_000;
 const pending = [];
 const slots = [];
+// paid messages held at the bottom until their pin runs out
+const pinned = [];
+// running overlay timers by id, drawn at the top right
+const timers = new Map();
 let slotHeight = 114514;
 let fontBoundingBoxAscent = 114514;
 
+/**
+ * Color of a message type, from the `--<type>-color` variables of the
+ * canvas. `undefined` for the default color.
+ * @param {string} type
+ */
+function kindColor(type) {
+  const color = window.getComputedStyle(canvas)
+    .getPropertyValue(`--${type}-color`)
+    .trim();
+  return color === "" ? undefined : color;
+}
+
 /**
  * @param {MessageEvent} ev
  */
 function onMessage(ev) {
-  const msg = ev.data;
+  const data = JSON.parse(ev.data);
+  // batched frames carry an array of envelopes
+  for (const envelope of Array.isArray(data) ? data : [data]) {
+    pushEnvelope(envelope);
+  }
+}
+
+const parser = new DOMParser();
+
+/**
+ * Payload text is HTML-escaped, canvas wants it plain.
+ * @param {string} html
+ */
+function htmlToText(html) {
+  return parser.parseFromString(html, "text/html").body.textContent ?? "";
+}
+
+/**
+ * @param {{url?: string, data?: string, mime?: string}} attachment
+ */
+function attachmentSrc(attachment) {
+  if (attachment.url != null) return attachment.url;
+  if (attachment.data != null) {
+    return `data:${attachment.mime ?? "image/png"};base64,${attachment.data}`;
+  }
+  return null;
+}
+
+/**
+ * @param {{type: string, username?: string, text: string, attachments?: object[]}} envelope
+ */
+function pushEnvelope(envelope) {
+  if (envelope.type === "reload") {
+    window.location.reload();
+    return;
+  }
+  if (envelope.type === "combo") {
+    pushCombo(envelope);
+    return;
+  }
+  if (envelope.type === "poll") {
+    // poll text is plain, shown like a message with the results in line
+    const results = envelope.results
+      .map((it) => `${it.option} ${it.votes}`)
+      .join(" / ");
+    const msg = `${envelope.question} ${results}`;
+    pending.push({
+      msg: msg,
+      images: [],
+      imagesWidth: 0,
+      width: canvas.getContext("2d").measureText(msg).width,
+      color: kindColor("poll"),
+      comboText: null,
+    });
+    return;
+  }
+  if (envelope.type === "timer") {
+    if (envelope.state === "stop") {
+      timers.delete(envelope.id);
+    } else {
+      timers.set(envelope.id, envelope);
+    }
+    return;
+  }
+
+  const text = htmlToText(envelope.text);
+  const msg = envelope.username != null && envelope.type !== "chat"
+    ? `${htmlToText(envelope.username)}: ${text}`
+    : text;
 
   const ctx = canvas.getContext("2d");
-  const text = ctx.measureText(msg);
-  pending.push({ msg: msg, width: text.width });
-  slotHeight = text.fontBoundingBoxAscent + text.fontBoundingBoxDescent;
-  fontBoundingBoxAscent = text.fontBoundingBoxAscent;
+  const metrics = ctx.measureText(msg);
+  slotHeight = metrics.fontBoundingBoxAscent + metrics.fontBoundingBoxDescent;
+  fontBoundingBoxAscent = metrics.fontBoundingBoxAscent;
+
+  // images go in front of the text, one square slot each
+  const images = [];
+  for (const attachment of envelope.attachments ?? []) {
+    const src = attachmentSrc(attachment);
+    if (src == null) continue;
+    const image = new Image();
+    image.src = src;
+    images.push(image);
+  }
+  const imagesWidth = images.length * slotHeight;
+  const pinSecs = envelope.paid?.pin_secs;
+  if (pinSecs != null) {
+    pinned.push({
+      msg: msg,
+      until: performance.now() + pinSecs * 1000,
+      color: kindColor(envelope.type),
+    });
+    // run out first at the front
+    pinned.sort((a, b) => a.until - b.until);
+    return;
+  }
+  pending.push({
+    msg: msg,
+    images: images,
+    imagesWidth: imagesWidth,
+    width: imagesWidth + metrics.width,
+    color: kindColor(envelope.type),
+    // a later combo frame for this text updates the item in place
+    comboText: envelope.type === "chat" && images.length === 0
+      ? text
+      : null,
+  });
+}
+
+/**
+ * @param {{text: string, count: number}} combo
+ */
+function pushCombo(combo) {
+  const text = htmlToText(combo.text);
+  const msg = `${text} ×${combo.count}`;
+  const width = canvas.getContext("2d").measureText(msg).width;
+
+  const item = [...pending, ...slots.flat()]
+    .findLast((it) => it?.comboText === text);
+  if (item != null) {
+    item.msg = msg;
+    item.width = width;
+    return;
+  }
+  pending.push({
+    msg: msg,
+    images: [],
+    imagesWidth: 0,
+    width: width,
+    comboText: text,
+  });
+}
+
+/**
+ * Fits the image into the `size` square at `x`, `y`.
+ * @param {CanvasRenderingContext2D} ctx
+ * @param {HTMLImageElement} image
+ */
+function drawImage(ctx, image, x, y, size) {
+  if (!image.complete || image.naturalWidth === 0) return;
+  const scale = size / Math.max(image.naturalWidth, image.naturalHeight);
+  const w = image.naturalWidth * scale;
+  const h = image.naturalHeight * scale;
+  ctx.drawImage(image, x + (size - w) / 2, y + (size - h) / 2, w, h);
 }
 
 let lastTime = performance.now();
@@ -53,7 +206,10 @@ function update() {
 
   ctx.clearRect(0, 0, width, height);
 
+  while (pinned.length > 0 && pinned[0].until <= now) pinned.shift();
   let slotNum = Math.floor(height / slotHeight);
+  const pinnedNum = Math.min(pinned.length, slotNum);
+  slotNum -= pinnedNum;
 
   for (let i = 0; i < slotNum; i++) {
     if (pending.length === 0) break;
@@ -108,7 +264,17 @@ function update() {
       if (item.x < -item.width) {
         needDelete.push(i);
       } else {
-        ctx.fillText(item.msg, item.x, y);
+        ctx.fillStyle = item.color ?? style.color;
+        item.images.forEach((image, idx) => {
+          drawImage(
+            ctx,
+            image,
+            item.x + idx * slotHeight,
+            y - fontBoundingBoxAscent,
+            slotHeight,
+          );
+        });
+        ctx.fillText(item.msg, item.x + item.imagesWidth, y);
         //ctx.strokeRect(
         //  item.x,
         //  y - fontBoundingBoxAscent,
@@ -124,6 +290,23 @@ function update() {
       slot.splice(it, 1);
     });
   }
+  for (let i = 0; i < pinnedNum; i++) {
+    const item = pinned[pinned.length - 1 - i];
+    const secs = Math.ceil((item.until - now) / 1000);
+    const y = height - (i + 1) * slotHeight + fontBoundingBoxAscent;
+    ctx.fillStyle = item.color ?? style.color;
+    ctx.fillText(`${item.msg} (${secs}s)`, 0, y);
+  }
+  [...timers.values()].forEach((timer, i) => {
+    const secs = timer.remaining_secs;
+    const clock = `${Math.floor(secs / 60)}:${
+      String(secs % 60).padStart(2, "0")
+    }`;
+    const msg = `${timer.label} ${clock}`;
+    const y = i * slotHeight + fontBoundingBoxAscent;
+    ctx.fillStyle = style.color;
+    ctx.fillText(msg, width - ctx.measureText(msg).width, y);
+  });
   window.requestAnimationFrame(update);
 }
 update();
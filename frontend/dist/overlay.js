@@ -0,0 +1,212 @@
+// Shared by the DOM based built-in layouts: keeps a connection to the
+// server and hands every envelope and combo frame to the layout. Text and
+// usernames arrive HTML-escaped, so they are safe to use as innerHTML.
+
+// how long poll results stay up
+const POLL_SHOW_MS = 15_000;
+
+/**
+ * @param {{url?: string, data?: string, mime?: string}} attachment
+ */
+function attachmentSrc(attachment) {
+  if (attachment.url != null) return attachment.url;
+  if (attachment.data != null) {
+    return `data:${attachment.mime ?? "image/png"};base64,${attachment.data}`;
+  }
+  return null;
+}
+
+/**
+ * A message element: username, text and attachments, with the message
+ * type as a class for styling.
+ * @param {{type: string, username?: string, text: string, attachments?: object[]}} envelope
+ */
+function messageElement(envelope) {
+  const el = document.createElement("div");
+  el.classList.add("msg", envelope.type);
+
+  if (envelope.username != null) {
+    const user = document.createElement("span");
+    user.className = "user";
+    user.innerHTML = envelope.username;
+    el.append(user);
+  }
+  for (const attachment of envelope.attachments ?? []) {
+    const src = attachmentSrc(attachment);
+    if (src == null) continue;
+    const img = document.createElement("img");
+    img.className = attachment.kind ?? "image";
+    img.src = src;
+    el.append(img);
+  }
+  const text = document.createElement("span");
+  text.className = "text";
+  text.innerHTML = envelope.text;
+  el.append(text);
+
+  if (envelope.type === "chat" && !envelope.attachments?.length) {
+    el.dataset.combo = envelope.text;
+  }
+  return el;
+}
+
+/**
+ * Shows a combo on the newest element for its text, `null` if there is
+ * none on screen.
+ * @param {ParentNode} root
+ * @param {{text: string, count: number}} combo
+ */
+function applyCombo(root, combo) {
+  const matches = [...root.querySelectorAll(".msg[data-combo]")]
+    .filter((it) => it.dataset.combo === combo.text);
+  const el = matches.at(-1);
+  if (el == null) return null;
+  let badge = el.querySelector(".combo");
+  if (badge == null) {
+    badge = document.createElement("span");
+    badge.className = "combo";
+    el.append(badge);
+  }
+  badge.textContent = `×${combo.count}`;
+  return el;
+}
+
+/**
+ * Keeps `el` in `container` for `pinSecs`, with a countdown.
+ * @param {Element} container
+ * @param {Element} el
+ * @param {number} pinSecs
+ */
+function pin(container, el, pinSecs) {
+  const countdown = document.createElement("span");
+  countdown.className = "countdown";
+  el.append(countdown);
+  container.append(el);
+
+  const until = performance.now() + pinSecs * 1000;
+  const tick = () => {
+    const left = Math.ceil((until - performance.now()) / 1000);
+    if (left <= 0) {
+      el.remove();
+      return;
+    }
+    countdown.textContent = `${left}s`;
+    setTimeout(tick, 250);
+  };
+  tick();
+}
+
+/**
+ * Shows a timer frame in a corner of the page, removed once it stops.
+ * @param {{id: number, label: string, state: string, remaining_secs: number}} timer
+ */
+function showTimer(timer) {
+  let root = document.querySelector("#timers");
+  if (root == null) {
+    root = document.createElement("div");
+    root.id = "timers";
+    root.style.cssText =
+      "position: fixed; top: 0; right: 0; text-align: right;";
+    document.body.append(root);
+  }
+  let el = root.querySelector(`[data-timer="${timer.id}"]`);
+  if (timer.state === "stop") {
+    el?.remove();
+    return;
+  }
+  if (el == null) {
+    el = document.createElement("div");
+    el.className = "timer";
+    el.dataset.timer = timer.id;
+    root.append(el);
+  }
+  el.textContent = `${timer.label} ${formatSecs(timer.remaining_secs)}`;
+}
+
+/**
+ * Shows the results of a closed poll in a corner of the page for a
+ * while. Its text is plain, not HTML-escaped.
+ * @param {{question: string, results: {option: string, votes: number}[], total: number}} poll
+ */
+function showPoll(poll) {
+  document.querySelector("#poll")?.remove();
+  const root = document.createElement("div");
+  root.id = "poll";
+  root.className = "poll";
+  root.style.cssText = "position: fixed; top: 0; left: 0;";
+  const question = document.createElement("div");
+  question.className = "poll-question";
+  question.textContent = poll.question;
+  root.append(question);
+  for (const result of poll.results) {
+    const share = Math.round(result.votes / Math.max(poll.total, 1) * 100);
+    const el = document.createElement("div");
+    el.className = "poll-result";
+    el.textContent = `${result.option} ${result.votes} (${share}%)`;
+    root.append(el);
+  }
+  document.body.append(root);
+  setTimeout(() => root.remove(), POLL_SHOW_MS);
+}
+
+/**
+ * `m:ss`, or `h:mm:ss` from an hour on.
+ * @param {number} secs
+ */
+function formatSecs(secs) {
+  const pad = (it) => String(it).padStart(2, "0");
+  const h = Math.floor(secs / 3600);
+  const m = Math.floor(secs / 60) % 60;
+  const s = secs % 60;
+  return h > 0 ? `${h}:${pad(m)}:${pad(s)}` : `${m}:${pad(s)}`;
+}
+
+/**
+ * Timer and poll frames go to `showTimer` and `showPoll` unless the
+ * layout handles them.
+ * @param {{
+ *   onMessage: (envelope: object) => void,
+ *   onCombo: (combo: object) => void,
+ *   onTimer?: (timer: object) => void,
+ *   onPoll?: (poll: object) => void,
+ * }} layout
+ */
+function connectOverlay(layout) {
+  let reconnectTimeout = null;
+
+  function reconnect() {
+    if (reconnectTimeout != null) return;
+    reconnectTimeout = setTimeout(() => {
+      reconnectTimeout = null;
+      connect();
+    }, 1e3);
+  }
+
+  function connect() {
+    const ws = new WebSocket(`ws://${window.location.host}/ws`);
+    ws.onmessage = (ev) => {
+      const data = JSON.parse(ev.data);
+      // batched frames carry an array of envelopes
+      for (const envelope of Array.isArray(data) ? data : [data]) {
+        if (envelope.type === "reload") {
+          window.location.reload();
+        } else if (envelope.type === "combo") {
+          layout.onCombo(envelope);
+        } else if (envelope.type === "timer") {
+          (layout.onTimer ?? showTimer)(envelope);
+        } else if (envelope.type === "poll") {
+          (layout.onPoll ?? showPoll)(envelope);
+        } else {
+          layout.onMessage(envelope);
+        }
+      }
+    };
+    ws.onerror = (err) => {
+      console.error(err);
+      reconnect();
+    };
+    ws.onclose = reconnect;
+  }
+
+  connect();
+}
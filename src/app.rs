@@ -1,34 +1,175 @@
 use core::{f32, f64};
-use std::{collections::VecDeque, ops::Range, time::Instant};
+use std::{
+    path::PathBuf,
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Context};
-use demo_source::DemoSource;
+use blooming_light_core::{
+    alert::AlertConfig,
+    alias::{UserAlias, UserAliases},
+    channel::ChannelStats,
+    command::{ChatCommands, CommandCooldowns},
+    demo_source::{DemoSource, StressConfig},
+    flag::{FlagNotifier, FlagWords},
+    flood::{FloodCollapser, FloodLimit},
+    gift::GiftAggregator,
+    history::{Moderation, ModerationHistory},
+    hotkey::{GlobalHotkeys, HotkeyAction, HotkeyBindings},
+    keywords::Keywords,
+    lang::LanguageFilter,
+    leaderboard::{Leaderboard, LeaderboardSort},
+    log::{
+        self,
+        redact::{Found, RedactMode, Redaction},
+        upload::UploadTarget,
+        LogConfig, LogEntry, LogEvent,
+    },
+    mention::Mentions,
+    message::{KindFilter, Message, MessageKind},
+    midi::{MidiAction, MidiBindings, MidiInput, MidiListener},
+    network::{
+        status::SourceState, Action, ActionRequest, Network,
+        RuntimeConfig, ServerConfig, SourceStatus, WsClientConfig,
+    },
+    poll::{Poll, PollFrame},
+    preview::OverlayPreview,
+    qna::{QnaConfig, Questions},
+    queue::{
+        MessageQueue, OverflowPolicy, QueueLimit, QueueSnapshot,
+        SharedQueue,
+    },
+    raffle::Raffle,
+    release::{ReleaseConfig, Released},
+    revenue::Revenue,
+    schedule::PauseSchedule,
+    stats::{LatencyStats, RateHistory, RateMark, RateMeter},
+    superchat::{ActiveSuperChats, PinDurations},
+    text::{
+        ImageAction, LengthLimit, OverlayMarkup, Sanitizer, UrlFilter,
+    },
+    timeout::Timeouts,
+    timer::{OverlayTimer, TimerFrame},
+    update::Release,
+    Notifier,
+};
+use chrono::{DateTime, Local, Utc};
 use eframe::{
     egui::{
-        pos2, CentralPanel, Color32, Context as EguiCtx, DragValue, Grid,
-        Id, Rect, RichText, ScrollArea, Sense, Window,
+        Button, CentralPanel, Color32, Context as EguiCtx, DragValue,
+        Grid, Id, Image, RichText, TextureHandle, Ui, ViewportCommand,
+        WidgetInfo, WidgetType, Window,
     },
     CreationContext,
 };
-use tracing::info;
+use tracing::{info, level_filters::LevelFilter};
 
-use self::network::Network;
+use self::{
+    chroma::{chroma_size, DEFAULT_CHROMA_COLOR, DEFAULT_CHROMA_SIZE},
+    commands::run_chat_command,
+    dropped::DropAction,
+    flags::notify_flagged,
+    log_viewer::LogView,
+    palette::Palette,
+    preview::{preview_released, PREVIEW_SIZE},
+    queue_window::{
+        copy_all_button, queue_list_ui, queue_tabs_ui, QueueRows,
+    },
+    schedule::schedule_status_ui,
+    scheduled::{scheduled_ui, ScheduledDraft},
+    thumbnail::ThumbnailLoader,
+    triage::{triage_button, Triage},
+    word_lists::WordListSyncs,
+};
+use crate::{
+    crash,
+    logging::{FileLog, LogConsole},
+};
 
-mod demo_source;
+mod actions;
+mod activity;
+mod alert_settings;
+mod aliases;
+mod chroma;
+mod commands;
+mod crash_report;
+mod debug_settings;
+mod delete_reason;
+mod dropped;
+mod flags;
 mod font;
-mod network;
+mod history;
+mod hotkeys;
+mod hud;
+mod keywords;
+mod leaderboard;
+mod log_console;
+mod log_viewer;
+mod midi;
+mod palette;
+mod poll;
+mod preview;
+mod purge;
+mod qna;
+mod qr_code;
+mod queue_settings;
+mod queue_window;
+mod raffle;
+mod recovery;
+mod redact;
+mod revenue;
+mod rule_tester;
+mod schedule;
+mod scheduled;
+mod secrets;
+mod server_settings;
+mod source_settings;
+mod stats;
+mod superchats;
+mod text_settings;
+mod thumbnail;
+mod timeouts;
+mod timers;
+mod triage;
+mod update_check;
+mod word_lists;
+
+const DEMO_EXTENSIONS: &[&str] = &["txt", "json", "jsonl", "scenario"];
+const THUMBNAIL_HEIGHT: f32 = 48.0;
+/// Refresh interval while send delay progress bars are moving.
+const PROGRESS_REPAINT: Duration = Duration::from_millis(50);
 
 pub struct App {
     network: anyhow::Result<NetworkState>,
     err_messages: Vec<String>,
 
-    message: VecDeque<(String, Instant, bool)>,
-    message_waiting: VecDeque<String>,
+    /// Released from on the network thread.
+    message: SharedQueue,
+    queue_snapshot_saved_at: Instant,
+    /// Found at startup but too old to restore without asking.
+    stale_queue_snapshot: Option<QueueSnapshot>,
+    /// Left unfinished in the log by a session that didn't exit cleanly.
+    unfinished_messages: Vec<Message>,
 
     pause: bool,
+    /// Latched by the pause hotkey until pressed again.
+    hold: bool,
+    /// Source stopped, waiting for the queue to empty before closing.
+    draining: bool,
 
     msg_send_delay_secs: f64,
     msg_send_delay_secs_id: Id,
+    /// Minimum spacing between broadcasts, 0 for off.
+    slow_mode_secs: f64,
+    slow_mode_secs_id: Id,
+    /// One click send delays next to the delay field.
+    delay_presets: Vec<f64>,
+    delay_presets_id: Id,
+    delay_presets_draft: String,
+    /// Step of the -/+ buttons next to the delay field.
+    delay_nudge_secs: f64,
+    delay_nudge_secs_id: Id,
 
     demo_settings_show: bool,
     demo_settings_show_id: Id,
@@ -36,11 +177,281 @@ pub struct App {
     demo_enable_id: Id,
     demo_interval_secs: f64,
     demo_interval_secs_id: Id,
+    demo_path_id: Id,
     demo_source: DemoSource,
+    demo_stress: StressConfig,
+    demo_stress_id: Id,
+    /// Send delay for demo messages, the global one if `None`.
+    demo_delay_secs: Option<f64>,
+    demo_delay_secs_id: Id,
+    /// Combo window for demo messages, 0 for off.
+    demo_combo_window_secs: f64,
+    demo_combo_window_secs_id: Id,
+
+    source_settings_show: bool,
+    source_settings_show_id: Id,
+    ws_client_config: WsClientConfig,
+    ws_client_config_id: Id,
+    ws_client_config_draft: WsClientConfig,
+    ws_client_subprotocols_draft: String,
+
+    stats_show: bool,
+    stats_show_id: Id,
+    latency: LatencyStats,
+    /// Messages from the source, or demo, entering the pipeline.
+    rate_in: RateMeter,
+    /// Messages released to the overlay.
+    rate_out: RateMeter,
+    /// Both per minute over the session, charted in Stats.
+    rate_history: RateHistory,
+    /// By hand or on schedule, as last marked in `rate_history`.
+    rate_history_paused: bool,
+    /// Messages per minute of the last logged session, loaded in Stats.
+    activity: Vec<(DateTime<Utc>, usize)>,
+    keywords_show: bool,
+    keywords_show_id: Id,
+    /// Of messages received over the last `keywords_mins`.
+    keywords: Keywords,
+    keywords_mins: u64,
+    keywords_mins_id: Id,
+    leaderboard_show: bool,
+    leaderboard_show_id: Id,
+    leaderboard: Leaderboard,
+    leaderboard_sort: LeaderboardSort,
+    /// Whose recent messages are shown, picked on the leaderboard.
+    chatter_messages: Option<String>,
+    poll_show: bool,
+    poll_show_id: Id,
+    poll: Option<Poll>,
+    poll_question_draft: String,
+    /// An option per line.
+    poll_options_draft: String,
+    poll_hide_votes: bool,
+    poll_hide_votes_id: Id,
+    qna_show: bool,
+    qna_show_id: Id,
+    qna: QnaConfig,
+    qna_id: Id,
+    /// Held back from the queue by `qna`.
+    questions: Questions,
+    raffle_show: bool,
+    raffle_show_id: Id,
+    raffle: Option<Raffle>,
+    raffle_keyword_draft: String,
+    raffle_secs: u64,
+    raffle_hide_entries: bool,
+    raffle_hide_entries_id: Id,
+    revenue_show: bool,
+    revenue_show_id: Id,
+    revenue: Revenue,
+    user_aliases: UserAliases,
+    user_aliases_id: Id,
+    /// Username and alias to set.
+    user_alias_draft: (String, UserAlias),
+    timeouts_show: bool,
+    timeouts_show_id: Id,
+    timeouts: Timeouts,
+    timeouts_id: Id,
+    timeout_username: String,
+    timeout_custom_mins: i64,
+    hud_show: bool,
+    hud_show_id: Id,
+    /// Time the last update took, from eframe.
+    frame_cpu_usage: Option<f32>,
+
+    server_settings_show: bool,
+    server_settings_show_id: Id,
+    server_config: ServerConfig,
+    server_config_id: Id,
+    server_config_draft: ServerConfig,
+    server_addrs_draft: String,
+    allowed_ips_draft: String,
+    denied_ips_draft: String,
+    test_message_kind: MessageKind,
+
+    purge_confirm_show: bool,
+    purge_reason: String,
+
+    scheduled_draft: ScheduledDraft,
+
+    redact_show: bool,
+    redact_username: String,
+    redact_mode: RedactMode,
+    /// Of `redact_username` and `redact_mode`, to be confirmed.
+    redact_preview: Option<Vec<Found>>,
+
+    timers_show: bool,
+    /// Shown on overlays as they run, see [`OverlayTimer`].
+    timers: Vec<OverlayTimer>,
+    timer_label_draft: String,
+    timer_secs_draft: u64,
+    next_timer_id: u32,
+
+    queue_settings_show: bool,
+    queue_settings_show_id: Id,
+    queue_limit: QueueLimit,
+    queue_limit_id: Id,
+    hotkeys: HotkeyBindings,
+    hotkeys_id: Id,
+    /// Per [`HotkeyAction::ALL`], as typed.
+    hotkey_drafts: [String; 2],
+    global_hotkeys: Option<GlobalHotkeys>,
+
+    pause_schedule_show: bool,
+    pause_schedule_show_id: Id,
+    pause_schedule: PauseSchedule,
+    pause_schedule_id: Id,
+
+    text_settings_show: bool,
+    text_settings_show_id: Id,
+    sanitizer: Sanitizer,
+    sanitizer_id: Id,
+    length_limit: LengthLimit,
+    length_limit_id: Id,
+    overlay_markup: OverlayMarkup,
+    overlay_markup_id: Id,
+    allowed_tags_draft: String,
+    url_filter: UrlFilter,
+    url_filter_id: Id,
+    allowed_domains_draft: String,
+    image_action: ImageAction,
+    image_action_id: Id,
+    kind_filter: KindFilter,
+    kind_filter_id: Id,
+    language_filter: LanguageFilter,
+    language_filter_id: Id,
+    flag_words: FlagWords,
+    flag_words_id: Id,
+    flag_words_draft: String,
+    chat_commands: ChatCommands,
+    chat_commands_id: Id,
+    command_cooldowns: CommandCooldowns,
+    /// Asked for by chat commands, run next frame.
+    command_actions: Vec<Action>,
+    mentions: Mentions,
+    mentions_id: Id,
+    mentions_draft: String,
+    /// Only messages with a mention are listed in the queue.
+    queue_mentions_only: bool,
+    word_list_draft: String,
+    rule_tester_samples: String,
+    word_list_syncs: WordListSyncs,
+    flag_notifier: FlagNotifier,
+
+    alert_settings_show: bool,
+    alert_settings_show_id: Id,
+    alert_config: AlertConfig,
+    alert_config_id: Id,
+    obs_password_draft: String,
+
+    midi_show: bool,
+    midi_show_id: Id,
+    midi_bindings: MidiBindings,
+    midi_bindings_id: Id,
+    /// Of `midi_bindings.device`, while connected.
+    midi_listener: Option<MidiListener>,
+    /// Bound to the next press.
+    midi_learn: Option<MidiAction>,
+    midi_last_input: Option<MidiInput>,
+    /// Attachment thumbnails in the queue.
+    show_thumbnails: bool,
+    show_thumbnails_id: Id,
+    thumbnail_loader: Arc<ThumbnailLoader>,
+    /// Gifts from one user within this many seconds are queued as one
+    /// summary, 0 for off.
+    gift_window_secs: f64,
+    gift_window_secs_id: Id,
+    gifts: GiftAggregator,
+    flood_limit: FloodLimit,
+    flood_limit_id: Id,
+    flood: FloodCollapser,
+
+    superchats_show: bool,
+    superchats_show_id: Id,
+    pin_durations: PinDurations,
+    pin_durations_id: Id,
+    pin_durations_draft: String,
+    active_superchats: ActiveSuperChats,
+
+    history: ModerationHistory,
+    /// Asked for after each delete.
+    ask_delete_reason: bool,
+    ask_delete_reason_id: Id,
+    /// Deleted, logged once a reason is given or skipped.
+    awaiting_reason: Vec<Message>,
+    delete_reason_draft: String,
+    history_show: bool,
+    history_show_id: Id,
+
+    preview_show: bool,
+    preview_show_id: Id,
+    overlay_preview: OverlayPreview,
+
+    chroma_show: bool,
+    chroma_show_id: Id,
+    chroma_open: bool,
+    chroma_open_id: Id,
+    chroma_color: [u8; 3],
+    chroma_color_id: Id,
+    chroma_size: [u32; 2],
+    chroma_size_id: Id,
+    chroma_output: OverlayPreview,
+
+    queue_popout: bool,
+    queue_popout_id: Id,
+    /// Position and size the popped out queue opens with.
+    queue_window: Option<[f32; 4]>,
+    queue_window_id: Id,
+    /// On while moderating the queue with the keyboard.
+    triage: Option<Triage>,
+
+    /// Waiting for confirmation.
+    drop_action: Option<DropAction>,
+    log_view: Option<LogView>,
+
+    palette: Option<Palette>,
+
+    /// Opted into at startup.
+    check_updates: bool,
+    check_updates_id: Id,
+    update_rx: Option<mpsc::Receiver<anyhow::Result<Option<Release>>>>,
+    update_status: String,
+    available_update: Option<Release>,
+
+    /// Of a crash not looked at yet, with its path.
+    crash_report: Option<(PathBuf, String)>,
+
+    qr_code_show: bool,
+    qr_code_show_id: Id,
+    qr_code_url: String,
+    qr_code: Option<(String, TextureHandle)>,
+
+    debug_settings_show: bool,
+    debug_settings_show_id: Id,
+    file_log: FileLog,
+    file_log_level_id: Id,
+    /// Dropping it stops it.
+    puffin_server: Option<puffin_http::Server>,
+    puffin_server_enable_id: Id,
+    /// Applied when the network next starts.
+    runtime_config: RuntimeConfig,
+    runtime_config_id: Id,
+    /// Of the message log, applied when the network next starts.
+    log_config: LogConfig,
+    log_config_id: Id,
+    log_upload_draft: UploadTarget,
+
+    log_console: LogConsole,
+    log_console_level: LevelFilter,
+    log_console_level_id: Id,
 }
 
 impl App {
-    pub fn new(cc: &CreationContext) -> Self {
+    pub fn new(
+        cc: &CreationContext,
+        file_log: FileLog,
+        log_console: LogConsole,
+    ) -> Self {
         font::setup_fonts(&cc.egui_ctx);
         // cc.egui_ctx.set_debug_on_hover(true);
         let msg_send_delay_secs_id =
@@ -49,6 +460,21 @@ impl App {
             .egui_ctx
             .data_mut(|d| d.get_persisted::<f64>(msg_send_delay_secs_id))
             .unwrap_or(10.0);
+        let slow_mode_secs_id = Id::new("config.slow_mode_secs");
+        let slow_mode_secs = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<f64>(slow_mode_secs_id))
+            .unwrap_or(0.0);
+        let delay_presets_id = Id::new("config.delay_presets");
+        let delay_presets = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<Vec<f64>>(delay_presets_id))
+            .unwrap_or_else(|| vec![5.0, 10.0, 30.0, 120.0]);
+        let delay_nudge_secs_id = Id::new("config.delay_nudge_secs");
+        let delay_nudge_secs = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<f64>(delay_nudge_secs_id))
+            .unwrap_or(1.0);
         let demo_settings_show_id = Id::new("config.demo_settings_show");
         let demo_settings_show = cc
             .egui_ctx
@@ -64,18 +490,471 @@ impl App {
             .egui_ctx
             .data_mut(|d| d.get_persisted::<f64>(demo_interval_secs_id))
             .unwrap_or(0.1);
+        let demo_path_id = Id::new("config.demo_path");
+        let demo_source = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<PathBuf>(demo_path_id))
+            .map(DemoSource::new)
+            .unwrap_or_default();
+        let demo_stress_id = Id::new("config.demo_stress");
+        let demo_stress = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<StressConfig>(demo_stress_id))
+            .unwrap_or_default();
 
-        Self {
-            network: Ok(NetworkState::new(cc.egui_ctx.clone())),
-            err_messages: vec![],
+        let demo_delay_secs_id = Id::new("config.demo_delay_secs");
+        let demo_delay_secs = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<Option<f64>>(demo_delay_secs_id)
+            })
+            .flatten();
+        let demo_combo_window_secs_id =
+            Id::new("config.demo_combo_window_secs");
+        let demo_combo_window_secs = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<f64>(demo_combo_window_secs_id)
+            })
+            .unwrap_or(0.0);
+
+        let source_settings_show_id =
+            Id::new("config.source_settings_show");
+        let source_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<bool>(source_settings_show_id)
+            })
+            .unwrap_or(false);
+        let queue_settings_show_id =
+            Id::new("config.queue_settings_show");
+        let queue_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(queue_settings_show_id))
+            .unwrap_or(false);
+        let queue_limit_id = Id::new("config.queue_limit");
+        let queue_limit = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<QueueLimit>(queue_limit_id))
+            .unwrap_or_default();
+        let pause_schedule_show_id =
+            Id::new("config.pause_schedule_show");
+        let pause_schedule_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(pause_schedule_show_id))
+            .unwrap_or(false);
+        let pause_schedule_id = Id::new("config.pause_schedule");
+        let pause_schedule = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<PauseSchedule>(pause_schedule_id)
+            })
+            .unwrap_or_default();
+        let text_settings_show_id = Id::new("config.text_settings_show");
+        let text_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(text_settings_show_id))
+            .unwrap_or(false);
+        let sanitizer_id = Id::new("config.sanitizer");
+        let sanitizer = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<Sanitizer>(sanitizer_id))
+            .unwrap_or_default();
+        let length_limit_id = Id::new("config.length_limit");
+        let length_limit = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<LengthLimit>(length_limit_id))
+            .unwrap_or_default();
+        let overlay_markup_id = Id::new("config.overlay_markup");
+        let overlay_markup = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<OverlayMarkup>(overlay_markup_id)
+            })
+            .unwrap_or_default();
+        let url_filter_id = Id::new("config.url_filter");
+        let url_filter = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<UrlFilter>(url_filter_id))
+            .unwrap_or_default();
+        let image_action_id = Id::new("config.image_action");
+        let image_action = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<ImageAction>(image_action_id))
+            .unwrap_or_default();
+        let kind_filter_id = Id::new("config.kind_filter");
+        let kind_filter = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<KindFilter>(kind_filter_id))
+            .unwrap_or_default();
+        let language_filter_id = Id::new("config.language_filter");
+        let language_filter = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<LanguageFilter>(language_filter_id)
+            })
+            .unwrap_or_default();
+        let chat_commands_id = Id::new("config.chat_commands");
+        let chat_commands = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<ChatCommands>(chat_commands_id)
+            })
+            .unwrap_or_default();
+        let mentions_id = Id::new("config.mentions");
+        let mentions = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<Mentions>(mentions_id))
+            .unwrap_or_default();
+        let alert_settings_show_id =
+            Id::new("config.alert_settings_show");
+        let alert_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(alert_settings_show_id))
+            .unwrap_or(false);
+        let hotkeys_id = Id::new("config.hotkeys");
+        let hotkeys = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<HotkeyBindings>(hotkeys_id))
+            .unwrap_or_default();
+        let alert_config_id = Id::new("config.alert_config");
+        let mut alert_config = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<AlertConfig>(alert_config_id))
+            .unwrap_or_default();
+        let flag_words_id = Id::new("config.flag_words");
+        let flag_words = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<FlagWords>(flag_words_id))
+            .unwrap_or_default();
+        let midi_show_id = Id::new("config.midi_show");
+        let midi_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(midi_show_id))
+            .unwrap_or(false);
+        let midi_bindings_id = Id::new("config.midi_bindings");
+        let midi_bindings = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<MidiBindings>(midi_bindings_id)
+            })
+            .unwrap_or_default();
+        let gift_window_secs_id = Id::new("config.gift_window_secs");
+        let gift_window_secs = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<f64>(gift_window_secs_id))
+            .unwrap_or(0.0);
+        let superchats_show_id = Id::new("config.superchats_show");
+        let superchats_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(superchats_show_id))
+            .unwrap_or(false);
+        let pin_durations_id = Id::new("config.pin_durations");
+        let pin_durations = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<PinDurations>(pin_durations_id)
+            })
+            .unwrap_or_default();
+        let show_thumbnails_id = Id::new("config.show_thumbnails");
+        let show_thumbnails = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(show_thumbnails_id))
+            .unwrap_or(true);
+        let ask_delete_reason_id = Id::new("config.ask_delete_reason");
+        let ask_delete_reason = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(ask_delete_reason_id))
+            .unwrap_or(false);
+        let keywords_show_id = Id::new("config.keywords_show");
+        let keywords_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(keywords_show_id))
+            .unwrap_or(false);
+        let keywords_mins_id = Id::new("config.keywords_mins");
+        let keywords_mins = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<u64>(keywords_mins_id))
+            .unwrap_or(5);
+        let leaderboard_show_id = Id::new("config.leaderboard_show");
+        let leaderboard_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(leaderboard_show_id))
+            .unwrap_or(false);
+        let poll_show_id = Id::new("config.poll_show");
+        let poll_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(poll_show_id))
+            .unwrap_or(false);
+        let poll_hide_votes_id = Id::new("config.poll_hide_votes");
+        let poll_hide_votes = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(poll_hide_votes_id))
+            .unwrap_or(true);
+        let qna_show_id = Id::new("config.qna_show");
+        let qna_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(qna_show_id))
+            .unwrap_or(false);
+        let qna_id = Id::new("config.qna");
+        let qna = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<QnaConfig>(qna_id))
+            .unwrap_or_default();
+        let raffle_show_id = Id::new("config.raffle_show");
+        let raffle_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(raffle_show_id))
+            .unwrap_or(false);
+        let raffle_hide_entries_id =
+            Id::new("config.raffle_hide_entries");
+        let raffle_hide_entries = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(raffle_hide_entries_id))
+            .unwrap_or(true);
+        let revenue_show_id = Id::new("config.revenue_show");
+        let revenue_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(revenue_show_id))
+            .unwrap_or(false);
+        let user_aliases_id = Id::new("config.user_aliases");
+        let user_aliases = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<UserAliases>(user_aliases_id))
+            .unwrap_or_default();
+        let timeouts_show_id = Id::new("config.timeouts_show");
+        let timeouts_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(timeouts_show_id))
+            .unwrap_or(false);
+        let timeouts_id = Id::new("config.timeouts");
+        let timeouts = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<Timeouts>(timeouts_id))
+            .unwrap_or_default();
+        let flood_limit_id = Id::new("config.flood_limit");
+        let flood_limit = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<FloodLimit>(flood_limit_id))
+            .unwrap_or_default();
+        let history_show_id = Id::new("config.history_show");
+        let history_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(history_show_id))
+            .unwrap_or(false);
+        let preview_show_id = Id::new("config.preview_show");
+        let preview_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(preview_show_id))
+            .unwrap_or(false);
+        let chroma_show_id = Id::new("config.chroma_show");
+        let chroma_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(chroma_show_id))
+            .unwrap_or(false);
+        let chroma_open_id = Id::new("config.chroma_open");
+        let chroma_open = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(chroma_open_id))
+            .unwrap_or(false);
+        let chroma_color_id = Id::new("config.chroma_color");
+        let chroma_color = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<[u8; 3]>(chroma_color_id))
+            .unwrap_or(DEFAULT_CHROMA_COLOR);
+        let chroma_size_id = Id::new("config.chroma_size");
+        let chroma_size = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<[u32; 2]>(chroma_size_id))
+            .unwrap_or(DEFAULT_CHROMA_SIZE);
+        let queue_popout_id = Id::new("config.queue_popout");
+        let queue_popout = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(queue_popout_id))
+            .unwrap_or(false);
+        let queue_window_id = Id::new("config.queue_window");
+        let queue_window = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<[f32; 4]>(queue_window_id));
+        let check_updates_id = Id::new("config.check_updates");
+        let check_updates = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(check_updates_id))
+            .unwrap_or(false);
+        let qr_code_show_id = Id::new("config.qr_code_show");
+        let qr_code_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(qr_code_show_id))
+            .unwrap_or(false);
+        let debug_settings_show_id =
+            Id::new("config.debug_settings_show");
+        let debug_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(debug_settings_show_id))
+            .unwrap_or(false);
+        let file_log_level_id = Id::new("config.file_log_level");
+        if let Some(level) = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<String>(file_log_level_id))
+            .and_then(|it| it.parse().ok())
+        {
+            file_log.set_level(level);
+        }
+        let puffin_server_enable_id = Id::new("config.puffin_server");
+        let puffin_server_enable = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<bool>(puffin_server_enable_id)
+            })
+            .unwrap_or(false)
+            || std::env::var("PUFFIN_PROFILER")
+                .is_ok_and(|it| it == "true");
+        let runtime_config_id = Id::new("config.runtime");
+        let runtime_config = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<RuntimeConfig>(runtime_config_id)
+            })
+            .unwrap_or_default();
+        let log_config_id = Id::new("config.log");
+        let mut log_config = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<LogConfig>(log_config_id))
+            .unwrap_or_default();
+        let log_console_level_id = Id::new("config.log_console_level");
+        let log_console_level = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<String>(log_console_level_id))
+            .and_then(|it| it.parse().ok())
+            .unwrap_or(LevelFilter::INFO);
+        let hud_show_id = Id::new("config.hud_show");
+        let hud_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(hud_show_id))
+            .unwrap_or(false);
+        let stats_show_id = Id::new("config.stats_show");
+        let stats_show = cc
+            .egui_ctx
+            .data_mut(|d| d.get_persisted::<bool>(stats_show_id))
+            .unwrap_or(false);
+        let server_settings_show_id =
+            Id::new("config.server_settings_show");
+        let server_settings_show = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<bool>(server_settings_show_id)
+            })
+            .unwrap_or(false);
+        let server_config_id = Id::new("config.server");
+        let mut server_config = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<ServerConfig>(server_config_id)
+            })
+            .unwrap_or_default();
+        let mut err_messages = vec![];
+        let puffin_server = if puffin_server_enable {
+            debug_settings::start_puffin_server()
+                .map_err(|err| err_messages.push(format!("{err:?}")))
+                .ok()
+        } else {
+            None
+        };
+        if let Err(err) = secrets::check_persistence() {
+            err_messages.push(format!("{err:?}"));
+        }
+        let mut load_secret = |name| {
+            secrets::load_secret(name).unwrap_or_else(|err| {
+                err_messages.push(format!("{err:?}"));
+                None
+            })
+        };
+        let upload_target = load_secret(secrets::UPLOAD_TARGET);
+        server_config.api_token =
+            load_secret(secrets::API_TOKEN).unwrap_or_default();
+        server_config.operator.password =
+            load_secret(secrets::OPERATOR_PASSWORD).unwrap_or_default();
+        alert_config.obs_password =
+            load_secret(secrets::OBS_PASSWORD).unwrap_or_default();
+        let ws_client_config_id = Id::new("config.ws_client");
+        let mut ws_client_config = cc
+            .egui_ctx
+            .data_mut(|d| {
+                d.get_persisted::<WsClientConfig>(ws_client_config_id)
+            })
+            .unwrap_or_default();
+        let ws_client_headers = load_secret(secrets::WS_CLIENT_HEADERS);
+        ws_client_config.cookie =
+            load_secret(secrets::WS_CLIENT_COOKIE).unwrap_or_default();
+        ws_client_config.proxy.username =
+            load_secret(secrets::PROXY_USERNAME).unwrap_or_default();
+        ws_client_config.proxy.password =
+            load_secret(secrets::PROXY_PASSWORD).unwrap_or_default();
+        if let Some(json) = ws_client_headers {
+            ws_client_config.headers = serde_json::from_str(&json)
+                .context("failed to parse stored ws_client headers")
+                .unwrap_or_else(|err| {
+                    err_messages.push(format!("{err:?}"));
+                    vec![]
+                });
+        }
+        log_config.upload = upload_target
+            .map(|json| {
+                serde_json::from_str(&json)
+                    .context("failed to parse stored upload target")
+                    .unwrap_or_else(|err| {
+                        err_messages.push(format!("{err:?}"));
+                        UploadTarget::default()
+                    })
+            })
+            .unwrap_or_default();
+        // before the network thread starts a new session in the log
+        let unfinished_messages =
+            log::unfinished_messages(&log::default_path())
+                .unwrap_or_else(|err| {
+                    err_messages.push(format!("{err:?}"));
+                    vec![]
+                });
+        egui_extras::install_image_loaders(&cc.egui_ctx);
+        let thumbnail_loader = Arc::new(ThumbnailLoader::default());
+        thumbnail_loader.set_proxy(ws_client_config.proxy.clone());
+        cc.egui_ctx.add_bytes_loader(thumbnail_loader.clone());
+
+        let mut message = MessageQueue::new();
+        message.set_min_spacing(Duration::from_secs_f64(slow_mode_secs));
+        message.set_limit(queue_limit.clone());
+        let message = SharedQueue::new(message);
+
+        let log_upload_draft = log_config.upload.clone();
+        let mut app = Self {
+            network: Ok(NetworkState::new(
+                cc.egui_ctx.clone(),
+                message.clone(),
+                runtime_config.clone(),
+                log_config.clone(),
+                server_config.clone(),
+                ws_client_config.clone(),
+            )),
+            err_messages,
 
-            message: VecDeque::new(),
-            message_waiting: VecDeque::new(),
+            message,
+            queue_snapshot_saved_at: Instant::now(),
+            stale_queue_snapshot: None,
+            unfinished_messages: vec![],
 
             pause: false,
+            hold: false,
+            draining: false,
 
             msg_send_delay_secs,
             msg_send_delay_secs_id,
+            slow_mode_secs,
+            slow_mode_secs_id,
+            delay_presets_draft: format_delay_presets(&delay_presets),
+            delay_presets,
+            delay_presets_id,
+            delay_nudge_secs,
+            delay_nudge_secs_id,
 
             demo_settings_show,
             demo_settings_show_id,
@@ -83,13 +962,347 @@ impl App {
             demo_enable_id,
             demo_interval_secs,
             demo_interval_secs_id,
-            demo_source: DemoSource::default(),
+            demo_path_id,
+            demo_source,
+            demo_stress,
+            demo_stress_id,
+            demo_delay_secs,
+            demo_delay_secs_id,
+            demo_combo_window_secs,
+            demo_combo_window_secs_id,
+
+            source_settings_show,
+            source_settings_show_id,
+            ws_client_config,
+            ws_client_config_id,
+            ws_client_config_draft: WsClientConfig::default(),
+            ws_client_subprotocols_draft: String::new(),
+
+            stats_show,
+            stats_show_id,
+            latency: LatencyStats::default(),
+            rate_in: RateMeter::default(),
+            rate_out: RateMeter::default(),
+            rate_history: RateHistory::new(Instant::now()),
+            rate_history_paused: false,
+            activity: vec![],
+            keywords_show,
+            keywords_show_id,
+            keywords: Keywords::new(Duration::from_secs(
+                keywords_mins * 60,
+            )),
+            keywords_mins,
+            keywords_mins_id,
+            leaderboard_show,
+            leaderboard_show_id,
+            leaderboard: Leaderboard::default(),
+            leaderboard_sort: LeaderboardSort::default(),
+            poll_show,
+            poll_show_id,
+            poll: None,
+            poll_question_draft: String::new(),
+            poll_options_draft: String::new(),
+            poll_hide_votes,
+            poll_hide_votes_id,
+            qna_show,
+            qna_show_id,
+            qna,
+            qna_id,
+            questions: Questions::default(),
+            raffle_show,
+            raffle_show_id,
+            raffle: None,
+            raffle_keyword_draft: String::new(),
+            raffle_secs: 60,
+            raffle_hide_entries,
+            raffle_hide_entries_id,
+            revenue_show,
+            revenue_show_id,
+            revenue: Revenue::default(),
+            chatter_messages: None,
+            user_aliases,
+            user_aliases_id,
+            user_alias_draft: Default::default(),
+            timeouts_show,
+            timeouts_show_id,
+            timeouts,
+            timeouts_id,
+            timeout_username: String::new(),
+            timeout_custom_mins: 10,
+            hud_show,
+            hud_show_id,
+            frame_cpu_usage: None,
+
+            server_settings_show,
+            server_settings_show_id,
+            server_config_draft: server_config.clone(),
+            server_addrs_draft: String::new(),
+            allowed_ips_draft: String::new(),
+            denied_ips_draft: String::new(),
+            server_config,
+            server_config_id,
+            test_message_kind: MessageKind::Chat,
+
+            purge_confirm_show: false,
+            purge_reason: String::new(),
+
+            scheduled_draft: ScheduledDraft::default(),
+
+            redact_show: false,
+            redact_username: String::new(),
+            redact_mode: RedactMode::default(),
+            redact_preview: None,
+
+            timers_show: false,
+            timers: vec![],
+            timer_label_draft: String::new(),
+            timer_secs_draft: 300,
+            next_timer_id: 0,
+
+            queue_settings_show,
+            queue_settings_show_id,
+            queue_limit,
+            queue_limit_id,
+            hotkey_drafts: HotkeyAction::ALL.map(|action| {
+                hotkeys
+                    .get(action)
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            }),
+            hotkeys,
+            hotkeys_id,
+            global_hotkeys: None,
+
+            pause_schedule_show,
+            pause_schedule_show_id,
+            pause_schedule,
+            pause_schedule_id,
+
+            text_settings_show,
+            text_settings_show_id,
+            sanitizer,
+            sanitizer_id,
+            length_limit,
+            length_limit_id,
+            allowed_tags_draft: overlay_markup.allowed_tags.join(", "),
+            overlay_markup,
+            overlay_markup_id,
+            allowed_domains_draft: url_filter.allowed_domains.join(", "),
+            url_filter,
+            url_filter_id,
+            image_action,
+            image_action_id,
+            kind_filter,
+            kind_filter_id,
+            language_filter,
+            language_filter_id,
+            flag_words_draft: flag_words.words.join(", "),
+            chat_commands,
+            chat_commands_id,
+            command_cooldowns: CommandCooldowns::default(),
+            command_actions: vec![],
+            mentions_draft: mentions.names.join(", "),
+            mentions,
+            mentions_id,
+            queue_mentions_only: false,
+            word_list_draft: String::new(),
+            rule_tester_samples: String::new(),
+            word_list_syncs: WordListSyncs::new(),
+            flag_words,
+            flag_words_id,
+            flag_notifier: FlagNotifier::new({
+                let ctx = cc.egui_ctx.clone();
+                Notifier::new(move || ctx.request_repaint())
+            }),
+
+            alert_settings_show,
+            alert_settings_show_id,
+            alert_config,
+            alert_config_id,
+            obs_password_draft: String::new(),
+
+            midi_show,
+            midi_show_id,
+            midi_bindings,
+            midi_bindings_id,
+            midi_listener: None,
+            midi_learn: None,
+            midi_last_input: None,
+            show_thumbnails,
+            show_thumbnails_id,
+            thumbnail_loader,
+            gift_window_secs,
+            gift_window_secs_id,
+            gifts: GiftAggregator::default(),
+            flood_limit,
+            flood_limit_id,
+            flood: FloodCollapser::default(),
+
+            superchats_show,
+            superchats_show_id,
+            pin_durations_draft: pin_durations.format(),
+            pin_durations,
+            pin_durations_id,
+            active_superchats: ActiveSuperChats::default(),
+
+            history: ModerationHistory::default(),
+            ask_delete_reason,
+            ask_delete_reason_id,
+            awaiting_reason: vec![],
+            delete_reason_draft: String::new(),
+            history_show,
+            history_show_id,
+
+            preview_show,
+            preview_show_id,
+            overlay_preview: OverlayPreview::default(),
+            chroma_show,
+            chroma_show_id,
+            chroma_open,
+            chroma_open_id,
+            chroma_color,
+            chroma_color_id,
+            chroma_size,
+            chroma_size_id,
+            chroma_output: OverlayPreview::default(),
+            queue_popout,
+            queue_popout_id,
+            queue_window,
+            queue_window_id,
+            triage: None,
+            drop_action: None,
+            log_view: None,
+
+            palette: None,
+            check_updates,
+            check_updates_id,
+            update_rx: None,
+            update_status: String::new(),
+            available_update: None,
+            crash_report: crash::unseen_report(),
+
+            qr_code_show,
+            qr_code_show_id,
+            qr_code_url: String::new(),
+            qr_code: None,
+
+            debug_settings_show,
+            debug_settings_show_id,
+            file_log,
+            file_log_level_id,
+            puffin_server,
+            puffin_server_enable_id,
+            runtime_config,
+            runtime_config_id,
+            log_config,
+            log_config_id,
+            log_upload_draft,
+
+            log_console,
+            log_console_level,
+            log_console_level_id,
+        };
+        app.reset_source_settings_draft();
+        app.reset_server_settings_draft();
+        app.load_recovery(unfinished_messages);
+        if !app.midi_bindings.device.is_empty() {
+            app.connect_midi(&cc.egui_ctx);
+        }
+        if app.hotkeys.global {
+            app.grab_hotkeys(&cc.egui_ctx);
+        }
+        if app.check_updates {
+            app.start_update_check(&cc.egui_ctx);
+        }
+        app
+    }
+
+    /// Runs a message from the source, or demo, through the intake
+    /// filters into `queue`, due `delay_secs` after `received_at`.
+    fn ingest(
+        &mut self,
+        queue: &mut MessageQueue,
+        msg: Message,
+        received_at: Instant,
+        delay_secs: Option<f64>,
+    ) {
+        let Ok(ref network) = self.network else {
+            return;
+        };
+        let delete = |msg: Message, reason: &str| {
+            network.write_log_entry(
+                LogEntry::new(msg, LogEvent::Delete)
+                    .with_reason(Some(reason.to_owned())),
+            );
+        };
+        let msg = self.sanitizer.apply(msg);
+        self.rate_in.record(queue.now());
+        self.rate_history.record_incoming(queue.now());
+        self.keywords.record(queue.now(), &msg.text);
+        self.leaderboard.record(&msg, queue.now_utc());
+        self.revenue.record(&msg);
+        network.write_log(msg.clone(), LogEvent::Receive);
+        if self.kind_filter.drops(&msg) {
+            return delete(msg, "kind");
+        }
+        if self.language_filter.drops(&msg) {
+            return delete(msg, "language");
+        }
+        if self.image_action.drops(&msg) {
+            return delete(msg, "image");
+        }
+        if self.timeouts.mutes(&msg, queue.now_utc()) {
+            return delete(msg, "timeout");
+        }
+        if run_chat_command(
+            &self.chat_commands,
+            &mut self.command_cooldowns,
+            &mut self.command_actions,
+            &msg,
+            queue,
+            network,
+        ) {
+            return delete(msg, "command");
+        }
+        let voted = self.poll.as_mut().is_some_and(|it| it.vote(&msg));
+        if voted && self.poll_hide_votes {
+            return delete(msg, "vote");
+        }
+        let entered = self
+            .raffle
+            .as_mut()
+            .is_some_and(|it| it.enter(&msg, queue.now_utc()));
+        if entered && self.raffle_hide_entries {
+            return delete(msg, "raffle");
+        }
+        let msg = self.image_action.apply(msg);
+        if self.qna.is_question(&msg) {
+            if let Some(dropped) =
+                self.questions.push(msg, queue.now_utc())
+            {
+                network.write_log(dropped, LogEvent::Overflow);
+            }
+            return;
         }
+        let gift_window = Duration::from_secs_f64(self.gift_window_secs);
+        let Some(msg) = self.gifts.push(msg, received_at, gift_window)
+        else {
+            return;
+        };
+        notify_flagged(&self.flag_words, &self.flag_notifier, &msg);
+        let Some(msg) =
+            self.flood.push(msg, received_at, self.flood_limit)
+        else {
+            return;
+        };
+        let delay_secs = self.mentions.delay_secs(&msg, delay_secs);
+        queue.push_with_delay(msg, received_at, delay_secs);
     }
 
     fn update_network_err(&mut self, ctx: &EguiCtx) -> bool {
         if let Ok(ref mut network) = self.network {
             network.update_children_errors();
+            network.update_source_status();
 
             if let Some(err) = network.pull_err() {
                 let mut network =
@@ -157,7 +1370,14 @@ impl App {
                 CentralPanel::default().show(ctx, |ui| {
                     ui.label(msg);
                     if ui.button("Retry").clicked() {
-                        self.network = Ok(NetworkState::new(ctx.clone()));
+                        self.network = Ok(NetworkState::new(
+                            ctx.clone(),
+                            self.message.clone(),
+                            self.runtime_config.clone(),
+                            self.log_config.clone(),
+                            self.server_config.clone(),
+                            self.ws_client_config.clone(),
+                        ));
                     }
                 });
 
@@ -166,134 +1386,137 @@ impl App {
         }
     }
 
-    fn update_err_messages(&mut self, ctx: &EguiCtx) {
-        if !self.err_messages.is_empty() {
-            Window::new("Error messages")
+    fn update_demo_settings(&mut self, ctx: &EguiCtx) {
+        if self.demo_settings_show {
+            Window::new("Demo Settings")
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
-                    Grid::new("messages")
-                        .num_columns(1)
-                        .spacing([0.0, 4.0])
-                        .striped(true)
-                        .min_col_width(ui.available_size_before_wrap().x)
-                        .show(ui, |ui| {
-                            for msg in &self.err_messages {
-                                ui.label(msg);
-                                ui.end_row();
-                            }
+                    if ui
+                        .checkbox(&mut self.demo_enable, "Enable")
+                        .changed()
+                    {
+                        if self.demo_enable {
+                            self.demo_source.restart_scenario();
+                        }
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.demo_enable_id,
+                                self.demo_enable,
+                            )
                         });
+                    }
 
-                    ui.separator();
-
-                    //ui.label(&self.err_messages[0]);
-                    //
-                    //for msg in &self.err_messages[1..] {
-                    //    ui.separator();
-                    //    ui.label(msg);
-                    //}
-
-                    if ui.button("Clear").clicked() {
-                        self.err_messages.clear();
+                    ui.label("Send Interval(secs)");
+                    let res = ui.add(
+                        DragValue::new(&mut self.demo_interval_secs)
+                            .min_decimals(1)
+                            .max_decimals(2)
+                            .range(0.01..=1000.0)
+                            .speed(0.01),
+                    );
+                    if res.changed() {
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.demo_interval_secs_id,
+                                self.demo_interval_secs,
+                            )
+                        });
                     }
-                });
-        }
-    }
-}
 
-impl eframe::App for App {
-    fn update(&mut self, ctx: &EguiCtx, _frame: &mut eframe::Frame) {
-        self.update_err_messages(ctx);
-
-        if self.update_network_err(ctx) {
-            return;
-        };
-
-        let mut new_msgs = VecDeque::new();
-        let Ok(ref network) = self.network else {
-            ctx.request_discard("unexpected network err state");
-            return;
-        };
-        if self.demo_enable {
-            if let Some(msg) =
-                self.demo_source.pull_demo_msg(self.demo_interval_secs)
-            {
-                new_msgs.push_back(msg);
-            }
-            while network.pull_ws_message().is_some() {}
-        } else {
-            while let Some(msg) = network.pull_ws_message() {
-                new_msgs.push_back(msg);
-            }
-        }
-
-        if !self.pause {
-            while let Some(msg) = self.message_waiting.pop_front() {
-                self.message.push_back((msg, Instant::now(), false));
-            }
-            while let Some(msg) = new_msgs.pop_front() {
-                self.message.push_back((msg, Instant::now(), false));
-            }
-
-            while let Some((_, arrive_at, _)) = self.message.front() {
-                if arrive_at.elapsed().as_secs_f64()
-                    < self.msg_send_delay_secs
-                {
-                    break;
-                }
-                let Some((msg, arrive_at, delete)) =
-                    self.message.pop_front()
-                else {
-                    break;
-                };
-
-                assert!(
-                    arrive_at.elapsed().as_secs_f64()
-                        >= self.msg_send_delay_secs
-                );
-                assert!(!delete);
-
-                network.broadcast_ws_message(msg.clone());
-                network.write_log(msg, false);
-            }
-        } else {
-            self.message_waiting.extend(new_msgs);
-        }
-
-        if self.demo_settings_show {
-            Window::new("Demo Settings")
-                .collapsible(false)
-                .resizable(false)
-                .show(ctx, |ui| {
-                    if ui
-                        .checkbox(&mut self.demo_enable, "Enable")
-                        .changed()
-                    {
+                    ui.label("Delay(secs)");
+                    if delay_override_ui(ui, &mut self.demo_delay_secs) {
                         ui.data_mut(|d| {
                             d.insert_persisted(
-                                self.demo_enable_id,
-                                self.demo_enable,
+                                self.demo_delay_secs_id,
+                                self.demo_delay_secs,
                             )
                         });
                     }
 
-                    ui.label("Send Interval(secs)");
-                    let res = ui.add(
-                        DragValue::new(&mut self.demo_interval_secs)
-                            .min_decimals(1)
-                            .max_decimals(2)
-                            .range(0.01..=1000.0)
-                            .speed(0.01),
-                    );
-                    if res.changed() {
+                    ui.label("Combo window(secs)");
+                    if combo_window_ui(
+                        ui,
+                        &mut self.demo_combo_window_secs,
+                    ) {
                         ui.data_mut(|d| {
                             d.insert_persisted(
-                                self.demo_interval_secs_id,
-                                self.demo_interval_secs,
+                                self.demo_combo_window_secs_id,
+                                self.demo_combo_window_secs,
                             )
                         });
                     }
 
+                    ui.label("Demo file");
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            self.demo_source.path().display().to_string(),
+                        );
+                        if ui.button("Choose...").clicked() {
+                            let mut dialog = rfd::FileDialog::new()
+                                .add_filter("Demo", DEMO_EXTENSIONS);
+                            let dir = self.demo_source.path().parent();
+                            if let Some(dir) = dir {
+                                dialog = dialog.set_directory(dir);
+                            }
+                            if let Some(path) = dialog.pick_file() {
+                                self.demo_source.set_path(path.clone());
+                                ui.data_mut(|d| {
+                                    d.insert_persisted(
+                                        self.demo_path_id,
+                                        path,
+                                    )
+                                });
+                            }
+                        }
+                        if ui.button("Reload").clicked() {
+                            self.demo_source.reload();
+                        }
+                    });
+                    let scenario_text = self.demo_source.scenario().map(
+                        |(scenario, elapsed)| {
+                            let mut text = format!(
+                                "Scenario: {}/{} events",
+                                scenario.position(),
+                                scenario.len(),
+                            );
+                            if let Some(next_at) = scenario.next_at() {
+                                let next_in =
+                                    next_at.saturating_sub(elapsed);
+                                text += &format!(
+                                    ", next in {:.1}s",
+                                    next_in.as_secs_f64()
+                                );
+                            }
+                            text
+                        },
+                    );
+                    if let Some(text) = scenario_text {
+                        ui.horizontal(|ui| {
+                            ui.label(text);
+                            if ui.button("Restart").clicked() {
+                                self.demo_source.restart_scenario();
+                            }
+                        });
+                    }
+                    match self.demo_source.data_len() {
+                        Some(len) => {
+                            ui.label(format!("{len} entries loaded"));
+                        }
+                        None => {
+                            ui.label(
+                                RichText::new(
+                                    "Not loaded, using built-in messages",
+                                )
+                                .color(ui.style().visuals.warn_fg_color),
+                            );
+                        }
+                    }
+
+                    ui.separator();
+
+                    self.demo_stress_ui(ui);
+
                     ui.separator();
 
                     if ui.button("Close").clicked() {
@@ -307,19 +1530,442 @@ impl eframe::App for App {
                     }
                 });
         }
+    }
+
+    fn demo_stress_ui(&mut self, ui: &mut Ui) {
+        ui.label("Stress test");
+        let mut changed = false;
+        Grid::new("demo stress").num_columns(2).show(ui, |ui| {
+            let stress = &mut self.demo_stress;
 
-        CentralPanel::default().show(ctx, |ui| {
+            ui.label("Messages/sec");
+            changed |= ui
+                .add(
+                    DragValue::new(&mut stress.rate)
+                        .range(1.0..=10000.0)
+                        .speed(10.0),
+                )
+                .changed();
+            ui.end_row();
+
+            ui.label("Length(chars)");
             ui.horizontal(|ui| {
-                ui.label("Send delay(secs): ");
-                let drag_value_res = ui.add(
-                    DragValue::new(&mut self.msg_send_delay_secs)
-                        .min_decimals(1)
-                        .max_decimals(1)
-                        .range(0.1..=1000.0)
-                        .speed(0.1)
-                        .update_while_editing(false),
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut stress.min_len)
+                            .range(1..=stress.max_len),
+                    )
+                    .changed();
+                ui.label("to");
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut stress.max_len)
+                            .range(stress.min_len..=1000),
+                    )
+                    .changed();
+            });
+            ui.end_row();
+
+            ui.label("Duration(secs)");
+            changed |= ui
+                .add(
+                    DragValue::new(&mut stress.duration_secs)
+                        .range(1.0..=3600.0),
+                )
+                .changed();
+            ui.end_row();
+        });
+        if changed {
+            ui.data_mut(|d| {
+                d.insert_persisted(
+                    self.demo_stress_id,
+                    self.demo_stress.clone(),
+                )
+            });
+        }
+
+        let running =
+            self.demo_source.stress().map(|(stress, elapsed)| {
+                format!(
+                    "{}/{} sent, {:.1}s",
+                    stress.sent(),
+                    stress.config().total(),
+                    elapsed.as_secs_f64()
+                )
+            });
+        match running {
+            Some(text) => {
+                ui.horizontal(|ui| {
+                    ui.label(text);
+                    if ui.button("Stop").clicked() {
+                        self.demo_source.stop_stress();
+                    }
+                });
+            }
+            None => {
+                if ui.button("Start").clicked() {
+                    self.demo_source
+                        .start_stress(self.demo_stress.clone());
+                    if !self.demo_enable {
+                        self.demo_enable = true;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(self.demo_enable_id, true)
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rounded by [`round_send_delay`], and persisted.
+    fn set_send_delay(&mut self, ctx: &EguiCtx, delay: f64) {
+        self.msg_send_delay_secs = round_send_delay(delay);
+        ctx.data_mut(|d| {
+            d.insert_persisted(
+                self.msg_send_delay_secs_id,
+                self.msg_send_delay_secs,
+            )
+        });
+    }
+
+    fn update_err_messages(&mut self, ctx: &EguiCtx) {
+        if !self.err_messages.is_empty() {
+            Window::new("Error messages")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    Grid::new("messages")
+                        .num_columns(1)
+                        .spacing([0.0, 4.0])
+                        .striped(true)
+                        .min_col_width(ui.available_size_before_wrap().x)
+                        .show(ui, |ui| {
+                            for msg in &self.err_messages {
+                                ui.label(msg);
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.separator();
+
+                    //ui.label(&self.err_messages[0]);
+                    //
+                    //for msg in &self.err_messages[1..] {
+                    //    ui.separator();
+                    //    ui.label(msg);
+                    //}
+
+                    if ui.button("Clear").clicked() {
+                        self.err_messages.clear();
+                    }
+                });
+        }
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &EguiCtx, frame: &mut eframe::Frame) {
+        self.frame_cpu_usage = frame.info().cpu_usage;
+        // before any central panel, and shown even when the network fails
+        self.update_log_console(ctx);
+        self.update_err_messages(ctx);
+
+        if self.update_network_err(ctx) {
+            return;
+        };
+
+        self.update_demo_settings(ctx);
+        self.update_source_settings(ctx);
+        self.update_stats(ctx);
+        self.update_keywords(ctx);
+        self.update_leaderboard(ctx);
+        self.update_revenue(ctx);
+        self.update_poll(ctx);
+        self.update_qna(ctx);
+        self.update_raffle(ctx);
+        self.update_timeouts(ctx);
+        self.update_server_settings(ctx);
+        self.update_queue_restore(ctx);
+        self.update_unfinished_messages(ctx);
+        self.update_hotkeys(ctx);
+        self.update_palette(ctx);
+        self.update_history(ctx);
+        self.update_triage(ctx);
+        self.update_actions();
+        self.update_midi(ctx);
+        self.update_flags();
+        self.update_word_lists(ctx);
+        self.update_purge(ctx);
+        self.update_delete_reason(ctx);
+        self.update_redact(ctx);
+        self.update_timers(ctx);
+        self.update_queue_settings(ctx);
+        self.update_pause_schedule(ctx);
+        self.update_text_settings(ctx);
+        self.update_alert_settings(ctx);
+        self.update_superchats(ctx);
+        self.update_preview(ctx);
+        self.update_chroma(ctx);
+        self.update_queue_window(ctx);
+        self.update_update_check(ctx);
+        self.update_crash_report(ctx);
+        self.update_dropped_files(ctx);
+        self.update_log_viewer(ctx);
+        self.update_qr_code(ctx);
+        self.update_debug_settings(ctx);
+        self.save_queue_snapshot(false);
+
+        let Ok(ref mut network) = self.network else {
+            ctx.request_discard("unexpected network err state");
+            return;
+        };
+        self.demo_source.poll_changes();
+        let gift_window = Duration::from_secs_f64(self.gift_window_secs);
+        // a clone, so the lock leaves self free to ingest into it
+        let message = self.message.clone();
+        let mut queue = message.lock();
+        if self.draining {
+            network.pull_ws_messages(usize::MAX);
+        } else if self.demo_enable {
+            if !network.pull_ws_messages(usize::MAX).is_empty() {
+                network.ws_client_state.on_message(Instant::now());
+            }
+            while !queue.pauses_sources() {
+                let Some(msg) = self
+                    .demo_source
+                    .pull_demo_msg(self.demo_interval_secs)
+                else {
+                    break;
+                };
+                let now = queue.now();
+                self.ingest(&mut queue, msg, now, self.demo_delay_secs);
+            }
+            if let Some((scenario, elapsed)) = self.demo_source.scenario()
+            {
+                if let Some(next_at) = scenario.next_at() {
+                    ctx.request_repaint_after(
+                        next_at.saturating_sub(elapsed),
+                    );
+                }
+            }
+        } else {
+            let batch = network.pull_ws_messages(queue.source_room());
+            if !batch.is_empty() {
+                network.ws_client_state.on_message(Instant::now());
+            }
+            let delay_secs = self.ws_client_config.delay_secs;
+            for (msg, received_at) in batch {
+                self.ingest(&mut queue, msg, received_at, delay_secs);
+            }
+        }
+        // again, ingesting needed all of self
+        let Ok(ref mut network) = self.network else {
+            return;
+        };
+        let summaries = if self.draining {
+            self.gifts.take_all()
+        } else {
+            self.gifts.take_due(queue.now(), gift_window)
+        };
+        let delay_secs = if self.demo_enable {
+            self.demo_delay_secs
+        } else {
+            self.ws_client_config.delay_secs
+        };
+        for summary in summaries {
+            if summary.gifts.len() > 1 {
+                for gift in summary.gifts {
+                    network.write_log(gift, LogEvent::Merge);
+                }
+                network.write_log(summary.msg.clone(), LogEvent::Receive);
+            }
+            queue.push_with_delay(
+                summary.msg,
+                summary.received_at,
+                delay_secs,
+            );
+        }
+        let floods = if self.draining {
+            self.flood.take_all()
+        } else {
+            self.flood.take_due(queue.now(), self.flood_limit)
+        };
+        for summary in floods {
+            for msg in summary.messages {
+                network.write_log(msg, LogEvent::Merge);
+            }
+            network.write_log(summary.msg.clone(), LogEvent::Receive);
+            queue.push_with_delay(
+                summary.msg,
+                summary.received_at,
+                delay_secs,
+            );
+        }
+        let next_due = [
+            self.gifts.next_due(gift_window),
+            self.flood.next_due(self.flood_limit),
+        ];
+        if let Some(due) = next_due.into_iter().flatten().min() {
+            ctx.request_repaint_after(
+                due.saturating_duration_since(queue.now()),
+            );
+        }
+        for msg in queue.take_overflowed() {
+            network.write_log(msg, LogEvent::Overflow);
+        }
+        if queue.pauses_sources() {
+            // nothing else wakes us up once there is room again
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+
+        let now = queue.now();
+        let schedule_state = self
+            .pause_schedule
+            .state(queue.now_utc().with_timezone(&Local).time());
+        // holding still under the pointer is too brief to mark
+        let paused = self.hold || schedule_state.is_paused();
+        if paused != self.rate_history_paused {
+            self.rate_history_paused = paused;
+            let mark = if paused {
+                RateMark::Paused
+            } else {
+                RateMark::Resumed
+            };
+            self.rate_history.mark(now, mark);
+        }
+        let waiting_len = queue.waiting_len();
+        let pending_len = queue.len() + waiting_len;
+        drop(queue);
+        if schedule_state.next_change().is_some() {
+            // keeps the countdown ticking, the releaser catches the
+            // window edges on its own
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+
+        let combo_window = Duration::from_secs_f64(if self.demo_enable {
+            self.demo_combo_window_secs
+        } else {
+            self.ws_client_config.combo_window_secs
+        });
+        network.set_release_config(ReleaseConfig {
+            delay_secs: self.msg_send_delay_secs,
+            paused: self.pause || self.hold || self.purge_confirm_show,
+            pause_schedule: self.pause_schedule.clone(),
+            url_filter: self.url_filter.clone(),
+            pin_durations: self.pin_durations.clone(),
+            length_limit: self.length_limit.clone(),
+            overlay_markup: self.overlay_markup.clone(),
+            combo_window,
+            alerts: self.alert_config.clone(),
+        });
+        for released in network.pull_released() {
+            if self.preview_show {
+                preview_released(
+                    &mut self.overlay_preview,
+                    ctx,
+                    &self.server_config.theme_vars,
+                    PREVIEW_SIZE,
+                    &self.length_limit,
+                    &released,
                 );
-                if drag_value_res.changed() {
+            }
+            if self.chroma_open {
+                preview_released(
+                    &mut self.chroma_output,
+                    ctx,
+                    &self.server_config.theme_vars,
+                    chroma_size(self.chroma_size),
+                    &self.length_limit,
+                    &released,
+                );
+            }
+            let Released {
+                filtered,
+                received_at,
+                released_at,
+                sent,
+                ..
+            } = released;
+            self.active_superchats.push(&filtered, released_at);
+            self.rate_out.record(released_at);
+            self.rate_history.record_forwarded(released_at);
+            if sent {
+                self.latency.record(
+                    released_at.saturating_duration_since(received_at),
+                );
+            }
+        }
+        self.active_superchats.expire(now);
+        if !self.active_superchats.is_empty() {
+            // countdowns, and the count on the button
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+
+        if self.draining {
+            if pending_len == 0 {
+                info!("drained, closing");
+                ctx.send_viewport_cmd(ViewportCommand::Close);
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+        }
+
+        // docking and popping out need all of self, set after the panel
+        let mut popout = None;
+        let central_panel = CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let label = ui.label("Send delay(secs): ");
+                let drag_value_res = ui
+                    .add(
+                        DragValue::new(&mut self.msg_send_delay_secs)
+                            .min_decimals(1)
+                            .max_decimals(1)
+                            .range(0.1..=1000.0)
+                            .speed(0.1)
+                            .update_while_editing(false),
+                    )
+                    .labelled_by(label.id);
+                let mut new_delay = None;
+                let nudge = self.delay_nudge_secs;
+                let nudge_down = ui.small_button("-");
+                nudge_down.widget_info(|| {
+                    WidgetInfo::labeled(
+                        WidgetType::Button,
+                        true,
+                        format!("Send delay down {nudge}s"),
+                    )
+                });
+                if nudge_down
+                    .on_hover_text(format!("-{nudge}s"))
+                    .clicked()
+                {
+                    new_delay = Some(self.msg_send_delay_secs - nudge);
+                }
+                let nudge_up = ui.small_button("+");
+                nudge_up.widget_info(|| {
+                    WidgetInfo::labeled(
+                        WidgetType::Button,
+                        true,
+                        format!("Send delay up {nudge}s"),
+                    )
+                });
+                if nudge_up.on_hover_text(format!("+{nudge}s")).clicked()
+                {
+                    new_delay = Some(self.msg_send_delay_secs + nudge);
+                }
+                for &preset in &self.delay_presets {
+                    let selected = self.msg_send_delay_secs == preset;
+                    if ui
+                        .selectable_label(selected, format!("{preset}s"))
+                        .clicked()
+                    {
+                        new_delay = Some(preset);
+                    }
+                }
+                if let Some(delay) = new_delay {
+                    self.msg_send_delay_secs = round_send_delay(delay);
+                }
+                if new_delay.is_some() || drag_value_res.changed() {
                     ui.data_mut(|d| {
                         d.insert_persisted(
                             self.msg_send_delay_secs_id,
@@ -327,9 +1973,265 @@ impl eframe::App for App {
                         )
                     });
                 }
+                ui.separator();
+                let label = ui.label("Slow mode(secs): ");
+                let drag_value_res = ui
+                    .add(
+                        DragValue::new(&mut self.slow_mode_secs)
+                            .min_decimals(1)
+                            .max_decimals(1)
+                            .range(0.0..=60.0)
+                            .speed(0.1)
+                            .update_while_editing(false),
+                    )
+                    .on_hover_text(
+                        "Minimum time between forwarded messages, 0 to \
+                         disable",
+                    )
+                    .labelled_by(label.id);
+                if drag_value_res.changed() {
+                    self.message.lock().set_min_spacing(
+                        Duration::from_secs_f64(self.slow_mode_secs),
+                    );
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.slow_mode_secs_id,
+                            self.slow_mode_secs,
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let purge_btn = Button::new(
+                    RichText::new("Purge queue")
+                        .color(ui.style().visuals.error_fg_color)
+                        .strong(),
+                );
+                if ui
+                    .add(purge_btn)
+                    .on_hover_text(
+                        "Delete every pending message without forwarding",
+                    )
+                    .clicked()
+                {
+                    self.purge_confirm_show = true;
+                }
 
                 ui.separator();
 
+                if ui.button("Source Settings").clicked() {
+                    self.source_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.source_settings_show_id,
+                            self.source_settings_show,
+                        )
+                    });
+                }
+                if ui.button("Server Settings").clicked() {
+                    self.server_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.server_settings_show_id,
+                            self.server_settings_show,
+                        )
+                    });
+                }
+                if ui.button("Queue Settings").clicked() {
+                    self.queue_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.queue_settings_show_id,
+                            self.queue_settings_show,
+                        )
+                    });
+                }
+                if ui.button("Text Settings").clicked() {
+                    self.text_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.text_settings_show_id,
+                            self.text_settings_show,
+                        )
+                    });
+                }
+                if ui.button("Alert Settings").clicked() {
+                    self.alert_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.alert_settings_show_id,
+                            self.alert_settings_show,
+                        )
+                    });
+                }
+                if ui.button("MIDI").clicked() {
+                    self.midi_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.midi_show_id,
+                            self.midi_show,
+                        )
+                    });
+                }
+                if ui.button("Pause Schedule").clicked() {
+                    self.pause_schedule_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.pause_schedule_show_id,
+                            self.pause_schedule_show,
+                        )
+                    });
+                }
+                if ui.button("Stats").clicked() {
+                    self.stats_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.stats_show_id,
+                            self.stats_show,
+                        )
+                    });
+                }
+                if ui.button("Redact User").clicked() {
+                    self.redact_show = true;
+                }
+                if ui.button("Keywords").clicked() {
+                    self.keywords_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.keywords_show_id,
+                            self.keywords_show,
+                        )
+                    });
+                }
+                if ui.button("Leaderboard").clicked() {
+                    self.leaderboard_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.leaderboard_show_id,
+                            self.leaderboard_show,
+                        )
+                    });
+                }
+                if ui.button("Revenue").clicked() {
+                    self.revenue_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.revenue_show_id,
+                            self.revenue_show,
+                        )
+                    });
+                }
+                if ui.button("Poll").clicked() {
+                    self.poll_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.poll_show_id,
+                            self.poll_show,
+                        )
+                    });
+                }
+                if ui.button("Q&A").clicked() {
+                    self.qna_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.qna_show_id,
+                            self.qna_show,
+                        )
+                    });
+                }
+                if ui.button("Raffle").clicked() {
+                    self.raffle_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.raffle_show_id,
+                            self.raffle_show,
+                        )
+                    });
+                }
+                if ui.button("Timeouts").clicked() {
+                    self.timeouts_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.timeouts_show_id,
+                            self.timeouts_show,
+                        )
+                    });
+                }
+                if ui.button("Timers").clicked() {
+                    self.timers_show = true;
+                }
+                if ui.button("History").clicked() {
+                    self.history_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.history_show_id,
+                            self.history_show,
+                        )
+                    });
+                }
+                if ui.button("Debug Settings").clicked() {
+                    self.debug_settings_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.debug_settings_show_id,
+                            self.debug_settings_show,
+                        )
+                    });
+                }
+                let superchats = match self.active_superchats.len() {
+                    0 => "SuperChats".to_owned(),
+                    len => format!("SuperChats ({len})"),
+                };
+                if ui.button(superchats).clicked() {
+                    self.superchats_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.superchats_show_id,
+                            self.superchats_show,
+                        )
+                    });
+                }
+                if ui.button("Preview").clicked() {
+                    self.preview_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.preview_show_id,
+                            self.preview_show,
+                        )
+                    });
+                }
+                if ui.button("Chroma output").clicked() {
+                    self.chroma_show = true;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.chroma_show_id,
+                            self.chroma_show,
+                        )
+                    });
+                }
+                if ui.button("Restart all").clicked() {
+                    if let Err(err) = network.restart_all() {
+                        self.err_messages.push(format!("{err:?}"));
+                    }
+                    self.draining = false;
+                }
+                let drain_btn = ui
+                    .add_enabled(
+                        !self.draining,
+                        Button::new("Drain and quit"),
+                    )
+                    .on_hover_text(
+                        "Stop receiving, forward everything still \
+                         queued, then quit",
+                    );
+                if drain_btn.clicked() {
+                    if let Err(err) = network.drain() {
+                        self.err_messages.push(format!("{err:?}"));
+                    } else {
+                        self.draining = true;
+                    }
+                }
                 if ui.button("Demo Settings").clicked() {
                     self.demo_settings_show = true;
                     ui.data_mut(|d| {
@@ -346,113 +2248,129 @@ impl eframe::App for App {
                     );
                 }
 
+                ui.separator();
+                source_status_ui(ui, &network.ws_client_state);
+                let dropped = network.ws_message_stats().dropped;
+                if dropped > 0 {
+                    ui.label(
+                        RichText::new(format!("{dropped} dropped"))
+                            .color(ui.style().visuals.warn_fg_color),
+                    )
+                    .on_hover_text(
+                        "Source messages dropped because the inbound \
+                         queue was full",
+                    );
+                }
+
                 ui.separator();
 
-                if self.pause {
+                if self.draining {
                     ui.label(
                         RichText::new(format!(
-                            "Paused, {} message pending",
-                            self.message_waiting.len()
+                            "Draining, {pending_len} message left"
+                        ))
+                        .color(ui.style().visuals.warn_fg_color),
+                    );
+                } else if self.pause
+                    || self.hold
+                    || schedule_state.is_paused()
+                {
+                    ui.label(
+                        RichText::new(format!(
+                            "Paused, {waiting_len} message pending"
                         ))
                         .color(ui.style().visuals.warn_fg_color),
                     );
                 } else {
                     ui.label("Receiving");
                 }
+                schedule_status_ui(ui, &schedule_state);
             });
 
-            ui.separator();
-
-            ScrollArea::vertical().show(ui, |ui| {
-                ui.set_width(ui.available_width());
-                let mut btn_x_range: Range<f32> = f32::INFINITY..0.0;
-                let mut btn_press = false;
+            {
+                let queue = self.message.lock();
+                if queue.is_full() {
+                    queue_full_banner(ui, &queue);
+                }
+            }
+            scheduled_ui(
+                ui,
+                &self.message,
+                network,
+                &mut self.scheduled_draft,
+                &self.sanitizer,
+                &self.length_limit,
+            );
 
-                for (idx, (msg, arrive_at, delete)) in
-                    self.message.iter_mut().rev().enumerate()
-                {
-                    let mut rect = ui
-                        .horizontal(|ui| {
-                            let btn_res = ui.button("Delete");
-                            let btn_rect = btn_res.rect;
-                            btn_x_range.start =
-                                btn_x_range.start.min(btn_rect.left());
-                            btn_x_range.end =
-                                btn_x_range.end.max(btn_rect.right());
-                            btn_press |= btn_res
-                                .is_pointer_button_down_on()
-                                || btn_res.clicked();
-
-                            ui.label(msg.as_str());
-
-                            if btn_res.clicked() {
-                                *delete = true;
-                            }
-                        })
-                        .response
-                        .rect;
-
-                    // draw bg
-                    rect.set_width(ui.available_width());
-                    let the_other_row = idx % 2 == 0;
-                    if the_other_row {
-                        ui.painter().rect_filled(
-                            rect,
-                            2.0,
-                            ui.style().visuals.faint_bg_color,
-                        );
-                    }
+            ui.separator();
 
-                    // draw timeout progress
-                    let progress = (arrive_at.elapsed().as_secs_f64()
-                        / self.msg_send_delay_secs)
-                        .min(1.0)
-                        as f32;
-                    rect.set_width(rect.width() * progress);
-                    rect = rect.with_min_y(rect.bottom());
-                    rect.set_height(ui.spacing().item_spacing.y);
-                    ui.painter().rect_filled(
-                        rect,
-                        1.0,
-                        ui.style()
-                            .visuals
-                            .warn_fg_color
-                            .gamma_multiply(0.4),
+            if self.queue_popout {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        "The queue is popped out into its own window",
                     );
-                    if progress < 1.0 {
-                        ui.ctx().request_repaint();
+                    if ui.button("Dock").clicked() {
+                        popout = Some(false);
                     }
-                }
-
-                self.message.iter().for_each(|(msg, _, delete)| {
-                    if *delete {
-                        network.write_log(msg.clone(), true);
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Pop out")
+                        .on_hover_text(
+                            "Show the queue in a window of its own, e.g. \
+                             on another monitor",
+                        )
+                        .clicked()
+                    {
+                        popout = Some(true);
                     }
+                    triage_button(ui, &mut self.triage);
+                    copy_all_button(ui, &self.message);
+                    queue_tabs_ui(ui, &mut self.queue_mentions_only);
                 });
-                self.message.retain(|(_, _, delete)| !delete);
-
-                let btn_area = Id::new("message list button area");
-                let hovered = ui
-                    .interact(
-                        Rect::from_min_max(
-                            pos2(btn_x_range.start, ui.clip_rect().top()),
-                            pos2(
-                                btn_x_range.end,
-                                ui.clip_rect().bottom(),
-                            ),
-                        ),
-                        btn_area,
-                        Sense::hover(),
-                    )
-                    .hovered();
+                let rows = QueueRows {
+                    flag_words: &self.flag_words,
+                    length_limit: &self.length_limit,
+                    url_filter: &self.url_filter,
+                    aliases: &self.user_aliases,
+                    languages: &self.language_filter,
+                    mentions: &self.mentions,
+                    mentions_only: self.queue_mentions_only,
+                    show_thumbnails: self.show_thumbnails,
+                };
+                self.pause = queue_list_ui(
+                    ui,
+                    &self.message,
+                    &rows,
+                    self.triage.as_mut(),
+                );
+            }
 
-                self.pause = hovered || btn_press;
-            })
+            let (deleted, now) = {
+                let mut queue = self.message.lock_quiet();
+                (queue.take_deleted_entries(), queue.now_utc())
+            };
+            for entry in deleted {
+                if self.ask_delete_reason {
+                    self.awaiting_reason.push(entry.msg.clone());
+                } else {
+                    network
+                        .write_log(entry.msg.clone(), LogEvent::Delete);
+                }
+                self.history.push(Moderation::Delete(entry), now);
+            }
         });
+        if let Some(popout) = popout {
+            self.set_queue_popout(ctx, popout);
+        }
+        self.update_hud(ctx, central_panel.response.rect);
     }
 
     fn on_exit(&mut self) {
         info!("exiting");
+        self.save_queue_snapshot(true);
+        self.skip_delete_reasons();
         let mut network = Err(anyhow!("stopping network"));
         std::mem::swap(&mut self.network, &mut network);
         if let Ok(network) = network {
@@ -462,18 +2380,215 @@ impl eframe::App for App {
     }
 }
 
+fn thumbnails_ui(ui: &mut Ui, msg: &Message) {
+    for attachment in &msg.attachments {
+        let Some(src) = attachment.src() else {
+            continue;
+        };
+        ui.add(Image::new(src).max_height(THUMBNAIL_HEIGHT))
+            .on_hover_text(attachment.kind.name());
+    }
+}
+
+/// Text over the length limit is shown truncated, with the full text on
+/// hover.
+/// `alias` of the sender, if any, goes after their name.
+fn message_label(
+    ui: &mut Ui,
+    msg: &Message,
+    limit: &LengthLimit,
+    alias: Option<&UserAlias>,
+) {
+    let kind_color = match msg.kind {
+        MessageKind::Chat => None,
+        MessageKind::Gift => Some(Color32::LIGHT_BLUE),
+        MessageKind::SuperChat => Some(Color32::GOLD),
+        MessageKind::Follow
+        | MessageKind::Subscription
+        | MessageKind::Raid => Some(Color32::LIGHT_GREEN),
+    };
+    if let Some(color) = kind_color {
+        ui.label(RichText::new(msg.kind.name()).color(color).small());
+    }
+    if let Some(ref username) = msg.username {
+        match alias {
+            Some(alias) => {
+                let text = if alias.alias.is_empty() {
+                    format!("{username}:")
+                } else {
+                    format!("{username} ({}):", alias.alias)
+                };
+                let res = ui.label(RichText::new(text).strong());
+                if !alias.note.is_empty() {
+                    res.on_hover_text(&alias.note);
+                }
+            }
+            None => {
+                ui.label(RichText::new(format!("{username}:")).strong());
+            }
+        }
+    }
+    if limit.exceeded_by(&msg.text) {
+        ui.label(limit.truncate(&msg.text))
+            .on_hover_text(msg.text.as_str());
+    } else {
+        ui.label(msg.text.as_str());
+    }
+}
+
+fn queue_full_banner(ui: &mut Ui, queue: &MessageQueue) {
+    let limit = queue.limit();
+    let action = match limit.policy {
+        OverflowPolicy::DropOldest => "dropping the oldest message",
+        OverflowPolicy::DropNewest => "dropping new messages",
+        OverflowPolicy::PauseSources => "sources paused",
+    };
+    let mut text =
+        format!("Queue full ({} message), {action}", limit.max_len);
+    if queue.overflow_count() > 0 {
+        text += &format!(", {} dropped so far", queue.overflow_count());
+    }
+    ui.label(
+        RichText::new(text)
+            .color(ui.style().visuals.error_fg_color)
+            .strong(),
+    );
+}
+
+fn source_status_ui(ui: &mut Ui, state: &SourceState) {
+    let now = Instant::now();
+    let color = match state.status() {
+        SourceStatus::Connecting => ui.style().visuals.warn_fg_color,
+        SourceStatus::Connected => Color32::LIGHT_GREEN,
+        SourceStatus::Disconnected(_) => {
+            ui.style().visuals.error_fg_color
+        }
+    };
+    let mut text = format!(
+        "Source: {} {}",
+        state.status().name(),
+        format_duration(state.elapsed(now)),
+    );
+    if let Some(age) = state.last_message_age(now) {
+        text += &format!(", last message {} ago", format_duration(age));
+    }
+    let res = ui.label(RichText::new(text).color(color));
+    if let SourceStatus::Disconnected(Some(err)) = state.status() {
+        res.on_hover_text(err);
+    }
+    ui.ctx().request_repaint_after(Duration::from_secs(1));
+}
+
+/// Returns whether it changed.
+fn combo_window_ui(ui: &mut Ui, window_secs: &mut f64) -> bool {
+    ui.add(
+        DragValue::new(window_secs)
+            .min_decimals(1)
+            .max_decimals(1)
+            .range(0.0..=60.0)
+            .speed(0.1),
+    )
+    .on_hover_text(
+        "Repeats of the same chat text within this many seconds are \
+         sent as one counted combo, 0 for off",
+    )
+    .changed()
+}
+
+/// Own send delay of a source, unchecked to use the global one. Returns
+/// whether it changed.
+fn delay_override_ui(ui: &mut Ui, delay_secs: &mut Option<f64>) -> bool {
+    ui.horizontal(|ui| {
+        let mut enabled = delay_secs.is_some();
+        let mut changed = ui
+            .checkbox(&mut enabled, "Own delay")
+            .on_hover_text("Use this instead of the global send delay")
+            .changed();
+        if changed {
+            *delay_secs = enabled.then_some(10.0);
+        }
+        if let Some(delay_secs) = delay_secs {
+            changed |= ui
+                .add(
+                    DragValue::new(delay_secs)
+                        .min_decimals(1)
+                        .max_decimals(1)
+                        .range(0.0..=1000.0)
+                        .speed(0.1),
+                )
+                .changed();
+        }
+        changed
+    })
+    .inner
+}
+
+/// To the 0.1s resolution of the drag value, within its range.
+fn round_send_delay(delay: f64) -> f64 {
+    ((delay * 10.0).round() / 10.0).clamp(0.1, 1000.0)
+}
+
+fn format_delay_presets(presets: &[f64]) -> String {
+    presets
+        .iter()
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Comma separated positive seconds, `None` if any isn't one.
+fn parse_delay_presets(presets: &str) -> Option<Vec<f64>> {
+    presets
+        .split(',')
+        .map(str::trim)
+        .filter(|it| !it.is_empty())
+        .map(|it| it.parse::<f64>().ok().filter(|it| *it > 0.0))
+        .collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
 struct NetworkState {
     network: Network,
     pub network_server_err: Option<anyhow::Error>,
     pub network_ws_client_err: Option<anyhow::Error>,
+    pub ws_client_state: SourceState,
 }
 
 impl NetworkState {
-    pub fn new(egui_ctx: EguiCtx) -> Self {
+    pub fn new(
+        egui_ctx: EguiCtx,
+        queue: SharedQueue,
+        runtime_config: RuntimeConfig,
+        log_config: LogConfig,
+        server_config: ServerConfig,
+        ws_client_config: WsClientConfig,
+    ) -> Self {
         Self {
-            network: Network::new(egui_ctx),
+            network: Network::new(
+                Notifier::new(move || egui_ctx.request_repaint()),
+                queue,
+                runtime_config,
+                log_config,
+                server_config,
+                ws_client_config,
+            ),
             network_server_err: None,
             network_ws_client_err: None,
+            ws_client_state: SourceState::new(Instant::now()),
+        }
+    }
+
+    pub fn update_source_status(&mut self) {
+        while let Some(status) = self.network.pull_ws_client_status() {
+            self.ws_client_state.set_status(status, Instant::now());
         }
     }
 
@@ -490,11 +2605,37 @@ impl NetworkState {
     delegate::delegate! {
         to self.network {
             pub fn pull_err(&self) -> Option<anyhow::Error>;
-            pub fn pull_ws_message(&self) -> Option<String>;
-            pub fn broadcast_ws_message(&self, msg: String);
-            pub fn write_log(&self, msg: String, is_delete: bool);
+            pub fn pull_ws_messages(
+                &self,
+                max: usize,
+            ) -> Vec<(Message, Instant)>;
+            pub fn broadcast_ws_message(&self, msg: &Message) -> bool;
+            pub fn broadcast_timer(&self, timer: &TimerFrame) -> bool;
+            pub fn broadcast_poll(&self, poll: &PollFrame) -> bool;
+            pub fn set_release_config(&self, config: ReleaseConfig);
+            pub fn pull_released(&self) -> Vec<Released>;
+            pub fn pull_action_requests(&self) -> Vec<ActionRequest>;
+            pub fn ws_message_stats(&self) -> ChannelStats;
+            pub fn log_stats(&self) -> ChannelStats;
+            pub fn ws_broadcast_stats(&self) -> ChannelStats;
+            pub fn write_log(&self, msg: Message, event: LogEvent);
+            pub fn write_log_entry(&self, entry: LogEntry);
+            pub fn redact_log(
+                &self,
+                redaction: Redaction,
+            ) -> anyhow::Result<Vec<Found>>;
             pub fn restart_server(&self) -> anyhow::Result<()>;
+            pub fn restart_all(&self) -> anyhow::Result<()>;
+            pub fn drain(&self) -> anyhow::Result<()>;
+            pub fn set_server_config(
+                &self,
+                config: ServerConfig,
+            ) -> anyhow::Result<()>;
             pub fn restart_ws_client(&self) -> anyhow::Result<()>;
+            pub fn set_ws_client_config(
+                &self,
+                config: WsClientConfig,
+            ) -> anyhow::Result<()>;
             pub fn stop(self);
         }
     }
@@ -0,0 +1,50 @@
+use blooming_light_core::network::{Action, ActionState};
+use chrono::Local;
+
+use super::App;
+
+impl App {
+    /// Runs what came in over the action API, replying to each request
+    /// with the state after it, and what chat commands asked for.
+    pub(super) fn update_actions(&mut self) {
+        for action in std::mem::take(&mut self.command_actions) {
+            self.run_action(action, "chat command");
+        }
+        let requests = match self.network {
+            Ok(ref network) => network.pull_action_requests(),
+            Err(_) => return,
+        };
+        for request in requests {
+            if let Some(action) = request.action {
+                self.run_action(action, "action API");
+            }
+            request.reply(self.action_state());
+        }
+    }
+
+    /// `by` is given as the reason of a purge.
+    fn run_action(&mut self, action: Action, by: &str) {
+        match action {
+            Action::TogglePause => self.hold = !self.hold,
+            Action::SendNext => self.approve_next(),
+            Action::Purge => {
+                self.purge_reason = by.to_owned();
+                self.purge();
+            }
+        }
+    }
+
+    fn action_state(&self) -> ActionState {
+        let queue = self.message.lock_quiet();
+        let scheduled = self
+            .pause_schedule
+            .state(queue.now_utc().with_timezone(&Local).time())
+            .is_paused();
+        ActionState {
+            paused: self.pause || self.hold || scheduled,
+            pending: queue.len()
+                + queue.waiting_len()
+                + queue.scheduled_len(),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use blooming_light_core::log::{self, report};
+use chrono::{DateTime, Duration, Local, Timelike, Utc};
+use eframe::egui::{pos2, vec2, Align2, FontId, Rect, Sense, Ui};
+
+use super::App;
+
+/// Minutes in a row of the heatmap.
+const ROW_MINUTES: usize = 60;
+/// Cells are twice as tall.
+const CELL_SIZE: f32 = 6.0;
+/// Room for the time a row starts at.
+const LABEL_WIDTH: f32 = 40.0;
+
+impl App {
+    /// Messages received per minute of the last session in the log, an
+    /// hour a row, busier minutes brighter.
+    pub(super) fn activity_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Activity");
+            if ui
+                .button("Load")
+                .on_hover_text(
+                    "Count the messages received in each minute of the \
+                     last session in the message log",
+                )
+                .clicked()
+            {
+                self.load_activity();
+            }
+        });
+        let Some(&(first, _)) = self.activity.first() else {
+            return;
+        };
+
+        let max = self.activity.iter().map(|it| it.1).max().unwrap_or(0);
+        // rows start on the hour, so columns line up with the clock
+        let offset = minute_of_hour(first);
+        let rows = (offset + self.activity.len()).div_ceil(ROW_MINUTES);
+        let (rect, res) = ui.allocate_exact_size(
+            vec2(
+                LABEL_WIDTH + CELL_SIZE * ROW_MINUTES as f32,
+                CELL_SIZE * 2.0 * rows as f32,
+            ),
+            Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        let visuals = ui.style().visuals.clone();
+        let cell = |idx: usize| {
+            let idx = offset + idx;
+            let (row, col) = (idx / ROW_MINUTES, idx % ROW_MINUTES);
+            Rect::from_min_size(
+                rect.min
+                    + vec2(
+                        LABEL_WIDTH + CELL_SIZE * col as f32,
+                        CELL_SIZE * 2.0 * row as f32,
+                    ),
+                vec2(CELL_SIZE, CELL_SIZE * 2.0),
+            )
+            .shrink(0.5)
+        };
+        for row in 0..rows {
+            let start = first.with_timezone(&Local)
+                + Duration::minutes(
+                    (row * ROW_MINUTES) as i64 - offset as i64,
+                );
+            painter.text(
+                pos2(
+                    rect.left(),
+                    rect.top() + CELL_SIZE * (2 * row + 1) as f32,
+                ),
+                Align2::LEFT_CENTER,
+                start.format("%H:%M"),
+                FontId::monospace(9.0),
+                visuals.weak_text_color(),
+            );
+        }
+        let mut hovered = None;
+        for (idx, &(start, count)) in self.activity.iter().enumerate() {
+            let cell = cell(idx);
+            let intensity = count as f32 / max.max(1) as f32;
+            let color = if count == 0 {
+                visuals.faint_bg_color
+            } else {
+                visuals
+                    .selection
+                    .bg_fill
+                    .gamma_multiply(0.2 + 0.8 * intensity)
+            };
+            painter.rect_filled(cell, 1.0, color);
+            if res.hover_pos().is_some_and(|it| cell.contains(it)) {
+                hovered = Some((start, count));
+            }
+        }
+        if let Some((start, count)) = hovered {
+            res.on_hover_text(format!(
+                "{} {count} message",
+                start.with_timezone(&Local).format("%H:%M"),
+            ));
+        }
+    }
+
+    fn load_activity(&mut self) {
+        match report::last_session(&log::default_path()) {
+            Ok(entries) => self.activity = report::activity(&entries),
+            Err(err) => self.err_messages.push(format!("{err:?}")),
+        }
+    }
+}
+
+fn minute_of_hour(ts: DateTime<Utc>) -> usize {
+    ts.with_timezone(&Local).minute() as usize
+}
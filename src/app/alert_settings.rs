@@ -0,0 +1,187 @@
+use blooming_light_core::{alert::Alert, message::MessageKind};
+use eframe::egui::{
+    Button, Context as EguiCtx, Grid, TextEdit, Ui, Window,
+};
+use tracing::info;
+
+use super::{secrets, App};
+
+impl App {
+    pub(super) fn update_alert_settings(&mut self, ctx: &EguiCtx) {
+        if !self.alert_settings_show {
+            return;
+        }
+
+        Window::new("Alert Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Run as a message of the kind is forwarded.");
+                let mut changed = false;
+                Grid::new("alert settings")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.label("Sound");
+                        ui.label("Notify");
+                        ui.label("Speak");
+                        ui.label("OBS request");
+                        ui.label("OBS request data");
+                        ui.end_row();
+
+                        for kind in MessageKind::ALL {
+                            ui.label(kind.name());
+                            let alert = self
+                                .alert_config
+                                .alerts
+                                .entry(kind)
+                                .or_default();
+                            changed |= alert_row(ui, alert);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+
+                Grid::new("obs settings").num_columns(2).show(ui, |ui| {
+                    ui.label("OBS websocket");
+                    changed |= ui
+                        .add(
+                            TextEdit::singleline(
+                                &mut self.alert_config.obs_url,
+                            )
+                            .hint_text("ws://127.0.0.1:4455"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("OBS password");
+                    self.obs_password_ui(ui);
+                    ui.end_row();
+                });
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.alert_config_id,
+                            self.alert_config.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.alert_settings_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.alert_settings_show_id,
+                            self.alert_settings_show,
+                        )
+                    });
+                }
+            });
+    }
+
+    fn obs_password_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let password = &mut self.alert_config.obs_password;
+            ui.add(
+                TextEdit::singleline(&mut self.obs_password_draft)
+                    .password(true)
+                    .hint_text(if password.is_empty() {
+                        "none"
+                    } else {
+                        "set"
+                    })
+                    .desired_width(120.0),
+            );
+            let draft = &self.obs_password_draft;
+            if ui
+                .add_enabled(!draft.is_empty(), Button::new("Set"))
+                .clicked()
+            {
+                match secrets::store_secret(
+                    secrets::OBS_PASSWORD,
+                    Some(draft),
+                ) {
+                    Ok(()) => {
+                        password.clone_from(draft);
+                        info!("OBS password set");
+                    }
+                    Err(err) => {
+                        self.err_messages.push(format!("{err:?}"))
+                    }
+                }
+                self.obs_password_draft.clear();
+            }
+            if ui
+                .add_enabled(!password.is_empty(), Button::new("Clear"))
+                .clicked()
+            {
+                match secrets::store_secret(secrets::OBS_PASSWORD, None) {
+                    Ok(()) => {
+                        password.clear();
+                        info!("OBS password cleared");
+                    }
+                    Err(err) => {
+                        self.err_messages.push(format!("{err:?}"))
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Cells of `alert` in the alert settings grid, returns whether it
+/// changed.
+fn alert_row(ui: &mut Ui, alert: &mut Alert) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .add(
+                TextEdit::singleline(&mut alert.sound)
+                    .hint_text("none")
+                    .desired_width(120.0),
+            )
+            .changed();
+        if ui.button("Choose...").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Sound", &["wav", "ogg", "mp3"])
+                .pick_file()
+            {
+                alert.sound = path.display().to_string();
+                changed = true;
+            }
+        }
+    });
+    changed |= ui
+        .checkbox(&mut alert.notify, "")
+        .on_hover_text("Desktop notification")
+        .changed();
+    changed |= ui
+        .checkbox(&mut alert.tts, "")
+        .on_hover_text("Read out with the system voice")
+        .changed();
+    changed |= ui
+        .add(
+            TextEdit::singleline(&mut alert.obs_request)
+                .hint_text("e.g. SetCurrentProgramScene")
+                .desired_width(160.0),
+        )
+        .changed();
+    let data_ok = alert.obs_data.trim().is_empty()
+        || serde_json::from_str::<serde_json::Value>(&alert.obs_data)
+            .is_ok();
+    changed |= ui
+        .add(
+            TextEdit::singleline(&mut alert.obs_data)
+                .hint_text(r#"{"sceneName": "Alert"}"#)
+                .desired_width(160.0)
+                .text_color_opt(
+                    (!data_ok).then(|| ui.style().visuals.error_fg_color),
+                ),
+        )
+        .changed();
+    changed
+}
@@ -0,0 +1,77 @@
+use blooming_light_core::alias::UserAlias;
+use eframe::egui::{Grid, TextEdit, Ui};
+
+use super::App;
+
+impl App {
+    /// Aliases set so far in the queue settings, with a row to set one.
+    pub(super) fn user_aliases_ui(&mut self, ui: &mut Ui) {
+        ui.label("User aliases").on_hover_text(
+            "Shown next to their messages in the queue, only on this \
+             computer",
+        );
+        let mut edit = None;
+        let mut remove = None;
+        Grid::new("user aliases").num_columns(4).striped(true).show(
+            ui,
+            |ui| {
+                for (username, alias) in self.user_aliases.iter() {
+                    ui.label(username);
+                    ui.label(&alias.alias);
+                    ui.label(&alias.note);
+                    ui.horizontal(|ui| {
+                        if ui.button("Edit").clicked() {
+                            edit =
+                                Some((username.clone(), alias.clone()));
+                        }
+                        if ui.button("Remove").clicked() {
+                            remove = Some(username.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            },
+        );
+
+        let (ref mut username, ref mut alias) = self.user_alias_draft;
+        if let Some(it) = edit {
+            (*username, *alias) = it;
+        }
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(username)
+                    .hint_text("Username")
+                    .desired_width(100.0),
+            );
+            ui.add(
+                TextEdit::singleline(&mut alias.alias)
+                    .hint_text("Alias")
+                    .desired_width(100.0),
+            );
+            ui.add(
+                TextEdit::singleline(&mut alias.note)
+                    .hint_text("Note")
+                    .desired_width(160.0),
+            );
+            if ui.button("Set").clicked() {
+                self.user_aliases.set(username, alias.clone());
+                *username = String::new();
+                *alias = UserAlias::default();
+                changed = true;
+            }
+        });
+        if let Some(username) = remove {
+            self.user_aliases.remove(&username);
+            changed = true;
+        }
+        if changed {
+            ui.data_mut(|d| {
+                d.insert_persisted(
+                    self.user_aliases_id,
+                    self.user_aliases.clone(),
+                )
+            });
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use eframe::egui::{
+    vec2, CentralPanel, Color32, Context as EguiCtx, DragValue, Frame,
+    Grid, Sense, Vec2, ViewportBuilder, ViewportCommand, ViewportId,
+    Window,
+};
+
+use super::{
+    preview::{paint_overlay, preview_frame},
+    App,
+};
+
+/// Chroma green, rarely a text color.
+pub(super) const DEFAULT_CHROMA_COLOR: [u8; 3] = [0, 177, 64];
+pub(super) const DEFAULT_CHROMA_SIZE: [u32; 2] = [1280, 720];
+
+impl App {
+    /// Draws forwarded messages like the built-in danmaku overlay in a
+    /// borderless window of its own, over a solid color for window
+    /// capture to key out.
+    pub(super) fn update_chroma(&mut self, ctx: &EguiCtx) {
+        self.update_chroma_settings(ctx);
+        if !self.chroma_open {
+            return;
+        }
+
+        let size = chroma_size(self.chroma_size);
+        let [r, g, b] = self.chroma_color;
+        let now = self.message.lock_quiet().now();
+        let mut closed = false;
+        ctx.show_viewport_immediate(
+            chroma_viewport(),
+            ViewportBuilder::default()
+                .with_title("Blooming Light output")
+                .with_decorations(false)
+                .with_always_on_top()
+                .with_resizable(false)
+                .with_inner_size(size),
+            |ctx, _| {
+                let vars = &self.server_config.theme_vars;
+                let frame = preview_frame(ctx, vars, size);
+                let dt = ctx.input(|i| i.stable_dt);
+                self.chroma_output.step(dt, &frame, now);
+                if !self.chroma_output.is_idle() {
+                    ctx.request_repaint();
+                }
+                closed = ctx.input(|i| i.viewport().close_requested());
+
+                CentralPanel::default()
+                    .frame(Frame::none().fill(Color32::from_rgb(r, g, b)))
+                    .show(ctx, |ui| {
+                        let rect = ui.max_rect();
+                        // no title bar to move it by
+                        let response = ui.interact(
+                            rect,
+                            ui.id().with("drag"),
+                            Sense::drag(),
+                        );
+                        if response.drag_started() {
+                            ctx.send_viewport_cmd(
+                                ViewportCommand::StartDrag,
+                            );
+                        }
+                        paint_overlay(
+                            ui,
+                            rect,
+                            &self.chroma_output,
+                            &frame,
+                            vars,
+                            now,
+                        );
+                    });
+            },
+        );
+        if closed {
+            self.set_chroma_open(ctx, false);
+        }
+    }
+
+    fn update_chroma_settings(&mut self, ctx: &EguiCtx) {
+        if !self.chroma_show {
+            return;
+        }
+
+        Window::new("Chroma output")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let grid = Grid::new("chroma output").num_columns(2);
+                grid.show(ui, |ui| {
+                    ui.label("Background").on_hover_text(
+                        "Key this color out of the window capture, pick \
+                         one no text or image uses",
+                    );
+                    let color =
+                        ui.color_edit_button_srgb(&mut self.chroma_color);
+                    if color.changed() {
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.chroma_color_id,
+                                self.chroma_color,
+                            )
+                        });
+                    }
+                    ui.end_row();
+
+                    ui.label("Size");
+                    let mut changed = false;
+                    ui.horizontal(|ui| {
+                        let [width, height] = &mut self.chroma_size;
+                        changed |= ui
+                            .add(DragValue::new(width).range(160..=7680))
+                            .changed();
+                        ui.label("×");
+                        changed |= ui
+                            .add(DragValue::new(height).range(90..=4320))
+                            .changed();
+                    });
+                    if changed {
+                        ctx.send_viewport_cmd_to(
+                            chroma_viewport(),
+                            ViewportCommand::InnerSize(chroma_size(
+                                self.chroma_size,
+                            )),
+                        );
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.chroma_size_id,
+                                self.chroma_size,
+                            )
+                        });
+                    }
+                    ui.end_row();
+                });
+
+                ui.label(
+                    "Theme variables apply as on the built-in danmaku \
+                     layout. Drag the output to move it.",
+                );
+                ui.horizontal(|ui| {
+                    let open = if self.chroma_open {
+                        "Close output"
+                    } else {
+                        "Open output"
+                    };
+                    if ui.button(open).clicked() {
+                        self.set_chroma_open(ctx, !self.chroma_open);
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.chroma_output.clear();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.chroma_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.chroma_show_id,
+                                self.chroma_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    fn set_chroma_open(&mut self, ctx: &EguiCtx, open: bool) {
+        self.chroma_open = open;
+        if !open {
+            self.chroma_output.clear();
+        }
+        ctx.data_mut(|d| {
+            d.insert_persisted(self.chroma_open_id, self.chroma_open)
+        });
+    }
+}
+
+fn chroma_viewport() -> ViewportId {
+    ViewportId::from_hash_of("chroma output")
+}
+
+pub(super) fn chroma_size([width, height]: [u32; 2]) -> Vec2 {
+    vec2(width as f32, height as f32)
+}
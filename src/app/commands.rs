@@ -0,0 +1,187 @@
+use blooming_light_core::{
+    command::{
+        ChatCommand, ChatCommands, CommandCooldowns, CommandHandler,
+        Routed,
+    },
+    log::LogEvent,
+    message::Message,
+    network::Action,
+    queue::MessageQueue,
+};
+use eframe::egui::{ComboBox, DragValue, Grid, TextEdit, Ui};
+use tracing::info;
+
+use super::{App, NetworkState};
+
+impl App {
+    /// The command table, in the text settings.
+    pub(super) fn chat_commands_ui(&mut self, ui: &mut Ui) {
+        let commands = &mut self.chat_commands;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut commands.enabled, "Chat commands")
+                .on_hover_text(
+                    "Chat starting with ! runs the command listed for it \
+                     instead of entering the queue",
+                )
+                .changed();
+            changed |= ui
+                .checkbox(&mut commands.drop_unknown, "Drop unknown")
+                .on_hover_text(
+                    "Keep unlisted commands out of the queue too",
+                )
+                .changed();
+        });
+
+        let mut removed = None;
+        Grid::new("chat commands").num_columns(4).show(ui, |ui| {
+            for (idx, command) in commands.commands.iter_mut().enumerate()
+            {
+                changed |= command_row_ui(ui, idx, command);
+                if ui.button("Remove").clicked() {
+                    removed = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = removed {
+            commands.commands.remove(idx);
+            changed = true;
+        }
+        if ui.button("Add command").clicked() {
+            commands.commands.push(ChatCommand::default());
+            changed = true;
+        }
+        if changed {
+            ui.data_mut(|d| {
+                d.insert_persisted(
+                    self.chat_commands_id,
+                    commands.clone(),
+                )
+            });
+        }
+    }
+}
+
+/// Name, handler and allowed users of `command`, the `idx`th. Returns
+/// whether any changed.
+fn command_row_ui(
+    ui: &mut Ui,
+    idx: usize,
+    command: &mut ChatCommand,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .add(
+                TextEdit::singleline(&mut command.name)
+                    .hint_text("!discord")
+                    .desired_width(80.0),
+            )
+            .changed();
+        ComboBox::from_id_salt(("command handler", idx))
+            .selected_text(command.handler.name())
+            .show_ui(ui, |ui| {
+                for kind in CommandHandler::KINDS {
+                    let selected = command.handler.same_kind(&kind);
+                    if ui
+                        .selectable_label(selected, kind.name())
+                        .clicked()
+                        && !selected
+                    {
+                        command.handler = kind;
+                        changed = true;
+                    }
+                }
+            });
+    });
+    match command.handler {
+        CommandHandler::Ignore => {
+            ui.label("");
+        }
+        CommandHandler::Respond(ref mut text) => {
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        TextEdit::singleline(text)
+                            .hint_text("Join at discord.gg/...")
+                            .desired_width(160.0),
+                    )
+                    .on_hover_text(
+                        "Queued in its place, {user} is the sender",
+                    )
+                    .changed();
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut command.cooldown_secs)
+                            .range(0.0..=3600.0)
+                            .speed(1.0)
+                            .suffix("s"),
+                    )
+                    .on_hover_text(
+                        "Least time between two responses, run again \
+                         sooner it's dropped without one",
+                    )
+                    .changed();
+            });
+        }
+        CommandHandler::Action(ref mut action) => {
+            ComboBox::from_id_salt(("command action", idx))
+                .selected_text(action.name())
+                .show_ui(ui, |ui| {
+                    for it in Action::ALL {
+                        changed |= ui
+                            .selectable_value(action, it, it.name())
+                            .changed();
+                    }
+                });
+        }
+    }
+    let anyone = match command.handler {
+        CommandHandler::Action(_) => "nobody",
+        _ => "anyone",
+    };
+    changed |= ui
+        .add(
+            TextEdit::singleline(&mut command.users)
+                .hint_text(anyone)
+                .desired_width(100.0),
+        )
+        .on_hover_text(
+            "Comma separated users allowed to run it. Actions need \
+             someone listed",
+        )
+        .changed();
+    changed
+}
+
+/// Runs `msg` if it's a chat command, returns whether it was taken out
+/// of the pipeline for it. Actions are left in `actions` to run after.
+pub(super) fn run_chat_command(
+    commands: &ChatCommands,
+    cooldowns: &mut CommandCooldowns,
+    actions: &mut Vec<Action>,
+    msg: &Message,
+    queue: &mut MessageQueue,
+    network: &NetworkState,
+) -> bool {
+    let command = match commands.route(msg) {
+        Routed::Queue => return false,
+        Routed::Drop => return true,
+        Routed::Run(command) => command,
+    };
+    info!(command = command.name, "chat command run");
+    let response = command.response(msg).filter(|_| {
+        let now = queue.now();
+        cooldowns.respond(command, now)
+    });
+    if let Some(response) = response {
+        network.write_log(response.clone(), LogEvent::Receive);
+        queue.push(response);
+    }
+    if let CommandHandler::Action(action) = command.handler {
+        actions.push(action);
+    }
+    true
+}
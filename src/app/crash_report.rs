@@ -0,0 +1,50 @@
+use eframe::egui::{Context as EguiCtx, ScrollArea, TextEdit, Window};
+
+use super::App;
+use crate::crash;
+
+impl App {
+    /// Shows the report of a crash last session until dismissed.
+    pub(super) fn update_crash_report(&mut self, ctx: &EguiCtx) {
+        let Some((ref path, ref report)) = self.crash_report else {
+            return;
+        };
+
+        let mut dismissed = false;
+        Window::new("Crashed last time")
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The app crashed last time, the report is saved to {}",
+                    path.display()
+                ));
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    ui.add(
+                        TextEdit::multiline(&mut report.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(report.clone());
+                    }
+                    if ui.button("Open folder").clicked() {
+                        if let Err(err) = crash::open_dir(&crash::dir()) {
+                            self.err_messages.push(format!("{err:?}"));
+                        }
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        dismissed = true;
+                    }
+                });
+            });
+        if dismissed {
+            if let Err(err) = crash::mark_seen() {
+                self.err_messages.push(format!("{err:?}"));
+            }
+            self.crash_report = None;
+        }
+    }
+}
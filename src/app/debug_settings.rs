@@ -0,0 +1,354 @@
+use anyhow::Context;
+use blooming_light_core::log::{upload::UploadTarget, SyncPolicy};
+use eframe::egui::{
+    Button, ComboBox, Context as EguiCtx, DragValue, Grid, TextEdit, Ui,
+    Window,
+};
+use tracing::info;
+
+use super::{secrets, App};
+use crate::logging::FileLog;
+
+const PUFFIN_ADDR: &str = "127.0.0.1:8585";
+
+impl App {
+    pub(super) fn update_debug_settings(&mut self, ctx: &EguiCtx) {
+        if !self.debug_settings_show {
+            return;
+        }
+
+        Window::new("Debug Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("debug settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Log file level");
+                        let level = self.file_log.level();
+                        let mut selected = level;
+                        ComboBox::from_id_salt("file log level")
+                            .selected_text(level.to_string())
+                            .show_ui(ui, |ui| {
+                                for it in FileLog::LEVELS {
+                                    ui.selectable_value(
+                                        &mut selected,
+                                        it,
+                                        it.to_string(),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text(format!(
+                                "Also write logs at this level or above \
+                                 to a file a day in {}, the last week's \
+                                 are kept",
+                                self.file_log.dir().display()
+                            ));
+                        if selected != level {
+                            self.file_log.set_level(selected);
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.file_log_level_id,
+                                    selected.to_string(),
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Performance HUD");
+                        let res = ui
+                            .checkbox(&mut self.hud_show, "")
+                            .on_hover_text(
+                                "Show frame time, queue depth, message \
+                                 rates and overlay lag in the corner of \
+                                 the main window",
+                            );
+                        if res.changed() {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.hud_show_id,
+                                    self.hud_show,
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Profiler server");
+                        let mut enable = self.puffin_server.is_some();
+                        let res = ui
+                            .checkbox(&mut enable, "")
+                            .on_hover_text(format!(
+                                "Serve profiling data at {PUFFIN_ADDR} \
+                                 for puffin_viewer to connect to"
+                            ));
+                        if res.changed() {
+                            self.puffin_server = None;
+                            puffin::set_scopes_on(false);
+                            if enable {
+                                match start_puffin_server() {
+                                    Ok(server) => {
+                                        self.puffin_server = Some(server)
+                                    }
+                                    Err(err) => self
+                                        .err_messages
+                                        .push(format!("{err:?}")),
+                                }
+                            }
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.puffin_server_enable_id,
+                                    self.puffin_server.is_some(),
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Network threads");
+                        let config = &mut self.runtime_config;
+                        let mut changed = ui
+                            .add(
+                                DragValue::new(&mut config.worker_threads)
+                                    .range(0..=64),
+                            )
+                            .on_hover_text(
+                                "Worker threads of the network runtime, 0 \
+                                 for one per CPU core. Applied when the \
+                                 network next starts",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("Network thread name");
+                        changed |= ui
+                            .add(
+                                TextEdit::singleline(
+                                    &mut config.thread_name,
+                                )
+                                .desired_width(120.0),
+                            )
+                            .on_hover_text(
+                                "Worker threads are named this with an \
+                                 index appended. Applied when the network \
+                                 next starts",
+                            )
+                            .changed();
+                        if changed {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.runtime_config_id,
+                                    self.runtime_config.clone(),
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Message log sync");
+                        let config = &mut self.log_config;
+                        let mut changed = log_sync_ui(ui, &mut config.sync);
+                        ui.end_row();
+
+                        ui.label("Message log limit(MB)");
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut config.max_total_mb)
+                                    .range(0..=100_000),
+                            )
+                            .on_hover_text(
+                                "Total size of log.jsonl and its gzipped \
+                                 archives, the oldest archives are \
+                                 deleted past it. 0 for no limit. \
+                                 Applied when the network next starts",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("Anonymize usernames");
+                        changed |= ui
+                            .checkbox(&mut config.anonymize, "")
+                            .on_hover_text(
+                                "Log and report each username as a \
+                                 pseudonym that's the same within a \
+                                 session but can't be traced back, for \
+                                 sharing logs. Such sessions aren't \
+                                 recovered after a crash. Applied when \
+                                 the network next starts",
+                            )
+                            .changed();
+                        if changed {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.log_config_id,
+                                    self.log_config.clone(),
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        self.log_upload_ui(ui);
+                        self.update_check_ui(ui);
+                    },
+                );
+
+                if ui.button("Close").clicked() {
+                    self.debug_settings_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.debug_settings_show_id,
+                            self.debug_settings_show,
+                        )
+                    });
+                }
+            });
+    }
+
+    /// Rows of the debug settings grid, one more per field of the
+    /// target.
+    fn log_upload_ui(&mut self, ui: &mut Ui) {
+        ui.label("Session upload");
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt("log upload")
+                .selected_text(self.log_upload_draft.name())
+                .show_ui(ui, |ui| {
+                    for it in [
+                        UploadTarget::None,
+                        UploadTarget::WebDav {
+                            url: String::new(),
+                            username: String::new(),
+                            password: String::new(),
+                        },
+                        UploadTarget::S3 {
+                            endpoint: String::new(),
+                            region: "us-east-1".to_owned(),
+                            bucket: String::new(),
+                            access_key: String::new(),
+                            secret_key: String::new(),
+                        },
+                    ] {
+                        let selected =
+                            it.name() == self.log_upload_draft.name();
+                        if ui
+                            .selectable_label(selected, it.name())
+                            .clicked()
+                            && !selected
+                        {
+                            self.log_upload_draft = it;
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Where a session's message log and its part of the \
+                     access log are uploaded once the network stops, \
+                     retried for up to 15s. Applied when the network \
+                     next starts",
+                );
+            let changed = self.log_upload_draft != self.log_config.upload;
+            if ui.add_enabled(changed, Button::new("Save")).clicked() {
+                let stored =
+                    serde_json::to_string(&self.log_upload_draft)
+                        .context("failed to serialize upload target")
+                        .and_then(|json| {
+                            secrets::store_secret(
+                                secrets::UPLOAD_TARGET,
+                                Some(&json),
+                            )
+                        });
+                match stored {
+                    Ok(()) => {
+                        self.log_config.upload =
+                            self.log_upload_draft.clone()
+                    }
+                    Err(err) => {
+                        self.err_messages.push(format!("{err:?}"))
+                    }
+                }
+            }
+        });
+        ui.end_row();
+
+        let fields = match self.log_upload_draft {
+            UploadTarget::None => vec![],
+            UploadTarget::WebDav {
+                ref mut url,
+                ref mut username,
+                ref mut password,
+            } => vec![
+                ("URL", url, false),
+                ("Username", username, false),
+                ("Password", password, true),
+            ],
+            UploadTarget::S3 {
+                ref mut endpoint,
+                ref mut region,
+                ref mut bucket,
+                ref mut access_key,
+                ref mut secret_key,
+            } => vec![
+                ("Endpoint", endpoint, false),
+                ("Region", region, false),
+                ("Bucket", bucket, false),
+                ("Access key", access_key, false),
+                ("Secret key", secret_key, true),
+            ],
+        };
+        for (label, value, password) in fields {
+            ui.label(label);
+            ui.add(
+                TextEdit::singleline(value)
+                    .password(password)
+                    .desired_width(240.0),
+            );
+            ui.end_row();
+        }
+    }
+}
+
+/// Returns whether it changed.
+fn log_sync_ui(ui: &mut Ui, sync: &mut SyncPolicy) -> bool {
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        ComboBox::from_id_salt("log sync")
+            .selected_text(sync.name())
+            .show_ui(ui, |ui| {
+                for it in [
+                    SyncPolicy::EveryWrite,
+                    SyncPolicy::Interval(1000),
+                    SyncPolicy::OnShutdown,
+                ] {
+                    let selected = it.name() == sync.name();
+                    if ui.selectable_label(selected, it.name()).clicked()
+                        && !selected
+                    {
+                        *sync = it;
+                        changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text(
+                "When log.jsonl is synced to disk, until then a power \
+                 loss may take the last entries. Applied when the \
+                 network next starts",
+            );
+        if let SyncPolicy::Interval(ref mut ms) = sync {
+            changed |= ui
+                .add(DragValue::new(ms).range(10..=60000).suffix("ms"))
+                .changed();
+        }
+        changed
+    })
+    .inner
+}
+
+/// Profiling runs only while it's served, scopes cost nothing when off.
+pub(super) fn start_puffin_server() -> anyhow::Result<puffin_http::Server>
+{
+    let server =
+        puffin_http::Server::new(PUFFIN_ADDR).with_context(|| {
+            format!("failed to start puffin server at {PUFFIN_ADDR}")
+        })?;
+    puffin::set_scopes_on(true);
+    info!("puffin server listenning at {PUFFIN_ADDR}");
+    Ok(server)
+}
@@ -0,0 +1,68 @@
+use blooming_light_core::{
+    log::{LogEntry, LogEvent},
+    message::Message,
+};
+use eframe::egui::{Button, Context as EguiCtx, Key, TextEdit, Window};
+
+use super::{queue_window::spoken_message, App};
+
+impl App {
+    /// Asks why each message was deleted, one at a time, when turned on.
+    /// They're only logged once answered or skipped.
+    pub(super) fn update_delete_reason(&mut self, ctx: &EguiCtx) {
+        let Some(msg) = self.awaiting_reason.first() else {
+            return;
+        };
+
+        let mut answered = None;
+        Window::new("Delete reason")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Deleted {}", spoken_message(msg)));
+                let more = self.awaiting_reason.len() - 1;
+                if more > 0 {
+                    ui.weak(format!("{more} more to go"));
+                }
+                let res = ui.add(
+                    TextEdit::singleline(&mut self.delete_reason_draft)
+                        .hint_text("Reason, e.g. spoiler"),
+                );
+                let reason = self.delete_reason_draft.trim();
+                let entered = res.lost_focus()
+                    && ui.input(|i| i.key_pressed(Key::Enter));
+                ui.horizontal(|ui| {
+                    let save = ui.add_enabled(
+                        !reason.is_empty(),
+                        Button::new("Save"),
+                    );
+                    if save.clicked() || (entered && !reason.is_empty()) {
+                        answered = Some(Some(reason.to_owned()));
+                    }
+                    if ui.button("Skip").clicked() {
+                        answered = Some(None);
+                    }
+                });
+            });
+        if let Some(reason) = answered {
+            let msg = self.awaiting_reason.remove(0);
+            self.log_delete(msg, reason);
+            self.delete_reason_draft.clear();
+        }
+    }
+
+    /// Without reasons, so none are lost on exit.
+    pub(super) fn skip_delete_reasons(&mut self) {
+        for msg in std::mem::take(&mut self.awaiting_reason) {
+            self.log_delete(msg, None);
+        }
+    }
+
+    fn log_delete(&self, msg: Message, reason: Option<String>) {
+        if let Ok(ref network) = self.network {
+            network.write_log_entry(
+                LogEntry::new(msg, LogEvent::Delete).with_reason(reason),
+            );
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use blooming_light_core::log;
+use eframe::egui::{
+    Align2, Color32, Context as EguiCtx, FontId, Id, LayerId, Order,
+    Window,
+};
+
+use super::{log_viewer::LogView, App, DEMO_EXTENSIONS};
+
+/// What a file dropped on the window is opened as, asked before doing
+/// it.
+#[derive(Debug, Clone)]
+pub(super) enum DropAction {
+    /// Replaces the demo file.
+    Demo(PathBuf),
+    OpenLog(PathBuf),
+}
+
+impl DropAction {
+    /// Demo files by extension, `.jsonl` ones told apart from logs by
+    /// their content.
+    fn for_path(path: PathBuf) -> anyhow::Result<Option<Self>> {
+        let extension = path.extension().and_then(|it| it.to_str());
+        if extension == Some("jsonl") && log::is_log(&path)? {
+            return Ok(Some(DropAction::OpenLog(path)));
+        }
+        let is_demo =
+            extension.is_some_and(|it| DEMO_EXTENSIONS.contains(&it));
+        Ok(is_demo.then_some(DropAction::Demo(path)))
+    }
+}
+
+impl App {
+    pub(super) fn update_dropped_files(&mut self, ctx: &EguiCtx) {
+        let (hovered, dropped) = ctx.input(|i| {
+            (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone())
+        });
+        if hovered {
+            let painter = ctx.layer_painter(LayerId::new(
+                Order::Foreground,
+                Id::new("file drop target"),
+            ));
+            let rect = ctx.screen_rect();
+            painter.rect_filled(
+                rect,
+                0.0,
+                Color32::from_black_alpha(160),
+            );
+            painter.text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                "Drop a demo file or message log",
+                FontId::proportional(24.0),
+                Color32::WHITE,
+            );
+        }
+        // only one is asked about at a time
+        if let Some(path) = dropped.into_iter().find_map(|it| it.path) {
+            match DropAction::for_path(path.clone()) {
+                Ok(Some(action)) => self.drop_action = Some(action),
+                Ok(None) => self.err_messages.push(format!(
+                    "don't know how to open {}, drop a demo file ({}) or \
+                     a .jsonl message log",
+                    path.display(),
+                    DEMO_EXTENSIONS.join(", "),
+                )),
+                Err(err) => self.err_messages.push(format!("{err:?}")),
+            }
+        }
+
+        let Some(action) = self.drop_action.clone() else {
+            return;
+        };
+        Window::new("Open dropped file")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let (text, confirm) = match action {
+                    DropAction::Demo(ref path) => (
+                        format!(
+                            "Load {} as the demo file, replacing {}?",
+                            file_name(path),
+                            file_name(self.demo_source.path()),
+                        ),
+                        "Load",
+                    ),
+                    DropAction::OpenLog(ref path) => (
+                        format!(
+                            "Open the message log {} in the log viewer?",
+                            file_name(path),
+                        ),
+                        "Open",
+                    ),
+                };
+                ui.label(text);
+                ui.horizontal(|ui| {
+                    if ui.button(confirm).clicked() {
+                        self.run_drop_action(ctx, action);
+                        self.drop_action = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.drop_action = None;
+                    }
+                });
+            });
+    }
+
+    fn run_drop_action(&mut self, ctx: &EguiCtx, action: DropAction) {
+        match action {
+            DropAction::Demo(path) => {
+                self.demo_source.set_path(path.clone());
+                self.demo_settings_show = true;
+                ctx.data_mut(|d| {
+                    d.insert_persisted(self.demo_path_id, path);
+                    d.insert_persisted(
+                        self.demo_settings_show_id,
+                        self.demo_settings_show,
+                    );
+                });
+            }
+            DropAction::OpenLog(path) => match LogView::open(path) {
+                Ok(view) => self.log_view = Some(view),
+                Err(err) => self.err_messages.push(format!("{err:?}")),
+            },
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
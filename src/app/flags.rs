@@ -0,0 +1,46 @@
+use blooming_light_core::{
+    flag::{FlagAction, FlagNotifier, FlagWords},
+    message::Message,
+};
+use tracing::info;
+
+use super::App;
+
+impl App {
+    /// Runs what was clicked on flagged message notifications, if the
+    /// message is still pending.
+    pub(super) fn update_flags(&mut self) {
+        for (msg, action) in self.flag_notifier.pull() {
+            info!(?action, "flag notification clicked");
+            let found = match action {
+                FlagAction::Delete => {
+                    let mut queue = self.message.lock();
+                    let pending = queue
+                        .iter_mut()
+                        .filter(|it| !it.delete && it.msg == msg)
+                        .last();
+                    // taken off and logged with the ones deleted by hand
+                    pending.map(|it| it.delete = true).is_some()
+                }
+                FlagAction::Approve => self.approve(&msg),
+            };
+            if !found {
+                info!("flagged message no longer pending");
+            }
+        }
+    }
+}
+
+/// Notifies `msg` if flagged and notifications are on.
+pub(super) fn notify_flagged(
+    words: &FlagWords,
+    notifier: &FlagNotifier,
+    msg: &Message,
+) {
+    if !words.notify {
+        return;
+    }
+    if let Some(word) = words.matches(msg) {
+        notifier.notify(msg.clone(), word);
+    }
+}
@@ -0,0 +1,193 @@
+use blooming_light_core::{
+    history::Moderation, log::LogEvent, message::Message,
+    queue::QueueSnapshot,
+};
+use chrono::Local;
+use eframe::egui::{
+    Button, Context as EguiCtx, Key, KeyboardShortcut, Modifiers,
+    RichText, ScrollArea, Window,
+};
+use tracing::info;
+
+use super::{queue_window::spoken_message, App};
+
+const UNDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+/// [`blooming_light_core::hotkey::REDO`], which hotkeys can't take.
+const REDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(
+    Modifiers::COMMAND.plus(Modifiers::SHIFT),
+    Key::Z,
+);
+
+impl App {
+    /// Undo and redo shortcuts, left to text edits while typing, and the
+    /// history window.
+    pub(super) fn update_history(&mut self, ctx: &EguiCtx) {
+        if !ctx.wants_keyboard_input() {
+            // first, as Ctrl+Z would also take Ctrl+Shift+Z
+            if ctx.input_mut(|i| i.consume_shortcut(&REDO_SHORTCUT)) {
+                self.redo();
+            }
+            if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+                self.undo();
+            }
+        }
+        if !self.history_show {
+            return;
+        }
+
+        Window::new("History").default_width(320.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        self.history.can_undo(),
+                        Button::new("Undo"),
+                    )
+                    .on_hover_text("Ctrl+Z")
+                    .clicked()
+                {
+                    self.undo();
+                }
+                if ui
+                    .add_enabled(
+                        self.history.can_redo(),
+                        Button::new("Redo"),
+                    )
+                    .on_hover_text("Ctrl+Shift+Z")
+                    .clicked()
+                {
+                    self.redo();
+                }
+            });
+            ui.separator();
+            ScrollArea::vertical()
+                .max_height(240.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    if self.history.entries().is_empty() {
+                        ui.weak("Nothing moderated yet");
+                    }
+                    for entry in self.history.entries() {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(
+                                    entry
+                                        .at
+                                        .with_timezone(&Local)
+                                        .format("%H:%M:%S")
+                                        .to_string(),
+                                )
+                                .monospace(),
+                            );
+                            let text =
+                                RichText::new(describe(&entry.action));
+                            let res = if entry.undone {
+                                ui.label(text.strikethrough())
+                                    .on_hover_text("Undone")
+                            } else {
+                                ui.label(text)
+                            };
+                            if !entry.action.can_undo() {
+                                res.on_hover_text(
+                                    "Forwarded already, can't be \
+                                         undone",
+                                );
+                            }
+                        });
+                    }
+                });
+            if ui.button("Close").clicked() {
+                self.history_show = false;
+                ui.data_mut(|d| {
+                    d.insert_persisted(
+                        self.history_show_id,
+                        self.history_show,
+                    )
+                });
+            }
+        });
+    }
+
+    /// Puts back what the latest delete or purge took off.
+    pub(super) fn undo(&mut self) {
+        let Some(action) = self.history.undo() else {
+            return;
+        };
+        info!(action = action.name(), "undoing");
+        let entries = match action {
+            Moderation::Delete(entry) => vec![entry.clone()],
+            Moderation::Purge(snapshot) => snapshot.entries.clone(),
+            Moderation::Approve(_) => return,
+        };
+        for entry in &entries {
+            // never logged as deleted
+            if let Some(idx) = self
+                .awaiting_reason
+                .iter()
+                .position(|it| *it == entry.msg)
+            {
+                self.awaiting_reason.remove(idx);
+            } else if let Ok(ref network) = self.network {
+                network.write_log(entry.msg.clone(), LogEvent::Restore);
+            }
+        }
+        let mut queue = self.message.lock();
+        let saved_at = queue.now_utc();
+        queue.restore(QueueSnapshot { saved_at, entries });
+    }
+
+    /// Deletes again what was put back, if still pending.
+    pub(super) fn redo(&mut self) {
+        let Some(Moderation::Delete(entry)) = self.history.redo() else {
+            return;
+        };
+        info!("redoing delete");
+        match self.message.lock().remove(&entry.msg) {
+            Some(removed) => {
+                if let Ok(ref network) = self.network {
+                    network
+                        .write_log(removed.msg.clone(), LogEvent::Delete);
+                }
+                *entry = removed;
+            }
+            None => info!("deleted message no longer pending"),
+        }
+    }
+
+    /// Sends the oldest pending message right away, kept in the
+    /// history.
+    pub(super) fn approve_next(&mut self) {
+        let mut queue = self.message.lock();
+        let Some(msg) = queue.next_pending().cloned() else {
+            return;
+        };
+        queue.send_next();
+        self.history.push(Moderation::Approve(msg), queue.now_utc());
+    }
+
+    /// Sends `msg` right away if it's still pending, like the button on
+    /// its flag notification.
+    pub(super) fn approve(&mut self, msg: &Message) -> bool {
+        let mut queue = self.message.lock();
+        let sent = queue.send_now(msg);
+        if sent {
+            self.history
+                .push(Moderation::Approve(msg.clone()), queue.now_utc());
+        }
+        sent
+    }
+}
+
+fn describe(action: &Moderation) -> String {
+    match action {
+        Moderation::Delete(entry) => {
+            format!("Deleted {}", spoken_message(&entry.msg))
+        }
+        Moderation::Approve(msg) => {
+            format!("Approved {}", spoken_message(msg))
+        }
+        Moderation::Purge(snapshot) => {
+            format!("Purged {} message", snapshot.len())
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use blooming_light_core::{
+    hotkey::{GlobalHotkeys, Hotkey, HotkeyAction, HotkeyBindings},
+    Notifier,
+};
+use eframe::egui::{
+    Context as EguiCtx, Grid, Key, KeyboardShortcut, Modifiers, TextEdit,
+    Ui,
+};
+use tracing::info;
+
+use super::App;
+
+impl App {
+    /// Runs the action of each bound hotkey pressed since the last
+    /// frame, seen while the window has focus unless grabbed
+    /// system-wide.
+    pub(super) fn update_hotkeys(&mut self, ctx: &EguiCtx) {
+        let mut pressed = self
+            .hotkeys
+            .bindings
+            .iter()
+            .filter(|(_, hotkey)| {
+                shortcut(hotkey).is_some_and(|it| {
+                    ctx.input_mut(|i| i.consume_shortcut(&it))
+                })
+            })
+            .map(|(action, _)| *action)
+            .collect::<Vec<_>>();
+        if let Some(ref global) = self.global_hotkeys {
+            pressed.extend(global.pull());
+        }
+        for action in pressed {
+            info!(?action, "hotkey pressed");
+            match action {
+                HotkeyAction::TogglePause => self.hold = !self.hold,
+                HotkeyAction::Purge => {
+                    self.purge_reason = "hotkey".to_owned();
+                    self.purge();
+                }
+            }
+        }
+    }
+
+    /// Grabs the bound hotkeys system-wide, in place of any grabbed
+    /// before.
+    pub(super) fn grab_hotkeys(&mut self, ctx: &EguiCtx) {
+        // the old grab has to go first, it holds the same keys
+        self.global_hotkeys = None;
+        if !GlobalHotkeys::SUPPORTED
+            || !self.hotkeys.global
+            || self.hotkeys.bindings.is_empty()
+        {
+            return;
+        }
+        let ctx = ctx.clone();
+        let notifier = Notifier::new(move || ctx.request_repaint());
+        match GlobalHotkeys::grab(&self.hotkeys, notifier) {
+            Ok(it) => self.global_hotkeys = Some(it),
+            Err(err) => self.err_messages.push(format!("{err:?}")),
+        }
+    }
+
+    /// Rows of the queue settings binding each action.
+    pub(super) fn hotkeys_ui(&mut self, ui: &mut Ui) {
+        Grid::new("hotkey settings").num_columns(2).show(ui, |ui| {
+            let mut changed = false;
+            if GlobalHotkeys::SUPPORTED {
+                ui.label("System-wide");
+                changed |= ui
+                    .checkbox(&mut self.hotkeys.global, "")
+                    .on_hover_text(
+                        "Pressed whatever window has focus, rather than \
+                         only this one. Only on X11",
+                    )
+                    .changed();
+                ui.end_row();
+            }
+            for (action, draft) in
+                HotkeyAction::ALL.into_iter().zip(&mut self.hotkey_drafts)
+            {
+                ui.label(action.name());
+                let parsed = check(&self.hotkeys, action, draft);
+                let color = parsed
+                    .is_err()
+                    .then(|| ui.style().visuals.error_fg_color);
+                let res = ui.add(
+                    TextEdit::singleline(draft)
+                        .hint_text("e.g. Ctrl+Shift+P")
+                        .desired_width(120.0)
+                        .text_color_opt(color),
+                );
+                let res = match parsed {
+                    Err(ref err) => res.on_hover_text(err),
+                    Ok(_) => res.on_hover_text("Empty for none"),
+                };
+                if res.changed() {
+                    if let Ok(hotkey) =
+                        check(&self.hotkeys, action, draft)
+                    {
+                        self.hotkeys.set(action, hotkey);
+                        changed = true;
+                    }
+                }
+                ui.end_row();
+            }
+            if changed {
+                ui.data_mut(|d| {
+                    d.insert_persisted(
+                        self.hotkeys_id,
+                        self.hotkeys.clone(),
+                    )
+                });
+                self.grab_hotkeys(ui.ctx());
+            }
+        });
+    }
+}
+
+fn shortcut(hotkey: &Hotkey) -> Option<KeyboardShortcut> {
+    let key = Key::from_name(&hotkey.key)?;
+    let mut modifiers = Modifiers::NONE;
+    if hotkey.ctrl {
+        modifiers = modifiers | Modifiers::COMMAND;
+    }
+    if hotkey.shift {
+        modifiers = modifiers | Modifiers::SHIFT;
+    }
+    if hotkey.alt {
+        modifiers = modifiers | Modifiers::ALT;
+    }
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+/// What `draft` binds `action` to, `None` if empty, or why it can't.
+fn check(
+    hotkeys: &HotkeyBindings,
+    action: HotkeyAction,
+    draft: &str,
+) -> Result<Option<Hotkey>, String> {
+    let draft = draft.trim();
+    if draft.is_empty() {
+        return Ok(None);
+    }
+    let hotkey =
+        draft.parse::<Hotkey>().map_err(|err| err.to_string())?;
+    if let Some(conflict) = hotkeys.conflict(action, &hotkey) {
+        return Err(conflict);
+    }
+    if shortcut(&hotkey).is_none() {
+        return Err(format!("unknown key `{}`", hotkey.key));
+    }
+    Ok(Some(hotkey))
+}
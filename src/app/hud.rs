@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use eframe::egui::{
+    Align2, Area, Context as EguiCtx, Frame, Grid, Id, Order, Rect,
+};
+
+use super::App;
+
+/// Gap between the HUD and the corner of the central panel.
+const HUD_MARGIN: f32 = 8.0;
+
+impl App {
+    /// Drawn over the top right corner of `panel`, the central panel.
+    pub(super) fn update_hud(&mut self, ctx: &EguiCtx, panel: Rect) {
+        if !self.hud_show {
+            return;
+        }
+        // rates decay even when nothing else moves
+        ctx.request_repaint_after(Duration::from_secs(1));
+
+        let (now, queued, waiting) = {
+            let queue = self.message.lock();
+            (queue.now(), queue.len(), queue.waiting_len())
+        };
+        let dt = ctx.input(|i| i.stable_dt);
+        let rate_in = self.rate_in.rate(now);
+        let rate_out = self.rate_out.rate(now);
+        Area::new(Id::new("performance hud"))
+            .order(Order::Foreground)
+            .interactable(false)
+            .pivot(Align2::RIGHT_TOP)
+            .fixed_pos(
+                panel.right_top() + (-HUD_MARGIN, HUD_MARGIN).into(),
+            )
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    Grid::new("performance hud").num_columns(2).show(
+                        ui,
+                        |ui| {
+                            ui.label("Frame");
+                            ui.label(match self.frame_cpu_usage {
+                                Some(cpu) => format!(
+                                    "{:.1}ms / {:.1}ms",
+                                    cpu * 1000.0,
+                                    dt * 1000.0
+                                ),
+                                None => {
+                                    format!("- / {:.1}ms", dt * 1000.0)
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label("Queue");
+                            ui.label(format!(
+                                "{queued} + {waiting} waiting"
+                            ));
+                            ui.end_row();
+
+                            ui.label("In / out");
+                            ui.label(format!(
+                                "{rate_in:.1} / {rate_out:.1} msg/s"
+                            ));
+                            ui.end_row();
+
+                            if let Ok(ref network) = self.network {
+                                let inbound = network.ws_message_stats();
+                                ui.label("Inbound");
+                                ui.label(format!(
+                                    "{} queued, {} dropped",
+                                    inbound.len, inbound.dropped
+                                ));
+                                ui.end_row();
+
+                                let broadcast =
+                                    network.ws_broadcast_stats();
+                                ui.label("Broadcast");
+                                ui.label(format!(
+                                    "{} queued, {} lagged",
+                                    broadcast.len, broadcast.dropped
+                                ));
+                                ui.end_row();
+                            }
+                        },
+                    );
+                });
+            });
+    }
+}
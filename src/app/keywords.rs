@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use eframe::egui::{Context as EguiCtx, DragValue, Grid, Window};
+
+use super::App;
+
+/// Rows listed at most.
+const TOP_KEYWORDS: usize = 20;
+
+impl App {
+    /// What chat has been saying most over the last few minutes.
+    pub(super) fn update_keywords(&mut self, ctx: &EguiCtx) {
+        if !self.keywords_show {
+            return;
+        }
+
+        let now = self.message.lock_quiet().now();
+        Window::new("Keywords")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Over the last");
+                    let res = ui.add(
+                        DragValue::new(&mut self.keywords_mins)
+                            .range(1..=120)
+                            .suffix(" min"),
+                    );
+                    if res.changed() {
+                        self.keywords.window =
+                            Duration::from_secs(self.keywords_mins * 60);
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.keywords_mins_id,
+                                self.keywords_mins,
+                            )
+                        });
+                    }
+                });
+                ui.separator();
+
+                let top = self.keywords.top(now, TOP_KEYWORDS);
+                if top.is_empty() {
+                    ui.label("Nothing received in that time");
+                } else {
+                    Grid::new("keywords")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (keyword, count) in top {
+                                ui.label(keyword);
+                                ui.label(count.to_string())
+                                    .on_hover_text("Messages with it");
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Clear").clicked() {
+                        self.keywords.clear();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.keywords_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.keywords_show_id,
+                                self.keywords_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+}
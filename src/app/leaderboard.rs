@@ -0,0 +1,133 @@
+use blooming_light_core::leaderboard::LeaderboardSort;
+use chrono::Local;
+use eframe::egui::{
+    Context as EguiCtx, Grid, Id, RichText, ScrollArea, Window,
+};
+
+use super::App;
+
+/// Rows listed at most.
+const LEADERBOARD_LEN: usize = 50;
+
+impl App {
+    /// Chatters of the session ranked by a column picked from the header,
+    /// with their recent messages a click on the name away.
+    pub(super) fn update_leaderboard(&mut self, ctx: &EguiCtx) {
+        self.update_chatter_messages(ctx);
+        if !self.leaderboard_show {
+            return;
+        }
+
+        Window::new("Leaderboard")
+            .collapsible(false)
+            .default_height(360.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} chatter this session",
+                    self.leaderboard.len()
+                ));
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    Grid::new("leaderboard")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("User");
+                            for (sort, name) in [
+                                (LeaderboardSort::Messages, "Messages"),
+                                (LeaderboardSort::Gifts, "Gift value"),
+                                (LeaderboardSort::Paid, "Paid"),
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.leaderboard_sort,
+                                    sort,
+                                    name,
+                                )
+                                .on_hover_text("Rank by this");
+                            }
+                            ui.end_row();
+
+                            let chatters = self
+                                .leaderboard
+                                .sorted(self.leaderboard_sort);
+                            for chatter in
+                                chatters.into_iter().take(LEADERBOARD_LEN)
+                            {
+                                if ui
+                                    .link(&chatter.username)
+                                    .on_hover_text("Show their messages")
+                                    .clicked()
+                                {
+                                    self.chatter_messages =
+                                        Some(chatter.username.clone());
+                                }
+                                ui.label(chatter.messages.to_string());
+                                ui.label(chatter.gift_value.to_string());
+                                ui.label(format!("{:.2}", chatter.paid));
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Reset").clicked() {
+                        self.leaderboard.clear();
+                        self.chatter_messages = None;
+                    }
+                    if ui.button("Close").clicked() {
+                        self.leaderboard_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.leaderboard_show_id,
+                                self.leaderboard_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    /// The recent messages of the chatter clicked on the leaderboard.
+    fn update_chatter_messages(&mut self, ctx: &EguiCtx) {
+        let Some(ref username) = self.chatter_messages else {
+            return;
+        };
+        let Some(chatter) = self.leaderboard.get(username) else {
+            self.chatter_messages = None;
+            return;
+        };
+
+        let mut close = false;
+        Window::new(format!("Messages from {username}"))
+            .id(Id::new("chatter messages"))
+            .collapsible(false)
+            .default_size([420.0, 320.0])
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+                    for (at, msg) in chatter.recent() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(
+                                RichText::new(
+                                    at.with_timezone(&Local)
+                                        .format("%H:%M:%S")
+                                        .to_string(),
+                                )
+                                .monospace(),
+                            );
+                            ui.label(
+                                RichText::new(msg.kind.name()).small(),
+                            );
+                            ui.label(&msg.text);
+                        });
+                    }
+                });
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+        if close {
+            self.chatter_messages = None;
+        }
+    }
+}
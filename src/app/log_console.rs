@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use eframe::egui::{
+    CollapsingHeader, ComboBox, Context as EguiCtx, RichText, ScrollArea,
+    TopBottomPanel,
+};
+use tracing::{level_filters::LevelFilter, Level};
+
+use super::App;
+
+const LOG_CONSOLE_HEIGHT: f32 = 200.0;
+/// Info and above are all that's kept.
+const LEVELS: [LevelFilter; 3] =
+    [LevelFilter::ERROR, LevelFilter::WARN, LevelFilter::INFO];
+
+impl App {
+    pub(super) fn update_log_console(&mut self, ctx: &EguiCtx) {
+        TopBottomPanel::bottom("log console").show(ctx, |ui| {
+            CollapsingHeader::new("Log").id_salt("log console").show(
+                ui,
+                |ui| {
+                    // events don't ask for a repaint themselves
+                    ui.ctx()
+                        .request_repaint_after(Duration::from_secs(1));
+                    let lines =
+                        self.log_console.lines(self.log_console_level);
+
+                    ui.horizontal(|ui| {
+                        let level = self.log_console_level;
+                        ComboBox::from_id_salt("log console level")
+                            .selected_text(level.to_string())
+                            .show_ui(ui, |ui| {
+                                for it in LEVELS {
+                                    ui.selectable_value(
+                                        &mut self.log_console_level,
+                                        it,
+                                        it.to_string(),
+                                    );
+                                }
+                            });
+                        if self.log_console_level != level {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.log_console_level_id,
+                                    self.log_console_level.to_string(),
+                                )
+                            });
+                        }
+                        if ui.button("Copy").clicked() {
+                            let text = lines
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            ui.ctx().copy_text(text);
+                        }
+                        if ui.button("Clear").clicked() {
+                            self.log_console.clear();
+                        }
+                    });
+
+                    ScrollArea::vertical()
+                        .max_height(LOG_CONSOLE_HEIGHT)
+                        .auto_shrink([false, true])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            let visuals = &ui.style().visuals;
+                            let (error, warn) = (
+                                visuals.error_fg_color,
+                                visuals.warn_fg_color,
+                            );
+                            for line in lines {
+                                let text =
+                                    RichText::new(line.to_string())
+                                        .monospace();
+                                let text = match line.level {
+                                    Level::ERROR => text.color(error),
+                                    Level::WARN => text.color(warn),
+                                    _ => text,
+                                };
+                                ui.label(text);
+                            }
+                        });
+                },
+            );
+        });
+    }
+}
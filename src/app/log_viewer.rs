@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use blooming_light_core::log::{self, LogEntry, LogEvent};
+use chrono::Local;
+use eframe::egui::{
+    Context as EguiCtx, RichText, ScrollArea, TextStyle, Ui, Window,
+};
+
+use super::App;
+
+/// A message log opened to look through, read once when opened.
+pub(super) struct LogView {
+    path: PathBuf,
+    entries: Vec<LogEntry>,
+}
+
+impl LogView {
+    pub(super) fn open(path: PathBuf) -> anyhow::Result<Self> {
+        let entries = log::read_entries(&path)?;
+        Ok(Self { path, entries })
+    }
+}
+
+impl App {
+    pub(super) fn update_log_viewer(&mut self, ctx: &EguiCtx) {
+        let Some(ref view) = self.log_view else {
+            return;
+        };
+
+        let mut close = false;
+        Window::new("Log viewer")
+            .collapsible(false)
+            .default_size([640.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{}, {} entry",
+                        view.path.display(),
+                        view.entries.len()
+                    ));
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                });
+                ui.separator();
+
+                let row_height = ui.text_style_height(&TextStyle::Body);
+                ScrollArea::both().auto_shrink(false).show_rows(
+                    ui,
+                    row_height,
+                    view.entries.len(),
+                    |ui, range| {
+                        for entry in &view.entries[range] {
+                            ui.horizontal(|ui| entry_ui(ui, entry));
+                        }
+                    },
+                );
+            });
+        if close {
+            self.log_view = None;
+        }
+    }
+}
+
+fn entry_ui(ui: &mut Ui, entry: &LogEntry) {
+    ui.label(
+        RichText::new(
+            entry
+                .ts
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        )
+        .monospace(),
+    );
+    let event = entry.event();
+    let event_name = match event {
+        LogEvent::Start => "start",
+        LogEvent::End => "end",
+        LogEvent::Receive => "receive",
+        LogEvent::Forward => "forward",
+        LogEvent::Delete => "delete",
+        LogEvent::Purge => "purge",
+        LogEvent::Overflow => "overflow",
+        LogEvent::Merge => "merge",
+        LogEvent::Restore => "restore",
+    };
+    ui.label(RichText::new(event_name).monospace().weak());
+    if matches!(event, LogEvent::Start | LogEvent::End) {
+        return;
+    }
+    ui.label(RichText::new(entry.kind.name()).small());
+    if let Some(ref username) = entry.username {
+        ui.label(RichText::new(format!("{username}:")).strong());
+    }
+    ui.label(entry.msg.as_str());
+    if let Some(ref reason) = entry.reason {
+        ui.label(RichText::new(format!("({reason})")).italics());
+    }
+}
@@ -0,0 +1,200 @@
+use blooming_light_core::{
+    midi::{self, MidiAction, MidiListener},
+    Notifier,
+};
+use eframe::egui::{
+    Button, ComboBox, Context as EguiCtx, Grid, RichText, Window,
+};
+use tracing::info;
+
+use super::App;
+
+impl App {
+    /// Also runs the bound actions while the window is closed.
+    pub(super) fn update_midi(&mut self, ctx: &EguiCtx) {
+        let inputs = match self.midi_listener {
+            Some(ref listener) => listener.pull(),
+            None => vec![],
+        };
+        for input in inputs {
+            self.midi_last_input = Some(input);
+            if let Some(action) = self.midi_learn.take() {
+                info!(?action, %input, "MIDI control bound");
+                self.midi_bindings.set(action, Some(input));
+                self.store_midi_bindings(ctx);
+            } else if let Some(action) = self.midi_bindings.action(input)
+            {
+                info!(?action, "MIDI control pressed");
+                self.run_midi_action(ctx, action);
+            }
+        }
+
+        if !self.midi_show {
+            return;
+        }
+
+        Window::new("MIDI")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Port");
+                    if self.midi_listener.is_some() {
+                        ui.label(&self.midi_bindings.device);
+                        if ui.button("Disconnect").clicked() {
+                            self.midi_listener = None;
+                            self.midi_learn = None;
+                            self.midi_bindings.device.clear();
+                            self.store_midi_bindings(ui.ctx());
+                        }
+                        return;
+                    }
+
+                    let device = &mut self.midi_bindings.device;
+                    let selected = match device.as_str() {
+                        "" => "None",
+                        device => device,
+                    };
+                    ComboBox::from_id_salt("midi port")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for path in midi::devices() {
+                                let path = path.display().to_string();
+                                let name = path.clone();
+                                ui.selectable_value(device, path, name);
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Raw MIDI ports of the sound cards, only \
+                             found on Linux",
+                        );
+                    if ui
+                        .add_enabled(
+                            !device.is_empty(),
+                            Button::new("Connect"),
+                        )
+                        .clicked()
+                    {
+                        self.connect_midi(ui.ctx());
+                        self.store_midi_bindings(ui.ctx());
+                    }
+                });
+
+                ui.separator();
+
+                Grid::new("midi bindings")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in MidiAction::ALL {
+                            ui.label(action.name());
+                            let learning =
+                                self.midi_learn == Some(action);
+                            let bound = self.midi_bindings.get(action);
+                            if learning {
+                                let color =
+                                    ui.style().visuals.warn_fg_color;
+                                ui.label(
+                                    RichText::new("Press a control...")
+                                        .color(color),
+                                );
+                            } else {
+                                ui.label(bound.map_or_else(
+                                    || "None".to_owned(),
+                                    |it| it.to_string(),
+                                ));
+                            }
+                            ui.horizontal(|ui| {
+                                if learning {
+                                    if ui.button("Cancel").clicked() {
+                                        self.midi_learn = None;
+                                    }
+                                } else if ui
+                                    .add_enabled(
+                                        self.midi_listener.is_some(),
+                                        Button::new("Learn"),
+                                    )
+                                    .clicked()
+                                {
+                                    self.midi_learn = Some(action);
+                                }
+                                if ui
+                                    .add_enabled(
+                                        bound.is_some(),
+                                        Button::new("Clear"),
+                                    )
+                                    .clicked()
+                                {
+                                    self.midi_bindings.set(action, None);
+                                    self.store_midi_bindings(ui.ctx());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(input) = self.midi_last_input {
+                    ui.label(format!("Last pressed: {input}"));
+                }
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.midi_show = false;
+                    self.midi_learn = None;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.midi_show_id,
+                            self.midi_show,
+                        )
+                    });
+                }
+            });
+    }
+
+    pub(super) fn connect_midi(&mut self, ctx: &EguiCtx) {
+        let ctx = ctx.clone();
+        let notifier = Notifier::new(move || ctx.request_repaint());
+        match MidiListener::open(
+            self.midi_bindings.device.as_ref(),
+            notifier,
+        ) {
+            Ok(listener) => self.midi_listener = Some(listener),
+            Err(err) => self.err_messages.push(format!("{err:?}")),
+        }
+    }
+
+    fn store_midi_bindings(&self, ctx: &EguiCtx) {
+        ctx.data_mut(|d| {
+            d.insert_persisted(
+                self.midi_bindings_id,
+                self.midi_bindings.clone(),
+            )
+        });
+    }
+
+    fn run_midi_action(&mut self, ctx: &EguiCtx, action: MidiAction) {
+        match action {
+            MidiAction::TogglePause => self.hold = !self.hold,
+            MidiAction::DelayUp => self.set_send_delay(
+                ctx,
+                self.msg_send_delay_secs + self.delay_nudge_secs,
+            ),
+            MidiAction::DelayDown => self.set_send_delay(
+                ctx,
+                self.msg_send_delay_secs - self.delay_nudge_secs,
+            ),
+            MidiAction::ApproveNext => self.approve_next(),
+            MidiAction::DeleteNext => {
+                // taken off and logged with the ones deleted by hand
+                let mut queue = self.message.lock();
+                if let Some(pending) =
+                    queue.iter_mut().filter(|it| !it.delete).last()
+                {
+                    pending.delete = true;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use std::cmp::Reverse;
+
+use blooming_light_core::fuzzy;
+use eframe::egui::{
+    vec2, Align2, Context as EguiCtx, Id, Key, KeyboardShortcut,
+    Modifiers, ScrollArea, TextEdit, Window,
+};
+use tracing::info;
+
+use super::App;
+
+/// [`blooming_light_core::hotkey::PALETTE`], which hotkeys can't take.
+const PALETTE_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::P);
+
+#[derive(Debug, Default)]
+pub(super) struct Palette {
+    query: String,
+    /// Index into the commands matching the query.
+    selected: usize,
+}
+
+/// What the palette can run, everything the top bar does and then some.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    TogglePause,
+    SendNext,
+    /// Asks first, like the button.
+    PurgeQueue,
+    Undo,
+    Redo,
+    RestartServer,
+    RestartClient,
+    RestartAll,
+    DrainAndQuit,
+    GenerateReport,
+    TogglePopout,
+    ToggleTriage,
+    ToggleHud,
+    SourceSettings,
+    ServerSettings,
+    QueueSettings,
+    TextSettings,
+    AlertSettings,
+    Midi,
+    PauseSchedule,
+    Stats,
+    Keywords,
+    Leaderboard,
+    Revenue,
+    Poll,
+    Qna,
+    Raffle,
+    Timeouts,
+    RedactUser,
+    Timers,
+    History,
+    DebugSettings,
+    SuperChats,
+    Preview,
+    ChromaOutput,
+    DemoSettings,
+}
+
+impl Command {
+    const ALL: [Command; 36] = [
+        Command::TogglePause,
+        Command::SendNext,
+        Command::PurgeQueue,
+        Command::Undo,
+        Command::Redo,
+        Command::RestartServer,
+        Command::RestartClient,
+        Command::RestartAll,
+        Command::DrainAndQuit,
+        Command::GenerateReport,
+        Command::TogglePopout,
+        Command::ToggleTriage,
+        Command::ToggleHud,
+        Command::SourceSettings,
+        Command::ServerSettings,
+        Command::QueueSettings,
+        Command::TextSettings,
+        Command::AlertSettings,
+        Command::Midi,
+        Command::PauseSchedule,
+        Command::Stats,
+        Command::Keywords,
+        Command::Leaderboard,
+        Command::Revenue,
+        Command::Poll,
+        Command::Qna,
+        Command::Raffle,
+        Command::Timeouts,
+        Command::RedactUser,
+        Command::Timers,
+        Command::History,
+        Command::DebugSettings,
+        Command::SuperChats,
+        Command::Preview,
+        Command::ChromaOutput,
+        Command::DemoSettings,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Command::TogglePause => "Pause/Resume queue",
+            Command::SendNext => "Send next message now",
+            Command::PurgeQueue => "Purge queue...",
+            Command::Undo => "Undo delete or purge",
+            Command::Redo => "Redo delete",
+            Command::RestartServer => "Restart server",
+            Command::RestartClient => "Restart Websocket client",
+            Command::RestartAll => "Restart all",
+            Command::DrainAndQuit => "Drain and quit",
+            Command::GenerateReport => "Generate session report",
+            Command::TogglePopout => "Pop out/Dock queue",
+            Command::ToggleTriage => "Start/Stop keyboard triage",
+            Command::ToggleHud => "Show/Hide performance HUD",
+            Command::SourceSettings => "Open Source Settings",
+            Command::ServerSettings => "Open Server Settings",
+            Command::QueueSettings => "Open Queue Settings",
+            Command::TextSettings => "Open Text Settings",
+            Command::AlertSettings => "Open Alert Settings",
+            Command::Midi => "Open MIDI",
+            Command::PauseSchedule => "Open Pause Schedule",
+            Command::Stats => "Open Stats",
+            Command::Keywords => "Open Keywords",
+            Command::Leaderboard => "Open Leaderboard",
+            Command::Revenue => "Open Revenue",
+            Command::Poll => "Open Poll",
+            Command::Qna => "Open Q&A",
+            Command::Raffle => "Open Raffle",
+            Command::Timeouts => "Open Timeouts",
+            Command::RedactUser => "Open Redact User",
+            Command::Timers => "Open Timers",
+            Command::History => "Open History",
+            Command::DebugSettings => "Open Debug Settings",
+            Command::SuperChats => "Open SuperChats",
+            Command::Preview => "Open Preview",
+            Command::ChromaOutput => "Open Chroma output",
+            Command::DemoSettings => "Open Demo Settings",
+        }
+    }
+}
+
+impl App {
+    /// Toggled with Ctrl+P, typing narrows the commands down and Enter
+    /// runs the selected one. After the hotkeys, as Ctrl+P would also
+    /// take Ctrl+Shift+P.
+    pub(super) fn update_palette(&mut self, ctx: &EguiCtx) {
+        if ctx.input_mut(|i| i.consume_shortcut(&PALETTE_SHORTCUT)) {
+            self.palette = match self.palette {
+                Some(_) => None,
+                None => Some(Palette::default()),
+            };
+        }
+        let Some(ref mut palette) = self.palette else {
+            return;
+        };
+
+        let mut matches = Command::ALL
+            .into_iter()
+            .filter_map(|it| {
+                Some((fuzzy::score(&palette.query, it.name())?, it))
+            })
+            .collect::<Vec<_>>();
+        // stable, ties stay in the order above
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        // before the text edit would take them
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        if down {
+            palette.selected += 1;
+        }
+        palette.selected =
+            palette.selected.min(matches.len().saturating_sub(1));
+
+        let mut run = matches
+            .get(palette.selected)
+            .filter(|_| enter)
+            .map(|(_, it)| *it);
+        Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_TOP, vec2(0.0, 40.0))
+            .show(ctx, |ui| {
+                let res = ui.add(
+                    TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command")
+                        .desired_width(360.0),
+                );
+                res.request_focus();
+                if res.changed() {
+                    palette.selected = 0;
+                }
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    if matches.is_empty() {
+                        ui.weak("No matching command");
+                    }
+                    for (i, (_, command)) in matches.iter().enumerate() {
+                        let selected = i == palette.selected;
+                        let res =
+                            ui.selectable_label(selected, command.name());
+                        if selected && (up || down) {
+                            res.scroll_to_me(None);
+                        }
+                        if res.clicked() {
+                            run = Some(*command);
+                        }
+                    }
+                });
+            });
+        if escape || run.is_some() {
+            self.palette = None;
+        }
+        if let Some(command) = run {
+            info!(?command, "running from the command palette");
+            self.run_command(ctx, command);
+        }
+    }
+
+    fn run_command(&mut self, ctx: &EguiCtx, command: Command) {
+        let open = |show: &mut bool, id: Id| {
+            *show = true;
+            ctx.data_mut(|d| d.insert_persisted(id, true));
+        };
+        match command {
+            Command::TogglePause => self.hold = !self.hold,
+            Command::SendNext => self.approve_next(),
+            Command::PurgeQueue => self.purge_confirm_show = true,
+            Command::Undo => self.undo(),
+            Command::Redo => self.redo(),
+            Command::RestartServer => {
+                if let Ok(ref mut network) = self.network {
+                    match network.restart_server() {
+                        Ok(()) => network.network_server_err = None,
+                        Err(err) => {
+                            self.err_messages.push(format!("{err:?}"))
+                        }
+                    }
+                }
+            }
+            Command::RestartClient => {
+                if let Ok(ref mut network) = self.network {
+                    match network.restart_ws_client() {
+                        Ok(()) => network.network_ws_client_err = None,
+                        Err(err) => {
+                            self.err_messages.push(format!("{err:?}"))
+                        }
+                    }
+                }
+            }
+            Command::RestartAll => {
+                if let Ok(ref network) = self.network {
+                    if let Err(err) = network.restart_all() {
+                        self.err_messages.push(format!("{err:?}"));
+                    }
+                    self.draining = false;
+                }
+            }
+            Command::DrainAndQuit if self.draining => {}
+            Command::DrainAndQuit => {
+                if let Ok(ref network) = self.network {
+                    match network.drain() {
+                        Ok(()) => self.draining = true,
+                        Err(err) => {
+                            self.err_messages.push(format!("{err:?}"))
+                        }
+                    }
+                }
+            }
+            Command::GenerateReport => self.generate_report(),
+            Command::TogglePopout => {
+                self.set_queue_popout(ctx, !self.queue_popout)
+            }
+            Command::ToggleTriage => {
+                self.set_triage(self.triage.is_none())
+            }
+            Command::ToggleHud => {
+                self.hud_show = !self.hud_show;
+                ctx.data_mut(|d| {
+                    d.insert_persisted(self.hud_show_id, self.hud_show)
+                });
+            }
+            Command::SourceSettings => open(
+                &mut self.source_settings_show,
+                self.source_settings_show_id,
+            ),
+            Command::ServerSettings => open(
+                &mut self.server_settings_show,
+                self.server_settings_show_id,
+            ),
+            Command::QueueSettings => open(
+                &mut self.queue_settings_show,
+                self.queue_settings_show_id,
+            ),
+            Command::TextSettings => open(
+                &mut self.text_settings_show,
+                self.text_settings_show_id,
+            ),
+            Command::AlertSettings => open(
+                &mut self.alert_settings_show,
+                self.alert_settings_show_id,
+            ),
+            Command::Midi => open(&mut self.midi_show, self.midi_show_id),
+            Command::PauseSchedule => open(
+                &mut self.pause_schedule_show,
+                self.pause_schedule_show_id,
+            ),
+            Command::Stats => {
+                open(&mut self.stats_show, self.stats_show_id)
+            }
+            Command::Keywords => {
+                open(&mut self.keywords_show, self.keywords_show_id)
+            }
+            Command::Leaderboard => {
+                open(&mut self.leaderboard_show, self.leaderboard_show_id)
+            }
+            Command::Revenue => {
+                open(&mut self.revenue_show, self.revenue_show_id)
+            }
+            Command::Poll => open(&mut self.poll_show, self.poll_show_id),
+            Command::Qna => open(&mut self.qna_show, self.qna_show_id),
+            Command::Raffle => {
+                open(&mut self.raffle_show, self.raffle_show_id)
+            }
+            Command::Timeouts => {
+                open(&mut self.timeouts_show, self.timeouts_show_id)
+            }
+            Command::RedactUser => self.redact_show = true,
+            Command::Timers => self.timers_show = true,
+            Command::History => {
+                open(&mut self.history_show, self.history_show_id)
+            }
+            Command::DebugSettings => open(
+                &mut self.debug_settings_show,
+                self.debug_settings_show_id,
+            ),
+            Command::SuperChats => {
+                open(&mut self.superchats_show, self.superchats_show_id)
+            }
+            Command::Preview => {
+                open(&mut self.preview_show, self.preview_show_id)
+            }
+            Command::ChromaOutput => {
+                open(&mut self.chroma_show, self.chroma_show_id)
+            }
+            Command::DemoSettings => open(
+                &mut self.demo_settings_show,
+                self.demo_settings_show_id,
+            ),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use blooming_light_core::poll::Poll;
+use eframe::egui::{
+    Button, Context as EguiCtx, Grid, ProgressBar, TextEdit, Ui, Window,
+};
+
+use super::App;
+
+impl App {
+    /// Puts a question to chat and shows the votes as they come in.
+    pub(super) fn update_poll(&mut self, ctx: &EguiCtx) {
+        if !self.poll_show {
+            return;
+        }
+
+        Window::new("Poll")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut new_poll = false;
+                let mut results = None;
+                match self.poll {
+                    Some(ref mut poll) => {
+                        ui.heading(&poll.question);
+                        results_ui(ui, poll);
+                        ui.label(format!("{} vote", poll.total()));
+                        ui.horizontal(|ui| {
+                            if poll.is_open() {
+                                if ui
+                                    .button("End and show results")
+                                    .on_hover_text(
+                                        "Stop taking votes and send the \
+                                         results to the overlays",
+                                    )
+                                    .clicked()
+                                {
+                                    results = Some(poll.close());
+                                }
+                                if ui
+                                    .button("End")
+                                    .on_hover_text("Stop taking votes")
+                                    .clicked()
+                                {
+                                    poll.close();
+                                }
+                            } else if ui.button("New poll").clicked() {
+                                new_poll = true;
+                            }
+                        });
+                    }
+                    None => {
+                        Grid::new("poll settings").num_columns(2).show(
+                            ui,
+                            |ui| {
+                                ui.label("Question");
+                                ui.add(
+                                    TextEdit::singleline(
+                                        &mut self.poll_question_draft,
+                                    )
+                                    .hint_text("e.g. Next game?"),
+                                );
+                                ui.end_row();
+
+                                ui.label("Options");
+                                ui.add(
+                                    TextEdit::multiline(
+                                        &mut self.poll_options_draft,
+                                    )
+                                    .desired_rows(3)
+                                    .hint_text("One per line"),
+                                )
+                                .on_hover_text(
+                                    "Chat votes by typing an option or \
+                                     its number, one vote each",
+                                );
+                                ui.end_row();
+                            },
+                        );
+                        let options = self
+                            .poll_options_draft
+                            .lines()
+                            .map(|it| it.trim().to_owned())
+                            .filter(|it| !it.is_empty())
+                            .collect::<Vec<_>>();
+                        if ui
+                            .add_enabled(
+                                options.len() >= 2,
+                                Button::new("Start"),
+                            )
+                            .clicked()
+                        {
+                            self.poll = Some(Poll::new(
+                                self.poll_question_draft.trim(),
+                                options,
+                            ));
+                        }
+                    }
+                }
+                if new_poll {
+                    self.poll = None;
+                }
+                if let (Some(frame), Ok(network)) =
+                    (results, &self.network)
+                {
+                    network.broadcast_poll(&frame);
+                }
+
+                ui.separator();
+
+                if ui
+                    .checkbox(&mut self.poll_hide_votes, "Hide votes")
+                    .on_hover_text(
+                        "Keep votes out of the queue during a poll",
+                    )
+                    .changed()
+                {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.poll_hide_votes_id,
+                            self.poll_hide_votes,
+                        )
+                    });
+                }
+                if ui.button("Close").clicked() {
+                    self.poll_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.poll_show_id,
+                            self.poll_show,
+                        )
+                    });
+                }
+            });
+    }
+}
+
+/// Votes for each option so far, with a bar of their share.
+fn results_ui(ui: &mut Ui, poll: &Poll) {
+    let total = poll.total();
+    Grid::new("poll results").num_columns(3).show(ui, |ui| {
+        for (idx, (option, votes)) in
+            poll.options.iter().zip(poll.counts()).enumerate()
+        {
+            ui.label(format!("{}. {option}", idx + 1));
+            ui.add(
+                ProgressBar::new(votes as f32 / total.max(1) as f32)
+                    .desired_width(160.0),
+            );
+            ui.label(votes.to_string());
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,231 @@
+use std::time::Instant;
+
+use blooming_light_core::{
+    combo::ComboUpdate,
+    message::{Message, MessageKind},
+    network::theme::ThemeVars,
+    preview::{OverlayPreview, PreviewFrame},
+    release::Released,
+    text::LengthLimit,
+};
+use eframe::{
+    egui::{
+        vec2, Align2, Color32, Context as EguiCtx, FontId, Image, Rect,
+        Sense, Ui, Vec2, Window,
+    },
+    epaint::FontFamily,
+};
+
+use super::App;
+
+pub(super) const PREVIEW_SIZE: Vec2 = vec2(480.0, 270.0);
+/// Overlays are usually captured at 1080p, spacing is in their pixels.
+const OVERLAY_WIDTH: f32 = 1920.0;
+
+impl App {
+    pub(super) fn update_preview(&mut self, ctx: &EguiCtx) {
+        if !self.preview_show {
+            return;
+        }
+
+        let vars = &self.server_config.theme_vars;
+        let frame = preview_frame(ctx, vars, PREVIEW_SIZE);
+        let dt = ctx.input(|i| i.stable_dt);
+        let now = self.message.lock().now();
+        self.overlay_preview.step(dt, &frame, now);
+        if !self.overlay_preview.is_idle() {
+            ctx.request_repaint();
+        }
+
+        Window::new("Overlay preview")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let (rect, _) =
+                    ui.allocate_exact_size(PREVIEW_SIZE, Sense::hover());
+                ui.painter_at(rect).rect_filled(
+                    rect,
+                    0.0,
+                    Color32::from_gray(32),
+                );
+                paint_overlay(
+                    ui,
+                    rect,
+                    &self.overlay_preview,
+                    &frame,
+                    vars,
+                    now,
+                );
+
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} waiting",
+                        self.overlay_preview.pending_len()
+                    ))
+                    .on_hover_text(
+                        "Drawn like the built-in danmaku layout with the \
+                         saved theme variables, other layouts and \
+                         themes look different",
+                    );
+                    if ui.button("Clear").clicked() {
+                        self.overlay_preview.clear();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.preview_show = false;
+                        self.overlay_preview.clear();
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.preview_show_id,
+                                self.preview_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+}
+
+/// Shows a message just released from the queue on `preview`, as the
+/// overlay shows it on a `size` large frame.
+pub(super) fn preview_released(
+    preview: &mut OverlayPreview,
+    ctx: &EguiCtx,
+    vars: &ThemeVars,
+    size: Vec2,
+    length_limit: &LengthLimit,
+    released: &Released,
+) {
+    let filtered = &released.filtered;
+    if released.combo > 1 {
+        let text = length_limit.truncate(&filtered.text);
+        let combo = ComboUpdate::new(text, released.combo);
+        let label = OverlayPreview::combo_label(&combo.text, combo.count);
+        let width = text_width(ctx, vars, size, label);
+        preview.push_combo(&combo, width);
+        return;
+    }
+    for part in length_limit.apply(filtered) {
+        preview_message(
+            preview,
+            ctx,
+            vars,
+            size,
+            &part,
+            released.released_at,
+        );
+    }
+}
+
+/// Shows a message just sent to the overlay on `preview`.
+pub(super) fn preview_message(
+    preview: &mut OverlayPreview,
+    ctx: &EguiCtx,
+    vars: &ThemeVars,
+    size: Vec2,
+    msg: &Message,
+    now: Instant,
+) {
+    let label = OverlayPreview::label(msg);
+    let images = msg.attachments.len() as f32
+        * preview_frame(ctx, vars, size).row_height;
+    let width = images + text_width(ctx, vars, size, label);
+    preview.push(msg, width, now);
+}
+
+/// Draws `preview` laid out for `frame` into `rect`, over whatever is
+/// painted there already.
+pub(super) fn paint_overlay(
+    ui: &mut Ui,
+    rect: Rect,
+    preview: &OverlayPreview,
+    frame: &PreviewFrame,
+    vars: &ThemeVars,
+    now: Instant,
+) {
+    let painter = ui.painter_at(rect);
+    let font_id = font_id(vars, rect.width());
+    // images paint through the ui, keep them inside too
+    let clip_rect = ui.clip_rect();
+    ui.set_clip_rect(rect.intersect(clip_rect));
+
+    for (idx, row) in preview.rows().enumerate() {
+        let top = rect.top() + idx as f32 * frame.row_height;
+        for item in row {
+            let mut x = rect.left() + item.x;
+            for src in &item.images {
+                let size = vec2(frame.row_height, frame.row_height);
+                Image::new(src.as_str()).paint_at(
+                    ui,
+                    Rect::from_min_size((x, top).into(), size),
+                );
+                x += frame.row_height;
+            }
+            painter.text(
+                (x, top).into(),
+                Align2::LEFT_TOP,
+                &item.label,
+                font_id.clone(),
+                kind_color(vars, item.kind),
+            );
+        }
+    }
+    for (idx, item) in preview.pinned(frame).enumerate() {
+        let secs = item
+            .until
+            .saturating_duration_since(now)
+            .as_secs_f64()
+            .ceil();
+        let bottom = rect.bottom() - idx as f32 * frame.row_height;
+        painter.text(
+            (rect.left(), bottom).into(),
+            Align2::LEFT_BOTTOM,
+            format!("{} ({secs}s)", item.label),
+            font_id.clone(),
+            kind_color(vars, item.kind),
+        );
+    }
+    ui.set_clip_rect(clip_rect);
+}
+
+/// Font size is a percentage of the overlay width.
+fn font_id(vars: &ThemeVars, width: f32) -> FontId {
+    FontId::new(vars.font_size / 100.0 * width, FontFamily::Proportional)
+}
+
+fn text_width(
+    ctx: &EguiCtx,
+    vars: &ThemeVars,
+    size: Vec2,
+    text: String,
+) -> f32 {
+    let font_id = font_id(vars, size.x);
+    ctx.fonts(|f| f.layout_no_wrap(text, font_id, Color32::WHITE))
+        .size()
+        .x
+}
+
+pub(super) fn preview_frame(
+    ctx: &EguiCtx,
+    vars: &ThemeVars,
+    size: Vec2,
+) -> PreviewFrame {
+    let row_height = ctx.fonts(|f| f.row_height(&font_id(vars, size.x)));
+    PreviewFrame {
+        width: size.x,
+        height: size.y,
+        row_height: row_height.max(1.0),
+        speed: vars.speed,
+        spacing: vars.spacing * size.x / OVERLAY_WIDTH,
+    }
+}
+fn kind_color(vars: &ThemeVars, kind: MessageKind) -> Color32 {
+    let color = match kind {
+        MessageKind::Chat => &vars.text_color,
+        MessageKind::Gift => &vars.gift_color,
+        MessageKind::SuperChat => &vars.superchat_color,
+        MessageKind::Follow
+        | MessageKind::Subscription
+        | MessageKind::Raid => &vars.event_color,
+    };
+    Color32::from_hex(color).unwrap_or(Color32::WHITE)
+}
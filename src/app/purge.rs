@@ -0,0 +1,80 @@
+use blooming_light_core::{
+    history::Moderation,
+    log::{LogEntry, LogEvent},
+    stats::RateMark,
+};
+use eframe::egui::{
+    Button, Context as EguiCtx, RichText, TextEdit, Window,
+};
+use tracing::warn;
+
+use super::App;
+
+impl App {
+    pub(super) fn update_purge(&mut self, ctx: &EguiCtx) {
+        if !self.purge_confirm_show {
+            return;
+        }
+
+        Window::new("Purge queue")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let pending = {
+                    let queue = self.message.lock();
+                    queue.len()
+                        + queue.waiting_len()
+                        + queue.scheduled_len()
+                };
+                ui.label(format!(
+                    "Delete all {pending} pending message? Nothing is \
+                     forwarded while this is open.",
+                ));
+                ui.add(
+                    TextEdit::singleline(&mut self.purge_reason)
+                        .hint_text("Reason (optional)"),
+                );
+                ui.horizontal(|ui| {
+                    let purge_btn = Button::new(
+                        RichText::new("Purge")
+                            .color(ui.style().visuals.error_fg_color),
+                    );
+                    if ui.add(purge_btn).clicked() {
+                        self.purge();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.purge_confirm_show = false;
+                        self.purge_reason.clear();
+                    }
+                });
+            });
+    }
+
+    pub(super) fn purge(&mut self) {
+        let reason = self.purge_reason.trim();
+        let reason = (!reason.is_empty()).then(|| reason.to_owned());
+        let mut queue = self.message.lock();
+        let snapshot = queue.snapshot();
+        let purged = queue.purge();
+        self.rate_history.mark(queue.now(), RateMark::Purged);
+        drop(queue);
+        if !snapshot.is_empty() {
+            let at = snapshot.saved_at;
+            self.history.push(Moderation::Purge(snapshot), at);
+        }
+        warn!(count = purged.len(), ?reason, "purging queue");
+        if let Ok(ref network) = self.network {
+            for msg in purged {
+                network.write_log_entry(
+                    LogEntry::new(msg, LogEvent::Purge)
+                        .with_reason(reason.clone()),
+                );
+            }
+        }
+        // don't bring purged messages back after a crash
+        self.save_queue_snapshot(true);
+
+        self.purge_confirm_show = false;
+        self.purge_reason.clear();
+    }
+}
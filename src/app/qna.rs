@@ -0,0 +1,161 @@
+use blooming_light_core::{
+    alias::UserAliases,
+    log::{LogEntry, LogEvent},
+    message::Message,
+    qna::Questions,
+    text::LengthLimit,
+};
+use chrono::Local;
+use eframe::egui::{
+    Context as EguiCtx, Id, ScrollArea, TextEdit, Ui, Window,
+};
+
+use super::{message_label, App};
+
+/// What to do with a held question.
+#[derive(Clone, Copy)]
+enum QuestionAction {
+    /// Forwards it right away.
+    Send,
+    /// Queues it like any other message.
+    Queue,
+    Dismiss,
+}
+
+impl App {
+    /// Questions routed away from the queue, forwarded one by one during
+    /// a Q&A segment.
+    pub(super) fn update_qna(&mut self, ctx: &EguiCtx) {
+        if !self.qna_show {
+            return;
+        }
+
+        Window::new(format!("Q&A ({})", self.questions.len()))
+            .id(Id::new("qna"))
+            .collapsible(false)
+            .default_height(360.0)
+            .show(ctx, |ui| {
+                self.qna_settings_ui(ui);
+
+                ui.separator();
+
+                let action = questions_ui(
+                    ui,
+                    &self.questions,
+                    &self.length_limit,
+                    &self.user_aliases,
+                );
+                if let Some((idx, action)) = action {
+                    if let Some(msg) = self.questions.take(idx) {
+                        self.run_question_action(msg, action);
+                    }
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    for (name, action) in [
+                        ("Queue all", QuestionAction::Queue),
+                        ("Dismiss all", QuestionAction::Dismiss),
+                    ] {
+                        if ui.button(name).clicked() {
+                            for msg in self.questions.take_all() {
+                                self.run_question_action(msg, action);
+                            }
+                        }
+                    }
+                    if ui.button("Close").clicked() {
+                        self.qna_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.qna_show_id,
+                                self.qna_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    fn qna_settings_ui(&mut self, ui: &mut Ui) {
+        let qna = &mut self.qna;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut qna.enabled, "Route questions here")
+                .on_hover_text(
+                    "Chat ending with a question mark is held here \
+                     instead of queued",
+                )
+                .changed();
+            ui.label("Prefix");
+            changed |= ui
+                .add(
+                    TextEdit::singleline(&mut qna.prefix)
+                        .hint_text("e.g. Q:")
+                        .desired_width(60.0),
+                )
+                .on_hover_text("Also holds messages starting with it")
+                .changed();
+        });
+        if changed {
+            ui.data_mut(|d| d.insert_persisted(self.qna_id, qna.clone()));
+        }
+    }
+
+    fn run_question_action(
+        &mut self,
+        msg: Message,
+        action: QuestionAction,
+    ) {
+        match action {
+            QuestionAction::Send => {
+                let mut queue = self.message.lock();
+                queue.push(msg.clone());
+                queue.send_now(&msg);
+            }
+            QuestionAction::Queue => self.message.lock().push(msg),
+            QuestionAction::Dismiss => {
+                if let Ok(ref network) = self.network {
+                    network.write_log_entry(
+                        LogEntry::new(msg, LogEvent::Delete)
+                            .with_reason(Some("question".to_owned())),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Held questions, oldest first, with a button for each action. Returns
+/// the one clicked.
+fn questions_ui(
+    ui: &mut Ui,
+    questions: &Questions,
+    length_limit: &LengthLimit,
+    aliases: &UserAliases,
+) -> Option<(usize, QuestionAction)> {
+    let mut clicked = None;
+    ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+        for (idx, (at, msg)) in questions.iter().enumerate() {
+            ui.horizontal(|ui| {
+                for (name, action) in [
+                    ("Send", QuestionAction::Send),
+                    ("Queue", QuestionAction::Queue),
+                    ("Dismiss", QuestionAction::Dismiss),
+                ] {
+                    if ui.button(name).clicked() {
+                        clicked = Some((idx, action));
+                    }
+                }
+                ui.label(
+                    at.with_timezone(&Local)
+                        .format("%H:%M:%S")
+                        .to_string(),
+                );
+                message_label(ui, msg, length_limit, aliases.of(msg));
+            });
+        }
+    });
+    clicked
+}
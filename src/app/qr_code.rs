@@ -0,0 +1,106 @@
+use eframe::egui::{
+    Color32, ColorImage, ComboBox, Context as EguiCtx, Image,
+    TextureHandle, TextureOptions, Window,
+};
+use qrcode::{Color, QrCode};
+
+use super::App;
+
+/// Size of one QR module on screen.
+const MODULE_SIZE: f32 = 4.0;
+/// Light modules around the code, so phones pick it up.
+const QUIET_ZONE: usize = 4;
+
+impl App {
+    pub(super) fn update_qr_code(&mut self, ctx: &EguiCtx) {
+        if !self.qr_code_show {
+            return;
+        }
+
+        let urls = self.server_config.lan_urls();
+        if !urls.contains(&self.qr_code_url) {
+            self.qr_code_url = self.server_config.lan_url();
+        }
+        let url = self.qr_code_url.clone();
+        if self.qr_code.as_ref().is_none_or(|(it, _)| *it != url) {
+            self.qr_code =
+                qr_texture(ctx, &url).map(|it| (url.clone(), it));
+        }
+
+        Window::new("Overlay QR code")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                match &self.qr_code {
+                    Some((_, texture)) => {
+                        ui.add(Image::new(texture).fit_to_exact_size(
+                            texture.size_vec2() * MODULE_SIZE,
+                        ));
+                    }
+                    None => {
+                        ui.label("URL too long for a QR code");
+                    }
+                }
+                if urls.len() > 1 {
+                    ComboBox::from_id_salt("qr code url")
+                        .selected_text(&url)
+                        .show_ui(ui, |ui| {
+                            for it in urls {
+                                let text = it.clone();
+                                ui.selectable_value(
+                                    &mut self.qr_code_url,
+                                    it,
+                                    text,
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "This machine is on several networks, pick \
+                             the one the other device is on",
+                        );
+                }
+                ui.horizontal(|ui| {
+                    ui.hyperlink(&url);
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(url.clone());
+                    }
+                });
+                if url.contains("://127.") || url.contains("://[::1]") {
+                    ui.label(
+                        "Only this machine can open it, listen on \
+                         0.0.0.0 in the server settings to reach it from \
+                         others",
+                    );
+                }
+
+                if ui.button("Close").clicked() {
+                    self.qr_code_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.qr_code_show_id,
+                            self.qr_code_show,
+                        )
+                    });
+                }
+            });
+    }
+}
+
+fn qr_texture(ctx: &EguiCtx, url: &str) -> Option<TextureHandle> {
+    let code = QrCode::new(url).ok()?;
+    let width = code.width();
+    let size = width + QUIET_ZONE * 2;
+    let mut image = ColorImage::new([size, size], Color32::WHITE);
+    for (idx, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (x, y) = (idx % width, idx / width);
+            image[(x + QUIET_ZONE, y + QUIET_ZONE)] = Color32::BLACK;
+        }
+    }
+    Some(ctx.load_texture(
+        "overlay qr code",
+        image,
+        TextureOptions::NEAREST,
+    ))
+}
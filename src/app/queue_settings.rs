@@ -0,0 +1,229 @@
+use blooming_light_core::queue::OverflowPolicy;
+use eframe::egui::{
+    ComboBox, Context as EguiCtx, DragValue, Grid, TextEdit, Window,
+};
+
+use super::{parse_delay_presets, App};
+
+impl App {
+    pub(super) fn update_queue_settings(&mut self, ctx: &EguiCtx) {
+        if !self.queue_settings_show {
+            return;
+        }
+
+        Window::new("Queue Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let limit = &mut self.queue_limit;
+                let mut changed = false;
+                Grid::new("queue settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Max length");
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut limit.max_len)
+                                    .range(0..=100000),
+                            )
+                            .on_hover_text(
+                                "Cap on pending messages, 0 for no cap",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("When full");
+                        ComboBox::from_id_salt("queue overflow policy")
+                            .selected_text(limit.policy.name())
+                            .show_ui(ui, |ui| {
+                                for policy in OverflowPolicy::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut limit.policy,
+                                            policy,
+                                            policy.name(),
+                                        )
+                                        .changed();
+                                }
+                            });
+                        ui.end_row();
+                    },
+                );
+                if changed {
+                    self.message.lock().set_limit(limit.clone());
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.queue_limit_id,
+                            limit.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                Grid::new("delay settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Delay presets(secs)");
+                        let presets = parse_delay_presets(
+                            &self.delay_presets_draft,
+                        );
+                        let res = ui
+                            .add(
+                                TextEdit::singleline(
+                                    &mut self.delay_presets_draft,
+                                )
+                                .text_color_opt(presets.is_none().then(
+                                    || ui.style().visuals.error_fg_color,
+                                )),
+                            )
+                            .on_hover_text(
+                                "Comma separated, e.g. 5, 10, 30",
+                            );
+                        if res.changed() {
+                            if let Some(presets) = parse_delay_presets(
+                                &self.delay_presets_draft,
+                            ) {
+                                ui.data_mut(|d| {
+                                    d.insert_persisted(
+                                        self.delay_presets_id,
+                                        presets.clone(),
+                                    )
+                                });
+                                self.delay_presets = presets;
+                            }
+                        }
+                        ui.end_row();
+
+                        ui.label("Nudge step(secs)");
+                        let res = ui.add(
+                            DragValue::new(&mut self.delay_nudge_secs)
+                                .min_decimals(1)
+                                .max_decimals(1)
+                                .range(0.1..=60.0)
+                                .speed(0.1),
+                        );
+                        if res.changed() {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.delay_nudge_secs_id,
+                                    self.delay_nudge_secs,
+                                )
+                            });
+                        }
+                        ui.end_row();
+                    },
+                );
+
+                ui.separator();
+
+                Grid::new("gift settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Gift window(secs)");
+                        let res = ui
+                            .add(
+                                DragValue::new(
+                                    &mut self.gift_window_secs,
+                                )
+                                .min_decimals(1)
+                                .max_decimals(1)
+                                .range(0.0..=60.0)
+                                .speed(0.1),
+                            )
+                            .on_hover_text(
+                                "Gifts from one user within this many \
+                             seconds are queued as one summary, 0 for \
+                             off",
+                            );
+                        if res.changed() {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.gift_window_secs_id,
+                                    self.gift_window_secs,
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        let flood = &mut self.flood_limit;
+                        ui.label("Flood limit");
+                        let mut changed = ui
+                            .add(
+                                DragValue::new(&mut flood.max_messages)
+                                    .range(0..=100)
+                                    .suffix(" msg"),
+                            )
+                            .on_hover_text(
+                                "Messages one user can send within the \
+                                 flood window, the rest are queued as one \
+                                 \"...and N more\" message. 0 for off",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("Flood window(secs)");
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut flood.window_secs)
+                                    .min_decimals(1)
+                                    .max_decimals(1)
+                                    .range(1.0..=600.0)
+                                    .speed(0.1),
+                            )
+                            .changed();
+                        if changed {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.flood_limit_id,
+                                    *flood,
+                                )
+                            });
+                        }
+                        ui.end_row();
+                    },
+                );
+
+                ui.separator();
+
+                let res = ui
+                    .checkbox(
+                        &mut self.ask_delete_reason,
+                        "Ask for a reason after deleting",
+                    )
+                    .on_hover_text(
+                        "Saved with the delete in the message log, handy \
+                         when moderating as a team",
+                    );
+                if res.changed() {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.ask_delete_reason_id,
+                            self.ask_delete_reason,
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                self.user_aliases_ui(ui);
+
+                ui.separator();
+
+                ui.label("Hotkeys, while this window has focus");
+                self.hotkeys_ui(ui);
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.queue_settings_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.queue_settings_show_id,
+                            self.queue_settings_show,
+                        )
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,420 @@
+use std::ops::Range;
+
+use blooming_light_core::{
+    alias::UserAliases,
+    flag::FlagWords,
+    lang::{Language, LanguageFilter},
+    mention::Mentions,
+    message::{Message, MessageKind},
+    queue::{PendingMessage, SharedQueue},
+    text::{LengthLimit, UrlFilter},
+};
+use chrono::Local;
+use eframe::egui::{
+    pos2, vec2, CentralPanel, Context as EguiCtx, Id, Rect, RichText,
+    ScrollArea, Sense, Ui, ViewportBuilder, ViewportId, WidgetInfo,
+    WidgetType,
+};
+
+use tracing::warn;
+
+use super::{
+    message_label, thumbnails_ui,
+    triage::{triage_button, Triage},
+    App, PROGRESS_REPAINT,
+};
+
+impl App {
+    /// Shows the pending queue in an OS window of its own while popped
+    /// out, where it was last left.
+    pub(super) fn update_queue_window(&mut self, ctx: &EguiCtx) {
+        if !self.queue_popout {
+            return;
+        }
+
+        let mut builder = ViewportBuilder::default()
+            .with_title("Blooming Light queue")
+            .with_inner_size(vec2(480.0, 640.0));
+        if let Some([x, y, width, height]) = self.queue_window {
+            builder = builder
+                .with_position(pos2(x, y))
+                .with_inner_size(vec2(width, height));
+        }
+        let mut docked = false;
+        let mut pause = false;
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of("queue window"),
+            builder,
+            |ctx, _| {
+                let (outer, inner, closed) = ctx.input(|i| {
+                    let info = i.viewport();
+                    (
+                        info.outer_rect,
+                        info.inner_rect,
+                        info.close_requested(),
+                    )
+                });
+                if let (Some(outer), Some(inner)) = (outer, inner) {
+                    let window = [
+                        outer.left(),
+                        outer.top(),
+                        inner.width(),
+                        inner.height(),
+                    ];
+                    ctx.data_mut(|d| {
+                        if d.get_persisted(self.queue_window_id)
+                            != Some(window)
+                        {
+                            d.insert_persisted(
+                                self.queue_window_id,
+                                window,
+                            )
+                        }
+                    });
+                }
+                docked = closed;
+
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        copy_all_button(ui, &self.message);
+                        let pending =
+                            self.message.lock_quiet().waiting_len();
+                        ui.label(format!("{pending} message pending"));
+                        if ui.button("Dock").clicked() {
+                            docked = true;
+                        }
+                        triage_button(ui, &mut self.triage);
+                        queue_tabs_ui(ui, &mut self.queue_mentions_only);
+                    });
+                    ui.separator();
+                    let rows = QueueRows {
+                        flag_words: &self.flag_words,
+                        length_limit: &self.length_limit,
+                        url_filter: &self.url_filter,
+                        aliases: &self.user_aliases,
+                        languages: &self.language_filter,
+                        mentions: &self.mentions,
+                        mentions_only: self.queue_mentions_only,
+                        show_thumbnails: self.show_thumbnails,
+                    };
+                    pause = queue_list_ui(
+                        ui,
+                        &self.message,
+                        &rows,
+                        self.triage.as_mut(),
+                    );
+                });
+            },
+        );
+        self.pause = pause;
+        if docked {
+            self.set_queue_popout(ctx, false);
+        }
+    }
+
+    pub(super) fn set_queue_popout(
+        &mut self,
+        ctx: &EguiCtx,
+        popout: bool,
+    ) {
+        self.queue_popout = popout;
+        self.pause = false;
+        if popout {
+            self.queue_window =
+                ctx.data_mut(|d| d.get_persisted(self.queue_window_id));
+        }
+        ctx.data_mut(|d| {
+            d.insert_persisted(self.queue_popout_id, self.queue_popout)
+        });
+    }
+}
+
+/// What [`queue_list_ui`] shows along with each message.
+#[derive(Clone, Copy)]
+pub(super) struct QueueRows<'a> {
+    pub(super) flag_words: &'a FlagWords,
+    pub(super) length_limit: &'a LengthLimit,
+    pub(super) url_filter: &'a UrlFilter,
+    pub(super) aliases: &'a UserAliases,
+    /// Tagged languages are marked.
+    pub(super) languages: &'a LanguageFilter,
+    /// Messages with one are highlighted.
+    pub(super) mentions: &'a Mentions,
+    /// Lists only messages with a mention.
+    pub(super) mentions_only: bool,
+    pub(super) show_thumbnails: bool,
+}
+
+/// Picks between listing every pending message and only ones with a
+/// mention.
+pub(super) fn queue_tabs_ui(ui: &mut Ui, mentions_only: &mut bool) {
+    ui.separator();
+    ui.selectable_value(mentions_only, false, "All");
+    ui.selectable_value(mentions_only, true, "Mentions")
+        .on_hover_text(
+            "Only messages mentioning a name set in Text Settings",
+        );
+}
+
+/// Pending messages with their Delete buttons and send progress.
+/// Returns whether the pointer is on the buttons, the queue holds still
+/// then so rows don't move under it.
+pub(super) fn queue_list_ui(
+    ui: &mut Ui,
+    queue: &SharedQueue,
+    rows: &QueueRows<'_>,
+    mut triage: Option<&mut Triage>,
+) -> bool {
+    let QueueRows {
+        flag_words,
+        length_limit,
+        url_filter,
+        aliases,
+        languages,
+        mentions,
+        mentions_only,
+        show_thumbnails,
+    } = *rows;
+    ScrollArea::vertical()
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            let mut btn_x_range: Range<f32> = f32::INFINITY..0.0;
+            let mut btn_press = false;
+
+            // deleting only pushes deadlines back, no need to wake the
+            // releaser for it
+            let mut queue = queue.lock_quiet();
+            let now = queue.now_utc();
+            let mut in_progress = false;
+            // deleted with the keyboard, keep going down the list
+            let mut focus_next = false;
+            for (idx, pending) in queue.iter_mut().enumerate() {
+                let mention = mentions.matches(&pending.msg);
+                if mentions_only && mention.is_none() {
+                    continue;
+                }
+                let send_at = pending
+                    .send_at
+                    .with_timezone(&Local)
+                    .format("%H:%M:%S%.3f")
+                    .to_string();
+                let row = ui
+                    .horizontal(|ui| {
+                        let btn_res = ui.button("Delete");
+                        btn_res.widget_info(|| {
+                            WidgetInfo::labeled(
+                                WidgetType::Button,
+                                true,
+                                format!(
+                                    "Delete {}, sends at {send_at}",
+                                    spoken_message(&pending.msg)
+                                ),
+                            )
+                        });
+                        if focus_next {
+                            btn_res.request_focus();
+                            focus_next = false;
+                        }
+                        let btn_rect = btn_res.rect;
+                        btn_x_range.start =
+                            btn_x_range.start.min(btn_rect.left());
+                        btn_x_range.end =
+                            btn_x_range.end.max(btn_rect.right());
+                        btn_press |= btn_res.is_pointer_button_down_on()
+                            || btn_res.clicked();
+
+                        if let Some(word) =
+                            flag_words.matches(&pending.msg)
+                        {
+                            let flagged =
+                                format!("Flagged for \"{word}\"");
+                            let res = ui.label(RichText::new("⚠").color(
+                                ui.style().visuals.error_fg_color,
+                            ));
+                            res.widget_info(|| {
+                                WidgetInfo::labeled(
+                                    WidgetType::Label,
+                                    true,
+                                    &flagged,
+                                )
+                            });
+                            res.on_hover_text(flagged);
+                        }
+                        if let Some(name) = mention {
+                            ui.label(RichText::new("@").strong().color(
+                                ui.style().visuals.selection.stroke.color,
+                            ))
+                            .on_hover_text(format!("Mentions {name}"));
+                        }
+                        if let Some(lang) = languages.tag(&pending.msg) {
+                            ui.label(
+                                RichText::new(lang.name()).small().color(
+                                    ui.style().visuals.warn_fg_color,
+                                ),
+                            );
+                        }
+                        message_label(
+                            ui,
+                            &pending.msg,
+                            length_limit,
+                            aliases.of(&pending.msg),
+                        );
+                        if show_thumbnails {
+                            thumbnails_ui(ui, &pending.msg);
+                        }
+
+                        if btn_res.clicked() {
+                            pending.delete = true;
+                            focus_next = btn_res.has_focus();
+                        }
+                    })
+                    .response
+                    .on_hover_ui(|ui| {
+                        ui.label(format!("Sends at {send_at}"));
+                        if let Some(lang) =
+                            Language::detect(&pending.msg.text)
+                        {
+                            ui.label(format!(
+                                "Language: {}",
+                                lang.name()
+                            ));
+                        }
+                        let (text, urls) =
+                            url_filter.apply(&pending.msg.text);
+                        if !urls.is_empty() {
+                            ui.label(format!("Sent as: {text}"));
+                        }
+                    });
+                row.context_menu(|ui| copy_menu_ui(ui, pending));
+                if let Some(ref mut triage) = triage {
+                    if triage.selected() == Some(&pending.msg) {
+                        ui.painter().rect_stroke(
+                            row.rect.with_max_x(ui.max_rect().right()),
+                            2.0,
+                            ui.style().visuals.selection.stroke,
+                        );
+                        if triage.take_moved() {
+                            row.scroll_to_me(None);
+                        }
+                    }
+                }
+                let mut rect = row.rect;
+
+                // draw bg
+                rect.set_width(ui.available_width());
+                let the_other_row = idx % 2 == 0;
+                if mention.is_some() {
+                    ui.painter().rect_filled(
+                        rect,
+                        2.0,
+                        ui.style()
+                            .visuals
+                            .selection
+                            .bg_fill
+                            .gamma_multiply(0.3),
+                    );
+                } else if the_other_row {
+                    ui.painter().rect_filled(
+                        rect,
+                        2.0,
+                        ui.style().visuals.faint_bg_color,
+                    );
+                }
+
+                // draw timeout progress
+                let progress = pending.progress(now);
+                rect.set_width(rect.width() * progress);
+                rect = rect.with_min_y(rect.bottom());
+                rect.set_height(ui.spacing().item_spacing.y);
+                ui.painter().rect_filled(
+                    rect,
+                    1.0,
+                    ui.style().visuals.warn_fg_color.gamma_multiply(0.4),
+                );
+                in_progress |= progress < 1.0;
+            }
+            if in_progress {
+                // releases are woken up for on their own, this only
+                // keeps the bars moving
+                ui.ctx().request_repaint_after(PROGRESS_REPAINT);
+            }
+            drop(queue);
+
+            let btn_area = Id::new("message list button area");
+            let hovered = ui
+                .interact(
+                    Rect::from_min_max(
+                        pos2(btn_x_range.start, ui.clip_rect().top()),
+                        pos2(btn_x_range.end, ui.clip_rect().bottom()),
+                    ),
+                    btn_area,
+                    Sense::hover(),
+                )
+                .hovered();
+
+            hovered || btn_press
+        })
+        .inner
+}
+
+/// Puts every row still listed on the clipboard, one per line.
+pub(super) fn copy_all_button(ui: &mut Ui, queue: &SharedQueue) {
+    let res = ui
+        .button("Copy all visible")
+        .on_hover_text("Copy the text of every pending message listed");
+    if res.clicked() {
+        let text = queue
+            .lock_quiet()
+            .iter_mut()
+            .filter(|it| !it.delete)
+            .map(|it| copied_text(it))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.ctx().copy_text(text);
+    }
+}
+
+fn copy_menu_ui(ui: &mut Ui, pending: &PendingMessage) {
+    if ui.button("Copy text").clicked() {
+        ui.ctx().copy_text(copied_text(pending));
+        ui.close_menu();
+    }
+    if ui
+        .button("Copy as JSON")
+        .on_hover_text("The full message with when it arrived and sends")
+        .clicked()
+    {
+        match serde_json::to_string_pretty(&pending.snapshot_entry()) {
+            Ok(json) => ui.ctx().copy_text(json),
+            Err(err) => warn!("failed to serialize message: {err}"),
+        }
+        ui.close_menu();
+    }
+}
+
+/// A line for pasting elsewhere, with the time the message arrived.
+fn copied_text(pending: &PendingMessage) -> String {
+    let msg = &pending.msg;
+    let mut text = pending
+        .arrive_at
+        .with_timezone(&Local)
+        .format("[%H:%M:%S] ")
+        .to_string();
+    if msg.kind != MessageKind::Chat {
+        text += &format!("[{}] ", msg.kind.name());
+    }
+    if let Some(ref username) = msg.username {
+        text += &format!("{username}: ");
+    }
+    text + &msg.text
+}
+
+/// What a screen reader reads out for `msg`, the kind and sender in
+/// front as the row shows them.
+pub(super) fn spoken_message(msg: &Message) -> String {
+    let mut text = format!("{} message", msg.kind.name());
+    if let Some(ref username) = msg.username {
+        text += &format!(" from {username}");
+    }
+    text + ": " + &msg.text
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use blooming_light_core::{
+    log::LogEvent, message::Message, raffle::Raffle, timeout::Timeouts,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use eframe::egui::{
+    Button, Context as EguiCtx, DragValue, Grid, TextEdit, Ui, Window,
+};
+use tracing::info;
+
+use super::App;
+
+/// Clicked in a running raffle.
+enum RaffleAction {
+    Drawn(String),
+    /// Everyone left was drawn or timed out.
+    NoneLeft,
+    New,
+}
+
+impl App {
+    /// Collects chatters typing a keyword and draws a winner among them,
+    /// announced through the queue.
+    pub(super) fn update_raffle(&mut self, ctx: &EguiCtx) {
+        if !self.raffle_show {
+            return;
+        }
+
+        let now = Utc::now();
+        Window::new("Raffle")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut action = None;
+                match self.raffle {
+                    Some(ref mut raffle) => {
+                        action =
+                            running_ui(ui, raffle, &self.timeouts, now);
+                    }
+                    None => {
+                        Grid::new("raffle settings").num_columns(2).show(
+                            ui,
+                            |ui| {
+                                ui.label("Keyword");
+                                ui.add(
+                                    TextEdit::singleline(
+                                        &mut self.raffle_keyword_draft,
+                                    )
+                                    .hint_text("e.g. !join"),
+                                );
+                                ui.end_row();
+
+                                ui.label("Open for(secs)");
+                                ui.add(
+                                    DragValue::new(&mut self.raffle_secs)
+                                        .range(1..=3600),
+                                );
+                                ui.end_row();
+                            },
+                        );
+                        let keyword = self.raffle_keyword_draft.trim();
+                        if ui
+                            .add_enabled(
+                                !keyword.is_empty(),
+                                Button::new("Start"),
+                            )
+                            .clicked()
+                        {
+                            self.raffle = Some(Raffle::new(
+                                keyword,
+                                now + TimeDelta::seconds(
+                                    self.raffle_secs as i64,
+                                ),
+                            ));
+                        }
+                    }
+                }
+                match action {
+                    Some(RaffleAction::Drawn(winner)) => {
+                        self.announce_winner(&winner)
+                    }
+                    Some(RaffleAction::NoneLeft) => {
+                        self.err_messages.push(
+                            "nobody left in the raffle to draw"
+                                .to_owned(),
+                        )
+                    }
+                    Some(RaffleAction::New) => self.raffle = None,
+                    None => {}
+                }
+
+                ui.separator();
+
+                if ui
+                    .checkbox(
+                        &mut self.raffle_hide_entries,
+                        "Hide entries",
+                    )
+                    .on_hover_text(
+                        "Keep entries out of the queue while it's open",
+                    )
+                    .changed()
+                {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.raffle_hide_entries_id,
+                            self.raffle_hide_entries,
+                        )
+                    });
+                }
+                if ui.button("Close").clicked() {
+                    self.raffle_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.raffle_show_id,
+                            self.raffle_show,
+                        )
+                    });
+                }
+            });
+    }
+
+    /// Queued like any other message, and logged as received.
+    fn announce_winner(&mut self, winner: &str) {
+        info!("raffle won by {winner}");
+        let msg = Message::chat(format!("Raffle winner: {winner}"));
+        if let Ok(ref network) = self.network {
+            network.write_log(msg.clone(), LogEvent::Receive);
+        }
+        self.message.lock().push(msg);
+    }
+}
+
+/// Entries and winners so far, with buttons to close entries and draw.
+fn running_ui(
+    ui: &mut Ui,
+    raffle: &mut Raffle,
+    timeouts: &Timeouts,
+    now: DateTime<Utc>,
+) -> Option<RaffleAction> {
+    let entrants = raffle.entrants().len();
+    if raffle.is_open(now) {
+        let left = (raffle.closes_at() - now).num_seconds() + 1;
+        ui.label(format!(
+            "Type \"{}\" to enter, {entrants} entrant so far, closes in \
+             {left}s",
+            raffle.keyword
+        ));
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+    } else {
+        ui.label(format!("Closed with {entrants} entrant"));
+    }
+    for (idx, winner) in raffle.winners().iter().enumerate() {
+        ui.label(format!("Winner {}: {winner}", idx + 1));
+    }
+
+    let mut action = None;
+    ui.horizontal(|ui| {
+        if raffle.is_open(now) && ui.button("Close entries").clicked() {
+            raffle.close(now);
+        }
+        let draw = if raffle.winners().is_empty() {
+            "Draw"
+        } else {
+            "Re-roll"
+        };
+        let res = ui
+            .add_enabled(entrants > 0, Button::new(draw))
+            .on_hover_text(
+            "Closes entries and picks someone not drawn yet or timed \
+                 out",
+        );
+        if res.clicked() {
+            raffle.close(now);
+            action = Some(
+                match raffle.draw(|it| timeouts.is_muted(it, now)) {
+                    Some(winner) => {
+                        RaffleAction::Drawn(winner.to_owned())
+                    }
+                    None => RaffleAction::NoneLeft,
+                },
+            );
+        }
+        if ui.button("New raffle").clicked() {
+            action = Some(RaffleAction::New);
+        }
+    });
+    action
+}
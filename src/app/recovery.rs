@@ -0,0 +1,139 @@
+use std::{
+    env::current_dir,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use blooming_light_core::{message::Message, queue::QueueSnapshot};
+use eframe::egui::{Context as EguiCtx, ScrollArea, Window};
+
+use super::{message_label, App};
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(2);
+/// Older snapshots are only restored after asking.
+const SNAPSHOT_STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+fn queue_snapshot_path() -> PathBuf {
+    current_dir().unwrap_or_default().join("queue.json")
+}
+
+impl App {
+    /// Restores the queue snapshot, then offers whatever the log still
+    /// has unfinished beyond it.
+    pub(super) fn load_recovery(&mut self, mut unfinished: Vec<Message>) {
+        let snapshot = QueueSnapshot::load(&queue_snapshot_path())
+            .unwrap_or_else(|err| {
+                self.err_messages.push(format!("{err:?}"));
+                None
+            });
+        let entries = snapshot.iter().flat_map(|it| &it.entries);
+        for entry in entries {
+            if let Some(idx) =
+                unfinished.iter().position(|it| *it == entry.msg)
+            {
+                unfinished.remove(idx);
+            }
+        }
+        self.unfinished_messages = unfinished;
+
+        let Some(snapshot) = snapshot.filter(|it| !it.is_empty()) else {
+            return;
+        };
+        if snapshot.age() > SNAPSHOT_STALE_AFTER {
+            self.stale_queue_snapshot = Some(snapshot);
+        } else {
+            self.message.lock().restore(snapshot);
+        }
+    }
+
+    /// Throttled unless `force`. Skipped while a stale snapshot is
+    /// waiting for a decision, so it isn't overwritten.
+    pub(super) fn save_queue_snapshot(&mut self, force: bool) {
+        if self.stale_queue_snapshot.is_some()
+            || (!force
+                && self.queue_snapshot_saved_at.elapsed()
+                    < SNAPSHOT_INTERVAL)
+        {
+            return;
+        }
+        self.queue_snapshot_saved_at = Instant::now();
+        // written outside the lock, the releaser needs it
+        let snapshot = self.message.lock().snapshot();
+        let result = snapshot.save(&queue_snapshot_path());
+        if let Err(err) = result {
+            self.err_messages.push(format!("{err:?}"));
+        }
+    }
+
+    pub(super) fn update_queue_restore(&mut self, ctx: &EguiCtx) {
+        let Some(ref snapshot) = self.stale_queue_snapshot else {
+            return;
+        };
+        let text = format!(
+            "{} message were pending when the app last closed, {} \
+             minutes ago.",
+            snapshot.len(),
+            snapshot.age().as_secs() / 60,
+        );
+
+        Window::new("Restore queue")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(text);
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        if let Some(snapshot) =
+                            self.stale_queue_snapshot.take()
+                        {
+                            self.message.lock().restore(snapshot);
+                        }
+                    }
+                    if ui.button("Discard").clicked() {
+                        self.stale_queue_snapshot = None;
+                    }
+                });
+            });
+    }
+}
+
+impl App {
+    pub(super) fn update_unfinished_messages(&mut self, ctx: &EguiCtx) {
+        if self.unfinished_messages.is_empty() {
+            return;
+        }
+
+        Window::new("Recover messages")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The last session didn't exit cleanly, {} message \
+                     were received but never forwarded or deleted.",
+                    self.unfinished_messages.len(),
+                ));
+                ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for msg in &self.unfinished_messages {
+                        ui.horizontal(|ui| {
+                            message_label(
+                                ui,
+                                msg,
+                                &self.length_limit,
+                                None,
+                            )
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Re-enqueue").clicked() {
+                        for msg in self.unfinished_messages.drain(..) {
+                            self.message.lock().push(msg);
+                        }
+                    }
+                    if ui.button("Discard").clicked() {
+                        self.unfinished_messages.clear();
+                    }
+                });
+            });
+    }
+}
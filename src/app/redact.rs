@@ -0,0 +1,162 @@
+use blooming_light_core::log::{
+    self,
+    redact::{self, Found, RedactMode, Redaction},
+};
+use eframe::egui::{
+    Button, ComboBox, Context as EguiCtx, RichText, TextEdit, Window,
+};
+use tracing::warn;
+
+use super::App;
+
+impl App {
+    pub(super) fn update_redact(&mut self, ctx: &EguiCtx) {
+        if !self.redact_show {
+            return;
+        }
+
+        Window::new("Redact User")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Remove everything a user sent from log.jsonl and its \
+                     archives, e.g. on their request.",
+                );
+                ui.horizontal(|ui| {
+                    let username = ui.add(
+                        TextEdit::singleline(&mut self.redact_username)
+                            .hint_text("Username")
+                            .desired_width(160.0),
+                    );
+                    let mut changed = username.changed();
+                    ComboBox::from_id_salt("redact mode")
+                        .selected_text(self.redact_mode.name())
+                        .show_ui(ui, |ui| {
+                            for it in RedactMode::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.redact_mode,
+                                        it,
+                                        it.name(),
+                                    )
+                                    .changed();
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Delete drops their entries, Redact keeps \
+                             them for counts without name or text",
+                        );
+                    if changed {
+                        self.redact_preview = None;
+                    }
+                });
+
+                let redaction = Redaction {
+                    username: self.redact_username.trim().to_owned(),
+                    mode: self.redact_mode,
+                };
+                if let Some(ref found) = self.redact_preview {
+                    if found.is_empty() {
+                        ui.label("Nothing logged from them.");
+                    }
+                    for it in found {
+                        ui.label(format!(
+                            "{}: {} entries",
+                            it.path.display(),
+                            it.entries
+                        ));
+                    }
+                    let unreadable = unreadable(found);
+                    if unreadable > 0 {
+                        ui.colored_label(
+                            ui.style().visuals.warn_fg_color,
+                            format!(
+                                "{unreadable} lines can't be read, they \
+                                 may still name them and won't be \
+                                 changed. Check them by hand."
+                            ),
+                        );
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !redaction.username.is_empty(),
+                            Button::new("Preview"),
+                        )
+                        .clicked()
+                    {
+                        match redact::preview(
+                            &log::default_path(),
+                            &redaction,
+                        ) {
+                            Ok(found) => self.redact_preview = Some(found),
+                            Err(err) => {
+                                self.err_messages.push(format!("{err:?}"))
+                            }
+                        }
+                    }
+                    let apply_btn = Button::new(
+                        RichText::new("Apply")
+                            .color(ui.style().visuals.error_fg_color),
+                    );
+                    let previewed = self
+                        .redact_preview
+                        .as_ref()
+                        .into_iter()
+                        .flatten()
+                        .any(|it| it.entries > 0);
+                    if ui
+                        .add_enabled(previewed, apply_btn)
+                        .on_hover_text(
+                            "Rewrites the files found, this can't be \
+                             undone",
+                        )
+                        .clicked()
+                    {
+                        if let Ok(ref network) = self.network {
+                            match network.redact_log(redaction) {
+                                Ok(found) => {
+                                    let entries = found
+                                        .iter()
+                                        .map(|it| it.entries)
+                                        .sum::<usize>();
+                                    let unreadable = unreadable(&found);
+                                    warn!(
+                                        entries,
+                                        unreadable,
+                                        files = found.len(),
+                                        "redacted a user from the log"
+                                    );
+                                    if unreadable > 0 {
+                                        self.err_messages.push(format!(
+                                            "{unreadable} lines of the \
+                                             log can't be read and were \
+                                             left as they are, they may \
+                                             still name the user"
+                                        ));
+                                    }
+                                }
+                                Err(err) => self
+                                    .err_messages
+                                    .push(format!("{err:?}")),
+                            }
+                        }
+                        self.redact_preview = None;
+                    }
+                    if ui.button("Close").clicked() {
+                        self.redact_show = false;
+                        self.redact_username.clear();
+                        self.redact_preview = None;
+                    }
+                });
+            });
+    }
+}
+
+fn unreadable(found: &[Found]) -> usize {
+    found.iter().map(|it| it.unreadable).sum()
+}
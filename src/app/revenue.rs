@@ -0,0 +1,110 @@
+use std::fs;
+
+use anyhow::Context;
+use eframe::egui::{Context as EguiCtx, Grid, ScrollArea, Window};
+
+use super::App;
+
+/// Users listed at most.
+const REVENUE_LEN: usize = 20;
+
+impl App {
+    /// Running totals of gifts and paid messages this session, and who
+    /// they came from.
+    pub(super) fn update_revenue(&mut self, ctx: &EguiCtx) {
+        if !self.revenue_show {
+            return;
+        }
+
+        Window::new("Revenue")
+            .collapsible(false)
+            .default_height(320.0)
+            .show(ctx, |ui| {
+                let total = self.revenue.total();
+                Grid::new("revenue total").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Paid");
+                        ui.label(format!(
+                            "{:.2} from {} message",
+                            total.paid, total.paid_messages
+                        ));
+                        ui.end_row();
+
+                        ui.label("Gifts");
+                        ui.label(format!(
+                            "{} worth {}",
+                            total.gifts, total.gift_value
+                        ))
+                        .on_hover_text("In the platform's own unit");
+                        ui.end_row();
+                    },
+                );
+
+                ui.separator();
+
+                ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    Grid::new("revenue by user")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("User");
+                            ui.label("Paid");
+                            ui.label("Gift value");
+                            ui.end_row();
+
+                            for (name, it) in self
+                                .revenue
+                                .by_user()
+                                .into_iter()
+                                .take(REVENUE_LEN)
+                            {
+                                ui.label(name);
+                                ui.label(format!("{:.2}", it.paid));
+                                ui.label(it.gift_value.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Export CSV")
+                        .on_hover_text("Save the totals of every user")
+                        .clicked()
+                    {
+                        if let Err(err) = self.export_revenue() {
+                            self.err_messages.push(format!("{err:?}"));
+                        }
+                    }
+                    if ui.button("Reset").clicked() {
+                        self.revenue.clear();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.revenue_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.revenue_show_id,
+                                self.revenue_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    fn export_revenue(&self) -> anyhow::Result<()> {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("revenue.csv")
+            .save_file()
+        else {
+            return Ok(());
+        };
+        fs::write(&path, self.revenue.to_csv()).with_context(|| {
+            format!("failed to write {}", path.display())
+        })
+    }
+}
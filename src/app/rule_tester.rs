@@ -0,0 +1,127 @@
+use blooming_light_core::{message::Message, text::UrlAction};
+use eframe::egui::{
+    CollapsingHeader, RichText, ScrollArea, TextEdit, Ui,
+};
+
+use super::App;
+
+impl App {
+    /// Runs sample messages through the text rules as they're set now,
+    /// without queueing anything.
+    pub(super) fn rule_tester_ui(&mut self, ui: &mut Ui) {
+        CollapsingHeader::new("Test rules").show(ui, |ui| {
+            ui.add(
+                TextEdit::multiline(&mut self.rule_tester_samples)
+                    .hint_text(
+                        "A sample message per line, \"name: text\" to \
+                         set the sender",
+                    )
+                    .desired_rows(3)
+                    .desired_width(f32::INFINITY),
+            );
+            ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                for sample in self
+                    .rule_tester_samples
+                    .lines()
+                    .filter(|it| !it.trim().is_empty())
+                {
+                    ui.separator();
+                    ui.label(RichText::new(sample).monospace());
+                    for (result, marked) in self.test_rules(sample) {
+                        let mut text =
+                            RichText::new(format!("• {result}"));
+                        if marked {
+                            text = text
+                                .color(ui.style().visuals.error_fg_color);
+                        }
+                        ui.label(text);
+                    }
+                }
+            });
+        });
+    }
+
+    /// What each rule does to `sample` in the order they're applied,
+    /// marked if it drops or flags it.
+    fn test_rules(&self, sample: &str) -> Vec<(String, bool)> {
+        let msg = sample_message(sample);
+        let mut results = vec![];
+        if self.kind_filter.drops(&msg) {
+            results.push((
+                format!("Dropped, {} messages are", msg.kind.name()),
+                true,
+            ));
+            return results;
+        }
+        if self.language_filter.drops(&msg) {
+            results.push(("Dropped for its language".to_owned(), true));
+            return results;
+        }
+        if let Some(lang) = self.language_filter.tag(&msg) {
+            results.push((format!("Tagged {}", lang.name()), false));
+        }
+
+        let sanitized = self.sanitizer.apply(msg.clone());
+        if sanitized.text != msg.text {
+            results.push((
+                format!("Sanitized to \"{}\"", sanitized.text),
+                false,
+            ));
+        }
+        for found in self.flag_words.matching(&sanitized) {
+            let from = found
+                .list
+                .map(|it| format!(" from {}", it.name()))
+                .unwrap_or_default();
+            let notify = if self.flag_words.notify {
+                ", notified"
+            } else {
+                ""
+            };
+            results.push((
+                format!("Flagged for \"{}\"{from}{notify}", found.word),
+                true,
+            ));
+        }
+
+        let (text, hits) = self.url_filter.apply(&sanitized.text);
+        for hit in hits {
+            let action = match hit.action {
+                UrlAction::Keep => "kept",
+                UrlAction::Strip => "stripped",
+                UrlAction::Replace => "replaced with [link]",
+            };
+            results.push((format!("Link {} {action}", hit.url), false));
+        }
+        let parts =
+            self.length_limit.apply(&Message { text, ..sanitized });
+        if parts.len() > 1 {
+            results.push((
+                format!("Split into {} parts", parts.len()),
+                false,
+            ));
+        } else if parts[0].text != msg.text
+            || self.length_limit.exceeded_by(&msg.text)
+        {
+            results
+                .push((format!("Sent as \"{}\"", parts[0].text), false));
+        }
+        if results.is_empty() {
+            results.push(("No rule applies".to_owned(), false));
+        }
+        results
+    }
+}
+
+/// A chat message, from `name` if it starts with `name: `.
+fn sample_message(sample: &str) -> Message {
+    match sample.split_once(": ") {
+        Some((name, text)) if !name.contains(char::is_whitespace) => {
+            Message {
+                username: Some(name.to_owned()),
+                ..Message::chat(text)
+            }
+        }
+        _ => Message::chat(sample),
+    }
+}
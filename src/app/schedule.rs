@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use blooming_light_core::schedule::{PauseWindow, ScheduleState};
+use chrono::{NaiveTime, Timelike};
+use eframe::egui::{
+    Checkbox, Context as EguiCtx, DragValue, Grid, RichText, TextEdit,
+    Ui, Window,
+};
+
+use super::{format_duration, App};
+
+/// How long before a scheduled pause it's announced in the top bar.
+const ANNOUNCE_BEFORE: Duration = Duration::from_secs(10 * 60);
+
+impl App {
+    pub(super) fn update_pause_schedule(&mut self, ctx: &EguiCtx) {
+        if !self.pause_schedule_show {
+            return;
+        }
+
+        Window::new("Pause Schedule")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let windows = &mut self.pause_schedule.windows;
+                let mut changed = false;
+                let mut remove = None;
+                Grid::new("pause schedule").num_columns(5).show(
+                    ui,
+                    |ui| {
+                        ui.label("");
+                        ui.label("Start");
+                        ui.label("End");
+                        ui.label("Label");
+                        ui.end_row();
+
+                        for (idx, window) in
+                            windows.iter_mut().enumerate()
+                        {
+                            changed |= window_ui(ui, window);
+                            if ui.button("Remove").clicked() {
+                                remove = Some(idx);
+                            }
+                            ui.end_row();
+                        }
+                    },
+                );
+                if let Some(idx) = remove {
+                    windows.remove(idx);
+                    changed = true;
+                }
+                if ui.button("Add").clicked() {
+                    windows.push(PauseWindow::default());
+                    changed = true;
+                }
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.pause_schedule_id,
+                            self.pause_schedule.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.pause_schedule_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.pause_schedule_show_id,
+                            self.pause_schedule_show,
+                        )
+                    });
+                }
+            });
+    }
+}
+
+fn window_ui(ui: &mut Ui, window: &mut PauseWindow) -> bool {
+    let mut changed = ui
+        .add(Checkbox::without_text(&mut window.enabled))
+        .on_hover_text("Enabled")
+        .changed();
+    changed |= ui
+        .horizontal(|ui| time_ui(ui, &mut window.start, false))
+        .inner;
+    changed |= ui
+        .horizontal(|ui| time_ui(ui, &mut window.end, false))
+        .inner;
+    changed |= ui
+        .add(
+            TextEdit::singleline(&mut window.label)
+                .hint_text("e.g. sponsor segment")
+                .desired_width(140.0),
+        )
+        .changed();
+    changed
+}
+
+/// Hours and minutes, and seconds if `seconds`.
+pub(super) fn time_ui(
+    ui: &mut Ui,
+    time: &mut NaiveTime,
+    seconds: bool,
+) -> bool {
+    let mut hour = time.hour();
+    let mut minute = time.minute();
+    let mut second = if seconds { time.second() } else { 0 };
+    let mut changed = ui
+        .add(
+            DragValue::new(&mut hour)
+                .range(0..=23)
+                .custom_formatter(|it, _| format!("{it:02}")),
+        )
+        .changed();
+    ui.label(":");
+    changed |= ui
+        .add(
+            DragValue::new(&mut minute)
+                .range(0..=59)
+                .custom_formatter(|it, _| format!("{it:02}")),
+        )
+        .changed();
+    if seconds {
+        ui.label(":");
+        changed |= ui
+            .add(
+                DragValue::new(&mut second)
+                    .range(0..=59)
+                    .custom_formatter(|it, _| format!("{it:02}")),
+            )
+            .changed();
+    }
+    if changed {
+        *time = NaiveTime::from_hms_opt(hour, minute, second)
+            .unwrap_or(*time);
+    }
+    changed
+}
+
+/// Countdown for the current or next scheduled pause, shown while
+/// paused or shortly before.
+pub(super) fn schedule_status_ui(ui: &mut Ui, state: &ScheduleState) {
+    let (window, text) = match *state {
+        ScheduleState::Paused { window, left } => {
+            (window, format!("{} left", format_delta(left)))
+        }
+        ScheduleState::Upcoming { window, until }
+            if until.to_std().unwrap_or_default() <= ANNOUNCE_BEFORE =>
+        {
+            (window, format!("in {}", format_delta(until)))
+        }
+        _ => return,
+    };
+    let mut label = "Scheduled pause".to_owned();
+    if !window.label.is_empty() {
+        label += &format!(" ({})", window.label);
+    }
+    ui.label(
+        RichText::new(format!("{label} {text}"))
+            .color(ui.style().visuals.warn_fg_color),
+    );
+}
+
+fn format_delta(delta: chrono::TimeDelta) -> String {
+    format_duration(delta.to_std().unwrap_or_default())
+}
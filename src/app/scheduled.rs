@@ -0,0 +1,108 @@
+use blooming_light_core::{
+    log::LogEvent,
+    message::Message,
+    queue::SharedQueue,
+    text::{LengthLimit, Sanitizer},
+};
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone, Timelike, Utc};
+use eframe::egui::{Button, CollapsingHeader, TextEdit, Ui};
+
+use super::{message_label, schedule::time_ui, NetworkState};
+
+/// What's being composed for [`scheduled_ui`].
+pub(super) struct ScheduledDraft {
+    pub text: String,
+    /// Local, the next time the clock reads it.
+    pub at: NaiveTime,
+}
+
+impl Default for ScheduledDraft {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            at: Local::now()
+                .time()
+                .with_nanosecond(0)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Composer for messages sent at a set time, and the ones waiting for
+/// it, soonest first. Above the queue, apart from the limit and the send
+/// delay.
+pub(super) fn scheduled_ui(
+    ui: &mut Ui,
+    queue: &SharedQueue,
+    network: &NetworkState,
+    draft: &mut ScheduledDraft,
+    sanitizer: &Sanitizer,
+    length_limit: &LengthLimit,
+) {
+    let len = queue.lock_quiet().scheduled_len();
+    CollapsingHeader::new(format!("Scheduled ({len})"))
+        .id_salt("scheduled")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    TextEdit::singleline(&mut draft.text)
+                        .hint_text("Announcement, countdown…")
+                        .desired_width(240.0),
+                );
+                ui.label("at");
+                time_ui(ui, &mut draft.at, true);
+                let send_at = next_at(draft.at);
+                let schedule_btn = ui
+                    .add_enabled(
+                        !draft.text.trim().is_empty(),
+                        Button::new("Schedule"),
+                    )
+                    .on_hover_text(format!(
+                        "Sends at {}, unless paused then",
+                        send_at
+                            .with_timezone(&Local)
+                            .format("%m-%d %H:%M:%S")
+                    ));
+                if schedule_btn.clicked() {
+                    let msg =
+                        sanitizer.apply(Message::chat(draft.text.trim()));
+                    network.write_log(msg.clone(), LogEvent::Receive);
+                    queue.lock().schedule(msg, send_at);
+                    draft.text.clear();
+                }
+            });
+
+            // deleting only drops deadlines, no need to wake the
+            // releaser for it
+            let mut queue = queue.lock_quiet();
+            for pending in queue.scheduled_mut() {
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        pending.delete = true;
+                    }
+                    ui.label(
+                        pending
+                            .send_at
+                            .with_timezone(&Local)
+                            .format("%H:%M:%S")
+                            .to_string(),
+                    );
+                    message_label(ui, &pending.msg, length_limit, None);
+                });
+            }
+        });
+}
+
+/// The next time the local clock reads `at`, today or tomorrow.
+fn next_at(at: NaiveTime) -> DateTime<Utc> {
+    let now = Local::now();
+    let today = now.date_naive();
+    [today, today + Days::new(1)]
+        .into_iter()
+        .filter_map(|day| {
+            Local.from_local_datetime(&day.and_time(at)).earliest()
+        })
+        .find(|it| *it > now)
+        .unwrap_or(now)
+        .with_timezone(&Utc)
+}
@@ -0,0 +1,64 @@
+use anyhow::{bail, Context};
+use keyring::{credential::CredentialPersistence, Entry};
+
+const SERVICE: &str = "BloomingLight";
+
+/// Of the upstream source, names and values, values are often tokens.
+pub const WS_CLIENT_HEADERS: &str = "ws_client_headers";
+pub const WS_CLIENT_COOKIE: &str = "ws_client_cookie";
+pub const PROXY_USERNAME: &str = "proxy_username";
+pub const PROXY_PASSWORD: &str = "proxy_password";
+/// Credentials of the bucket or server sessions are uploaded to.
+pub const UPLOAD_TARGET: &str = "upload_target";
+/// Of obs-websocket, for alerts that send OBS requests.
+pub const OBS_PASSWORD: &str = "obs_password";
+/// Required by the server's action API, e.g. from Stream Deck buttons.
+pub const API_TOKEN: &str = "api_token";
+/// Asked of operator routes of the server, with the username.
+pub const OPERATOR_PASSWORD: &str = "operator_password";
+
+/// Fails if this build only has the mock keyring, which forgets every
+/// secret once its entry is dropped, e.g. on Linux.
+pub fn check_persistence() -> anyhow::Result<()> {
+    let builder = keyring::default::default_credential_builder();
+    match builder.persistence() {
+        CredentialPersistence::EntryOnly
+        | CredentialPersistence::ProcessOnly => bail!(
+            "no OS keyring available, passwords, tokens and source \
+             credentials won't be kept after quitting"
+        ),
+        _ => Ok(()),
+    }
+}
+
+pub fn load_secret(name: &str) -> anyhow::Result<Option<String>> {
+    let entry = Entry::new(SERVICE, name)
+        .context("failed to open keyring entry")?;
+    match entry.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err).with_context(|| {
+            format!("failed to read {name} from keyring")
+        }),
+    }
+}
+
+/// `None` removes it.
+pub fn store_secret(
+    name: &str,
+    secret: Option<&str>,
+) -> anyhow::Result<()> {
+    let entry = Entry::new(SERVICE, name)
+        .context("failed to open keyring entry")?;
+    match secret {
+        Some(secret) => entry.set_password(secret).with_context(|| {
+            format!("failed to store {name} in keyring")
+        }),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(err).with_context(|| {
+                format!("failed to delete {name} from keyring")
+            }),
+        },
+    }
+}
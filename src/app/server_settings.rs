@@ -0,0 +1,559 @@
+use blooming_light_core::{
+    message::{Message, MessageKind},
+    network::{
+        access::IpAccess,
+        access_log, actions,
+        operator::OperatorLogin,
+        theme::{self, Layout},
+        Action, ServerConfig,
+    },
+    text::overlay_messages,
+};
+use eframe::egui::{
+    Button, Color32, ComboBox, Context as EguiCtx, DragValue, Grid,
+    OpenUrl, TextEdit, Ui, Window,
+};
+
+use super::{
+    chroma::chroma_size,
+    preview::{preview_message, PREVIEW_SIZE},
+    secrets, App,
+};
+
+impl App {
+    pub(super) fn update_server_settings(&mut self, ctx: &EguiCtx) {
+        if !self.server_settings_show {
+            return;
+        }
+
+        Window::new("Server Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let draft = &mut self.server_config_draft;
+                Grid::new("server settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Addresses");
+                        let addrs = ServerConfig::parse_addrs(
+                            &self.server_addrs_draft,
+                        );
+                        let res = ui
+                            .add(
+                                TextEdit::singleline(
+                                    &mut self.server_addrs_draft,
+                                )
+                                .text_color_opt(addrs.is_none().then(
+                                    || ui.style().visuals.error_fg_color,
+                                )),
+                            )
+                            .on_hover_text(
+                                "ip:port, comma separated. 0.0.0.0:port \
+                                 lets other machines connect over IPv4, \
+                                 [::]:port over IPv6",
+                            );
+                        if res.changed() {
+                            let draft_addrs = &self.server_addrs_draft;
+                            let addrs =
+                                ServerConfig::parse_addrs(draft_addrs);
+                            if let Some(addrs) = addrs {
+                                draft.addrs = addrs;
+                            }
+                        }
+                        ui.end_row();
+
+                        ui.label("Local socket");
+                        ui.add(
+                            TextEdit::singleline(&mut draft.local_socket)
+                                .hint_text(if cfg!(windows) {
+                                    r"\\.\pipe\blooming-light"
+                                } else {
+                                    "/tmp/blooming-light.sock"
+                                }),
+                        )
+                        .on_hover_text(
+                            "Also serve on this Unix socket or Windows \
+                             named pipe, for automation tools on this \
+                             machine that shouldn't need a port. Empty \
+                             for none",
+                        );
+                        ui.end_row();
+
+                        for (label, ranges, ranges_draft) in [
+                            (
+                                "Allowed clients",
+                                &mut draft.access.allow,
+                                &mut self.allowed_ips_draft,
+                            ),
+                            (
+                                "Denied clients",
+                                &mut draft.access.deny,
+                                &mut self.denied_ips_draft,
+                            ),
+                        ] {
+                            ui.label(label);
+                            let parsed = IpAccess::parse(ranges_draft);
+                            let res = ui
+                                .add(
+                                    TextEdit::singleline(ranges_draft)
+                                        .hint_text("192.168.1.0/24, ...")
+                                        .text_color_opt(
+                                            parsed.is_none().then(|| {
+                                                ui.style()
+                                                    .visuals
+                                                    .error_fg_color
+                                            }),
+                                        ),
+                                )
+                                .on_hover_text(
+                                    "Addresses or CIDR ranges, comma \
+                                     separated. Denied ones are refused, \
+                                     and if any are allowed only those \
+                                     get in. This machine always does",
+                                );
+                            if res.changed() {
+                                if let Some(parsed) =
+                                    IpAccess::parse(ranges_draft)
+                                {
+                                    *ranges = parsed;
+                                }
+                            }
+                            ui.end_row();
+                        }
+
+                        ui.label("Advertise");
+                        ui.checkbox(&mut draft.advertise, "")
+                            .on_hover_text(
+                            "Announce the server on the LAN via mDNS \
+                                 so other machines can find it, when it \
+                                 isn't only listening on this one",
+                        );
+                        ui.end_row();
+
+                        ui.label("Batch window(ms)");
+                        ui.add(
+                        DragValue::new(&mut draft.batch_window_ms)
+                            .range(0..=1000),
+                    )
+                    .on_hover_text(
+                        "Send messages arriving close together to the \
+                         overlay as one frame, 0 to disable",
+                    );
+                        ui.end_row();
+
+                        ui.label("Dev mode");
+                        ui.checkbox(&mut draft.dev_mode, "")
+                            .on_hover_text(format!(
+                                "Serve the built-in overlay from {}, and \
+                                 reload overlays when files there or in \
+                                 the themes directory change",
+                                theme::dev_dir().display()
+                            ));
+                        ui.end_row();
+
+                        ui.label("Action API");
+                        ui.horizontal(|ui| {
+                            ui.label(if draft.api_token.is_empty() {
+                                "Off"
+                            } else {
+                                "On"
+                            });
+                            if ui
+                                .button("New token")
+                                .on_hover_text(
+                                    "Turn on /api/actions/pause, \
+                                     send-next and purge, for Stream \
+                                     Deck buttons. Old URLs stop working",
+                                )
+                                .clicked()
+                            {
+                                draft.api_token =
+                                    actions::generate_token();
+                            }
+                            if ui
+                                .add_enabled(
+                                    !draft.api_token.is_empty(),
+                                    Button::new("Turn off"),
+                                )
+                                .clicked()
+                            {
+                                draft.api_token.clear();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Operator login");
+                        operator_login_ui(ui, &mut draft.operator);
+                        ui.end_row();
+
+                        ui.label("Access log");
+                        ui.checkbox(&mut draft.access_log, "")
+                            .on_hover_text(format!(
+                                "Write every request and overlay \
+                                 connection to {}, to look into \
+                                 overlays failing to connect",
+                                access_log::default_path().display()
+                            ));
+                        ui.end_row();
+                    },
+                );
+
+                let lan_urls = self.server_config.lan_urls();
+                if !lan_urls.is_empty() {
+                    ui.separator();
+
+                    ui.label("Reachable from other machines at")
+                        .on_hover_text(
+                        "Use one of these in OBS on another machine, \
+                             the address it listens on isn't one",
+                    );
+                    Grid::new("server lan urls").num_columns(2).show(
+                        ui,
+                        |ui| {
+                            for url in lan_urls {
+                                ui.hyperlink(&url);
+                                if ui.button("Copy").clicked() {
+                                    ui.ctx().copy_text(url);
+                                }
+                                ui.end_row();
+                            }
+                        },
+                    );
+                }
+
+                if !self.server_config.api_token.is_empty() {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        ui.label("Actions").on_hover_text(
+                            "Open with GET or POST, each replies with \
+                             whether the queue is paused and how many \
+                             messages are pending",
+                        );
+                        if ui
+                            .button("Copy token")
+                            .on_hover_text(
+                                "Send it as Authorization: Bearer \
+                                 <token>",
+                            )
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(
+                                self.server_config.api_token.clone(),
+                            );
+                        }
+                    });
+                    Grid::new("server action urls").num_columns(3).show(
+                        ui,
+                        |ui| {
+                            for action in Action::ALL {
+                                ui.label(action.slug());
+                                let config = &self.server_config;
+                                if ui.button("Copy URL").clicked() {
+                                    ui.ctx().copy_text(
+                                        config.action_url(action),
+                                    );
+                                }
+                                if ui
+                                    .button("Copy URL with token")
+                                    .on_hover_text(
+                                        "For clients that can't set \
+                                         headers. The token is then \
+                                         seen in their history",
+                                    )
+                                    .clicked()
+                                {
+                                    ui.ctx().copy_text(
+                                        config.action_url_with_token(
+                                            action,
+                                        ),
+                                    );
+                                }
+                                ui.end_row();
+                            }
+                        },
+                    );
+                }
+
+                ui.separator();
+
+                theme_ui(ui, draft);
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Test message");
+                    ComboBox::from_id_salt("test message kind")
+                        .selected_text(self.test_message_kind.name())
+                        .show_ui(ui, |ui| {
+                            for kind in MessageKind::ALL {
+                                ui.selectable_value(
+                                    &mut self.test_message_kind,
+                                    kind,
+                                    kind.name(),
+                                );
+                            }
+                        });
+                    if ui
+                        .button("Send")
+                        .on_hover_text(
+                            "Send a message marked as a test straight to \
+                             the overlays, skipping the queue",
+                        )
+                        .clicked()
+                    {
+                        self.send_test_message(ui.ctx());
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Preview")
+                        .on_hover_text(
+                            "Open the selected theme in the browser, \
+                             with the saved variables",
+                        )
+                        .clicked()
+                    {
+                        ui.ctx().open_url(OpenUrl::new_tab(
+                            self.server_config_draft.preview_url(),
+                        ));
+                    }
+                    if ui
+                        .button("QR code")
+                        .on_hover_text(
+                            "Open the saved overlay on a phone or \
+                             another machine",
+                        )
+                        .clicked()
+                    {
+                        self.qr_code_show = true;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.qr_code_show_id,
+                                self.qr_code_show,
+                            )
+                        });
+                    }
+                    if ui.button("Save and restart").clicked() {
+                        self.apply_server_settings(ui.ctx());
+                    }
+                    if ui.button("Close").clicked() {
+                        self.server_settings_show = false;
+                        self.reset_server_settings_draft();
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.server_settings_show_id,
+                                self.server_settings_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    fn send_test_message(&mut self, ctx: &EguiCtx) {
+        let Ok(ref network) = self.network else {
+            return;
+        };
+        let mut msg = Message::test(self.test_message_kind);
+        self.pin_durations.apply(&mut msg);
+        let mut sent = false;
+        for part in overlay_messages(
+            &msg,
+            &self.length_limit,
+            &self.overlay_markup,
+        ) {
+            sent |= network.broadcast_ws_message(&part);
+        }
+        if !sent {
+            self.err_messages
+                .push("no overlay connected to show the test".to_owned());
+        }
+        let now = self.message.lock().now();
+        if self.preview_show {
+            preview_message(
+                &mut self.overlay_preview,
+                ctx,
+                &self.server_config.theme_vars,
+                PREVIEW_SIZE,
+                &msg,
+                now,
+            );
+        }
+        if self.chroma_open {
+            preview_message(
+                &mut self.chroma_output,
+                ctx,
+                &self.server_config.theme_vars,
+                chroma_size(self.chroma_size),
+                &msg,
+                now,
+            );
+        }
+    }
+
+    pub(super) fn reset_server_settings_draft(&mut self) {
+        let config = &self.server_config;
+        self.server_config_draft = config.clone();
+        self.server_addrs_draft =
+            ServerConfig::format_addrs(&config.addrs);
+        self.allowed_ips_draft = IpAccess::format(&config.access.allow);
+        self.denied_ips_draft = IpAccess::format(&config.access.deny);
+    }
+
+    fn apply_server_settings(&mut self, ctx: &EguiCtx) {
+        let config = self.server_config_draft.clone();
+        if config.api_token != self.server_config.api_token {
+            let token = &config.api_token;
+            let token = (!token.is_empty()).then_some(token.as_str());
+            if let Err(err) =
+                secrets::store_secret(secrets::API_TOKEN, token)
+            {
+                self.err_messages.push(format!("{err:?}"));
+            }
+        }
+        let password = &config.operator.password;
+        if *password != self.server_config.operator.password {
+            let password =
+                (!password.is_empty()).then_some(password.as_str());
+            if let Err(err) = secrets::store_secret(
+                secrets::OPERATOR_PASSWORD,
+                password,
+            ) {
+                self.err_messages.push(format!("{err:?}"));
+            }
+        }
+        ctx.data_mut(|d| {
+            d.insert_persisted(self.server_config_id, config.clone())
+        });
+        if let Ok(ref network) = self.network {
+            if let Err(err) = network.set_server_config(config.clone()) {
+                self.err_messages.push(format!("{err:?}"));
+            }
+        }
+        self.server_config = config;
+    }
+}
+
+fn operator_login_ui(ui: &mut Ui, login: &mut OperatorLogin) {
+    ui.horizontal(|ui| {
+        ui.add(
+            TextEdit::singleline(&mut login.username)
+                .hint_text("Username")
+                .desired_width(100.0),
+        );
+        ui.add(
+            TextEdit::singleline(&mut login.password)
+                .hint_text("Password")
+                .password(true)
+                .desired_width(100.0),
+        )
+        .on_hover_text(
+            "Asked by the browser for everything under /api, empty to \
+             turn it off. Stream Deck buttons get in with their token",
+        );
+    });
+}
+
+fn theme_ui(ui: &mut Ui, draft: &mut ServerConfig) {
+    Grid::new("server theme").num_columns(2).show(ui, |ui| {
+        ui.label("Theme");
+        let selected = match draft.theme.as_str() {
+            "" => "Built-in",
+            theme => theme,
+        };
+        ComboBox::from_id_salt("server theme")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut draft.theme,
+                    String::new(),
+                    "Built-in",
+                );
+                for theme in theme::list(&theme::default_dir()) {
+                    let name = theme.clone();
+                    ui.selectable_value(&mut draft.theme, theme, name);
+                }
+            })
+            .response
+            .on_hover_text(format!(
+                "Directories with an index.html under {}",
+                theme::default_dir().display()
+            ));
+        ui.end_row();
+
+        if draft.theme.is_empty() {
+            ui.label("Layout");
+            ComboBox::from_id_salt("server layout")
+                .selected_text(draft.layout.name())
+                .show_ui(ui, |ui| {
+                    for layout in Layout::ALL {
+                        ui.selectable_value(
+                            &mut draft.layout,
+                            layout,
+                            layout.name(),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Served at the root, every layout is also served \
+                     at its own URL",
+                );
+            ui.end_row();
+
+            ui.label("URL");
+            ui.label(draft.layout_url(draft.layout));
+            ui.end_row();
+        }
+
+        let vars = &mut draft.theme_vars;
+        for (label, color) in [
+            ("Text color", &mut vars.text_color),
+            ("Gift color", &mut vars.gift_color),
+            ("SuperChat color", &mut vars.superchat_color),
+            ("Event color", &mut vars.event_color),
+        ] {
+            ui.label(label);
+            let mut value = Color32::from_hex(color).unwrap_or_default();
+            if ui.color_edit_button_srgba(&mut value).changed() {
+                *color = value.to_hex();
+            }
+            ui.end_row();
+        }
+
+        ui.label("Font family");
+        ui.add(
+            TextEdit::singleline(&mut vars.font_family)
+                .hint_text("CSS font-family"),
+        );
+        ui.end_row();
+
+        ui.label("Font size(%)");
+        ui.add(
+            DragValue::new(&mut vars.font_size)
+                .max_decimals(1)
+                .range(0.1..=20.0)
+                .speed(0.1),
+        )
+        .on_hover_text("Of the overlay width");
+        ui.end_row();
+
+        ui.label("Spacing(px)");
+        ui.add(DragValue::new(&mut vars.spacing).range(0.0..=500.0));
+        ui.end_row();
+
+        ui.label("Speed");
+        ui.add(
+            DragValue::new(&mut vars.speed)
+                .max_decimals(2)
+                .range(0.01..=10.0)
+                .speed(0.01),
+        )
+        .on_hover_text("Overlay widths per second");
+        ui.end_row();
+    });
+}
@@ -0,0 +1,318 @@
+use anyhow::Context;
+use blooming_light_core::network::{
+    decoder::{DecoderConfig, DecoderKind},
+    emote::EmoteMode,
+    proxy::ProxyKind,
+    WsClientConfig,
+};
+use eframe::egui::{
+    ComboBox, Context as EguiCtx, Grid, TextEdit, Ui, Window,
+};
+
+use super::{combo_window_ui, delay_override_ui, secrets, App};
+
+impl App {
+    pub(super) fn update_source_settings(&mut self, ctx: &EguiCtx) {
+        if !self.source_settings_show {
+            return;
+        }
+
+        Window::new("Source Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                connection_ui(
+                    ui,
+                    &mut self.ws_client_config_draft,
+                    &mut self.ws_client_subprotocols_draft,
+                );
+
+                ui.label("Headers");
+                headers_ui(ui, &mut self.ws_client_config_draft);
+
+                ui.label("Message format");
+                decoder_ui(ui, &mut self.ws_client_config_draft.decoder);
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Save and reconnect").clicked() {
+                        self.apply_source_settings(ctx);
+                    }
+                    if ui.button("Close").clicked() {
+                        self.source_settings_show = false;
+                        self.reset_source_settings_draft();
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.source_settings_show_id,
+                                self.source_settings_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    pub(super) fn reset_source_settings_draft(&mut self) {
+        self.ws_client_config_draft = self.ws_client_config.clone();
+        self.ws_client_subprotocols_draft =
+            self.ws_client_config.subprotocols.join(", ");
+    }
+
+    fn apply_source_settings(&mut self, ctx: &EguiCtx) {
+        let mut config = self.ws_client_config_draft.clone();
+        config.subprotocols = self
+            .ws_client_subprotocols_draft
+            .split(',')
+            .map(str::trim)
+            .filter(|it| !it.is_empty())
+            .map(str::to_owned)
+            .collect();
+        config.headers.retain(|(name, value)| {
+            !name.is_empty() || !value.is_empty()
+        });
+
+        if let Err(err) = store_ws_client_secrets(&config) {
+            self.err_messages.push(format!("{err:?}"));
+        }
+        ctx.data_mut(|d| {
+            d.insert_persisted(self.ws_client_config_id, config.clone())
+        });
+        if let Ok(ref network) = self.network {
+            if let Err(err) = network.set_ws_client_config(config.clone())
+            {
+                self.err_messages.push(format!("{err:?}"));
+            }
+        }
+        self.thumbnail_loader.set_proxy(config.proxy.clone());
+        self.ws_client_config = config;
+        self.reset_source_settings_draft();
+    }
+}
+
+fn store_ws_client_secrets(
+    config: &WsClientConfig,
+) -> anyhow::Result<()> {
+    let headers = serde_json::to_string(&config.headers)
+        .context("failed to serialize ws_client headers")?;
+    let secrets = [
+        (secrets::WS_CLIENT_HEADERS, headers.as_str()),
+        (secrets::WS_CLIENT_COOKIE, config.cookie.as_str()),
+        (secrets::PROXY_USERNAME, config.proxy.username.as_str()),
+        (secrets::PROXY_PASSWORD, config.proxy.password.as_str()),
+    ];
+    for (name, secret) in secrets {
+        let secret = (!secret.is_empty()).then_some(secret);
+        secrets::store_secret(name, secret)?;
+    }
+    Ok(())
+}
+
+fn connection_ui(
+    ui: &mut Ui,
+    draft: &mut WsClientConfig,
+    subprotocols: &mut String,
+) {
+    Grid::new("source settings").num_columns(2).show(ui, |ui| {
+        ui.label("URL");
+        ui.text_edit_singleline(&mut draft.url);
+        ui.end_row();
+
+        ui.label("Cookie");
+        ui.add(TextEdit::singleline(&mut draft.cookie).password(true));
+        ui.end_row();
+
+        ui.label("Subprotocols");
+        ui.add(
+            TextEdit::singleline(subprotocols)
+                .hint_text("comma separated"),
+        );
+        ui.end_row();
+
+        ui.label("Delay(secs)");
+        delay_override_ui(ui, &mut draft.delay_secs);
+        ui.end_row();
+
+        ui.label("Combo window(secs)");
+        combo_window_ui(ui, &mut draft.combo_window_secs);
+        ui.end_row();
+
+        tls_ui(ui, draft);
+
+        let proxy = &mut draft.proxy;
+        ui.label("Proxy");
+        ComboBox::from_id_salt("source proxy kind")
+            .selected_text(proxy.kind.name())
+            .show_ui(ui, |ui| {
+                for kind in ProxyKind::ALL {
+                    ui.selectable_value(
+                        &mut proxy.kind,
+                        kind,
+                        kind.name(),
+                    );
+                }
+            });
+        ui.end_row();
+
+        if proxy.kind != ProxyKind::None {
+            ui.label("Proxy address");
+            ui.add(
+                TextEdit::singleline(&mut proxy.addr)
+                    .hint_text("host:port"),
+            );
+            ui.end_row();
+
+            ui.label("Proxy username");
+            ui.text_edit_singleline(&mut proxy.username);
+            ui.end_row();
+
+            ui.label("Proxy password");
+            ui.add(
+                TextEdit::singleline(&mut proxy.password).password(true),
+            );
+            ui.end_row();
+        }
+    });
+}
+
+fn tls_ui(ui: &mut Ui, draft: &mut WsClientConfig) {
+    let tls = &mut draft.tls;
+    ui.label("CA file");
+    ui.horizontal(|ui| {
+        ui.add(
+            TextEdit::singleline(&mut tls.ca_file)
+                .hint_text("system roots only"),
+        );
+        if ui.button("Choose...").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("PEM", &["pem", "crt"])
+                .pick_file()
+            {
+                tls.ca_file = path.display().to_string();
+            }
+        }
+    });
+    ui.end_row();
+
+    ui.label("Pinned SHA-256");
+    ui.add(
+        TextEdit::singleline(&mut tls.pinned_sha256)
+            .hint_text("leaf certificate fingerprint"),
+    );
+    ui.end_row();
+}
+
+fn decoder_ui(ui: &mut Ui, decoder: &mut DecoderConfig) {
+    Grid::new("source decoder").num_columns(2).show(ui, |ui| {
+        ui.label("Decoder");
+        ComboBox::from_id_salt("source decoder kind")
+            .selected_text(decoder.kind.name())
+            .show_ui(ui, |ui| {
+                for kind in DecoderKind::ALL {
+                    ui.selectable_value(
+                        &mut decoder.kind,
+                        kind,
+                        kind.name(),
+                    );
+                }
+            });
+        ui.end_row();
+
+        if decoder.kind == DecoderKind::JsonPath {
+            let paths = &mut decoder.json_paths;
+            ui.label("Text path");
+            ui.add(
+                TextEdit::singleline(&mut paths.text)
+                    .hint_text("e.g. data.content"),
+            );
+            ui.end_row();
+
+            ui.label("Username path");
+            ui.add(
+                TextEdit::singleline(&mut paths.username)
+                    .hint_text("optional"),
+            );
+            ui.end_row();
+
+            ui.label("Type path");
+            ui.add(
+                TextEdit::singleline(&mut paths.kind)
+                    .hint_text("optional, chat/gift/superchat"),
+            );
+            ui.end_row();
+
+            ui.label("Image path");
+            ui.add(
+                TextEdit::singleline(&mut paths.image)
+                    .hint_text("optional, image URL"),
+            );
+            ui.end_row();
+
+            ui.label("Amount path");
+            ui.add(
+                TextEdit::singleline(&mut paths.amount)
+                    .hint_text("optional, paid amount"),
+            );
+            ui.end_row();
+
+            ui.label("Emotes path");
+            ui.add(
+                TextEdit::singleline(&mut paths.emotes)
+                    .hint_text("optional, Twitch emotes tag"),
+            );
+            ui.end_row();
+        }
+
+        if matches!(
+            decoder.kind,
+            DecoderKind::JsonPath | DecoderKind::Bilibili
+        ) {
+            ui.label("Emotes");
+            ComboBox::from_id_salt("source decoder emotes")
+                .selected_text(decoder.emotes.name())
+                .show_ui(ui, |ui| {
+                    for mode in EmoteMode::ALL {
+                        ui.selectable_value(
+                            &mut decoder.emotes,
+                            mode,
+                            mode.name(),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "What happens to emote codes in the text, images are \
+                     sent to overlays as emote attachments",
+                );
+            ui.end_row();
+        }
+    });
+}
+
+fn headers_ui(ui: &mut Ui, draft: &mut WsClientConfig) {
+    let mut remove = None;
+    Grid::new("source headers").num_columns(3).show(ui, |ui| {
+        for (idx, (name, value)) in draft.headers.iter_mut().enumerate() {
+            ui.add(
+                TextEdit::singleline(name)
+                    .hint_text("name")
+                    .desired_width(120.0),
+            );
+            ui.add(
+                TextEdit::singleline(value)
+                    .hint_text("value")
+                    .password(true),
+            );
+            if ui.button("Remove").clicked() {
+                remove = Some(idx);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(idx) = remove {
+        draft.headers.remove(idx);
+    }
+    if ui.button("Add header").clicked() {
+        draft.headers.push(Default::default());
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::{
+    channel::ChannelStats,
+    log::{self, report},
+    stats::{RateHistory, RateMark},
+};
+use eframe::egui::{Context as EguiCtx, Grid, Ui, Window};
+use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints, VLine};
+use tracing::info;
+
+use super::App;
+
+impl App {
+    pub(super) fn update_stats(&mut self, ctx: &EguiCtx) {
+        if !self.stats_show {
+            return;
+        }
+
+        Window::new("Stats")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("stats latency").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Send delay");
+                        ui.label(format!(
+                            "{:.1}s",
+                            self.msg_send_delay_secs
+                        ));
+                        ui.end_row();
+
+                        ui.label("Latency p50");
+                        ui.label(format_latency(
+                            self.latency.percentile(0.5),
+                        ));
+                        ui.end_row();
+
+                        ui.label("Latency p95");
+                        ui.label(format_latency(
+                            self.latency.percentile(0.95),
+                        ));
+                        ui.end_row();
+
+                        ui.label("Samples");
+                        ui.label(self.latency.len().to_string());
+                        ui.end_row();
+                    },
+                );
+                ui.label(
+                    "Measured from the source receiving a message to it \
+                     being sent to the overlay, only while an overlay is \
+                     connected.",
+                );
+
+                ui.separator();
+                rate_chart_ui(ui, &self.rate_history);
+                self.activity_ui(ui);
+
+                if let Ok(ref network) = self.network {
+                    ui.separator();
+                    Grid::new("stats channels").num_columns(2).show(
+                        ui,
+                        |ui| {
+                            channel_row(
+                                ui,
+                                "Inbound queue",
+                                network.ws_message_stats(),
+                            );
+                            channel_row(
+                                ui,
+                                "Log queue",
+                                network.log_stats(),
+                            );
+                            channel_row(
+                                ui,
+                                "Overlay broadcast",
+                                network.ws_broadcast_stats(),
+                            );
+                        },
+                    );
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Reset").clicked() {
+                        self.latency.clear();
+                    }
+                    if ui
+                        .button("Generate report")
+                        .on_hover_text(
+                            "Summarize the last session of the message log \
+                             into a Markdown file next to it",
+                        )
+                        .clicked()
+                    {
+                        self.generate_report();
+                    }
+                    if ui.button("Close").clicked() {
+                        self.stats_show = false;
+                        ui.data_mut(|d| {
+                            d.insert_persisted(
+                                self.stats_show_id,
+                                self.stats_show,
+                            )
+                        });
+                    }
+                });
+            });
+    }
+
+    /// Of the last session in the message log.
+    pub(super) fn generate_report(&mut self) {
+        match report::generate(
+            &log::default_path(),
+            self.log_config.anonymize,
+        ) {
+            Ok(path) => {
+                info!("session report saved to {}", path.display())
+            }
+            Err(err) => self.err_messages.push(format!("{err:?}")),
+        }
+    }
+}
+
+/// Incoming and forwarded messages per minute, with a line where the
+/// queue was paused, resumed or purged.
+fn rate_chart_ui(ui: &mut Ui, history: &RateHistory) {
+    let minutes = history.elapsed(Instant::now()).as_secs() / 60;
+    // up to the current minute, empty ones at the end included
+    let series = |counts: &[u32]| {
+        PlotPoints::from_iter((0..=minutes).map(|minute| {
+            let count = counts.get(minute as usize).copied().unwrap_or(0);
+            [minute as f64, count as f64]
+        }))
+    };
+    let visuals = ui.style().visuals.clone();
+    Plot::new("stats rate")
+        .width(360.0)
+        .height(160.0)
+        .legend(Legend::default())
+        .x_axis_label("Minutes")
+        .y_axis_label("Messages")
+        .include_y(0.0)
+        .allow_scroll(false)
+        .show(ui, |plot| {
+            plot.line(
+                Line::new(series(history.incoming())).name("Incoming"),
+            );
+            plot.line(
+                Line::new(series(history.forwarded())).name("Forwarded"),
+            );
+            for &(at, mark) in history.marks() {
+                let (name, color) = match mark {
+                    RateMark::Paused => ("Paused", visuals.warn_fg_color),
+                    RateMark::Resumed => (
+                        "Resumed",
+                        visuals.widgets.active.fg_stroke.color,
+                    ),
+                    RateMark::Purged => {
+                        ("Purged", visuals.error_fg_color)
+                    }
+                };
+                plot.vline(
+                    VLine::new(at.as_secs_f64() / 60.0)
+                        .name(name)
+                        .color(color)
+                        .style(LineStyle::dashed_loose()),
+                );
+            }
+        });
+}
+
+fn channel_row(ui: &mut Ui, name: &str, stats: ChannelStats) {
+    ui.label(name);
+    ui.label(format!(
+        "{}/{}, {} dropped",
+        stats.len, stats.capacity, stats.dropped
+    ));
+    ui.end_row();
+}
+
+fn format_latency(latency: Option<Duration>) -> String {
+    match latency {
+        Some(latency) => format!("{:.3}s", latency.as_secs_f64()),
+        None => "-".to_owned(),
+    }
+}
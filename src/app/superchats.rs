@@ -0,0 +1,98 @@
+use blooming_light_core::superchat::PinDurations;
+use eframe::egui::{Context as EguiCtx, Grid, TextEdit, Window};
+
+use super::{format_duration, App};
+
+impl App {
+    pub(super) fn update_superchats(&mut self, ctx: &EguiCtx) {
+        if !self.superchats_show {
+            return;
+        }
+
+        Window::new("SuperChats")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if self.active_superchats.is_empty() {
+                    ui.label("No pinned SuperChats");
+                } else {
+                    let now = self.message.lock().now();
+                    Grid::new("active superchats")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for it in self.active_superchats.iter() {
+                                let amount = it
+                                    .msg
+                                    .paid
+                                    .as_ref()
+                                    .map_or(0.0, |it| it.amount);
+                                ui.label(
+                                    it.msg
+                                        .username
+                                        .as_deref()
+                                        .unwrap_or_default(),
+                                );
+                                ui.label(format!("{amount}"));
+                                ui.label(&it.msg.text);
+                                ui.label(format_duration(
+                                    it.until
+                                        .saturating_duration_since(now),
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Pin tiers");
+                    let tiers =
+                        PinDurations::parse(&self.pin_durations_draft);
+                    let res = ui
+                        .add(
+                            TextEdit::singleline(
+                                &mut self.pin_durations_draft,
+                            )
+                            .text_color_opt(
+                                tiers.is_none().then(|| {
+                                    ui.style().visuals.error_fg_color
+                                }),
+                            ),
+                        )
+                        .on_hover_text(
+                            "amount=secs, comma separated. A paid \
+                             message stays pinned for the secs of the \
+                             highest amount it reaches, not at all \
+                             below the lowest",
+                        );
+                    if res.changed() {
+                        if let Some(tiers) =
+                            PinDurations::parse(&self.pin_durations_draft)
+                        {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.pin_durations_id,
+                                    tiers.clone(),
+                                )
+                            });
+                            self.pin_durations = tiers;
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.superchats_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.superchats_show_id,
+                            self.superchats_show,
+                        )
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,454 @@
+use blooming_light_core::{
+    flag::FlagNotifier,
+    lang::Language,
+    message::MessageKind,
+    text::{ImageAction, LongMessage, UrlAction},
+};
+use eframe::egui::{
+    ComboBox, Context as EguiCtx, DragValue, Grid, TextEdit, Ui, Window,
+};
+
+use super::App;
+
+impl App {
+    pub(super) fn update_text_settings(&mut self, ctx: &EguiCtx) {
+        if !self.text_settings_show {
+            return;
+        }
+
+        Window::new("Text Settings")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let sanitizer = &mut self.sanitizer;
+                let mut changed = false;
+                Grid::new("sanitizer settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Sanitize");
+                        changed |= ui
+                            .checkbox(&mut sanitizer.enabled, "")
+                            .on_hover_text(
+                                "Normalize unicode and strip invisible \
+                                 and bidi control characters",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("Max combining marks");
+                        changed |= ui
+                            .add_enabled(
+                                sanitizer.enabled,
+                                DragValue::new(
+                                    &mut sanitizer.max_combining_marks,
+                                )
+                                .range(0..=16),
+                            )
+                            .on_hover_text("Per character")
+                            .changed();
+                        ui.end_row();
+                    },
+                );
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.sanitizer_id,
+                            sanitizer.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let limit = &mut self.length_limit;
+                let mut changed = false;
+                Grid::new("length settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Max message length");
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut limit.max_chars)
+                                    .range(0..=10000),
+                            )
+                            .on_hover_text(
+                                "In characters, 0 for no limit",
+                            )
+                            .changed();
+                        ui.end_row();
+
+                        ui.label("Longer messages");
+                        ComboBox::from_id_salt("long message")
+                            .selected_text(limit.long_message.name())
+                            .show_ui(ui, |ui| {
+                                for it in LongMessage::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut limit.long_message,
+                                            it,
+                                            it.name(),
+                                        )
+                                        .changed();
+                                }
+                            });
+                        ui.end_row();
+                    },
+                );
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.length_limit_id,
+                            limit.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let markup = &mut self.overlay_markup;
+                let mut changed = false;
+                Grid::new("markup settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Allowed tags");
+                        let res = ui
+                            .add(
+                                TextEdit::singleline(
+                                    &mut self.allowed_tags_draft,
+                                )
+                                .hint_text("e.g. b, i"),
+                            )
+                            .on_hover_text(
+                                "Everything else is HTML-escaped before \
+                                 it reaches the overlay",
+                            );
+                        if res.changed() {
+                            markup.allowed_tags = self
+                                .allowed_tags_draft
+                                .split(',')
+                                .map(|it| it.trim().to_ascii_lowercase())
+                                .filter(|it| !it.is_empty())
+                                .collect();
+                            changed = true;
+                        }
+                        ui.end_row();
+                    },
+                );
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.overlay_markup_id,
+                            markup.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let filter = &mut self.url_filter;
+                let mut changed = false;
+                Grid::new("url settings").num_columns(2).show(ui, |ui| {
+                    ui.label("Links");
+                    ComboBox::from_id_salt("url action")
+                        .selected_text(filter.action.name())
+                        .show_ui(ui, |ui| {
+                            for action in UrlAction::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut filter.action,
+                                        action,
+                                        action.name(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Allowed domains");
+                    let res = ui
+                        .add(
+                            TextEdit::singleline(
+                                &mut self.allowed_domains_draft,
+                            )
+                            .hint_text("e.g. bilibili.com"),
+                        )
+                        .on_hover_text(
+                            "Comma separated, links to these and their \
+                             subdomains are always kept",
+                        );
+                    if res.changed() {
+                        filter.allowed_domains = self
+                            .allowed_domains_draft
+                            .split(',')
+                            .map(|it| it.trim().to_ascii_lowercase())
+                            .filter(|it| !it.is_empty())
+                            .collect();
+                        changed = true;
+                    }
+                    ui.end_row();
+                });
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.url_filter_id,
+                            filter.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                Grid::new("image settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Image messages");
+                        let mut changed = false;
+                        ComboBox::from_id_salt("image action")
+                            .selected_text(self.image_action.name())
+                            .show_ui(ui, |ui| {
+                                for action in ImageAction::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut self.image_action,
+                                            action,
+                                            action.name(),
+                                        )
+                                        .changed();
+                                }
+                            });
+                        if changed {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.image_action_id,
+                                    self.image_action,
+                                )
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Thumbnails in queue");
+                        if ui
+                            .checkbox(&mut self.show_thumbnails, "")
+                            .changed()
+                        {
+                            ui.data_mut(|d| {
+                                d.insert_persisted(
+                                    self.show_thumbnails_id,
+                                    self.show_thumbnails,
+                                )
+                            });
+                        }
+                        ui.end_row();
+                    },
+                );
+
+                ui.separator();
+
+                let filter = &mut self.kind_filter;
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("Drop")
+                        .on_hover_text("Logged as deleted, never queued");
+                    for kind in MessageKind::ALL {
+                        let mut dropped = filter.drops_kind(kind);
+                        if ui
+                            .checkbox(&mut dropped, kind.name())
+                            .changed()
+                        {
+                            filter.set_dropped(kind, dropped);
+                            changed = true;
+                        }
+                    }
+                });
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.kind_filter_id,
+                            filter.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let filter = &mut self.language_filter;
+                let mut changed = false;
+                ui.label("Scripts").on_hover_text(
+                    "The script most letters are in, which doesn't tell \
+                     apart languages sharing one, e.g. English and \
+                     Spanish. Tagged ones are marked in the queue, \
+                     dropped ones are logged as deleted and never queued",
+                );
+                Grid::new("script filter").num_columns(3).show(
+                    ui,
+                    |ui| {
+                        for lang in Language::ALL {
+                            ui.label(lang.name());
+                            let mut tagged =
+                                filter.tagged.contains(&lang);
+                            if ui.checkbox(&mut tagged, "Tag").changed() {
+                                filter.set_tagged(lang, tagged);
+                                changed = true;
+                            }
+                            let mut dropped =
+                                filter.dropped.contains(&lang);
+                            if ui.checkbox(&mut dropped, "Drop").changed()
+                            {
+                                filter.set_dropped(lang, dropped);
+                                changed = true;
+                            }
+                            ui.end_row();
+                        }
+                    },
+                );
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.language_filter_id,
+                            filter.clone(),
+                        )
+                    });
+                }
+
+                ui.separator();
+
+                let flags = &mut self.flag_words;
+                let mut changed = false;
+                Grid::new("flag settings").num_columns(2).show(
+                    ui,
+                    |ui| {
+                        ui.label("Flag words");
+                        let res = ui
+                        .add(
+                            TextEdit::singleline(
+                                &mut self.flag_words_draft,
+                            )
+                            .hint_text("e.g. spoiler"),
+                        )
+                        .on_hover_text(
+                            "Comma separated, messages containing any \
+                             of these are marked in the queue",
+                        );
+                        if res.changed() {
+                            flags.words = self
+                                .flag_words_draft
+                                .split(',')
+                                .map(|it| it.trim().to_owned())
+                                .filter(|it| !it.is_empty())
+                                .collect();
+                            changed = true;
+                        }
+                        ui.end_row();
+
+                        ui.label("Notify flagged");
+                        changed |= ui
+                            .checkbox(&mut flags.notify, "")
+                            .on_hover_text(if FlagNotifier::HAS_ACTIONS {
+                                "Desktop notification for each flagged \
+                                 message. With notify-send it can delete \
+                                 or approve the message right there"
+                            } else {
+                                "Desktop notification for each flagged \
+                                 message. Deleting or approving from it \
+                                 isn't supported on this system yet"
+                            })
+                            .changed();
+                        ui.end_row();
+                    },
+                );
+                if changed {
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.flag_words_id,
+                            flags.clone(),
+                        )
+                    });
+                }
+                self.word_lists_ui(ui);
+
+                ui.separator();
+
+                self.mentions_ui(ui);
+
+                ui.separator();
+
+                self.chat_commands_ui(ui);
+
+                ui.separator();
+
+                self.rule_tester_ui(ui);
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.text_settings_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.text_settings_show_id,
+                            self.text_settings_show,
+                        )
+                    });
+                }
+            });
+    }
+
+    /// Names highlighted in the queue when mentioned.
+    fn mentions_ui(&mut self, ui: &mut Ui) {
+        let mentions = &mut self.mentions;
+        let mut changed = false;
+        Grid::new("mention settings").num_columns(2).show(ui, |ui| {
+            ui.label("Mentions");
+            let res = ui
+                .add(
+                    TextEdit::singleline(&mut self.mentions_draft)
+                        .hint_text("e.g. your handle, co-hosts"),
+                )
+                .on_hover_text(
+                    "Comma separated, messages mentioning any of these \
+                     are highlighted in the queue",
+                );
+            if res.changed() {
+                mentions.names = self
+                    .mentions_draft
+                    .split(',')
+                    .map(|it| it.trim().to_owned())
+                    .filter(|it| !it.is_empty())
+                    .collect();
+                changed = true;
+            }
+            ui.end_row();
+
+            ui.label("Fast track");
+            ui.horizontal(|ui| {
+                let mut enabled = mentions.fast_track_secs.is_some();
+                if ui
+                    .checkbox(&mut enabled, "")
+                    .on_hover_text(
+                        "Send messages with a mention after this delay \
+                         instead of the source's",
+                    )
+                    .changed()
+                {
+                    mentions.fast_track_secs = enabled.then_some(2.0);
+                    changed = true;
+                }
+                if let Some(ref mut secs) = mentions.fast_track_secs {
+                    changed |= ui
+                        .add(
+                            DragValue::new(secs)
+                                .range(0.0..=600.0)
+                                .speed(0.1)
+                                .suffix("s"),
+                        )
+                        .changed();
+                }
+            });
+            ui.end_row();
+        });
+        if changed {
+            ui.data_mut(|d| {
+                d.insert_persisted(self.mentions_id, mentions.clone())
+            });
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use blooming_light_core::network::{fetch, proxy::ProxyConfig};
+use eframe::egui::{
+    load::{Bytes, BytesLoadResult, BytesLoader, BytesPoll, LoadError},
+    Context as EguiCtx,
+};
+use tracing::debug;
+
+type Entry = Option<Result<(Arc<[u8]>, Option<String>), String>>;
+
+/// Loads attachment images for the queue: `data:` URIs are decoded in
+/// place, http(s) ones fetched on a background thread through the
+/// source's proxy. The image loader from `egui_extras` decodes the bytes.
+#[derive(Default)]
+pub struct ThumbnailLoader {
+    proxy: Mutex<ProxyConfig>,
+    /// `None` while the fetch is in flight.
+    cache: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl ThumbnailLoader {
+    pub const ID: &'static str =
+        eframe::egui::generate_loader_id!(ThumbnailLoader);
+
+    pub fn set_proxy(&self, proxy: ProxyConfig) {
+        *self.proxy.lock().unwrap() = proxy;
+    }
+}
+
+impl BytesLoader for ThumbnailLoader {
+    fn id(&self) -> &str {
+        Self::ID
+    }
+
+    fn load(&self, ctx: &EguiCtx, uri: &str) -> BytesLoadResult {
+        if let Some(data) = uri.strip_prefix("data:") {
+            let (mime, data) = data
+                .split_once(";base64,")
+                .ok_or(LoadError::NotSupported)?;
+            let bytes = BASE64_STANDARD
+                .decode(data)
+                .map_err(|err| LoadError::Loading(err.to_string()))?;
+            return Ok(BytesPoll::Ready {
+                size: None,
+                bytes: Bytes::Shared(bytes.into()),
+                mime: Some(mime.to_owned()),
+            });
+        }
+        if !uri.starts_with("http://") && !uri.starts_with("https://") {
+            return Err(LoadError::NotSupported);
+        }
+
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(uri) {
+            Some(Some(Ok((bytes, mime)))) => Ok(BytesPoll::Ready {
+                size: None,
+                bytes: Bytes::Shared(bytes.clone()),
+                mime: mime.clone(),
+            }),
+            Some(Some(Err(err))) => Err(LoadError::Loading(err.clone())),
+            Some(None) => Ok(BytesPoll::Pending { size: None }),
+            None => {
+                cache.insert(uri.to_owned(), None);
+                let uri = uri.to_owned();
+                let proxy = self.proxy.lock().unwrap().clone();
+                let cache = self.cache.clone();
+                let ctx = ctx.clone();
+                thread::spawn(move || {
+                    let result =
+                        fetch::fetch_blocking(&uri, "image/*", &proxy)
+                            .map(|it| (Arc::from(&it.bytes[..]), it.mime))
+                            .map_err(|err| {
+                                debug!("thumbnail {uri}: {err:?}");
+                                format!("{err:#}")
+                            });
+                    let mut cache = cache.lock().unwrap();
+                    // forgotten meanwhile
+                    if let Some(entry) = cache.get_mut(&uri) {
+                        *entry = Some(result);
+                    }
+                    ctx.request_repaint();
+                });
+                Ok(BytesPoll::Pending { size: None })
+            }
+        }
+    }
+
+    fn forget(&self, uri: &str) {
+        self.cache.lock().unwrap().remove(uri);
+    }
+
+    fn forget_all(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn byte_size(&self) -> usize {
+        self.cache
+            .lock()
+            .unwrap()
+            .values()
+            .map(|it| match it {
+                Some(Ok((bytes, _))) => bytes.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+}
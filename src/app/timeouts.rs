@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use blooming_light_core::timeout::TIMEOUT_PRESETS;
+use chrono::TimeDelta;
+use eframe::egui::{
+    Button, Context as EguiCtx, DragValue, Grid, TextEdit, Window,
+};
+use tracing::info;
+
+use super::{format_duration, App};
+
+impl App {
+    /// Lifts timeouts that are over, and lists the rest with a row to
+    /// time someone out.
+    pub(super) fn update_timeouts(&mut self, ctx: &EguiCtx) {
+        let now = self.message.lock_quiet().now_utc();
+        let expired = self.timeouts.expire(now);
+        if !expired.is_empty() {
+            info!(?expired, "timeouts over");
+            self.save_timeouts(ctx);
+        }
+        if !self.timeouts_show {
+            return;
+        }
+
+        let mut changed = false;
+        Window::new("Timeouts")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Messages from timed out users are dropped until it's \
+                     over, then flow again.",
+                );
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.timeout_username)
+                            .hint_text("Username")
+                            .desired_width(120.0),
+                    );
+                    let username = self.timeout_username.trim().to_owned();
+                    let mut timeout = None;
+                    for (name, duration) in TIMEOUT_PRESETS {
+                        if ui
+                            .add_enabled(
+                                !username.is_empty(),
+                                Button::new(name),
+                            )
+                            .clicked()
+                        {
+                            timeout = Some(duration);
+                        }
+                    }
+                    ui.add(
+                        DragValue::new(&mut self.timeout_custom_mins)
+                            .range(1..=10080)
+                            .suffix(" min"),
+                    );
+                    if ui
+                        .add_enabled(!username.is_empty(), Button::new("Mute"))
+                        .clicked()
+                    {
+                        timeout = Some(TimeDelta::minutes(
+                            self.timeout_custom_mins,
+                        ));
+                    }
+                    if let Some(duration) = timeout {
+                        info!(username, ?duration, "timing out");
+                        self.timeouts.add(&username, now + duration);
+                        self.timeout_username.clear();
+                        changed = true;
+                    }
+                });
+
+                ui.separator();
+
+                if self.timeouts.is_empty() {
+                    ui.label("No one is timed out");
+                } else {
+                    let mut lift = None;
+                    Grid::new("timeouts").num_columns(3).striped(true).show(
+                        ui,
+                        |ui| {
+                            for (username, until) in self.timeouts.active() {
+                                ui.label(username);
+                                let left = (until - now)
+                                    .to_std()
+                                    .unwrap_or_default();
+                                ui.label(format_duration(left));
+                                if ui.button("Lift").clicked() {
+                                    lift = Some(username.to_owned());
+                                }
+                                ui.end_row();
+                            }
+                        },
+                    );
+                    if let Some(username) = lift {
+                        info!(username, "lifting timeout");
+                        self.timeouts.remove(&username);
+                        changed = true;
+                    }
+                    // keeps the time left ticking
+                    ctx.request_repaint_after(Duration::from_secs(1));
+                }
+
+                ui.separator();
+
+                if ui.button("Close").clicked() {
+                    self.timeouts_show = false;
+                    ui.data_mut(|d| {
+                        d.insert_persisted(
+                            self.timeouts_show_id,
+                            self.timeouts_show,
+                        )
+                    });
+                }
+            });
+        if changed {
+            self.save_timeouts(ctx);
+        }
+    }
+
+    fn save_timeouts(&self, ctx: &EguiCtx) {
+        ctx.data_mut(|d| {
+            d.insert_persisted(self.timeouts_id, self.timeouts.clone())
+        });
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use blooming_light_core::timer::OverlayTimer;
+use eframe::egui::{
+    Button, Context as EguiCtx, DragValue, Grid, TextEdit, Window,
+};
+
+use super::{format_duration, App};
+
+impl App {
+    /// Also keeps running timers ticking while the window is closed.
+    pub(super) fn update_timers(&mut self, ctx: &EguiCtx) {
+        let now = Instant::now();
+        if let Ok(ref network) = self.network {
+            for timer in &mut self.timers {
+                if let Some(frame) = timer.tick(now) {
+                    network.broadcast_timer(&frame);
+                }
+                if let Some(wait) = timer.next_tick_in(now) {
+                    ctx.request_repaint_after(wait);
+                }
+            }
+        }
+
+        if !self.timers_show {
+            return;
+        }
+
+        Window::new("Timers")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut frames = vec![];
+                let mut removed = None;
+                Grid::new("timers").num_columns(5).striped(true).show(
+                    ui,
+                    |ui| {
+                        for (idx, timer) in
+                            self.timers.iter_mut().enumerate()
+                        {
+                            ui.label(&timer.label);
+                            ui.label(format_duration(
+                                timer.remaining(now),
+                            ));
+                            if timer.is_running() {
+                                if ui.button("Stop").clicked() {
+                                    frames.push(timer.stop(now));
+                                }
+                            } else if ui.button("Start").clicked() {
+                                frames.push(timer.start(now));
+                            }
+                            if ui.button("Reset").clicked() {
+                                frames.extend(timer.reset(now));
+                            }
+                            if ui.button("Remove").clicked() {
+                                frames.extend(timer.reset(now));
+                                removed = Some(idx);
+                            }
+                            ui.end_row();
+                        }
+                    },
+                );
+                if let Some(idx) = removed {
+                    self.timers.remove(idx);
+                }
+                if let Ok(ref network) = self.network {
+                    for frame in &frames {
+                        network.broadcast_timer(frame);
+                    }
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.timer_label_draft)
+                            .hint_text("Starting soon")
+                            .desired_width(160.0),
+                    );
+                    ui.label("Duration(secs)");
+                    ui.add(
+                        DragValue::new(&mut self.timer_secs_draft)
+                            .range(1..=86400),
+                    );
+                    if ui
+                        .add_enabled(
+                            !self.timer_label_draft.trim().is_empty(),
+                            Button::new("Add"),
+                        )
+                        .clicked()
+                    {
+                        self.next_timer_id += 1;
+                        self.timers.push(OverlayTimer::new(
+                            self.next_timer_id,
+                            self.timer_label_draft.trim(),
+                            Duration::from_secs(self.timer_secs_draft),
+                        ));
+                        self.timer_label_draft.clear();
+                    }
+                });
+
+                if ui.button("Close").clicked() {
+                    self.timers_show = false;
+                }
+            });
+    }
+}
@@ -0,0 +1,118 @@
+use blooming_light_core::{message::Message, queue::PendingMessage};
+use eframe::egui::{Context as EguiCtx, Key, Modifiers, Ui};
+use tracing::info;
+
+use super::App;
+
+/// The cursor of triage mode, over queued messages as listed.
+#[derive(Debug, Default)]
+pub(super) struct Triage {
+    /// Found again by content as rows come and go.
+    selected: Option<Message>,
+    /// Falls back to the row here when it's gone.
+    idx: usize,
+    /// Scrolled to once the list is drawn.
+    moved: bool,
+}
+
+impl Triage {
+    pub(super) fn selected(&self) -> Option<&Message> {
+        self.selected.as_ref()
+    }
+
+    pub(super) fn take_moved(&mut self) -> bool {
+        std::mem::take(&mut self.moved)
+    }
+}
+
+/// Rows the cursor stops on, approved ones are on their way out.
+fn is_triaged(pending: &PendingMessage) -> bool {
+    !pending.delete && !pending.forced
+}
+
+fn switch(triage: &mut Option<Triage>, on: bool) {
+    info!(on, "triage mode");
+    *triage = on.then(Triage::default);
+}
+
+/// Over the queue, docked or popped out.
+pub(super) fn triage_button(ui: &mut Ui, triage: &mut Option<Triage>) {
+    let on = triage.is_some();
+    if ui
+        .selectable_label(on, "Triage")
+        .on_hover_text(
+            "Go through the queue with the keyboard: j and k to move, d \
+             to delete, a to approve, Esc to leave",
+        )
+        .clicked()
+    {
+        switch(triage, !on);
+    }
+}
+
+impl App {
+    pub(super) fn set_triage(&mut self, on: bool) {
+        switch(&mut self.triage, on);
+    }
+
+    /// j and k move the cursor down and up, d deletes and a approves the
+    /// message under it and Escape leaves, unless typing.
+    pub(super) fn update_triage(&mut self, ctx: &EguiCtx) {
+        if self.triage.is_none() || ctx.wants_keyboard_input() {
+            return;
+        }
+        let key =
+            |key| ctx.input_mut(|i| i.consume_key(Modifiers::NONE, key));
+        let (down, up) = (key(Key::J), key(Key::K));
+        let (delete, approve) = (key(Key::D), key(Key::A));
+        if key(Key::Escape) {
+            self.set_triage(false);
+            return;
+        }
+        let Some(ref mut triage) = self.triage else {
+            return;
+        };
+
+        // deleting only pushes deadlines back, no need to wake the
+        // releaser for it
+        let mut queue = self.message.lock_quiet();
+        let len = queue.iter_mut().filter(|it| is_triaged(it)).count();
+        let found = triage.selected.as_ref().and_then(|selected| {
+            queue
+                .iter_mut()
+                .filter(|it| is_triaged(it))
+                .position(|it| it.msg == *selected)
+        });
+        let mut idx =
+            found.unwrap_or(triage.idx).min(len.saturating_sub(1));
+        if down {
+            idx = (idx + 1).min(len.saturating_sub(1));
+        }
+        if up {
+            idx = idx.saturating_sub(1);
+        }
+        let mut approved = None;
+        if let Some(pending) =
+            queue.iter_mut().filter(|it| is_triaged(it)).nth(idx)
+        {
+            if delete {
+                // taken off and logged with the ones deleted by hand
+                pending.delete = true;
+            } else if approve {
+                approved = Some(pending.msg.clone());
+            }
+        }
+        let rows = queue.iter_mut().filter(|it| {
+            is_triaged(it) && Some(&it.msg) != approved.as_ref()
+        });
+        // the one after a deleted or approved row, or the new last one
+        let selected = rows.take(idx + 1).last().map(|it| it.msg.clone());
+        triage.moved |= selected != triage.selected;
+        triage.selected = selected;
+        triage.idx = idx;
+        drop(queue);
+        if let Some(msg) = approved {
+            self.approve(&msg);
+        }
+    }
+}
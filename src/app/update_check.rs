@@ -0,0 +1,102 @@
+use std::{sync::mpsc, thread};
+
+use blooming_light_core::update::{self, Release};
+use eframe::egui::{Button, Context as EguiCtx, TopBottomPanel, Ui};
+use tracing::{info, warn};
+
+use super::App;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+impl App {
+    /// Asks GitHub for the latest release on a thread of its own.
+    pub(super) fn start_update_check(&mut self, ctx: &EguiCtx) {
+        let (result_tx, result_rx) = mpsc::channel();
+        let proxy = self.ws_client_config.proxy.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ =
+                result_tx.send(update::check_blocking(VERSION, &proxy));
+            ctx.request_repaint();
+        });
+        self.update_rx = Some(result_rx);
+        self.update_status = "Checking...".to_owned();
+    }
+
+    /// Shows a banner over the main window while a newer release is out.
+    pub(super) fn update_update_check(&mut self, ctx: &EguiCtx) {
+        let result =
+            self.update_rx.as_ref().and_then(|it| it.try_recv().ok());
+        if let Some(result) = result {
+            self.update_rx = None;
+            match result {
+                Ok(Some(release)) => {
+                    info!("{} is out", release.tag_name);
+                    self.update_status =
+                        format!("{} is out", release.tag_name);
+                    self.available_update = Some(release);
+                }
+                Ok(None) => self.update_status = "Up to date".to_owned(),
+                Err(err) => {
+                    // not worth interrupting anyone for
+                    warn!("failed to check for updates: {err:?}");
+                    self.update_status =
+                        "Failed to check, see the log".to_owned();
+                }
+            }
+        }
+
+        let Some(Release {
+            ref tag_name,
+            ref html_url,
+        }) = self.available_update
+        else {
+            return;
+        };
+        let mut dismissed = false;
+        TopBottomPanel::top("update banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Blooming Light {tag_name} is out, this is {VERSION}"
+                ));
+                ui.hyperlink_to("Changelog", html_url);
+                if ui.button("Dismiss").clicked() {
+                    dismissed = true;
+                }
+            });
+        });
+        if dismissed {
+            self.available_update = None;
+        }
+    }
+
+    /// A row of the debug settings grid.
+    pub(super) fn update_check_ui(&mut self, ui: &mut Ui) {
+        ui.label("Check for updates");
+        ui.horizontal(|ui| {
+            let res = ui
+                .checkbox(&mut self.check_updates, "At startup")
+                .on_hover_text(
+                    "Ask GitHub for the latest release when the app \
+                     starts, through the source proxy if one is set",
+                );
+            if res.changed() {
+                ui.data_mut(|d| {
+                    d.insert_persisted(
+                        self.check_updates_id,
+                        self.check_updates,
+                    )
+                });
+            }
+            let checking = self.update_rx.is_some();
+            if ui
+                .add_enabled(!checking, Button::new("Check now"))
+                .clicked()
+            {
+                self.start_update_check(ui.ctx());
+            }
+            ui.label(&self.update_status);
+        });
+        ui.end_row();
+    }
+}
@@ -0,0 +1,255 @@
+use std::{collections::HashMap, sync::mpsc, thread, time::Duration};
+
+use blooming_light_core::flag::word_list::{WordList, WordListSource};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use eframe::egui::{
+    Button, Context as EguiCtx, DragValue, Grid, RichText, TextEdit, Ui,
+};
+use tracing::{info, warn};
+
+use super::App;
+
+type WordListResult = (WordListSource, anyhow::Result<Vec<String>>);
+
+/// Of a list not synced as it should be.
+#[derive(Debug)]
+enum WordListStatus {
+    Syncing,
+    /// Tried again at the list's next sync.
+    Failed(DateTime<Utc>, String),
+}
+
+/// Syncs of word lists running on threads of their own.
+pub(super) struct WordListSyncs {
+    result_tx: mpsc::Sender<WordListResult>,
+    result_rx: mpsc::Receiver<WordListResult>,
+    status: HashMap<WordListSource, WordListStatus>,
+}
+
+impl WordListSyncs {
+    pub(super) fn new() -> Self {
+        let (result_tx, result_rx) = mpsc::channel();
+        Self {
+            result_tx,
+            result_rx,
+            status: HashMap::new(),
+        }
+    }
+
+    fn is_due(&self, list: &WordList, now: DateTime<Utc>) -> bool {
+        if !list.resync_due(now) {
+            return false;
+        }
+        match self.status.get(&list.source) {
+            Some(WordListStatus::Syncing) => false,
+            Some(WordListStatus::Failed(at, _)) => {
+                now - *at >= TimeDelta::minutes(list.resync_mins.into())
+            }
+            None => true,
+        }
+    }
+}
+
+impl App {
+    /// Takes in finished syncs and starts the ones due.
+    pub(super) fn update_word_lists(&mut self, ctx: &EguiCtx) {
+        let now = Utc::now();
+        let mut changed = false;
+        while let Ok((source, result)) =
+            self.word_list_syncs.result_rx.try_recv()
+        {
+            let list = self
+                .flag_words
+                .lists
+                .iter_mut()
+                .find(|it| it.source == source);
+            // removed meanwhile
+            let Some(list) = list else {
+                self.word_list_syncs.status.remove(&source);
+                continue;
+            };
+            match result {
+                Ok(words) => {
+                    info!(%source, count = words.len(), "word list synced");
+                    list.words = words;
+                    list.synced_at = Some(now);
+                    self.word_list_syncs.status.remove(&source);
+                    changed = true;
+                }
+                Err(err) => {
+                    warn!(%source, "failed to sync word list: {err:?}");
+                    self.word_list_syncs.status.insert(
+                        source,
+                        WordListStatus::Failed(now, format!("{err:#}")),
+                    );
+                }
+            }
+        }
+        if changed {
+            ctx.data_mut(|d| {
+                d.insert_persisted(
+                    self.flag_words_id,
+                    self.flag_words.clone(),
+                )
+            });
+        }
+
+        let due = self
+            .flag_words
+            .lists
+            .iter()
+            .filter(|it| self.word_list_syncs.is_due(it, now))
+            .map(|it| it.source.clone())
+            .collect::<Vec<_>>();
+        for source in due {
+            self.start_word_list_sync(ctx, source);
+        }
+        if self.flag_words.lists.iter().any(|it| it.resync_mins > 0) {
+            // nothing else wakes us up when one is due
+            ctx.request_repaint_after(Duration::from_secs(60));
+        }
+    }
+
+    fn start_word_list_sync(
+        &mut self,
+        ctx: &EguiCtx,
+        source: WordListSource,
+    ) {
+        self.word_list_syncs
+            .status
+            .insert(source.clone(), WordListStatus::Syncing);
+        let result_tx = self.word_list_syncs.result_tx.clone();
+        let proxy = self.ws_client_config.proxy.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = source.load_blocking(&proxy);
+            let _ = result_tx.send((source, result));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Imported lists in the text settings, with a row to add one.
+    pub(super) fn word_lists_ui(&mut self, ui: &mut Ui) {
+        let mut changed = false;
+        let mut sync = None;
+        let mut remove = None;
+        ui.label("Word lists").on_hover_text(
+            "Flag words imported from files or URLs, kept in sync",
+        );
+        Grid::new("word lists").num_columns(5).show(ui, |ui| {
+            for (idx, list) in
+                self.flag_words.lists.iter_mut().enumerate()
+            {
+                changed |= ui
+                    .checkbox(&mut list.enabled, list.source.name())
+                    .on_hover_text(list.source.to_string())
+                    .changed();
+                match self.word_list_syncs.status.get(&list.source) {
+                    Some(WordListStatus::Syncing) => {
+                        ui.label("Syncing...");
+                    }
+                    Some(WordListStatus::Failed(_, err)) => {
+                        ui.label(
+                            RichText::new("Failed to sync")
+                                .color(ui.style().visuals.error_fg_color),
+                        )
+                        .on_hover_text(err);
+                    }
+                    None => {
+                        let synced = list.synced_at.map_or(
+                            "never synced".to_owned(),
+                            |it| {
+                                it.with_timezone(&Local)
+                                    .format("synced %H:%M")
+                                    .to_string()
+                            },
+                        );
+                        ui.label(format!(
+                            "{} words, {synced}",
+                            list.words.len()
+                        ));
+                    }
+                }
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut list.resync_mins)
+                            .range(0..=1440)
+                            .suffix(" min"),
+                    )
+                    .on_hover_text(
+                        "Time between syncs, 0 to only sync by hand",
+                    )
+                    .changed();
+                let syncing = matches!(
+                    self.word_list_syncs.status.get(&list.source),
+                    Some(WordListStatus::Syncing)
+                );
+                if ui.add_enabled(!syncing, Button::new("Sync")).clicked()
+                {
+                    sync = Some(list.source.clone());
+                }
+                if ui.button("Remove").clicked() {
+                    remove = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.word_list_draft)
+                    .hint_text("File or URL of a word list"),
+            )
+            .on_hover_text(
+                "Plain text with a word per line, or CSV with words in \
+                 the first column",
+            );
+            if ui.button("Choose...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Word list", &["txt", "csv"])
+                    .pick_file()
+                {
+                    self.word_list_draft = path.display().to_string();
+                }
+            }
+            let source = (!self.word_list_draft.trim().is_empty())
+                .then(|| WordListSource::parse(&self.word_list_draft))
+                .filter(|source| {
+                    self.flag_words
+                        .lists
+                        .iter()
+                        .all(|it| it.source != *source)
+                });
+            if ui
+                .add_enabled(source.is_some(), Button::new("Add"))
+                .clicked()
+            {
+                if let Some(source) = source {
+                    self.flag_words
+                        .lists
+                        .push(WordList::new(source.clone()));
+                    self.word_list_draft.clear();
+                    sync = Some(source);
+                    changed = true;
+                }
+            }
+        });
+
+        if let Some(idx) = remove {
+            let list = self.flag_words.lists.remove(idx);
+            self.word_list_syncs.status.remove(&list.source);
+            changed = true;
+        }
+        if let Some(source) = sync {
+            self.start_word_list_sync(ui.ctx(), source);
+        }
+        if changed {
+            ui.data_mut(|d| {
+                d.insert_persisted(
+                    self.flag_words_id,
+                    self.flag_words.clone(),
+                )
+            });
+        }
+    }
+}
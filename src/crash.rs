@@ -0,0 +1,108 @@
+use std::{
+    backtrace::Backtrace,
+    env::current_dir,
+    fmt::Write as _,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+};
+
+use anyhow::Context;
+use chrono::Local;
+
+use crate::logging::LogConsole;
+
+/// Log events at the end of a crash report.
+const CRASH_LOG_LINES: usize = 100;
+/// Names the report not looked at yet, if any.
+const UNSEEN_MARKER: &str = "unseen";
+
+pub fn dir() -> PathBuf {
+    current_dir().unwrap_or_default().join("crashes")
+}
+
+/// Writes a report to [`dir`] on panic, before the default hook prints
+/// the panic as usual.
+pub fn install(log_console: LogConsole) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        match write_report(info, &log_console) {
+            Ok(path) => {
+                eprintln!("crash report written to {}", path.display())
+            }
+            Err(err) => {
+                eprintln!("failed to write crash report: {err:?}")
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+fn write_report(
+    info: &PanicHookInfo<'_>,
+    log_console: &LogConsole,
+) -> anyhow::Result<PathBuf> {
+    let now = Local::now();
+    let mut report = format!(
+        "Blooming Light {} crashed at {}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        now.format("%Y-%m-%d %H:%M:%S%.3f %:z"),
+    );
+    let thread = thread::current();
+    let _ = writeln!(
+        report,
+        "thread '{}' {info}\n",
+        thread.name().unwrap_or("<unnamed>")
+    );
+    let _ =
+        writeln!(report, "Backtrace:\n{}", Backtrace::force_capture());
+    let _ = writeln!(report, "Last log events:");
+    for line in log_console.recent(CRASH_LOG_LINES) {
+        let _ = writeln!(report, "{line}");
+    }
+
+    let dir = dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+    let name = format!("crash-{}.txt", now.format("%Y%m%dT%H%M%S"));
+    let path = dir.join(&name);
+    fs::write(&path, report)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    fs::write(dir.join(UNSEEN_MARKER), name)
+        .context("failed to mark crash report unseen")?;
+    Ok(path)
+}
+
+/// The report of a crash not looked at yet and its path, shown until
+/// [`mark_seen`].
+pub fn unseen_report() -> Option<(PathBuf, String)> {
+    let dir = dir();
+    let name = fs::read_to_string(dir.join(UNSEEN_MARKER)).ok()?;
+    let path = dir.join(name.trim());
+    let report = fs::read_to_string(&path).ok()?;
+    Some((path, report))
+}
+
+pub fn mark_seen() -> anyhow::Result<()> {
+    let marker = dir().join(UNSEEN_MARKER);
+    fs::remove_file(&marker)
+        .with_context(|| format!("failed to remove {}", marker.display()))
+}
+
+/// In the file manager.
+pub fn open_dir(dir: &Path) -> anyhow::Result<()> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    Command::new(program)
+        .arg(dir)
+        .spawn()
+        .with_context(|| format!("failed to run {program}"))?;
+    Ok(())
+}
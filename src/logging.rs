@@ -0,0 +1,277 @@
+use std::{
+    collections::VecDeque,
+    env::current_dir,
+    fmt::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use chrono::{DateTime, Local};
+
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use tracing::{
+    error, field::Field, level_filters::LevelFilter, Event, Level,
+    Subscriber,
+};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+    field::Visit,
+    filter::filter_fn,
+    layer::{Context, SubscriberExt},
+    util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+/// Daily files kept in [`FileLog::dir`].
+const FILE_LOG_DAYS: usize = 7;
+/// Events kept for [`LogConsole`], the oldest dropped beyond.
+const LOG_CONSOLE_CAPACITY: usize = 1000;
+
+/// Tracing output written to daily files besides the console, at a level
+/// that can be changed while running.
+#[derive(Clone)]
+pub struct FileLog {
+    dir: PathBuf,
+    level: Arc<RwLock<LevelFilter>>,
+}
+
+impl FileLog {
+    pub const LEVELS: [LevelFilter; 6] = [
+        LevelFilter::OFF,
+        LevelFilter::ERROR,
+        LevelFilter::WARN,
+        LevelFilter::INFO,
+        LevelFilter::DEBUG,
+        LevelFilter::TRACE,
+    ];
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn level(&self) -> LevelFilter {
+        *self.level.read().unwrap()
+    }
+
+    pub fn set_level(&self, level: LevelFilter) {
+        *self.level.write().unwrap() = level;
+    }
+}
+
+/// One event as the in-app console shows it.
+#[derive(Clone)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    /// The message followed by the other fields as `key=value`.
+    pub text: String,
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:>5} {}: {}",
+            self.time.format("%H:%M:%S%.3f"),
+            self.level,
+            self.target,
+            self.text
+        )
+    }
+}
+
+/// Recent events, at info or above whatever `RUST_LOG` says, for the
+/// app to show without a terminal.
+#[derive(Clone, Default)]
+pub struct LogConsole {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+}
+
+impl LogConsole {
+    /// Oldest first, at `level` or above.
+    pub fn lines(&self, level: LevelFilter) -> Vec<LogLine> {
+        self.lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|it| level >= it.level)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+
+    /// The last `len` lines, oldest first. Empty if the lines are being
+    /// written, as during a panic in the middle of that.
+    pub fn recent(&self, len: usize) -> Vec<LogLine> {
+        let Ok(lines) = self.lines.try_lock() else {
+            return vec![];
+        };
+        let skip = lines.len().saturating_sub(len);
+        lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogConsole {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = TextVisitor::default();
+        event.record(&mut visitor);
+        let line = LogLine {
+            time: Local::now(),
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_owned(),
+            text: visitor.text,
+        };
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() >= LOG_CONSOLE_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+#[derive(Default)]
+struct TextVisitor {
+    text: String,
+}
+
+impl Visit for TextVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        let _ = if field.name() == "message" {
+            write!(self.text, "{value:?}")
+        } else {
+            write!(self.text, "{}={value:?}", field.name())
+        };
+    }
+}
+
+/// Everything tracing writes to, kept until the app exits.
+pub struct Logging {
+    pub file_log: FileLog,
+    pub log_console: LogConsole,
+    _file_guard: Option<WorkerGuard>,
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl Logging {
+    /// `RUST_LOG` filters the console and OTLP, the file has its own
+    /// level.
+    pub fn init() -> Self {
+        let file_log = FileLog {
+            dir: current_dir().unwrap_or_default().join("logs"),
+            level: Arc::new(RwLock::new(LevelFilter::INFO)),
+        };
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("blooming_light")
+            .filename_suffix("log")
+            .max_log_files(FILE_LOG_DAYS)
+            .build(&file_log.dir);
+        let (file_layer, file_guard, file_err) = match appender {
+            Ok(appender) => {
+                let (writer, guard) =
+                    tracing_appender::non_blocking(appender);
+                let level = Arc::clone(&file_log.level);
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false)
+                    .with_filter(filter_fn(move |meta| {
+                        *level.read().unwrap() >= *meta.level()
+                    }));
+                (Some(layer), Some(guard), None)
+            }
+            Err(err) => (None, None, Some(err)),
+        };
+
+        let log_console = LogConsole::default();
+
+        let (tracer_provider, otlp_err) = match otlp_tracer_provider() {
+            Ok(it) => (it, None),
+            Err(err) => (None, Some(err)),
+        };
+        let otlp_layer = tracer_provider.as_ref().map(|it| {
+            tracing_opentelemetry::layer()
+                .with_tracer(it.tracer("blooming_light"))
+                .with_filter(env_filter())
+        });
+
+        tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_filter(env_filter()),
+            )
+            .with(file_layer)
+            .with(log_console.clone().with_filter(LevelFilter::INFO))
+            .with(otlp_layer)
+            .init();
+        if let Some(err) = file_err {
+            error!(
+                "failed to open log files in {}: {err}",
+                file_log.dir.display()
+            );
+        }
+        if let Some(err) = otlp_err {
+            error!("failed to set up otlp exporter: {err:?}");
+        }
+
+        Self {
+            file_log,
+            log_console,
+            _file_guard: file_guard,
+            tracer_provider,
+        }
+    }
+
+    /// Sends what's still batched for OTLP, the file guard flushes on
+    /// drop.
+    pub fn shutdown(self) {
+        if let Some(tracer_provider) = self.tracer_provider {
+            if let Err(err) = tracer_provider.shutdown() {
+                error!("failed to shut down otlp exporter: {err}");
+            }
+        }
+    }
+}
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::builder()
+        .with_default_directive(LevelFilter::WARN.into())
+        .from_env_lossy()
+}
+
+/// Ships spans to an OTLP collector over HTTP when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` (or `..._TRACES_ENDPOINT`) is set, the
+/// other standard `OTEL_*` variables apply too.
+fn otlp_tracer_provider() -> anyhow::Result<Option<SdkTracerProvider>> {
+    let configured = [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    ]
+    .into_iter()
+    .any(|it| std::env::var_os(it).is_some());
+    if !configured {
+        return Ok(None);
+    }
+
+    let exporter = SpanExporter::builder().with_http().build()?;
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name("blooming_light")
+                .build(),
+        )
+        .build();
+    Ok(Some(tracer_provider))
+}
@@ -1,20 +1,36 @@
 use eframe::egui::ViewportBuilder;
-use tracing::{error, info, level_filters::LevelFilter};
-use tracing_subscriber::EnvFilter;
+use tracing::{error, info};
+
+use self::logging::Logging;
 
 mod app;
+mod crash;
+mod logging;
+mod portable;
 
 fn main() -> eframe::Result {
+    // logs, the message log and the rest are relative to it
+    let portable_dir = portable::dir();
+    let portable_err = portable_dir
+        .as_ref()
+        .and_then(|dir| std::env::set_current_dir(dir).err());
     dotenv::dotenv().ok();
-    tracing_subscriber::fmt::fmt()
-        .with_env_filter(
-            EnvFilter::builder()
-                .with_default_directive(LevelFilter::WARN.into())
-                .from_env_lossy(),
-        )
-        .init();
-    if std::env::var("PUFFIN_PROFILER").is_ok_and(|it| it == "true") {
-        start_puffin_server()
+    let logging = Logging::init();
+    crash::install(logging.log_console.clone());
+    if let Some(ref dir) = portable_dir {
+        match portable_err {
+            None => info!(
+                "running portable in {}, secrets stay in this machine's \
+                 keyring and don't move with it",
+                dir.display()
+            ),
+            Some(err) => {
+                error!(
+                    "failed to run portable in {}: {err}",
+                    dir.display()
+                )
+            }
+        }
     }
 
     let options = eframe::NativeOptions {
@@ -22,30 +38,22 @@ fn main() -> eframe::Result {
             .with_title("Blooming Light")
             .with_inner_size([600.0, 400.0]),
         persist_window: true,
+        persistence_path: portable_dir.map(|it| it.join("app.ron")),
         ..Default::default()
     };
 
-    eframe::run_native(
+    let res = eframe::run_native(
         "BloomingLight",
         options,
-        Box::new(|cc| Ok(Box::new(app::App::new(cc)))),
-    )
-}
-
-fn start_puffin_server() {
-    puffin::set_scopes_on(true);
+        Box::new(|cc| {
+            Ok(Box::new(app::App::new(
+                cc,
+                logging.file_log.clone(),
+                logging.log_console.clone(),
+            )))
+        }),
+    );
 
-    match puffin_http::Server::new("127.0.0.1:8585") {
-        Ok(puffin_server) => {
-            info!("puffin server listenning at 127.0.0.1:8585");
-
-            // We can store the server if we want, but in this case we just want
-            // it to keep running. Dropping it closes the server, so let's not drop it!
-            #[allow(clippy::mem_forget)]
-            std::mem::forget(puffin_server);
-        }
-        Err(err) => {
-            error!("failed to start puffin server: {err}");
-        }
-    };
+    logging.shutdown();
+    res
 }
@@ -0,0 +1,17 @@
+use std::{env, path::PathBuf};
+
+/// Runs portable even without the marker.
+const PORTABLE_FLAG: &str = "--portable";
+/// Put next to the executable to always run portable.
+const PORTABLE_MARKER: &str = "portable";
+
+/// The executable's directory if running portable, where settings, logs
+/// and the rest are kept instead of the working directory and the app
+/// data directory. Secrets stay in the system keyring either way.
+pub fn dir() -> Option<PathBuf> {
+    let exe = env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    let flagged = env::args().skip(1).any(|it| it == PORTABLE_FLAG);
+    (flagged || dir.join(PORTABLE_MARKER).exists())
+        .then(|| dir.to_owned())
+}